@@ -1,10 +1,10 @@
 ///< Constants used throughout the application.
-pub const CONFIG_EXTENSIONS: [&str; 15] = [
-    "txt", "json", "toml", "lock", "yaml", "gemspec", "xml", "gradle",
-    "csproj", "config", "sln", "mod", "sbt", "cabal", "md",
+pub const CONFIG_EXTENSIONS: [&str; 18] = [
+    "txt", "json", "toml", "lock", "yaml", "yml", "gemspec", "xml", "gradle",
+    "csproj", "config", "sln", "mod", "sbt", "cabal", "ini", "cfg", "md",
 ];
 
-pub const CONFIG_FILES: [&str; 9] = [
+pub const CONFIG_FILES: [&str; 14] = [
     "Pipfile",       // Python
     "Gemfile",       // Ruby
     "cpanfile",      // Perl
@@ -13,7 +13,33 @@ pub const CONFIG_FILES: [&str; 9] = [
     "DESCRIPTION",   // R
     "NAMESPACE",     // R
     "Package.swift", // Swift
+    "Dockerfile",
+    "Makefile",
+    "Procfile",
+    ".env",
+    ".dockerignore",
     ".gitignore",
 ];
 
+/// Maximum number of codebase files sent as context when generating code
+pub const CONTEXT_MAX_FILES: usize = 10;
+/// Number of most relevant files sent verbatim, the others being reduced to
+/// their signatures
+pub const CONTEXT_FULL_FILES: usize = 4;
+
+/// Maximum number of rounds in which the LLM is asked to fix the syntax
+/// errors of the code it generated
+pub const MAX_REPAIR_ROUNDS: u32 = 2;
+
+/// Maximum number of rounds in which the LLM can call tools before giving
+/// its answer
+pub const MAX_TOOL_ROUNDS: usize = 4;
+
+/// Maximum number of times a task can be run, including retries
+pub const MAX_TASK_ATTEMPTS: usize = 3;
+
+/// Maximum number of times the LLM is prompted for an answer in JSON,
+/// including the retries of answers that are not valid JSON
+pub const MAX_JSON_ATTEMPTS: u32 = 3;
+
 pub const BASE_BETA_URL: &str = "https://api.openai.com/v1/beta";
@@ -0,0 +1,295 @@
+use anyhow::{anyhow, Result};
+use js_sys::{Function, JsString};
+use parser::parser::diff::AsDiff;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::{
+    consts::MAX_TOOL_ROUNDS,
+    models::app_data::AppData,
+    openai::{
+        msg::OpenAIMsg,
+        params::OpenAIParams,
+        tools::{chat_with_tools, ToolRegistry},
+    },
+    utils::log,
+    validation::extract_code,
+};
+
+#[wasm_bindgen]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeEditParams {
+    pub(crate) filename: String,
+    /// Change requested to the file
+    pub(crate) instruction: String,
+}
+
+#[wasm_bindgen]
+impl CodeEditParams {
+    #[wasm_bindgen(constructor)]
+    pub fn new(filename: String, instruction: String) -> CodeEditParams {
+        CodeEditParams {
+            filename,
+            instruction,
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn filename(&self) -> JsString {
+        self.filename.clone().into()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn instruction(&self) -> JsString {
+        self.instruction.clone().into()
+    }
+}
+
+/// Outcome of an edit, i.e. the new content of the file along with the
+/// hunks of the diff which could not be applied.
+#[wasm_bindgen]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeEdit {
+    pub(crate) content: String,
+    pub(crate) conflicts: Vec<String>,
+}
+
+#[wasm_bindgen]
+impl CodeEdit {
+    #[wasm_bindgen(getter)]
+    pub fn content(&self) -> JsString {
+        self.content.clone().into()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn conflicts(&self) -> Vec<JsString> {
+        self.conflicts
+            .iter()
+            .map(|conflict| JsString::from(conflict.as_str()))
+            .collect()
+    }
+
+    #[wasm_bindgen(js_name = hasConflicts)]
+    pub fn has_conflicts(&self) -> bool {
+        !self.conflicts.is_empty()
+    }
+}
+
+/// Asks the LLM for a unified diff applying the instruction of `task_params`
+/// to the current `content` of the file, and applies it. Hand-written
+/// changes outside of the hunks are therefore preserved.
+///
+/// The LLM can read the schemas of the interfaces the edit relies on with
+/// the `read_schema` tool.
+pub async fn edit_code(
+    app_state: &AppData,
+    ai_params: &OpenAIParams,
+    task_params: &CodeEditParams,
+    content: &str,
+    request_callback: &Function,
+) -> Result<CodeEdit> {
+    let language = app_state.language_()?;
+
+    let CodeEditParams {
+        filename,
+        instruction,
+    } = task_params;
+
+    log(&format!("[INFO] Running `CodeEdit` Job: {}", filename));
+
+    let templates = &app_state.prompts;
+
+    let mut prompts = Vec::new();
+
+    prompts.push(OpenAIMsg::system(&templates.render(
+        "code_gen_system",
+        &json!({ "language": language.name() }),
+    )?));
+
+    if let Some(specs) = &app_state.specs {
+        prompts.push(OpenAIMsg::user(specs));
+    }
+
+    prompts.push(OpenAIMsg::user(&templates.render(
+        "code_edit",
+        &json!({
+            "language": language.name(),
+            "filename": filename,
+            "content": content,
+            "instruction": instruction,
+        }),
+    )?));
+
+    let registry = schema_tools(interface_schemas(app_state));
+
+    let (chat, _) = chat_with_tools(
+        request_callback,
+        &app_state.provider,
+        ai_params,
+        prompts,
+        &registry,
+        MAX_TOOL_ROUNDS,
+    )
+    .await?;
+
+    let answer = &chat
+        .choices
+        .first()
+        .ok_or_else(|| anyhow!("LLM Respose seems to be empty :("))?
+        .message
+        .content;
+
+    apply_diff(answer, content)
+}
+
+/// Returns the schema files of the interfaces of the project, by path, i.e.
+/// by the name of the interface followed by the name of the schema.
+fn interface_schemas(app_state: &AppData) -> BTreeMap<String, String> {
+    app_state
+        .interfaces
+        .iter()
+        .flat_map(|(name, interface)| {
+            interface.schemas_().into_iter().flatten().map(
+                move |(schema_name, schema)| {
+                    (format!("{}/{}", name, schema_name), schema.clone())
+                },
+            )
+        })
+        .collect()
+}
+
+/// Registers the `read_schema` tool, returning the schema files by path. No
+/// tool is registered if there are no schemas.
+fn schema_tools(schemas: BTreeMap<String, String>) -> ToolRegistry {
+    let mut registry = ToolRegistry::new();
+
+    if schemas.is_empty() {
+        return registry;
+    }
+
+    let paths = schemas.keys().cloned().collect::<Vec<String>>();
+
+    registry.register(
+        "read_schema",
+        "Reads a schema file of the interfaces of the project",
+        json!({
+            "type": "object",
+            "properties": {
+                "path": { "type": "string", "enum": paths }
+            },
+            "required": ["path"]
+        }),
+        move |args| {
+            let path = args["path"]
+                .as_str()
+                .ok_or_else(|| anyhow!("Missing `path` argument"))?;
+
+            schemas
+                .get(path)
+                .cloned()
+                .ok_or_else(|| anyhow!("Unknown schema `{}`", path))
+        },
+    );
+
+    registry
+}
+
+/// Applies the unified diff of the LLM `answer` to the `content` of the file.
+pub fn apply_diff(answer: &str, content: &str) -> Result<CodeEdit> {
+    let diff = extract_code(answer, "diff").as_str().as_diff()?;
+
+    let patched = diff.apply(content);
+
+    Ok(CodeEdit {
+        content: patched.content,
+        conflicts: patched
+            .conflicts
+            .iter()
+            .map(|conflict| conflict.to_string())
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::openai::tools::{FunctionCall, ToolCall};
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn applies_diff_answers() -> Result<()> {
+        let content = "def greet(name):\n    print(\"Hello\", name)\n\n# Manual tweak\nTIMEOUT = 30\n";
+
+        let answer = r#"Sure, here is the change:
+
+```diff
+--- a/app.py
++++ b/app.py
+@@ ... @@
+-def greet(name):
+-    print("Hello", name)
++def greet(name, greeting="Hello"):
++    print(greeting, name)
+```"#;
+
+        let edit = apply_diff(answer, content)?;
+
+        // Lines outside of the hunks are preserved
+        assert!(!edit.has_conflicts());
+        assert_eq!(
+            edit.content,
+            "def greet(name, greeting=\"Hello\"):\n    print(greeting, name)\n\n# Manual tweak\nTIMEOUT = 30\n"
+        );
+
+        let answer = "```diff\n@@ -5 +5 @@\n-TIMEOUT = 10\n+TIMEOUT = 60\n```";
+
+        let edit = apply_diff(answer, content)?;
+
+        assert_eq!(edit.content, content);
+        assert_eq!(edit.conflicts.len(), 1);
+        assert!(edit.conflicts[0].contains("TIMEOUT = 10"));
+
+        assert!(apply_diff("The file is fine as is.", content).is_err());
+
+        Ok(())
+    }
+    #[wasm_bindgen_test]
+    fn reads_schemas_with_tools() {
+        assert!(schema_tools(BTreeMap::new()).defs().is_empty());
+
+        let schemas = BTreeMap::from([(
+            String::from("db/users.sql"),
+            String::from("CREATE TABLE users (id INT);"),
+        )]);
+
+        let registry = schema_tools(schemas);
+
+        assert_eq!(registry.defs()[0].function.name, "read_schema");
+
+        let call = |arguments: &str| ToolCall {
+            id: String::from("call_abc123"),
+            call_type: String::from("function"),
+            function: FunctionCall {
+                name: String::from("read_schema"),
+                arguments: String::from(arguments),
+            },
+        };
+
+        assert_eq!(
+            registry
+                .dispatch(&call(r#"{"path":"db/users.sql"}"#))
+                .content,
+            "CREATE TABLE users (id INT);"
+        );
+        assert_eq!(
+            registry
+                .dispatch(&call(r#"{"path":"db/teams.sql"}"#))
+                .content,
+            "Error: Unknown schema `db/teams.sql`"
+        );
+    }
+}
@@ -0,0 +1,583 @@
+use anyhow::{anyhow, Result};
+use js_sys::{Function, JsString};
+use parser::parser::{
+    graphql::{GraphQl, OperationKind},
+    proto::ProtoSet,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::Path;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::{
+    consts::MAX_REPAIR_ROUNDS,
+    endpoints::repair_code::{repair_code, CodeValidation, ResolverCheck},
+    models::app_data::{
+        interfaces::{
+            apis::Api,
+            asyncapi::{AsyncApi, RECEIVE, SEND},
+            openapi::OpenApi,
+        },
+        language::{Language, LanguageType},
+        AppData,
+    },
+    openai::{msg::OpenAIMsg, params::OpenAIParams, request::chat_raw},
+    utils::log,
+};
+
+/// Side of an API written by an `ApiStubGen` task.
+#[wasm_bindgen]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub enum StubKind {
+    /// Client calling the operations of the API
+    Client,
+    /// Handlers serving the operations of the API
+    Server,
+    /// Producer sending the messages of the channels of an event-driven API
+    Producer,
+    /// Consumer handling the messages received on the channels of an
+    /// event-driven API
+    Consumer,
+}
+
+impl StubKind {
+    /// Whether the stubs are written for the channels of an AsyncAPI spec,
+    /// rather than for request-response operations.
+    pub fn is_event_driven(&self) -> bool {
+        matches!(self, StubKind::Producer | StubKind::Consumer)
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiStubParams {
+    /// Name of the `Api` interface
+    pub(crate) interface: String,
+    pub(crate) kind: StubKind,
+    /// File of the stubs, relative to the `src` folder
+    pub(crate) filename: String,
+    /// Group of the operations written, e.g. the tag of OpenAPI endpoints
+    /// or the address of an AsyncAPI channel. All the operations of the interface are written if `None`.
+    pub(crate) group: Option<String>,
+}
+
+#[wasm_bindgen]
+impl ApiStubParams {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        interface: String,
+        kind: StubKind,
+        filename: String,
+        group: Option<String>,
+    ) -> ApiStubParams {
+        ApiStubParams {
+            interface,
+            kind,
+            filename,
+            group,
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn interface(&self) -> JsString {
+        self.interface.clone().into()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn kind(&self) -> StubKind {
+        self.kind
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn filename(&self) -> JsString {
+        self.filename.clone().into()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn group(&self) -> Option<JsString> {
+        self.group.clone().map(JsString::from)
+    }
+}
+
+impl ApiStubParams {
+    /// Creates the parameters to write the stubs of the operations of
+    /// `group`, placing them in a `clients`, `handlers`, `producers` or
+    /// `consumers` folder.
+    pub fn for_group(
+        interface: &str,
+        kind: StubKind,
+        group: Option<&str>,
+        language: &Language,
+    ) -> Self {
+        ApiStubParams {
+            interface: interface.to_string(),
+            kind,
+            filename: stub_filename(interface, kind, group, language),
+            group: group.map(String::from),
+        }
+    }
+
+    /// Describes the stubs in the task pool.
+    pub fn description(&self) -> String {
+        let side = match self.kind {
+            StubKind::Client => "Client",
+            StubKind::Server => "Handlers",
+            StubKind::Producer => "Producer",
+            StubKind::Consumer => "Consumer",
+        };
+
+        match &self.group {
+            Some(group) => {
+                format!(
+                    "{} of the `{}` operations of {}",
+                    side, group, self.interface
+                )
+            }
+            None => format!("{} of {}", side, self.interface),
+        }
+    }
+}
+
+/// Specification the stubs of an API are written from. OpenAPI specs take
+/// precedence over AsyncAPI specs, then GraphQL schemas, then `.proto`
+/// files.
+enum Spec {
+    OpenApi(OpenApi),
+    AsyncApi(AsyncApi),
+    GraphQl(GraphQl),
+    Protos(ProtoSet),
+}
+
+impl Spec {
+    fn of(api: &Api) -> Result<Spec> {
+        if let Some(open_api) = api.open_api_() {
+            return Ok(Spec::OpenApi(open_api));
+        }
+
+        if let Some(async_api) = api.async_api_() {
+            return Ok(Spec::AsyncApi(async_api));
+        }
+
+        if let Some(graphql) = api.graphql_() {
+            return Ok(Spec::GraphQl(graphql));
+        }
+
+        api.protos_().map(Spec::Protos).ok_or_else(|| {
+            anyhow!(
+                "The interface {} has no OpenAPI, Swagger, AsyncAPI, GraphQL or protobuf schema",
+                api.name
+            )
+        })
+    }
+}
+
+/// Groups of operations of `api`, each of them being written by a task: the
+/// tags of OpenAPI endpoints, the AsyncAPI channels on which messages are
+/// sent by producers or received by consumers, the root types of GraphQL
+/// operations or the gRPC services. Returns a single `None` group if the
+/// operations are not grouped.
+pub fn stub_groups(api: &Api, kind: StubKind) -> Result<Vec<Option<String>>> {
+    let spec = Spec::of(api)?;
+
+    if matches!(spec, Spec::AsyncApi(_)) != kind.is_event_driven() {
+        return Err(match kind.is_event_driven() {
+            true => anyhow!(
+                "Producers and consumers are only written from AsyncAPI specs, which {} has none of",
+                api.name
+            ),
+            false => anyhow!(
+                "The interface {} is event-driven, write its producers or consumers instead",
+                api.name
+            ),
+        });
+    }
+
+    let groups = match spec {
+        Spec::OpenApi(open_api) => open_api.groups(),
+        Spec::AsyncApi(async_api) => {
+            let action = match kind {
+                StubKind::Producer => SEND,
+                _ => RECEIVE,
+            };
+
+            let channels = async_api.channels_with(action);
+
+            if channels.is_empty() {
+                return Err(anyhow!(
+                    "No channel of {} has operations to {}",
+                    api.name,
+                    action
+                ));
+            }
+
+            channels
+        }
+        Spec::GraphQl(graphql) => OperationKind::ALL
+            .iter()
+            .filter(|kind| graphql.root(**kind).is_some())
+            .map(|kind| kind.to_string())
+            .collect(),
+        Spec::Protos(protos) => protos
+            .services()
+            .iter()
+            .map(|(_, service)| service.name.clone())
+            .collect(),
+    };
+
+    if groups.iter().all(String::is_empty) {
+        return Ok(vec![None]);
+    }
+
+    Ok(groups.into_iter().map(Some).collect())
+}
+
+/// Compact description of the operations of `group` of `spec`, from which
+/// the stubs are written. GraphQL operations and gRPC services are
+/// described along with the types they reach.
+fn stub_operations(spec: &Spec, group: Option<&str>) -> String {
+    match spec {
+        Spec::OpenApi(open_api) => open_api.summary_of(group),
+        Spec::AsyncApi(async_api) => async_api.summary_of(group),
+        Spec::GraphQl(graphql) => {
+            let roots = graphql_roots(graphql, group);
+            graphql.subset(&roots).to_string()
+        }
+        Spec::Protos(protos) => protos
+            .services()
+            .iter()
+            .filter(|(_, service)| group.is_none_or(|g| service.name == g))
+            .filter_map(|(_, service)| protos.service_summary(&service.name))
+            .collect::<Vec<String>>()
+            .join("\n\n"),
+    }
+}
+
+/// Root types of the GraphQL operations of `group`, or of all operations.
+fn graphql_roots<'a>(
+    graphql: &'a GraphQl,
+    group: Option<&str>,
+) -> Vec<&'a str> {
+    OperationKind::ALL
+        .iter()
+        .filter(|kind| group.is_none_or(|group| kind.to_string() == group))
+        .filter_map(|kind| graphql.root(*kind))
+        .map(|root| root.name.as_str())
+        .collect()
+}
+
+/// Path of the stubs of `group`, relative to the `src` folder. Untagged
+/// operations are named after the interface.
+pub fn stub_filename(
+    interface: &str,
+    kind: StubKind,
+    group: Option<&str>,
+    language: &Language,
+) -> String {
+    let name = group.filter(|group| !group.is_empty()).unwrap_or(interface);
+
+    let words = name
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<String>>();
+
+    let (folder, suffix) = match kind {
+        StubKind::Client => ("clients", "Client"),
+        StubKind::Server => ("handlers", "Handler"),
+        StubKind::Producer => ("producers", "Producer"),
+        StubKind::Consumer => ("consumers", "Consumer"),
+    };
+
+    let stem = match language.language {
+        // Files are named after the classes they define
+        LanguageType::Java
+        | LanguageType::Kotlin
+        | LanguageType::Scala
+        | LanguageType::CSharp
+        | LanguageType::Swift => {
+            let mut stem = words
+                .iter()
+                .map(|word| {
+                    let mut chars = word.chars();
+                    chars
+                        .next()
+                        .map(|first| {
+                            first
+                                .to_uppercase()
+                                .chain(chars)
+                                .collect::<String>()
+                        })
+                        .unwrap_or_default()
+                })
+                .collect::<String>();
+
+            stem.push_str(suffix);
+            stem
+        }
+        _ => words.join("_"),
+    };
+
+    let extension = language.language.default_extension().unwrap_or_default();
+
+    Path::new(folder)
+        .join(format!("{}{}", stem, extension))
+        .to_str()
+        .map(String::from)
+        .unwrap_or_default()
+}
+
+/// Asks the LLM for the client, the handlers, the producer or the consumer
+/// of the operations of `task_params`, matching the specification of the interface. The
+/// `existing` stubs, if any, are updated. The stubs are then validated, and
+/// the handlers of GraphQL schemas checked to resolve every field.
+pub async fn gen_api_stubs(
+    app_state: &AppData,
+    ai_params: &OpenAIParams,
+    task_params: &ApiStubParams,
+    existing: Option<&str>,
+    request_callback: &Function,
+) -> Result<CodeValidation> {
+    let language = app_state.language_()?;
+
+    log(&format!(
+        "[INFO] Running `ApiStubGen` Job: {}",
+        task_params.filename
+    ));
+
+    let api = app_state
+        .interfaces
+        .get(&task_params.interface)
+        .and_then(|interface| interface.inner.api.as_ref())
+        .ok_or_else(|| {
+            anyhow!("The interface {} is not an API", task_params.interface)
+        })?;
+
+    let spec = Spec::of(api)?;
+    let operations = stub_operations(&spec, task_params.group.as_deref());
+
+    let side = match task_params.kind {
+        StubKind::Client => "client",
+        StubKind::Server => "request handlers",
+        StubKind::Producer => "producer",
+        StubKind::Consumer => "consumer",
+    };
+
+    let templates = &app_state.prompts;
+
+    let mut prompts = Vec::new();
+
+    prompts.push(OpenAIMsg::system(&templates.render(
+        "code_gen_system",
+        &json!({ "language": language.name() }),
+    )?));
+
+    if let Some(specs) = &app_state.specs {
+        prompts.push(OpenAIMsg::user(specs));
+    }
+
+    prompts.push(OpenAIMsg::user(&templates.render(
+        "api_stubs",
+        &json!({
+            "language": language.name(),
+            "filename": task_params.filename,
+            "interface": api.name,
+            "api_type": api.api_type.to_string(),
+            "side": side,
+            "client": task_params.kind == StubKind::Client,
+            "producer": task_params.kind == StubKind::Producer,
+            "asyncapi": matches!(spec, Spec::AsyncApi(_)),
+            "graphql": matches!(spec, Spec::GraphQl(_)),
+            "grpc": matches!(spec, Spec::Protos(_)),
+            "group": task_params.group.as_deref().filter(|g| !g.is_empty()),
+            "host": api.host,
+            "port": api.port,
+            "operations": operations,
+            "existing": existing.filter(|stubs| !stubs.trim().is_empty()),
+        }),
+    )?));
+
+    let prompts = prompts.iter().collect::<Vec<&OpenAIMsg>>();
+
+    let chat = chat_raw(
+        request_callback,
+        &app_state.provider,
+        ai_params,
+        &prompts,
+        &[],
+    )
+    .await?;
+
+    let answer = &chat
+        .choices
+        .first()
+        .ok_or_else(|| anyhow!("LLM Respose seems to be empty :("))?
+        .message
+        .content;
+
+    // Resolvers are checked against the schema, and the LLM is asked to add
+    // the missing ones
+    let resolvers = match (&spec, task_params.kind) {
+        (Spec::GraphQl(graphql), StubKind::Server) => Some(ResolverCheck {
+            schema: graphql,
+            roots: graphql_roots(graphql, task_params.group.as_deref()),
+            operations: &operations,
+        }),
+        _ => None,
+    };
+
+    repair_code(
+        &app_state.provider,
+        ai_params,
+        templates,
+        language,
+        &task_params.filename,
+        answer,
+        None,
+        resolvers.as_ref(),
+        MAX_REPAIR_ROUNDS,
+        request_callback,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::app_data::interfaces::apis::ApiType;
+    use std::collections::BTreeMap;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn places_stubs_by_language() {
+        let rust = Language::new(LanguageType::Rust);
+        let java = Language::new(LanguageType::Java);
+
+        let expected = [
+            (
+                "Pet Store",
+                StubKind::Client,
+                None,
+                &rust,
+                "clients/pet_store.rs",
+            ),
+            (
+                "Pet Store",
+                StubKind::Server,
+                Some("users"),
+                &rust,
+                "handlers/users.rs",
+            ),
+            (
+                "Pet Store",
+                StubKind::Server,
+                Some(""),
+                &rust,
+                "handlers/pet_store.rs",
+            ),
+            (
+                "Pet Store",
+                StubKind::Client,
+                Some("store-admin"),
+                &java,
+                "clients/StoreAdminClient.java",
+            ),
+        ];
+
+        for (interface, kind, group, language, filename) in expected {
+            let params =
+                ApiStubParams::for_group(interface, kind, group, language);
+
+            assert_eq!(params.filename, filename);
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn groups_graphql_operations() {
+        let schemas = BTreeMap::from([
+            (
+                String::from("schema.graphql"),
+                String::from("type Query { user(id: ID!): User }\ntype User { id: ID! }\ntype Team { id: ID! }"),
+            ),
+            (
+                String::from("mutations.graphql"),
+                String::from("type Mutation { renameTeam(id: ID!, name: String!): Team }"),
+            ),
+        ]);
+
+        let api =
+            Api::new_(String::from("Accounts"), ApiType::GraphQL, schemas);
+
+        assert_eq!(
+            stub_groups(&api, StubKind::Server).unwrap(),
+            vec![Some(String::from("Query")), Some(String::from("Mutation"))]
+        );
+
+        let spec = Spec::of(&api).unwrap();
+        let queries = stub_operations(&spec, Some("Query"));
+
+        assert!(queries.contains("type User"));
+        assert!(!queries.contains("type Team"));
+    }
+
+    #[wasm_bindgen_test]
+    fn groups_grpc_services() {
+        let schemas = BTreeMap::from([
+            (
+                String::from("users.proto"),
+                String::from("syntax = \"proto3\";\npackage users;\nimport \"common.proto\";\n\nservice Users {\n  rpc Get (GetUser) returns (common.User);\n  rpc Watch (GetUser) returns (stream common.User);\n}\n\nservice Admin {\n  rpc Ban (GetUser) returns (common.User);\n}\n\nmessage GetUser { string id = 1; }\n"),
+            ),
+            (
+                String::from("common.proto"),
+                String::from("syntax = \"proto3\";\npackage common;\n\nmessage User { string id = 1; }\nmessage Team { string id = 1; }\n"),
+            ),
+        ]);
+
+        let api = Api::new_(String::from("Users"), ApiType::GRpcApi, schemas);
+
+        assert_eq!(
+            stub_groups(&api, StubKind::Client).unwrap(),
+            vec![Some(String::from("Users")), Some(String::from("Admin"))]
+        );
+
+        let spec = Spec::of(&api).unwrap();
+        let users = stub_operations(&spec, Some("Users"));
+
+        assert!(
+            users.contains("rpc Watch(GetUser) returns (stream common.User);")
+        );
+        assert!(users.contains("message User"));
+        assert!(!users.contains("service Admin"));
+        assert!(!users.contains("message Team"));
+    }
+
+    #[wasm_bindgen_test]
+    fn groups_asyncapi_channels() {
+        let schemas = BTreeMap::from([(
+            String::from("asyncapi.yaml"),
+            String::from("asyncapi: 2.6.0\ninfo:\n  title: Lights\nchannels:\n  lights/measured:\n    publish:\n      message:\n        payload:\n          type: integer\n  lights/turn:\n    subscribe:\n      message:\n        payload:\n          type: boolean\n"),
+        )]);
+
+        let api = Api::new_(String::from("Lights"), ApiType::Mqtt, schemas);
+
+        assert_eq!(
+            stub_groups(&api, StubKind::Producer).unwrap(),
+            vec![Some(String::from("lights/turn"))]
+        );
+        assert_eq!(
+            stub_groups(&api, StubKind::Consumer).unwrap(),
+            vec![Some(String::from("lights/measured"))]
+        );
+        assert!(stub_groups(&api, StubKind::Client).is_err());
+
+        let rust = Language::new(LanguageType::Rust);
+        let params = ApiStubParams::for_group(
+            "Lights",
+            StubKind::Consumer,
+            Some("lights/measured"),
+            &rust,
+        );
+
+        assert_eq!(params.filename, "consumers/lights_measured.rs");
+    }
+}
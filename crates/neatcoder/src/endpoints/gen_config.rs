@@ -0,0 +1,698 @@
+use anyhow::{anyhow, Result};
+use js_sys::{Function, JsString};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+};
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::{
+    consts::{CONFIG_EXTENSIONS, CONFIG_FILES},
+    models::app_data::{
+        interfaces::{
+            apis::ApiType, dbs::DbType, storage::StorageType, AsContext,
+            Interface,
+        },
+        language::{Language, LanguageType},
+        AppData,
+    },
+    openai::{
+        budget::{ContextBudget, Priority},
+        msg::OpenAIMsg,
+        params::OpenAIParams,
+        request::chat_raw,
+    },
+    utils::log,
+    validation::extract_code,
+};
+
+#[wasm_bindgen]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigGenParams {
+    /// Configuration file, relative to the root of the project
+    pub(crate) filename: String,
+    /// Purpose of the file, e.g. as described in the scaffold
+    pub(crate) description: String,
+}
+
+#[wasm_bindgen]
+impl ConfigGenParams {
+    #[wasm_bindgen(constructor)]
+    pub fn new(filename: String, description: String) -> ConfigGenParams {
+        ConfigGenParams {
+            filename,
+            description,
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn filename(&self) -> JsString {
+        self.filename.clone().into()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn description(&self) -> JsString {
+        self.description.clone().into()
+    }
+}
+
+impl ConfigGenParams {
+    /// Creates the parameters of the configuration file `filename` of the
+    /// scaffold, relative to the `src` folder. Files at the top of the `src`
+    /// folder, such as manifests, live at the root of the project.
+    pub fn for_scaffold_file(filename: &str, description: &str) -> Self {
+        let filename = match filename.contains('/') {
+            true => format!("src/{}", filename),
+            false => filename.to_string(),
+        };
+
+        ConfigGenParams {
+            filename,
+            description: description.to_string(),
+        }
+    }
+}
+
+/// Whether `filename` is a configuration file rather than code, either by
+/// its name or by its extension.
+pub fn is_config_file(filename: &str) -> bool {
+    let path = Path::new(filename);
+
+    let by_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| CONFIG_FILES.contains(&name));
+
+    let by_extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| CONFIG_EXTENSIONS.contains(&ext));
+
+    by_name || by_extension
+}
+
+/// Manifests expected by the build tools of the language, along with their
+/// description.
+pub fn manifest_files(
+    language: &Language,
+) -> Vec<(&'static str, &'static str)> {
+    match language.language {
+        LanguageType::Rust => {
+            vec![("Cargo.toml", "Manifest of the crate and its dependencies")]
+        }
+        LanguageType::Python => {
+            vec![("requirements.txt", "Dependencies of the project")]
+        }
+        LanguageType::JavaScript => vec![(
+            "package.json",
+            "Manifest of the package, its dependencies and scripts",
+        )],
+        LanguageType::TypeScript => vec![
+            (
+                "package.json",
+                "Manifest of the package, its dependencies and scripts",
+            ),
+            ("tsconfig.json", "Options of the TypeScript compiler"),
+        ],
+        LanguageType::Go => {
+            vec![("go.mod", "Definition of the module and its dependencies")]
+        }
+        LanguageType::Java => {
+            vec![("pom.xml", "Maven build and dependencies of the project")]
+        }
+        LanguageType::Kotlin => vec![(
+            "build.gradle.kts",
+            "Gradle build and dependencies of the project",
+        )],
+        LanguageType::Scala => {
+            vec![("build.sbt", "sbt build and dependencies of the project")]
+        }
+        LanguageType::Ruby => vec![("Gemfile", "Gems required by the project")],
+        LanguageType::PHP => {
+            vec![("composer.json", "Composer packages of the project")]
+        }
+        LanguageType::CSharp => {
+            vec![("App.csproj", "Build and NuGet packages of the project")]
+        }
+        LanguageType::Swift => {
+            vec![("Package.swift", "Swift package and its dependencies")]
+        }
+        LanguageType::Haskell => {
+            vec![("package.yaml", "Package description and dependencies")]
+        }
+        LanguageType::Perl => {
+            vec![("cpanfile", "CPAN modules required by the project")]
+        }
+        LanguageType::C | LanguageType::CPlusPLus => {
+            vec![("CMakeLists.txt", "CMake build of the project")]
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Dependency required by `interface`, e.g. the database driver, suggested
+/// to the LLM.
+pub fn interface_dependency(
+    interface: &Interface,
+    language: &Language,
+) -> Option<String> {
+    use LanguageType::*;
+
+    let inner = &interface.inner;
+
+    if let Some(db) = &inner.database {
+        let driver = match (db.db_type, language.language) {
+            (DbType::PostgreSql | DbType::TimescaleDB, Rust) => {
+                Some("`sqlx` with the `postgres` feature, or `tokio-postgres`")
+            }
+            (DbType::PostgreSql | DbType::TimescaleDB, Python) => {
+                Some("`psycopg2-binary` or `asyncpg`")
+            }
+            (
+                DbType::PostgreSql | DbType::TimescaleDB,
+                JavaScript | TypeScript,
+            ) => Some("`pg`"),
+            (DbType::PostgreSql | DbType::TimescaleDB, Go) => {
+                Some("`github.com/jackc/pgx/v5`")
+            }
+            (DbType::PostgreSql | DbType::TimescaleDB, Java | Kotlin) => {
+                Some("`org.postgresql:postgresql`")
+            }
+            (DbType::MySql, Rust) => Some("`sqlx` with the `mysql` feature"),
+            (DbType::MySql, Python) => Some("`PyMySQL` or `mysqlclient`"),
+            (DbType::MySql, JavaScript | TypeScript) => Some("`mysql2`"),
+            (DbType::MySql, Go) => Some("`github.com/go-sql-driver/mysql`"),
+            (DbType::MySql, Java | Kotlin) => {
+                Some("`com.mysql:mysql-connector-j`")
+            }
+            (DbType::SqLite, Rust) => {
+                Some("`rusqlite` or `sqlx` with the `sqlite` feature")
+            }
+            (DbType::SqLite, JavaScript | TypeScript) => {
+                Some("`better-sqlite3`")
+            }
+            (DbType::SqLite, Go) => Some("`github.com/mattn/go-sqlite3`"),
+            (DbType::MongoDB, Rust) => Some("`mongodb`"),
+            (DbType::MongoDB, Python) => Some("`pymongo`"),
+            (DbType::MongoDB, JavaScript | TypeScript) => Some("`mongodb`"),
+            (DbType::MongoDB, Go) => Some("`go.mongodb.org/mongo-driver`"),
+            (DbType::Redis, Rust) => Some("`redis`"),
+            (DbType::Redis, Python) => Some("`redis`"),
+            (DbType::Redis, JavaScript | TypeScript) => {
+                Some("`redis` or `ioredis`")
+            }
+            (DbType::Redis, Go) => Some("`github.com/redis/go-redis/v9`"),
+            (DbType::ClickHouse, Rust) => Some("`clickhouse`"),
+            (DbType::ClickHouse, Python) => Some("`clickhouse-connect`"),
+            (DbType::DuckDb, Rust) => Some("`duckdb`"),
+            (DbType::DuckDb, Python) => Some("`duckdb`"),
+            _ => None,
+        };
+
+        return Some(match driver {
+            Some(driver) => format!(
+                "{} driver for the database `{}`: {}",
+                db.db_type, db.name, driver
+            ),
+            None => format!(
+                "{} client library for the database `{}`",
+                db.db_type, db.name
+            ),
+        });
+    }
+
+    if let Some(storage) = &inner.storage {
+        let client = match (storage.storage_type, language.language) {
+            (StorageType::AwsS3, Rust) => Some("`aws-sdk-s3` and `aws-config`"),
+            (StorageType::AwsS3, Python) => Some("`boto3`"),
+            (StorageType::AwsS3, JavaScript | TypeScript) => {
+                Some("`@aws-sdk/client-s3`")
+            }
+            (StorageType::AwsS3, Go) => {
+                Some("`github.com/aws/aws-sdk-go-v2/service/s3`")
+            }
+            (StorageType::GoogleCloudStorage, Python) => {
+                Some("`google-cloud-storage`")
+            }
+            (StorageType::GoogleCloudStorage, JavaScript | TypeScript) => {
+                Some("`@google-cloud/storage`")
+            }
+            (StorageType::AzureBlobStorage, Python) => {
+                Some("`azure-storage-blob`")
+            }
+            (StorageType::AzureBlobStorage, JavaScript | TypeScript) => {
+                Some("`@azure/storage-blob`")
+            }
+            (StorageType::LocalStorage, _) => return None,
+            _ => None,
+        };
+
+        return Some(match client {
+            Some(client) => format!(
+                "{} client for the storage `{}`: {}",
+                storage.storage_type, storage.name, client
+            ),
+            None => format!(
+                "{} client library for the storage `{}`",
+                storage.storage_type, storage.name
+            ),
+        });
+    }
+
+    if let Some(api) = &inner.api {
+        let library = match (api.api_type, language.language) {
+            (ApiType::GRpcApi, Rust) => Some(
+                "`tonic` and `prost`, with `tonic-build` as build dependency",
+            ),
+            (ApiType::GRpcApi, Python) => Some("`grpcio` and `grpcio-tools`"),
+            (ApiType::GRpcApi, JavaScript | TypeScript) => {
+                Some("`@grpc/grpc-js` and `@grpc/proto-loader`")
+            }
+            (ApiType::GRpcApi, Go) => Some(
+                "`google.golang.org/grpc` and `google.golang.org/protobuf`",
+            ),
+            (ApiType::GraphQL, Rust) => Some("`async-graphql`"),
+            (ApiType::GraphQL, Python) => Some("`strawberry-graphql`"),
+            (ApiType::GraphQL, JavaScript | TypeScript) => Some("`graphql`"),
+            (ApiType::GraphQL, Go) => Some("`github.com/99designs/gqlgen`"),
+            _ => None,
+        }?;
+
+        return Some(format!(
+            "{} library for the API `{}`: {}",
+            api.api_type, api.name, library
+        ));
+    }
+
+    None
+}
+
+/// Packages imported by the code of the `codebase` which are not part of it
+/// nor of the standard library, for the languages whose imports map to
+/// packages.
+pub fn external_imports(
+    codebase: &BTreeMap<String, String>,
+    language: &Language,
+) -> BTreeSet<String> {
+    // Modules of the codebase, which are imported by their path or name
+    let local = codebase
+        .keys()
+        .flat_map(|path| {
+            let path = path.trim_start_matches('/');
+            let path = path.strip_prefix("src/").unwrap_or(path);
+
+            Path::new(path)
+                .iter()
+                .filter_map(|component| Path::new(component).file_stem())
+                .filter_map(|stem| stem.to_str())
+                .map(String::from)
+                .collect::<Vec<String>>()
+        })
+        .collect::<BTreeSet<String>>();
+
+    let mut packages = BTreeSet::new();
+
+    for code in codebase.values() {
+        for line in code.lines().map(str::trim) {
+            let imports = match language.language {
+                LanguageType::Rust => rust_import(line).into_iter().collect(),
+                LanguageType::Python => python_imports(line),
+                LanguageType::JavaScript | LanguageType::TypeScript => {
+                    js_import(line).into_iter().collect()
+                }
+                LanguageType::Go => go_import(line).into_iter().collect(),
+                _ => Vec::new(),
+            };
+
+            packages.extend(
+                imports
+                    .into_iter()
+                    .filter(|package| !local.contains(package)),
+            );
+        }
+    }
+
+    packages
+}
+
+fn rust_import(line: &str) -> Option<String> {
+    let path = line
+        .strip_prefix("use ")
+        .or_else(|| line.strip_prefix("pub use "))
+        .or_else(|| line.strip_prefix("extern crate "))?;
+
+    let name = path
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .next()?;
+
+    match name {
+        "" | "crate" | "self" | "super" | "std" | "core" | "alloc" => None,
+        name => Some(name.to_string()),
+    }
+}
+
+fn python_imports(line: &str) -> Vec<String> {
+    const STDLIB: [&str; 48] = [
+        "__future__",
+        "abc",
+        "argparse",
+        "asyncio",
+        "base64",
+        "collections",
+        "contextlib",
+        "copy",
+        "csv",
+        "dataclasses",
+        "datetime",
+        "decimal",
+        "enum",
+        "functools",
+        "glob",
+        "hashlib",
+        "heapq",
+        "http",
+        "importlib",
+        "inspect",
+        "io",
+        "itertools",
+        "json",
+        "logging",
+        "math",
+        "operator",
+        "os",
+        "pathlib",
+        "pickle",
+        "platform",
+        "queue",
+        "random",
+        "re",
+        "secrets",
+        "shutil",
+        "signal",
+        "socket",
+        "sqlite3",
+        "string",
+        "subprocess",
+        "sys",
+        "tempfile",
+        "threading",
+        "time",
+        "typing",
+        "unittest",
+        "urllib",
+        "uuid",
+    ];
+
+    // Modules of `from x import a, b` or `import x, y as z`
+    let modules: Vec<&str> = if let Some(rest) = line.strip_prefix("from ") {
+        rest.split_whitespace().take(1).collect()
+    } else if let Some(rest) = line.strip_prefix("import ") {
+        rest.split(',').map(str::trim).collect()
+    } else {
+        return Vec::new();
+    };
+
+    modules
+        .into_iter()
+        .filter_map(|module| module.split(['.', ' ']).next())
+        .filter(|name| !name.is_empty() && !STDLIB.contains(name))
+        .map(String::from)
+        .collect()
+}
+
+fn js_import(line: &str) -> Option<String> {
+    let after = |pattern: &str| line.split_once(pattern).map(|(_, rest)| rest);
+
+    // Clauses of imports and re-exports, possibly closing a multi-line list
+    let from = match line.starts_with("import ")
+        || line.starts_with("export ")
+        || line.starts_with('}')
+    {
+        true => line.rsplit_once("from ").map(|(_, rest)| rest),
+        false => None,
+    };
+
+    // Module specifier of `from "x"`, `import "x"`, `import("x")` or
+    // `require("x")`
+    let specifier = from
+        .and_then(quoted)
+        .or_else(|| line.strip_prefix("import ").and_then(quoted))
+        .or_else(|| after("import(").and_then(quoted))
+        .or_else(|| after("require(").and_then(quoted))?;
+
+    if specifier.starts_with('.')
+        || specifier.starts_with('/')
+        || specifier.starts_with("node:")
+    {
+        return None;
+    }
+
+    const BUILTINS: [&str; 12] = [
+        "assert",
+        "buffer",
+        "child_process",
+        "crypto",
+        "events",
+        "fs",
+        "http",
+        "https",
+        "os",
+        "path",
+        "stream",
+        "util",
+    ];
+
+    let mut segments = specifier.split('/');
+    let name = segments.next()?;
+
+    match name.starts_with('@') {
+        true => Some(format!("{}/{}", name, segments.next()?)),
+        false if BUILTINS.contains(&name) => None,
+        false => Some(name.to_string()),
+    }
+}
+
+/// Returns the string literal at the start of `code`, without its quotes.
+fn quoted(code: &str) -> Option<&str> {
+    let code = code.trim_start();
+    let quote = code
+        .chars()
+        .next()
+        .filter(|c| matches!(c, '"' | '\'' | '`'))?;
+
+    code[1..].split_once(quote).map(|(literal, _)| literal)
+}
+
+fn go_import(line: &str) -> Option<String> {
+    let line = line.strip_prefix("import ").unwrap_or(line);
+
+    // Import paths are quoted, and those of modules start with a domain
+    let path = line.split('"').nth(1)?;
+
+    match path.split('/').next()?.contains('.') {
+        true => Some(path.to_string()),
+        false => None,
+    }
+}
+
+/// Asks the LLM for the content of the configuration file of `task_params`,
+/// with dependencies inferred from the imports of the `codebase` and from
+/// the interfaces of the project. The `existing` file, if any, is updated
+/// rather than rewritten.
+pub async fn gen_config(
+    app_state: &AppData,
+    ai_params: &OpenAIParams,
+    task_params: &ConfigGenParams,
+    codebase: BTreeMap<String, String>,
+    existing: Option<&str>,
+    request_callback: &Function,
+) -> Result<String> {
+    let language = app_state.language_()?;
+
+    log(&format!(
+        "[INFO] Running `ConfigGen` Job: {}",
+        task_params.filename
+    ));
+
+    let templates = &app_state.prompts;
+
+    let mut prompts = Vec::new();
+
+    prompts.push((
+        OpenAIMsg::system(&templates.render(
+            "code_gen_system",
+            &json!({ "language": language.name() }),
+        )?),
+        Priority::Required,
+    ));
+
+    if let Some(specs) = &app_state.specs {
+        prompts.push((OpenAIMsg::user(specs), Priority::High));
+    }
+
+    if let Some(scaffold) = &app_state.scaffold {
+        prompts.push((OpenAIMsg::user(&scaffold.render()), Priority::Medium));
+    }
+
+    for interface in app_state.interfaces.values() {
+        let mut context = Vec::new();
+        interface.add_context(templates, &mut context)?;
+
+        prompts.extend(context.into_iter().map(|msg| (msg, Priority::Low)));
+    }
+
+    let dependencies = app_state
+        .interfaces
+        .values()
+        .filter_map(|interface| interface_dependency(interface, language))
+        .collect::<Vec<String>>();
+
+    prompts.push((
+        OpenAIMsg::user(&templates.render(
+            "config_gen",
+            &json!({
+                "language": language.name(),
+                "filename": task_params.filename,
+                "description": task_params.description,
+                "imports": external_imports(&codebase, language),
+                "dependencies": dependencies,
+                "existing": existing.filter(|config| !config.trim().is_empty()),
+            }),
+        )?),
+        Priority::Required,
+    ));
+
+    let prompts = ContextBudget::new(ai_params).fit(prompts)?;
+
+    let prompts = prompts.iter().collect::<Vec<&OpenAIMsg>>();
+
+    let chat = chat_raw(
+        request_callback,
+        &app_state.provider,
+        ai_params,
+        &prompts,
+        &[],
+    )
+    .await?;
+
+    let answer = &chat
+        .choices
+        .first()
+        .ok_or_else(|| anyhow!("LLM Respose seems to be empty :("))?
+        .message
+        .content;
+
+    let tag = Path::new(&task_params.filename)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("");
+
+    let mut config = extract_code(answer, tag);
+
+    if !config.ends_with('\n') {
+        config.push('\n');
+    }
+
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::app_data::interfaces::dbs::Database;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn infers_dependencies() {
+        let rust = Language::new(LanguageType::Rust);
+
+        let codebase = BTreeMap::from([
+            (
+                String::from("src/main.rs"),
+                String::from(
+                    "use std::env;\nuse actix_web::{web, App};\nuse crate::db;\nmod handlers;\nuse handlers::users;",
+                ),
+            ),
+            (
+                String::from("src/db.rs"),
+                String::from("use sqlx::PgPool;\nuse serde_json::json;"),
+            ),
+            (String::from("src/handlers/users.rs"), String::new()),
+        ]);
+
+        assert_eq!(
+            external_imports(&codebase, &rust),
+            BTreeSet::from([
+                String::from("actix_web"),
+                String::from("serde_json"),
+                String::from("sqlx"),
+            ])
+        );
+
+        let python = Language::new(LanguageType::Python);
+        let codebase = BTreeMap::from([(
+            String::from("app/main.py"),
+            String::from(
+                "import os, sys\nfrom fastapi import FastAPI\nfrom app.models import User\nimport numpy as np\nimport requests, yaml as pyyaml\nfrom . import utils",
+            ),
+        )]);
+
+        assert_eq!(
+            external_imports(&codebase, &python),
+            BTreeSet::from([
+                String::from("fastapi"),
+                String::from("numpy"),
+                String::from("requests"),
+                String::from("yaml"),
+            ])
+        );
+
+        let typescript = Language::new(LanguageType::TypeScript);
+        let codebase = BTreeMap::from([(
+            String::from("index.ts"),
+            String::from(
+                "import express from \"express\";\nimport { Pool } from 'pg';\nimport * as fs from \"fs\";\nimport { router } from \"./routes\";\nimport \"reflect-metadata\";\nimport {\n  z,\n} from \"zod\";\nconst s3 = require(\"@aws-sdk/client-s3/dist\");\nexport const API_URL = \"https://api.example.com\";\nexport { handler } from \"./handler\";",
+            ),
+        )]);
+
+        assert_eq!(
+            external_imports(&codebase, &typescript),
+            BTreeSet::from([
+                String::from("@aws-sdk/client-s3"),
+                String::from("express"),
+                String::from("pg"),
+                String::from("reflect-metadata"),
+                String::from("zod"),
+            ])
+        );
+
+        let db = Interface::new_db(Database::new_(
+            String::from("orders"),
+            DbType::PostgreSql,
+            BTreeMap::new(),
+        ));
+
+        assert!(interface_dependency(&db, &rust)
+            .unwrap()
+            .contains("`sqlx` with the `postgres` feature"));
+    }
+
+    #[wasm_bindgen_test]
+    fn places_config_files() {
+        assert!(is_config_file("Cargo.toml"));
+        assert!(is_config_file("Gemfile"));
+        assert!(!is_config_file("main.rs"));
+
+        assert_eq!(
+            ConfigGenParams::for_scaffold_file("Cargo.toml", "").filename,
+            "Cargo.toml"
+        );
+        assert_eq!(
+            ConfigGenParams::for_scaffold_file("config/app.yaml", "").filename,
+            "src/config/app.yaml"
+        );
+    }
+}
@@ -0,0 +1,409 @@
+use anyhow::{anyhow, Result};
+use js_sys::{Function, JsString};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{collections::BTreeMap, path::Path};
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::{
+    models::app_data::{
+        interfaces::AsContext,
+        language::{Language, LanguageType},
+        scaffold::{Scaffold, ScaffoldEntry},
+        AppData,
+    },
+    openai::{
+        budget::{ContextBudget, Priority},
+        msg::OpenAIMsg,
+        params::OpenAIParams,
+        request::chat_raw,
+    },
+    utils::log,
+};
+
+/// Kind of documentation page written by a `DocGen` task.
+#[wasm_bindgen]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub enum DocKind {
+    /// README of the project
+    Readme,
+    /// Documentation of a folder of the `src` folder
+    Module,
+    /// Reference of an interface of the project, e.g. an API
+    ApiReference,
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DocGenParams {
+    pub(crate) kind: DocKind,
+    /// Markdown file of the docs, relative to the root of the project
+    pub(crate) filename: String,
+    /// Folder documented by `Module` docs, relative to the `src` folder, or
+    /// name of the interface documented by `ApiReference` docs
+    pub(crate) subject: Option<String>,
+}
+
+#[wasm_bindgen]
+impl DocGenParams {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        kind: DocKind,
+        filename: String,
+        subject: Option<String>,
+    ) -> DocGenParams {
+        DocGenParams {
+            kind,
+            filename,
+            subject,
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn kind(&self) -> DocKind {
+        self.kind
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn filename(&self) -> JsString {
+        self.filename.clone().into()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn subject(&self) -> Option<JsString> {
+        self.subject.clone().map(JsString::from)
+    }
+}
+
+impl DocGenParams {
+    /// Creates the parameters to document `subject`, writing the docs where
+    /// the conventions of the language expect them. Returns `None` if the
+    /// `subject` is missing for `Module` or `ApiReference` docs.
+    pub fn for_subject(
+        kind: DocKind,
+        subject: Option<&str>,
+        language: &Language,
+    ) -> Option<Self> {
+        let filename = doc_filename(kind, subject, language)?;
+
+        Some(DocGenParams {
+            kind,
+            filename,
+            subject: subject.map(String::from),
+        })
+    }
+
+    /// Describes the docs in the task pool.
+    pub fn description(&self) -> String {
+        match (self.kind, &self.subject) {
+            (DocKind::Module, Some(folder)) => {
+                format!("Documentation of the `{}` module", folder)
+            }
+            (DocKind::ApiReference, Some(interface)) => {
+                format!("API reference of {}", interface)
+            }
+            _ => String::from("README of the project"),
+        }
+    }
+}
+
+/// Path of the docs of `subject`, relative to the root of the project.
+///
+/// Languages whose ecosystem renders the README of folders, such as Go or
+/// JavaScript, document modules next to their code, whereas the others
+/// gather them in a `docs` folder, as expected by MkDocs or mdBook.
+pub fn doc_filename(
+    kind: DocKind,
+    subject: Option<&str>,
+    language: &Language,
+) -> Option<String> {
+    let path = match kind {
+        DocKind::Readme => return Some(String::from("README.md")),
+        DocKind::Module => {
+            let folder = subject?.trim_matches('/');
+            let folder = folder.strip_prefix("src/").unwrap_or(folder);
+
+            match language.language {
+                LanguageType::Go
+                | LanguageType::JavaScript
+                | LanguageType::TypeScript
+                | LanguageType::PHP
+                | LanguageType::Ruby
+                | LanguageType::Lua
+                | LanguageType::Perl
+                | LanguageType::Shell
+                | LanguageType::C
+                | LanguageType::CPlusPLus => {
+                    Path::new("src").join(folder).join("README.md")
+                }
+                _ => Path::new("docs/modules").join(format!("{}.md", folder)),
+            }
+        }
+        DocKind::ApiReference => {
+            Path::new("docs/api").join(format!("{}.md", slug(subject?)))
+        }
+    };
+
+    path.to_str().map(String::from)
+}
+
+/// Top-level folders of the `src` folder of the scaffold, each of them
+/// being documented as a module.
+pub fn scaffold_modules(scaffold: &Scaffold) -> Vec<String> {
+    scaffold
+        .folders()
+        .into_iter()
+        .filter_map(|folder| {
+            let module = folder.strip_prefix("src/")?;
+            (!module.contains('/')).then(|| module.to_string())
+        })
+        .collect()
+}
+
+/// Asks the LLM for the Markdown docs of `task_params`. The `existing` docs,
+/// if any, are updated rather than rewritten, such that the task can be run
+/// again when the docs are stale.
+pub async fn gen_docs(
+    app_state: &AppData,
+    ai_params: &OpenAIParams,
+    task_params: &DocGenParams,
+    codebase: BTreeMap<String, String>,
+    existing: Option<&str>,
+    request_callback: &Function,
+) -> Result<String> {
+    let language = app_state.language_()?;
+
+    log(&format!(
+        "[INFO] Running `DocGen` Job: {}",
+        task_params.filename
+    ));
+
+    let templates = &app_state.prompts;
+
+    let mut prompts = Vec::new();
+
+    prompts.push((
+        OpenAIMsg::system(&templates.render(
+            "code_gen_system",
+            &json!({ "language": language.name() }),
+        )?),
+        Priority::Required,
+    ));
+
+    if let Some(specs) = &app_state.specs {
+        prompts.push((OpenAIMsg::user(specs), Priority::High));
+    }
+
+    // Files documented, along with their description in the scaffold
+    let folder = match task_params.kind {
+        DocKind::Module => task_params.subject.as_deref(),
+        _ => None,
+    };
+
+    let normalise = |path: &str| {
+        let path = path.trim_matches('/');
+        path.strip_prefix("src/").unwrap_or(path).to_string()
+    };
+
+    let in_scope = |path: &str| {
+        folder.is_none_or(|folder| {
+            Path::new(&normalise(path)).starts_with(normalise(folder))
+        })
+    };
+
+    let descriptions = app_state
+        .scaffold
+        .as_ref()
+        .map(Scaffold::files)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|file| in_scope(&file.path))
+        .collect::<Vec<ScaffoldEntry>>();
+
+    match task_params.kind {
+        DocKind::ApiReference => {
+            let name = task_params
+                .subject
+                .as_ref()
+                .ok_or_else(|| anyhow!("No interface to document"))?;
+
+            let interface =
+                app_state.interfaces.get(name).ok_or_else(|| {
+                    anyhow!("The interface {} does not exist", name)
+                })?;
+
+            let mut context = Vec::new();
+            interface.add_context(templates, &mut context)?;
+
+            prompts
+                .extend(context.into_iter().map(|msg| (msg, Priority::High)));
+        }
+        _ => {
+            for interface in app_state.interfaces.values() {
+                let mut context = Vec::new();
+                interface.add_context(templates, &mut context)?;
+
+                prompts.extend(
+                    context.into_iter().map(|msg| (msg, Priority::Low)),
+                );
+            }
+
+            // The code itself is only sent for modules, the README
+            // building upon the descriptions of the files
+            if folder.is_some() {
+                for (path, content) in codebase.iter() {
+                    if in_scope(path) {
+                        prompts.push((
+                            OpenAIMsg::user(&format!(
+                                "File `{}`:\n```\n{}\n```",
+                                path, content
+                            )),
+                            Priority::Medium,
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    prompts.push((
+        OpenAIMsg::user(&templates.render(
+            "doc_gen",
+            &json!({
+                "language": language.name(),
+                "filename": task_params.filename,
+                "readme": task_params.kind == DocKind::Readme,
+                "module": task_params.kind == DocKind::Module,
+                "api_reference": task_params.kind == DocKind::ApiReference,
+                "subject": task_params.subject,
+                "files": descriptions
+                    .iter()
+                    .map(|file| json!({
+                        "path": file.path,
+                        "description": file.description,
+                    }))
+                    .collect::<Vec<Value>>(),
+                "existing": existing.filter(|docs| !docs.trim().is_empty()),
+            }),
+        )?),
+        Priority::Required,
+    ));
+
+    let prompts = ContextBudget::new(ai_params).fit(prompts)?;
+
+    let prompts = prompts.iter().collect::<Vec<&OpenAIMsg>>();
+
+    let chat = chat_raw(
+        request_callback,
+        &app_state.provider,
+        ai_params,
+        &prompts,
+        &[],
+    )
+    .await?;
+
+    let answer = &chat
+        .choices
+        .first()
+        .ok_or_else(|| anyhow!("LLM Respose seems to be empty :("))?
+        .message
+        .content;
+
+    Ok(strip_markdown_fence(answer))
+}
+
+/// Removes the code block wrapping the whole answer, if any. Code blocks
+/// within the docs are kept.
+fn strip_markdown_fence(answer: &str) -> String {
+    let trimmed = answer.trim();
+
+    let unwrapped = trimmed
+        .strip_prefix("```markdown")
+        .or_else(|| trimmed.strip_prefix("```md"))
+        .and_then(|docs| docs.strip_suffix("```"));
+
+    let mut docs = unwrapped.unwrap_or(trimmed).trim().to_string();
+    docs.push('\n');
+
+    docs
+}
+
+fn slug(name: &str) -> String {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<String>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn places_docs_by_language() {
+        let rust = Language::new(LanguageType::Rust);
+        let go = Language::new(LanguageType::Go);
+
+        let expected = [
+            (DocKind::Readme, None, &rust, "README.md"),
+            (
+                DocKind::Module,
+                Some("handlers"),
+                &rust,
+                "docs/modules/handlers.md",
+            ),
+            (
+                DocKind::Module,
+                Some("src/store/"),
+                &go,
+                "src/store/README.md",
+            ),
+            (
+                DocKind::ApiReference,
+                Some("Payments API"),
+                &go,
+                "docs/api/payments-api.md",
+            ),
+        ];
+
+        for (kind, subject, language, filename) in expected {
+            let params =
+                DocGenParams::for_subject(kind, subject, language).unwrap();
+
+            assert_eq!(params.filename, filename);
+        }
+
+        assert!(
+            DocGenParams::for_subject(DocKind::Module, None, &rust).is_none()
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn lists_modules_and_unwraps_docs() {
+        let scaffold = Scaffold::from_value(&json!({
+            "src": {
+                "main.rs": "Entry point",
+                "handlers": { "user.rs": "User endpoints" },
+                "models": { "user.rs": "User model", "db": {} },
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(scaffold_modules(&scaffold), vec!["handlers", "models"]);
+
+        let answer = "```markdown\n# Title\n\n```sh\nmake\n```\n```";
+        assert_eq!(
+            strip_markdown_fence(answer),
+            "# Title\n\n```sh\nmake\n```\n"
+        );
+
+        let answer = "# Title\n\n```sh\nmake\n```";
+        assert_eq!(
+            strip_markdown_fence(answer),
+            "# Title\n\n```sh\nmake\n```\n"
+        );
+    }
+}
@@ -0,0 +1,261 @@
+use anyhow::{anyhow, Result};
+use js_sys::{Function, JsString};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::{
+    models::app_data::{
+        language::{Language, LanguageType},
+        AppData,
+    },
+    openai::{msg::OpenAIMsg, params::OpenAIParams, request::chat_raw},
+    prompts::code_gen::LanguageTemplate,
+    utils::log,
+    validation::{extract_code, Syntax},
+};
+
+#[wasm_bindgen]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TestGenParams {
+    /// File under test, relative to the `src` folder
+    pub(crate) filename: String,
+    /// File of the tests, relative to the root of the project. Tests are
+    /// appended to the file under test if it is the same file.
+    pub(crate) test_filename: String,
+}
+
+#[wasm_bindgen]
+impl TestGenParams {
+    #[wasm_bindgen(constructor)]
+    pub fn new(filename: String, test_filename: String) -> TestGenParams {
+        TestGenParams {
+            filename,
+            test_filename,
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn filename(&self) -> JsString {
+        self.filename.clone().into()
+    }
+
+    #[wasm_bindgen(getter, js_name = testFilename)]
+    pub fn test_filename(&self) -> JsString {
+        self.test_filename.clone().into()
+    }
+
+    /// Whether the tests live in the file under test, as in Rust.
+    #[wasm_bindgen(js_name = isInline)]
+    pub fn is_inline(&self) -> bool {
+        Path::new("src").join(&self.filename) == Path::new(&self.test_filename)
+    }
+}
+
+impl TestGenParams {
+    /// Creates the parameters to test `filename`, placing the tests where
+    /// the conventions of the language expect them. Returns `None` for
+    /// languages without unit tests, such as HTML.
+    pub fn for_file(filename: &str, language: &Language) -> Option<Self> {
+        let test_filename = test_filename(filename, language)?;
+
+        Some(TestGenParams {
+            filename: String::from(filename),
+            test_filename,
+        })
+    }
+}
+
+/// Path of the tests of the file `filename`, relative to the root of the
+/// project, following the conventions of the language. Source files are
+/// relative to the `src` folder, and test folders mirror their structure.
+pub fn test_filename(filename: &str, language: &Language) -> Option<String> {
+    let path = Path::new(filename);
+    let stem = path.file_stem()?.to_str()?;
+    let folder = path.parent().unwrap_or(Path::new(""));
+
+    let extension = match path.extension().and_then(|ext| ext.to_str()) {
+        Some(extension) => format!(".{}", extension),
+        None => language
+            .language
+            .default_extension()
+            .map(String::from)
+            .unwrap_or_default(),
+    };
+
+    let in_src = |name: String| Path::new("src").join(folder).join(name);
+    let in_tests = |tests: &str, name: String| -> PathBuf {
+        Path::new(tests).join(folder).join(name)
+    };
+
+    let test_path = match language.language {
+        // Unit tests live in a `#[cfg(test)]` module of the file
+        LanguageType::Rust => Path::new("src").join(path),
+        LanguageType::Go => in_src(format!("{}_test{}", stem, extension)),
+        LanguageType::JavaScript | LanguageType::TypeScript => {
+            in_src(format!("{}.test{}", stem, extension))
+        }
+        LanguageType::Java | LanguageType::Kotlin | LanguageType::Scala => {
+            let language_folder = match language.language {
+                LanguageType::Java => "java",
+                LanguageType::Kotlin => "kotlin",
+                _ => "scala",
+            };
+
+            // Maven layout, i.e. `src/main/java` and `src/test/java`
+            let main = Path::new("main").join(language_folder);
+            let package = folder.strip_prefix(&main).unwrap_or(folder);
+
+            Path::new("src/test")
+                .join(language_folder)
+                .join(package)
+                .join(format!("{}Test{}", stem, extension))
+        }
+        LanguageType::Python => {
+            in_tests("tests", format!("test_{}{}", stem, extension))
+        }
+        LanguageType::Ruby | LanguageType::Lua => {
+            in_tests("spec", format!("{}_spec{}", stem, extension))
+        }
+        LanguageType::PHP | LanguageType::MATLAB => {
+            in_tests("tests", format!("{}Test{}", stem, extension))
+        }
+        LanguageType::CSharp => {
+            in_tests("tests", format!("{}Tests{}", stem, extension))
+        }
+        LanguageType::Swift | LanguageType::ObjectiveC => {
+            in_tests("Tests", format!("{}Tests{}", stem, extension))
+        }
+        LanguageType::C | LanguageType::CPlusPLus => {
+            in_tests("tests", format!("test_{}{}", stem, extension))
+        }
+        LanguageType::Haskell => {
+            in_tests("test", format!("{}Spec{}", stem, extension))
+        }
+        LanguageType::Perl => in_tests("t", format!("{}.t", stem)),
+        LanguageType::Shell => in_tests("test", format!("{}.bats", stem)),
+        LanguageType::R => {
+            in_tests("tests/testthat", format!("test-{}{}", stem, extension))
+        }
+        LanguageType::HTML => return None,
+        LanguageType::Custom => {
+            in_tests("tests", format!("{}_test{}", stem, extension))
+        }
+    };
+
+    test_path.to_str().map(String::from)
+}
+
+/// Asks the LLM for unit tests of the file of `task_params`, whose current
+/// content is `content`, and returns their code.
+pub async fn gen_tests(
+    app_state: &AppData,
+    ai_params: &OpenAIParams,
+    task_params: &TestGenParams,
+    content: &str,
+    request_callback: &Function,
+) -> Result<String> {
+    let language = app_state.language_()?;
+
+    log(&format!(
+        "[INFO] Running `TestGen` Job: {}",
+        task_params.filename
+    ));
+
+    let template = LanguageTemplate::new(language);
+    let templates = &app_state.prompts;
+
+    let mut prompts = Vec::new();
+
+    prompts.push(OpenAIMsg::system(&templates.render(
+        "code_gen_system",
+        &json!({ "language": language.name() }),
+    )?));
+
+    if let Some(specs) = &app_state.specs {
+        prompts.push(OpenAIMsg::user(specs));
+    }
+
+    prompts.push(OpenAIMsg::user(&templates.render(
+        "test_gen",
+        &json!({
+            "language": language.name(),
+            "filename": task_params.filename,
+            "test_filename": task_params.test_filename,
+            "inline": task_params.is_inline(),
+            "content": content,
+            "test_framework": template.test_framework,
+            "module_system": template.module_system,
+        }),
+    )?));
+
+    let prompts = prompts.iter().collect::<Vec<&OpenAIMsg>>();
+
+    let chat = chat_raw(
+        request_callback,
+        &app_state.provider,
+        ai_params,
+        &prompts,
+        &[],
+    )
+    .await?;
+
+    let answer = &chat
+        .choices
+        .first()
+        .ok_or_else(|| anyhow!("LLM Respose seems to be empty :("))?
+        .message
+        .content;
+
+    let tag = Syntax::new(&task_params.test_filename, language)
+        .map(|syntax| syntax.tag().to_string())
+        .unwrap_or_else(|| language.name().to_lowercase());
+
+    Ok(extract_code(answer, &tag))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn places_tests_by_language() {
+        let expected = [
+            (LanguageType::Rust, "models/user.rs", "src/models/user.rs"),
+            (LanguageType::Rust, "main", "src/main"),
+            (
+                LanguageType::Python,
+                "app/user.py",
+                "tests/app/test_user.py",
+            ),
+            (LanguageType::TypeScript, "user.ts", "src/user.test.ts"),
+            (LanguageType::Go, "store/user.go", "src/store/user_test.go"),
+            (
+                LanguageType::Java,
+                "main/java/com/acme/User.java",
+                "src/test/java/com/acme/UserTest.java",
+            ),
+            (LanguageType::Ruby, "user.rb", "spec/user_spec.rb"),
+        ];
+
+        for (language_type, filename, test_filename) in expected {
+            let params = TestGenParams::for_file(
+                filename,
+                &Language::new(language_type),
+            )
+            .unwrap();
+
+            assert_eq!(params.test_filename, test_filename);
+            assert_eq!(
+                params.is_inline(),
+                matches!(language_type, LanguageType::Rust)
+            );
+        }
+
+        let html = Language::new(LanguageType::HTML);
+        assert!(TestGenParams::for_file("index.html", &html).is_none());
+    }
+}
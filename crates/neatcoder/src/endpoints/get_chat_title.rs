@@ -1,42 +1,34 @@
 use anyhow::{anyhow, Result};
 use js_sys::Function;
-
-use crate::openai::{
-    msg::{GptRole, OpenAIMsg},
-    params::{OpenAIModels, OpenAIParams},
-    request::chat_raw,
+use serde_json::json;
+
+use crate::{
+    openai::{
+        msg::OpenAIMsg,
+        params::{OpenAIModels, OpenAIParams},
+        request::chat_raw,
+    },
+    prompts::PromptTemplates,
+    providers::LlmProvider,
 };
 
 pub async fn get_chat_title(
+    provider: &dyn LlmProvider,
     msg: &str,
+    templates: &PromptTemplates,
     request_callback: &Function,
 ) -> Result<String> {
     let mut prompts = Vec::new();
 
-    prompts.push(OpenAIMsg {
-        role: GptRole::System,
-        content: String::from(
-            "
-- Context: Briefly describe the key topics or themes of the chat.
-- Title Specifications: The title should be concise, and not exceed 6 words. It should reflect the tone of the chat (e.g., professional, casual, informative, provocative, etc.).
-- Output: Provide a title that encapsulates the main focus of the chat.
-            ",
-        ),
-    });
+    let context = json!({ "msg": msg });
 
-    let main_prompt = format!(
-        "
-Your task is to create a title for the following prompt:
-\"\"\"{}\"\"\"
+    prompts.push(OpenAIMsg::system(
+        &templates.render("chat_title_system", &context)?,
+    ));
 
-The title of the prompt is:",
-        msg
-    );
+    let main_prompt = templates.render("chat_title", &context)?;
 
-    prompts.push(OpenAIMsg {
-        role: GptRole::User,
-        content: main_prompt,
-    });
+    prompts.push(OpenAIMsg::user(&main_prompt));
 
     let prompts = prompts.iter().map(|x| x).collect::<Vec<&OpenAIMsg>>();
 
@@ -44,7 +36,7 @@ The title of the prompt is:",
         OpenAIParams::empty(OpenAIModels::Gpt35Turbo).max_tokens(15);
 
     let chat =
-        chat_raw(request_callback, &ai_params, &prompts, &[], &[]).await?;
+        chat_raw(request_callback, provider, &ai_params, &prompts, &[]).await?;
 
     let mut answer = chat
         .choices
@@ -2,3 +2,10 @@
 pub mod scaffold_project;
 pub mod stream_code;
 pub mod get_chat_title;
+pub mod edit_code;
+pub mod gen_api_stubs;
+pub mod gen_config;
+pub mod gen_docs;
+pub mod gen_tests;
+pub mod repair_code;
+pub mod review_code;
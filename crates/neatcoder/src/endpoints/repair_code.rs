@@ -0,0 +1,187 @@
+use anyhow::{anyhow, Result};
+use js_sys::{Function, JsString};
+use parser::parser::graphql::GraphQl;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::{
+    models::app_data::{interfaces::sql_schema::SqlSchema, language::Language},
+    openai::{msg::OpenAIMsg, params::OpenAIParams, request::chat_raw},
+    prompts::PromptTemplates,
+    providers::LlmProvider,
+    utils::log,
+    validation::{check_queries, check_resolvers, extract_code, Syntax},
+};
+
+/// Outcome of the syntax validation of a generated file.
+#[wasm_bindgen]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeValidation {
+    /// Code of the file, after the repair rounds if any
+    pub(crate) code: String,
+    /// Parser error of the final code, if it is still invalid
+    pub(crate) diagnostic: Option<String>,
+    /// Number of times the LLM was asked to repair the code
+    pub(crate) rounds: u32,
+}
+
+#[wasm_bindgen]
+impl CodeValidation {
+    #[wasm_bindgen(getter)]
+    pub fn code(&self) -> JsString {
+        self.code.clone().into()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn diagnostic(&self) -> Option<JsString> {
+        self.diagnostic.clone().map(JsString::from)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn rounds(&self) -> u32 {
+        self.rounds
+    }
+
+    #[wasm_bindgen(js_name = isValid)]
+    pub fn is_valid(&self) -> bool {
+        self.diagnostic.is_none()
+    }
+}
+
+/// Resolvers which the generated code must implement, i.e. the fields of
+/// the `roots` types of a GraphQL `schema`.
+pub struct ResolverCheck<'a> {
+    pub schema: &'a GraphQl,
+    pub roots: Vec<&'a str>,
+    /// Operations of the schema, shown to the LLM along with the missing
+    /// resolvers
+    pub operations: &'a str,
+}
+
+/// Parses the generated `code` of the file `filename` and, if it is invalid,
+/// prompts the LLM with the parser error to fix it, for at most `max_rounds`
+/// rounds. If a `schema` is given, the queries of the code are also checked
+/// against the tables of the databases, and if `resolvers` are given, the
+/// code is checked to resolve every field of the GraphQL schema. Files which
+/// cannot be checked are considered valid.
+#[allow(clippy::too_many_arguments)]
+pub async fn repair_code(
+    provider: &dyn LlmProvider,
+    ai_params: &OpenAIParams,
+    templates: &PromptTemplates,
+    language: &Language,
+    filename: &str,
+    code: &str,
+    schema: Option<&SqlSchema>,
+    resolvers: Option<&ResolverCheck<'_>>,
+    max_rounds: u32,
+    request_callback: &Function,
+) -> Result<CodeValidation> {
+    let syntax = Syntax::new(filename, language);
+    let schema = schema.filter(|schema| !schema.is_empty());
+
+    let tag = match syntax {
+        Some(syntax) => syntax.tag().to_string(),
+        None if schema.is_some() || resolvers.is_some() => {
+            language.name().to_lowercase()
+        }
+        None => {
+            return Ok(CodeValidation {
+                code: String::from(code),
+                diagnostic: None,
+                rounds: 0,
+            })
+        }
+    };
+
+    let mut code = extract_code(code, &tag);
+    let mut rounds = 0;
+
+    loop {
+        let syntax_error = syntax.and_then(|syntax| syntax.check(&code).err());
+
+        // Queries and resolvers are only checked once the code parses
+        let query_issues = match (&syntax_error, schema) {
+            (None, Some(schema)) => check_queries(schema, &code, syntax),
+            _ => Vec::new(),
+        };
+
+        let resolver_issues = match (&syntax_error, resolvers) {
+            (None, Some(check)) if query_issues.is_empty() => {
+                check_resolvers(check.schema, &check.roots, &code)
+            }
+            _ => Vec::new(),
+        };
+
+        let error = match syntax_error {
+            Some(error) => error,
+            None if !query_issues.is_empty() => query_issues.join("\n"),
+            None if !resolver_issues.is_empty() => resolver_issues.join("\n"),
+            None => {
+                return Ok(CodeValidation {
+                    code,
+                    diagnostic: None,
+                    rounds,
+                })
+            }
+        };
+
+        if rounds >= max_rounds {
+            return Ok(CodeValidation {
+                code,
+                diagnostic: Some(error),
+                rounds,
+            });
+        }
+
+        rounds += 1;
+
+        log(&format!(
+            "[INFO] Repairing `{}` ({}/{}): {}",
+            filename, rounds, max_rounds, error
+        ));
+
+        let mut prompts = Vec::new();
+
+        prompts.push(OpenAIMsg::system(&templates.render(
+            "code_gen_system",
+            &json!({ "language": language.name() }),
+        )?));
+
+        prompts.push(OpenAIMsg::user(&templates.render(
+            "code_repair",
+            &json!({
+                "filename": filename,
+                "syntax": syntax
+                    .map(|syntax| syntax.to_string())
+                    .unwrap_or_else(|| language.name()),
+                "tag": tag,
+                "error": error,
+                "queries": !query_issues.is_empty(),
+                "resolvers": !resolver_issues.is_empty(),
+                "schema": match resolver_issues.is_empty() {
+                    true => schema.map(SqlSchema::summary),
+                    false => resolvers.map(|check| check.operations.to_string()),
+                },
+                "code": code,
+            }),
+        )?));
+
+        let prompts = prompts.iter().collect::<Vec<&OpenAIMsg>>();
+
+        let chat =
+            chat_raw(request_callback, provider, ai_params, &prompts, &[])
+                .await?;
+
+        let answer = &chat
+            .choices
+            .first()
+            .ok_or_else(|| anyhow!("LLM Respose seems to be empty :("))?
+            .message
+            .content;
+
+        code = extract_code(answer, &tag);
+    }
+}
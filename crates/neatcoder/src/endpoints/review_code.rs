@@ -0,0 +1,303 @@
+use anyhow::{anyhow, Result};
+use js_sys::{Function, JsString};
+use parser::parser::json::AsJson;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::{
+    models::app_data::{interfaces::AsContext, AppData},
+    openai::{msg::OpenAIMsg, params::OpenAIParams, request::chat_raw},
+    utils::log,
+};
+
+#[wasm_bindgen]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewParams {
+    /// File to review, relative to the `src` folder
+    pub(crate) filename: String,
+    /// Whether only the uncommitted changes to the file are reviewed
+    #[serde(default)]
+    pub(crate) changes_only: bool,
+}
+
+#[wasm_bindgen]
+impl ReviewParams {
+    #[wasm_bindgen(constructor)]
+    pub fn new(filename: String, changes_only: bool) -> ReviewParams {
+        ReviewParams {
+            filename,
+            changes_only,
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn filename(&self) -> JsString {
+        self.filename.clone().into()
+    }
+
+    #[wasm_bindgen(getter, js_name = changesOnly)]
+    pub fn changes_only(&self) -> bool {
+        self.changes_only
+    }
+}
+
+/// Severity of a review finding, mirroring the severities of the
+/// diagnostics of the editor. The aliases cover the severity scales LLMs
+/// tend to fall back to.
+#[wasm_bindgen]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    #[serde(alias = "critical", alias = "high", alias = "major")]
+    Error,
+    #[serde(alias = "warn", alias = "medium", alias = "moderate")]
+    Warning,
+    #[serde(alias = "information", alias = "low", alias = "minor")]
+    Info,
+    #[serde(alias = "suggestion", alias = "nit")]
+    Hint,
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FindingCategory {
+    Bug,
+    Security,
+    Performance,
+    Maintainability,
+    Style,
+    Documentation,
+    #[serde(other)]
+    Other,
+}
+
+/// Issue raised by the LLM when reviewing a file. Lines are 1-based and
+/// inclusive.
+#[wasm_bindgen]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewFinding {
+    pub severity: Severity,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub category: FindingCategory,
+    pub(crate) message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) suggested_fix: Option<String>,
+}
+
+#[wasm_bindgen]
+impl ReviewFinding {
+    #[wasm_bindgen(getter)]
+    pub fn message(&self) -> JsString {
+        self.message.clone().into()
+    }
+
+    #[wasm_bindgen(getter, js_name = suggestedFix)]
+    pub fn suggested_fix(&self) -> Option<JsString> {
+        self.suggested_fix.clone().map(JsString::from)
+    }
+}
+
+/// Asks the LLM to review the file of `task_params`, whose current content is
+/// `content`. If `diff` is given, the review focuses on the changes of the
+/// diff, the file being sent as context.
+pub async fn review_code(
+    app_state: &AppData,
+    ai_params: &OpenAIParams,
+    task_params: &ReviewParams,
+    content: &str,
+    diff: Option<&str>,
+    request_callback: &Function,
+) -> Result<Vec<ReviewFinding>> {
+    let language = app_state.language_()?;
+
+    log(&format!(
+        "[INFO] Running `Review` Job: {}",
+        task_params.filename
+    ));
+
+    let templates = &app_state.prompts;
+
+    let mut prompts = Vec::new();
+
+    prompts.push(OpenAIMsg::system(&templates.render(
+        "code_review_system",
+        &json!({ "language": language.name() }),
+    )?));
+
+    if let Some(specs) = &app_state.specs {
+        prompts.push(OpenAIMsg::user(specs));
+    }
+
+    for interface in app_state.interfaces.values() {
+        interface.add_context(templates, &mut prompts)?;
+    }
+
+    prompts.push(OpenAIMsg::user(&templates.render(
+        "code_review",
+        &json!({
+            "language": language.name(),
+            "filename": task_params.filename,
+            "content": number_lines(content),
+            "diff": diff.filter(|diff| !diff.trim().is_empty()),
+        }),
+    )?));
+
+    let prompts = prompts.iter().collect::<Vec<&OpenAIMsg>>();
+
+    let chat = chat_raw(
+        request_callback,
+        &app_state.provider,
+        ai_params,
+        &prompts,
+        &[],
+    )
+    .await?;
+
+    let answer = &chat
+        .choices
+        .first()
+        .ok_or_else(|| anyhow!("LLM Respose seems to be empty :("))?
+        .message
+        .content;
+
+    parse_findings(answer, content.lines().count())
+}
+
+/// Prefixes each line of `content` with its number, such that the LLM can
+/// refer to line ranges accurately.
+fn number_lines(content: &str) -> String {
+    content
+        .lines()
+        .enumerate()
+        .map(|(i, line)| format!("{:>4} | {}", i + 1, line))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Parses the findings of the JSON block of `answer`, which is either an
+/// array of findings or an object with a `findings` array. Line ranges are
+/// clamped to the `line_count` lines of the file, and findings which cannot
+/// be parsed, e.g. because of an unknown severity, are left out rather than
+/// failing the whole review.
+pub fn parse_findings(
+    answer: &str,
+    line_count: usize,
+) -> Result<Vec<ReviewFinding>> {
+    let json = answer
+        .strip_json()
+        .map_err(|e| anyhow!("Failed to parse the review: {}", e))?;
+
+    let findings = match json {
+        Value::Object(mut object) => object
+            .remove("findings")
+            .ok_or_else(|| anyhow!("The review has no `findings` field"))?,
+        json => json,
+    };
+
+    let findings = match findings {
+        Value::Array(findings) => findings,
+        _ => {
+            return Err(anyhow!("The findings of the review are not an array"))
+        }
+    };
+
+    let last_line = line_count.max(1);
+
+    let findings = findings
+        .into_iter()
+        .filter_map(|mut finding| {
+            for field in ["severity", "category"] {
+                if let Some(Value::String(value)) = finding.get_mut(field) {
+                    *value = value.to_lowercase();
+                }
+            }
+
+            serde_json::from_value::<ReviewFinding>(finding).ok()
+        })
+        .map(|mut finding| {
+            finding.start_line = finding.start_line.clamp(1, last_line);
+            finding.end_line =
+                finding.end_line.clamp(finding.start_line, last_line);
+            finding
+        })
+        .collect();
+
+    Ok(findings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn parses_findings() -> Result<()> {
+        let answer = r#"Here is my review:
+```json
+[
+    {
+        "severity": "error",
+        "startLine": 3,
+        "endLine": 1,
+        "category": "bug",
+        "message": "Division by zero",
+        "suggestedFix": "Check that `b` is not zero"
+    },
+    {
+        "severity": "hint",
+        "startLine": 40,
+        "endLine": 42,
+        "category": "naming",
+        "message": "Unclear name"
+    }
+]
+```"#;
+
+        let findings = parse_findings(answer, 10)?;
+
+        assert_eq!(findings.len(), 2);
+
+        assert_eq!(findings[0].severity, Severity::Error);
+        assert_eq!(findings[0].category, FindingCategory::Bug);
+        assert_eq!((findings[0].start_line, findings[0].end_line), (3, 3));
+        assert_eq!(
+            findings[0].suggested_fix.as_deref(),
+            Some("Check that `b` is not zero")
+        );
+
+        // Unknown categories and lines out of the file are tolerated
+        assert_eq!(findings[1].category, FindingCategory::Other);
+        assert_eq!((findings[1].start_line, findings[1].end_line), (10, 10));
+        assert_eq!(findings[1].suggested_fix, None);
+
+        let answer = "```json\n{\"findings\": []}\n```";
+        assert!(parse_findings(answer, 10)?.is_empty());
+
+        // Other severity scales are mapped, and invalid findings left out
+        let answer = r#"```json
+{
+    "findings": [
+        { "severity": "Critical", "startLine": 2, "endLine": 2, "category": "Security", "message": "SQL injection" },
+        { "severity": "blocking", "startLine": 4, "endLine": 4, "category": "bug", "message": "Unknown severity" },
+        { "severity": "nit", "startLine": 5, "category": "style", "message": "Missing end line" },
+        { "severity": "low", "startLine": 6, "endLine": 7, "category": "style", "message": "Long line" }
+    ]
+}
+```"#;
+
+        let findings = parse_findings(answer, 10)?;
+
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].severity, Severity::Error);
+        assert_eq!(findings[0].category, FindingCategory::Security);
+        assert_eq!(findings[1].severity, Severity::Info);
+        assert_eq!(findings[1].message, "Long line");
+
+        Ok(())
+    }
+}
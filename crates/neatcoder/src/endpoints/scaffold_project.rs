@@ -1,21 +1,23 @@
 use anyhow::{anyhow, Result};
 use js_sys::{Function, JsString};
 use serde::{Deserialize, Serialize};
-use serde_json::{Map, Value};
+use serde_json::{json, Map, Value};
 use std::{
     collections::VecDeque,
     ops::{Deref, DerefMut},
-    path::{Path, PathBuf},
+    path::Path,
 };
 use wasm_bindgen::prelude::wasm_bindgen;
 
 use crate::{
-    consts::{CONFIG_EXTENSIONS, CONFIG_FILES},
-    models::app_data::language::Language,
-    openai::{
-        msg::{GptRole, OpenAIMsg},
-        params::OpenAIParams,
+    endpoints::gen_config::is_config_file,
+    models::app_data::{
+        language::Language,
+        scaffold::{Scaffold, ScaffoldDiff},
     },
+    openai::{msg::OpenAIMsg, params::OpenAIParams},
+    prompts::PromptTemplates,
+    providers::LlmProvider,
     utils::write_json,
 };
 
@@ -39,59 +41,118 @@ impl ScaffoldParams {
     }
 }
 
+#[wasm_bindgen]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RescaffoldParams {
+    /// Change requested to the existing project
+    pub(crate) change_request: String,
+}
+
+#[wasm_bindgen]
+impl RescaffoldParams {
+    #[wasm_bindgen(constructor)]
+    pub fn new(change_request: String) -> RescaffoldParams {
+        RescaffoldParams { change_request }
+    }
+
+    #[wasm_bindgen(getter, js_name = changeRequest)]
+    pub fn change_request(&self) -> JsString {
+        self.change_request.clone().into()
+    }
+}
+
 pub async fn scaffold_project(
+    provider: &dyn LlmProvider,
     language: &Language,
     ai_params: &OpenAIParams,
     client_params: &ScaffoldParams,
+    templates: &PromptTemplates,
     request_callback: &Function,
-) -> Result<(Value, Files)> {
+) -> Result<(Scaffold, Files, Files)> {
     let mut prompts = Vec::new();
 
-    prompts.push(OpenAIMsg {
-        role: GptRole::System,
-        content: format!(
-            "You are a software engineer who is specialised in building software in {}.", language.name()
-        ),
+    let context = json!({
+        "language": language.name(),
+        "specs": client_params.specs,
     });
 
-    // TODO: We should add the Database and API interfaces in previous messages, and add the name of the files here in order to index them
-    let main_prompt = format!("
-You are a software engineer tasked with creating project in {} based on the following project description:\n{}\n
-The project should retrieve the relevant data from the database.
-
-Based on the information provided write the project's folder structure, starting from `src`.
+    prompts.push(OpenAIMsg::system(
+        &templates.render("scaffold_system", &context)?,
+    ));
 
-Answer in JSON format (Do not forget to start with ```json). For each file provide a brief description included in the json", language.name(), client_params.specs);
+    // TODO: We should add the Database and API interfaces in previous messages, and add the name of the files here in order to index them
+    let main_prompt = templates.render("scaffold", &context)?;
 
-    prompts.push(OpenAIMsg {
-        role: GptRole::User,
-        content: main_prompt,
-    });
+    prompts.push(OpenAIMsg::user(&main_prompt));
 
     let prompts = prompts.iter().map(|x| x).collect::<Vec<&OpenAIMsg>>();
 
     let (_, mut scaffold_json) =
-        write_json(&ai_params, &prompts, request_callback).await?;
+        write_json(provider, &ai_params, &prompts, request_callback).await?;
 
     process_response(&mut scaffold_json)?;
 
-    let mut files = Files::empty();
+    let scaffold = Scaffold::from_value(&scaffold_json)?;
 
-    let src_json = scaffold_json.get("src").ok_or_else(|| {
-        anyhow!("Unable to find `src` folder in scaffold response")
-    })?;
+    if scaffold.get("src").is_none() {
+        return Err(anyhow!(
+            "Unable to find `src` folder in scaffold response"
+        ));
+    }
 
-    if src_json.is_object() {
-        for (key, value) in src_json.as_object().unwrap() {
-            files.add_files(value, key, &mut PathBuf::new())?;
-        }
-    } else {
-        return Err(anyhow!("Unable to parse scaffold json."));
+    let mut files = Files::from_scaffold(&scaffold);
+
+    let configs = files.cleanup(language)?;
+
+    Ok((scaffold, files, configs))
+}
+
+/// Asks the LLM for the changes to the `scaffold` of an existing project
+/// implementing the change request of `client_params`. Changes which do not
+/// apply to the scaffold are left out of the diff.
+#[allow(clippy::too_many_arguments)]
+pub async fn rescaffold_project(
+    provider: &dyn LlmProvider,
+    language: &Language,
+    ai_params: &OpenAIParams,
+    client_params: &RescaffoldParams,
+    scaffold: &Scaffold,
+    specs: Option<&str>,
+    templates: &PromptTemplates,
+    request_callback: &Function,
+) -> Result<ScaffoldDiff> {
+    let mut prompts = Vec::new();
+
+    prompts.push(OpenAIMsg::system(&templates.render(
+        "scaffold_system",
+        &json!({ "language": language.name() }),
+    )?));
+
+    if let Some(specs) = specs {
+        prompts.push(OpenAIMsg::user(specs));
     }
 
-    files.cleanup(language)?;
+    prompts.push(OpenAIMsg::user(&templates.render(
+        "rescaffold",
+        &json!({
+            "language": language.name(),
+            "tree": scaffold.render(),
+            "change_request": client_params.change_request,
+        }),
+    )?));
 
-    Ok((scaffold_json, files))
+    let prompts = prompts.iter().collect::<Vec<&OpenAIMsg>>();
+
+    let (_, diff_json) =
+        write_json(provider, ai_params, &prompts, request_callback).await?;
+
+    let mut diff: ScaffoldDiff = serde_json::from_value(diff_json)
+        .map_err(|e| anyhow!("Unable to parse scaffold diff: {}", e))?;
+
+    diff.normalise(scaffold);
+
+    Ok(diff)
 }
 
 fn process_response(llm_response: &mut Value) -> Result<()> {
@@ -159,44 +220,25 @@ impl Files {
         Files(files)
     }
 
-    pub fn add_files(
-        &mut self,
-        json: &Value,
-        current_key: &str,
-        path: &mut PathBuf,
-    ) -> Result<()> {
-        match json {
-            // If the value is an object, i.e. a map, then it means that it
-            // represents a folder. We therefore recursively call add_files itself
-            // to get to the leaf nodes, which represent the files
-            Value::Object(map) => {
-                for (key, value) in map.iter() {
-                    let mut new_path = path.join(current_key);
-                    self.add_files(value, key, &mut new_path)?;
-                }
-            }
-            // For other types of Value (Number, String, Bool, Null), consider them as leaf values.
-            _ => {
-                let full_path = path.join(current_key);
-                let filename = full_path
-                    .as_path()
-                    .to_str()
-                    .map(|s| s.to_string())
-                    .ok_or_else(|| {
-                        anyhow!("Unable to convert filename path to string :(")
-                    })?;
-
-                self.push_back(File {
-                    name: filename,
-                    description: json.to_string(),
-                });
-            }
-        }
+    /// Lists the files of the `src` folder of the scaffold, relative to it.
+    pub fn from_scaffold(scaffold: &Scaffold) -> Files {
+        let files = scaffold
+            .files_in("src")
+            .into_iter()
+            .map(|file| File {
+                name: file.path,
+                description: file.description,
+            })
+            .collect();
 
-        Ok(())
+        Files(files)
     }
 
-    pub fn cleanup(&mut self, language: &Language) -> Result<()> {
+    /// Normalises the names of the files and removes the folders and the
+    /// configuration files, which are returned. Configuration files are
+    /// written by `ConfigGen` tasks rather than `CodeGen` ones, except for
+    /// Markdown files, which are written by `DocGen` tasks.
+    pub fn cleanup(&mut self, language: &Language) -> Result<Files> {
         // Remove 'src/' prefix if it exists
         for file in &mut self.0 {
             if file.name.starts_with('/') {
@@ -208,29 +250,20 @@ impl Files {
             }
         }
 
-        // Filter out files that are configuration files, both by extension name
-        // or by filename if no extension exists
-        self.retain(|file| {
-            let path = Path::new(&file.name);
-            // This indicates that its not a file but a folder
-            if file.name.ends_with('/') {
-                return false;
-            }
+        // This indicates that its not a file but a folder
+        self.retain(|file| !file.name.ends_with('/'));
 
-            if let Some(file_name) = path.file_name().and_then(|n| n.to_str()) {
-                if CONFIG_FILES.contains(&file_name) {
-                    return false;
-                }
-            }
-            if let Some(extension) =
-                path.extension().and_then(|ext| ext.to_str())
-            {
-                if CONFIG_EXTENSIONS.contains(&extension) {
-                    return false;
-                }
-            }
-            true
-        });
+        // Split out the configuration files, both by extension name or by
+        // filename if no extension exists
+        let (configs, files): (VecDeque<File>, VecDeque<File>) =
+            self.drain(..).partition(|file| is_config_file(&file.name));
+
+        self.0 = files;
+
+        let configs = configs
+            .into_iter()
+            .filter(|file| !file.name.ends_with(".md"))
+            .collect();
 
         if !language.is_custom() {
             let default_extension = language.language.default_extension();
@@ -246,6 +279,6 @@ impl Files {
             }
         }
 
-        Ok(())
+        Ok(Files(configs))
     }
 }
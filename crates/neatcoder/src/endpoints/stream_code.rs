@@ -1,16 +1,21 @@
 use anyhow::{anyhow, Result};
 use js_sys::JsString;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use std::collections::BTreeMap;
 use wasm_bindgen::prelude::wasm_bindgen;
 
 use crate::{
+    consts::{CONTEXT_FULL_FILES, CONTEXT_MAX_FILES},
     models::app_data::{interfaces::AsContext, AppData},
     openai::{
-        msg::{GptRole, OpenAIMsg},
+        budget::{ContextBudget, Priority},
+        msg::OpenAIMsg,
         params::OpenAIParams,
         request::request_stream,
     },
+    prompts::code_gen::code_gen_prompt,
+    retrieval::select_context,
     utils::log,
 };
 
@@ -72,74 +77,76 @@ pub fn stream_code(
         anyhow!("It seems that the the field `specs` is missing..")
     })?;
 
-    prompts.push(OpenAIMsg {
-        role: GptRole::System,
-        content: format!(
-            "You are a software engineer who is specialised in {}.",
-            language.name()
-        ),
-    });
-
-    prompts.push(OpenAIMsg {
-        role: GptRole::User,
-        content: String::from(project_description),
-    });
-
-    for file in codebase.keys() {
-        let code = codebase
-            .get(file)
-            .ok_or_else(|| anyhow!("Unable to find fild {:?}", file))?;
-
-        prompts.push(OpenAIMsg {
-            role: GptRole::User,
-            content: code.clone(),
-        });
-    }
+    let templates = &app_state.prompts;
+
+    prompts.push((
+        OpenAIMsg::system(&templates.render(
+            "code_gen_system",
+            &json!({ "language": language.name() }),
+        )?),
+        Priority::Required,
+    ));
+
+    prompts.push((OpenAIMsg::user(project_description), Priority::High));
 
-    prompts.push(OpenAIMsg {
-        role: GptRole::User,
-        // Needs to be optimized
-        content: project_scaffold.to_string(),
-    });
-
-    let mut main_prompt = format!(
-        "
-        You are a {} engineer and you're assigned to build the project
-        defined in the previous prompts.
-
-        Your current task is to write the module `{}.rs`.
-        Consider the description of the module: {}\n
-        ",
-        language.name(),
+    // Only the files relevant to the task are sent, the most relevant ones
+    // in full and the others as signatures
+    let context = select_context(
+        &codebase,
+        app_state.scaffold.as_ref(),
         filename,
-        description
+        description,
+        CONTEXT_MAX_FILES,
+        CONTEXT_FULL_FILES,
     );
 
-    if !app_state.interfaces.is_empty() {
-        main_prompt.push_str(
-            "Consider the following interfaces relevant to this project:\n",
-        )
+    for file in context.iter() {
+        let (label, priority) = match file.summarised {
+            true => ("Signatures of the file", Priority::Low),
+            false => ("File", Priority::Medium),
+        };
+
+        prompts.push((
+            OpenAIMsg::user(&format!(
+                "{} `{}`:\n```\n{}\n```",
+                label, file.path, file.content
+            )),
+            priority,
+        ));
     }
 
-    for (_, interface) in app_state.interfaces.iter() {
+    // Needs to be optimized
+    prompts.push((
+        OpenAIMsg::user(&project_scaffold.render()),
+        Priority::Medium,
+    ));
+
+    for interface in app_state.interfaces.values() {
         // Attaches context to the message sequence
-        interface.add_context(&mut prompts)?;
+        let mut context = Vec::new();
+        interface.add_context(templates, &mut context)?;
 
-        main_prompt.push_str(&format!(
-            "- Name {}; Type {}",
-            interface.name(),
-            interface.itype()
-        ));
+        prompts.extend(context.into_iter().map(|msg| (msg, Priority::Medium)));
     }
 
-    prompts.push(OpenAIMsg {
-        role: GptRole::User,
-        content: main_prompt,
-    });
+    let main_prompt = code_gen_prompt(
+        templates,
+        &language,
+        filename,
+        description,
+        &app_state.interfaces,
+    )?;
+
+    prompts.push((OpenAIMsg::user(&main_prompt), Priority::Required));
+
+    // Trims the codebase and the context first if the prompt exceeds the
+    // context window
+    let prompts = ContextBudget::new(ai_params).fit(prompts)?;
 
-    let prompts = prompts.iter().map(|x| x).collect::<Vec<&OpenAIMsg>>();
+    let prompts = prompts.iter().collect::<Vec<&OpenAIMsg>>();
 
-    let request_body = request_stream(ai_params, &prompts, &[], &[])?;
+    let request_body =
+        request_stream(&app_state.provider, ai_params, &prompts, &[])?;
 
     Ok(request_body)
 }
@@ -13,8 +13,12 @@ pub mod endpoints;
 pub mod models;
 pub mod openai;
 pub mod prelude;
+pub mod prompts;
+pub mod providers;
+pub mod retrieval;
 pub mod typescript;
 pub mod utils;
+pub mod validation;
 
 /// Type alias for JavaScript errors represented as JsValue
 pub type JsError = JsValue;
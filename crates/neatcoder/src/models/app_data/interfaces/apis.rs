@@ -1,12 +1,16 @@
-use super::{AsContext, SchemaFile};
+use super::{asyncapi::AsyncApi, openapi::OpenApi, AsContext, SchemaFile};
 use crate::{
-    openai::msg::{GptRole, OpenAIMsg},
-    typescript::ISchemas,
+    openai::msg::OpenAIMsg, prompts::PromptTemplates, typescript::ISchemas,
     JsError, WasmType,
 };
 use anyhow::Result;
 use js_sys::JsString;
+use parser::parser::{
+    graphql::{AsGraphQl, GraphQl},
+    proto::{AsProto, ProtoSet},
+};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use std::{
     collections::BTreeMap,
     fmt::{self, Display},
@@ -97,6 +101,19 @@ impl Api {
     pub fn set_host(&mut self, host: Option<String>) {
         self.host = host;
     }
+
+    /// Typed model of the OpenAPI or Swagger specifications of the schemas,
+    /// if any
+    #[wasm_bindgen(getter, js_name = openApi)]
+    pub fn open_api(&self) -> Option<OpenApi> {
+        self.open_api_()
+    }
+
+    /// Typed model of the AsyncAPI specifications of the schemas, if any
+    #[wasm_bindgen(getter, js_name = asyncApi)]
+    pub fn async_api(&self) -> Option<AsyncApi> {
+        self.async_api_()
+    }
 }
 
 /// Enum documenting the type of APIs.
@@ -151,41 +168,156 @@ impl Api {
             schemas,
         }
     }
-}
 
-impl AsContext for Api {
-    fn add_context(&self, msg_sequence: &mut Vec<OpenAIMsg>) -> Result<()> {
-        let mut main_prompt = format!(
-            "
-Have in consideration the following {} communication service:
-
-- service name: {}
-",
-            self.api_type, self.name
-        );
-
-        if let Some(port) = &self.port {
-            main_prompt = format!("{}\n{} {}", main_prompt, "- port:", port);
+    /// Merges the OpenAPI and Swagger specifications of the schemas. Returns
+    /// `None` if none of the schemas is such a specification.
+    pub fn open_api_(&self) -> Option<OpenApi> {
+        let mut specs = self
+            .schemas
+            .values()
+            .filter_map(|schema| OpenApi::from_spec_(schema).ok());
+
+        let mut open_api = specs.next()?;
+
+        for spec in specs {
+            open_api.merge(spec);
+        }
+
+        Some(open_api)
+    }
+
+    /// Merges the AsyncAPI specifications of the schemas, e.g. of the
+    /// WebSockets or MQTT channels of the API. Returns `None` if none of the
+    /// schemas is such a specification.
+    pub fn async_api_(&self) -> Option<AsyncApi> {
+        let mut specs = self
+            .schemas
+            .values()
+            .filter_map(|schema| AsyncApi::from_spec_(schema).ok());
+
+        let mut async_api = specs.next()?;
+
+        for spec in specs {
+            async_api.merge(spec);
+        }
+
+        Some(async_api)
+    }
+
+    /// Merges the GraphQL SDL schemas, extensions included. Returns `None`
+    /// if none of the schemas is written in SDL.
+    pub fn graphql_(&self) -> Option<GraphQl> {
+        let mut schemas = self
+            .schemas
+            .values()
+            .filter_map(|schema| schema.as_str().as_graphql().ok());
+
+        let mut graphql = schemas.next()?;
+
+        for schema in schemas {
+            graphql.merge(schema);
         }
 
-        if let Some(host) = &self.host {
-            main_prompt = format!("{}\n{} {}", main_prompt, "- host:", host);
+        Some(graphql)
+    }
+
+    /// Gathers the `.proto` files of the schemas, keyed by schema name, such
+    /// that their messages can refer to each other through imports. Returns
+    /// `None` if none of the schemas is a `.proto` file.
+    pub fn protos_(&self) -> Option<ProtoSet> {
+        let files = self
+            .schemas
+            .iter()
+            .filter_map(|(name, schema)| {
+                let proto = schema.as_str().as_proto().ok()?;
+                Some((name.clone(), proto))
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        match files.is_empty() {
+            true => None,
+            false => Some(ProtoSet::new(files)),
         }
+    }
+}
+
+impl AsContext for Api {
+    fn add_context(
+        &self,
+        templates: &PromptTemplates,
+        msg_sequence: &mut Vec<OpenAIMsg>,
+    ) -> Result<()> {
+        let main_prompt = templates.render(
+            "api_context",
+            &json!({
+                "api_type": self.api_type.to_string(),
+                "name": self.name,
+                "port": self.port,
+                "host": self.host,
+            }),
+        )?;
+
+        msg_sequence.push(OpenAIMsg::user(&main_prompt));
 
-        msg_sequence.push(OpenAIMsg {
-            role: GptRole::User,
-            content: main_prompt,
-        });
+        // SDL files are merged into a single type system, such that types
+        // split across files or extended are only described once
+        let mut graphql: Option<GraphQl> = None;
+        let mut sdl_files = Vec::new();
 
         for (schema_name, schema) in self.schemas.iter() {
-            let prompt = format!("
-Consider the following schema as part of the {} database. It's called `{}` and the schema is:\n```\n{}```
-            ", self.name, schema_name, schema);
-
-            msg_sequence.push(OpenAIMsg {
-                role: GptRole::User,
-                content: prompt,
-            });
+            if let Ok(sdl) = schema.as_str().as_graphql() {
+                match graphql.as_mut() {
+                    Some(graphql) => graphql.merge(sdl),
+                    None => graphql = Some(sdl),
+                }
+
+                sdl_files.push(schema_name.as_str());
+                continue;
+            }
+
+            // Specifications are summarised as their endpoints or channels,
+            // which spares the descriptions and examples of the raw files,
+            // and `.proto` files as their definitions, without comments and
+            // options
+            let summary = OpenApi::from_spec_(schema)
+                .ok()
+                .filter(|open_api| !open_api.endpoints.is_empty())
+                .map(|open_api| open_api.summary())
+                .or_else(|| {
+                    AsyncApi::from_spec_(schema)
+                        .ok()
+                        .filter(|async_api| !async_api.channels.is_empty())
+                        .map(|async_api| async_api.summary())
+                })
+                .or_else(|| {
+                    schema.as_str().as_proto().ok().map(|p| p.to_string())
+                });
+
+            let prompt = templates.render(
+                "api_schema",
+                &json!({
+                    "interface": self.name,
+                    "name": schema_name,
+                    "summary": summary.is_some(),
+                    "schema": summary.as_deref().unwrap_or(schema),
+                }),
+            )?;
+
+            msg_sequence.push(OpenAIMsg::user(&prompt));
+        }
+
+        if let Some(graphql) = graphql {
+            let prompt = templates.render(
+                "api_schema",
+                &json!({
+                    "interface": self.name,
+                    "name": sdl_files.join(", "),
+                    "summary": true,
+                    "schema": graphql.to_string(),
+                }),
+            )?;
+
+            msg_sequence.push(OpenAIMsg::user(&prompt));
         }
 
         Ok(())
@@ -0,0 +1,838 @@
+use anyhow::{anyhow, Result};
+use js_sys::JsString;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{collections::BTreeMap, fmt::Write};
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use super::openapi::{
+    first_line, parse_document, resolve, scalar, schema_type, used_schemas,
+};
+use crate::JsError;
+
+/// Action of an operation which sends messages to its channel, from the
+/// point of view of the application described by the specification.
+pub const SEND: &str = "send";
+/// Action of an operation which receives messages from its channel.
+pub const RECEIVE: &str = "receive";
+
+/// Typed model of an AsyncAPI 2.x or 3.x specification, in JSON or YAML,
+/// restricted to what is needed to write producers and consumers of the
+/// API: its servers, its channels, the operations on each channel, and the
+/// payloads and protocol bindings of their messages.
+#[wasm_bindgen]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AsyncApi {
+    pub(crate) title: Option<String>,
+    /// Version of the specification format, e.g. `2.6.0` or `3.0.0`
+    pub(crate) spec_version: String,
+    pub(crate) servers: Vec<AsyncServer>,
+    pub(crate) channels: Vec<Channel>,
+    /// Compact types of the reusable schemas, by name
+    pub(crate) schemas: BTreeMap<String, String>,
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AsyncServer {
+    pub(crate) name: String,
+    pub(crate) url: String,
+    /// Protocol of the server, e.g. `mqtt`, `ws` or `kafka`
+    pub(crate) protocol: String,
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Channel {
+    /// Address of the channel, e.g. the MQTT topic `users/{userId}/signup`
+    pub(crate) address: String,
+    pub(crate) description: Option<String>,
+    /// Compact types of the parameters of the address, e.g.
+    /// `userId: string`
+    pub(crate) params: Vec<String>,
+    pub(crate) operations: Vec<ChannelOperation>,
+    /// Protocol bindings of the channel, e.g. `ws(method: GET)`
+    pub(crate) bindings: Vec<String>,
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelOperation {
+    /// Either `send` or `receive`. The `subscribe` and `publish` operations
+    /// of AsyncAPI 2 are mapped to `send` and `receive` respectively.
+    pub(crate) action: String,
+    pub(crate) operation_id: Option<String>,
+    pub(crate) summary: Option<String>,
+    pub(crate) messages: Vec<ChannelMessage>,
+    pub(crate) bindings: Vec<String>,
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelMessage {
+    pub(crate) name: Option<String>,
+    /// Compact type of the payload, prefixed by its content type unless it
+    /// is JSON
+    pub(crate) payload: String,
+    pub(crate) headers: Option<String>,
+    pub(crate) bindings: Vec<String>,
+}
+
+#[wasm_bindgen]
+impl AsyncApi {
+    /// Parses an AsyncAPI 2.x or 3.x specification, in JSON or YAML.
+    #[wasm_bindgen(js_name = fromSpec)]
+    pub fn from_spec(spec: &str) -> Result<AsyncApi, JsError> {
+        AsyncApi::from_spec_(spec)
+            .map_err(|e| JsError::from_str(&e.to_string()))
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn title(&self) -> Option<JsString> {
+        self.title.clone().map(JsString::from)
+    }
+
+    #[wasm_bindgen(getter, js_name = specVersion)]
+    pub fn spec_version(&self) -> JsString {
+        self.spec_version.clone().into()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn servers(&self) -> Vec<AsyncServer> {
+        self.servers.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn channels(&self) -> Vec<Channel> {
+        self.channels.clone()
+    }
+
+    /// Addresses of the channels with operations of the given `action`,
+    /// i.e. `send` for producers and `receive` for consumers.
+    #[wasm_bindgen(js_name = channelsWith)]
+    pub fn channels_with(&self, action: &str) -> Vec<String> {
+        self.channels
+            .iter()
+            .filter(|channel| channel.has(action))
+            .map(|channel| channel.address.clone())
+            .collect()
+    }
+
+    /// Renders the channels of the API compactly, followed by the reusable
+    /// schemas their messages refer to.
+    pub fn summary(&self) -> String {
+        self.summary_of(None)
+    }
+}
+
+impl AsyncApi {
+    pub fn from_spec_(spec: &str) -> Result<AsyncApi> {
+        let root = parse_document(spec)?;
+
+        let spec_version = root
+            .get("asyncapi")
+            .and_then(scalar)
+            .ok_or_else(|| anyhow!("Not an AsyncAPI specification"))?;
+
+        let is_v2 = spec_version.starts_with('2');
+
+        let mut async_api = AsyncApi {
+            title: root.pointer("/info/title").and_then(scalar),
+            spec_version,
+            ..Default::default()
+        };
+
+        if let Some(Value::Object(schemas)) =
+            root.pointer("/components/schemas")
+        {
+            for (name, schema) in schemas.iter() {
+                async_api
+                    .schemas
+                    .insert(name.clone(), schema_type(&root, schema, 0));
+            }
+        }
+
+        if let Some(Value::Object(servers)) = root.get("servers") {
+            for (name, server) in servers.iter() {
+                async_api
+                    .servers
+                    .push(AsyncServer::new(name, resolve(&root, server)));
+            }
+        }
+
+        if let Some(Value::Object(channels)) = root.get("channels") {
+            for (key, channel) in channels.iter() {
+                let channel = resolve(&root, channel);
+
+                async_api.channels.push(match is_v2 {
+                    true => Channel::v2(&root, key, channel),
+                    false => Channel::v3(&root, key, channel),
+                });
+            }
+        }
+
+        Ok(async_api)
+    }
+
+    /// Appends the servers, channels and schemas of `other`, e.g. of
+    /// another file of the same API.
+    pub fn merge(&mut self, other: AsyncApi) {
+        self.title = self.title.take().or(other.title);
+        self.channels.extend(other.channels);
+        self.schemas.extend(other.schemas);
+
+        for server in other.servers {
+            if !self.servers.contains(&server) {
+                self.servers.push(server);
+            }
+        }
+    }
+
+    pub fn channel(&self, address: &str) -> Option<&Channel> {
+        self.channels
+            .iter()
+            .find(|channel| channel.address == address)
+    }
+
+    /// Renders the channel at `address`, or all of them, along with the
+    /// schemas their messages refer to. Schemas referred to by other
+    /// schemas are included as well.
+    pub fn summary_of(&self, address: Option<&str>) -> String {
+        let channels = match address {
+            Some(address) => self.channel(address).into_iter().collect(),
+            None => self.channels.iter().collect::<Vec<&Channel>>(),
+        };
+
+        let mut summary = String::new();
+
+        // Writing to a `String` cannot fail
+        if let Some(title) = &self.title {
+            let _ = writeln!(summary, "API: {}", title);
+        }
+
+        for server in self.servers.iter() {
+            let _ = writeln!(
+                summary,
+                "Server {}: {} ({})",
+                server.name, server.url, server.protocol
+            );
+        }
+
+        for channel in channels.iter() {
+            summary.push('\n');
+            summary.push_str(&channel.summary());
+        }
+
+        let mut types = Vec::new();
+
+        for channel in channels.iter() {
+            types.extend(channel.params.iter().map(String::as_str));
+
+            for message in channel.operations.iter().flat_map(|o| &o.messages) {
+                types.push(message.payload.as_str());
+                types.extend(message.headers.as_deref());
+            }
+        }
+
+        let used = used_schemas(&self.schemas, types);
+
+        if !used.is_empty() {
+            summary.push_str("\nSchemas:\n");
+
+            for name in used {
+                let _ = writeln!(summary, "  {} {}", name, self.schemas[name]);
+            }
+        }
+
+        summary
+    }
+}
+
+#[wasm_bindgen]
+impl AsyncServer {
+    #[wasm_bindgen(getter)]
+    pub fn name(&self) -> JsString {
+        self.name.clone().into()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn url(&self) -> JsString {
+        self.url.clone().into()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn protocol(&self) -> JsString {
+        self.protocol.clone().into()
+    }
+}
+
+impl AsyncServer {
+    fn new(name: &str, server: &Value) -> AsyncServer {
+        let field = |name: &str| server.get(name).and_then(scalar);
+
+        // AsyncAPI 3 splits the `url` of AsyncAPI 2 in `host` and `pathname`
+        let url = field("url").unwrap_or_else(|| {
+            format!(
+                "{}{}",
+                field("host").unwrap_or_default(),
+                field("pathname").unwrap_or_default()
+            )
+        });
+
+        AsyncServer {
+            name: name.to_string(),
+            url,
+            protocol: field("protocol").unwrap_or_default(),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Channel {
+    #[wasm_bindgen(getter)]
+    pub fn address(&self) -> JsString {
+        self.address.clone().into()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn operations(&self) -> Vec<ChannelOperation> {
+        self.operations.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn bindings(&self) -> Vec<String> {
+        self.bindings.clone()
+    }
+}
+
+impl Channel {
+    fn new(root: &Value, address: &str, channel: &Value) -> Channel {
+        Channel {
+            address: address.to_string(),
+            description: channel
+                .get("description")
+                .and_then(Value::as_str)
+                .map(first_line),
+            params: params(root, channel.get("parameters")),
+            operations: Vec::new(),
+            bindings: bindings(root, channel.get("bindings")),
+        }
+    }
+
+    /// AsyncAPI 2 channels are keyed by address and hold their operations,
+    /// named after what other applications do on the channel: the
+    /// application receives what others `publish` and sends what others
+    /// `subscribe` to.
+    fn v2(root: &Value, address: &str, channel: &Value) -> Channel {
+        let mut result = Channel::new(root, address, channel);
+
+        for (key, action) in [("publish", RECEIVE), ("subscribe", SEND)] {
+            let operation = match channel.get(key) {
+                Some(operation) => resolve(root, operation),
+                None => continue,
+            };
+
+            let mut messages = Vec::new();
+
+            if let Some(message) = operation.get("message") {
+                match resolve(root, message).get("oneOf") {
+                    Some(Value::Array(one_of)) => {
+                        for message in one_of.iter() {
+                            messages
+                                .push(ChannelMessage::new(root, None, message));
+                        }
+                    }
+                    _ => {
+                        messages.push(ChannelMessage::new(root, None, message))
+                    }
+                }
+            }
+
+            result
+                .operations
+                .push(ChannelOperation::new(root, action, operation, messages));
+        }
+
+        result
+    }
+
+    /// AsyncAPI 3 channels are keyed by id and declare their messages, the
+    /// operations being declared at the root and referring to the channel.
+    fn v3(root: &Value, key: &str, channel: &Value) -> Channel {
+        let address = channel
+            .get("address")
+            .and_then(scalar)
+            .unwrap_or_else(|| key.to_string());
+
+        let mut result = Channel::new(root, &address, channel);
+
+        let reference = format!("#/channels/{}", key.replace('/', "~1"));
+
+        let operations = match root.get("operations") {
+            Some(Value::Object(operations)) => operations,
+            _ => return result,
+        };
+
+        for (id, operation) in operations.iter() {
+            let operation = resolve(root, operation);
+
+            if operation.pointer("/channel/$ref").and_then(Value::as_str)
+                != Some(&reference)
+            {
+                continue;
+            }
+
+            // Operations without messages convey all those of the channel
+            let messages = match operation.get("messages") {
+                Some(Value::Array(messages)) if !messages.is_empty() => {
+                    messages
+                        .iter()
+                        .map(|message| ChannelMessage::new(root, None, message))
+                        .collect()
+                }
+                _ => match channel.get("messages") {
+                    Some(Value::Object(messages)) => messages
+                        .iter()
+                        .map(|(key, message)| {
+                            ChannelMessage::new(root, Some(key), message)
+                        })
+                        .collect(),
+                    _ => Vec::new(),
+                },
+            };
+
+            let action = operation
+                .get("action")
+                .and_then(scalar)
+                .unwrap_or_else(|| SEND.to_string());
+
+            let mut operation =
+                ChannelOperation::new(root, &action, operation, messages);
+
+            operation.operation_id.get_or_insert_with(|| id.clone());
+            result.operations.push(operation);
+        }
+
+        result
+    }
+
+    /// Whether the channel has operations of the given `action`.
+    pub fn has(&self, action: &str) -> bool {
+        self.operations
+            .iter()
+            .any(|operation| operation.action == action)
+    }
+
+    pub fn summary(&self) -> String {
+        let mut summary = format!("Channel {}", self.address);
+
+        // Writing to a `String` cannot fail
+        if let Some(description) = &self.description {
+            let _ = write!(summary, ": {}", description);
+        }
+
+        if !self.bindings.is_empty() {
+            let _ =
+                write!(summary, " [bindings: {}]", self.bindings.join(", "));
+        }
+
+        summary.push('\n');
+
+        for param in self.params.iter() {
+            let _ = writeln!(summary, "  param {}", param);
+        }
+
+        for operation in self.operations.iter() {
+            summary.push_str(&operation.summary());
+        }
+
+        summary
+    }
+}
+
+#[wasm_bindgen]
+impl ChannelOperation {
+    #[wasm_bindgen(getter)]
+    pub fn action(&self) -> JsString {
+        self.action.clone().into()
+    }
+
+    #[wasm_bindgen(getter, js_name = operationId)]
+    pub fn operation_id(&self) -> Option<JsString> {
+        self.operation_id.clone().map(JsString::from)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn messages(&self) -> Vec<ChannelMessage> {
+        self.messages.clone()
+    }
+}
+
+impl ChannelOperation {
+    fn new(
+        root: &Value,
+        action: &str,
+        operation: &Value,
+        messages: Vec<ChannelMessage>,
+    ) -> ChannelOperation {
+        ChannelOperation {
+            action: action.to_string(),
+            operation_id: operation.get("operationId").and_then(scalar),
+            summary: operation
+                .get("summary")
+                .or_else(|| operation.get("description"))
+                .and_then(Value::as_str)
+                .map(first_line),
+            messages,
+            bindings: bindings(root, operation.get("bindings")),
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        let mut summary = format!("  {}", self.action);
+
+        // Writing to a `String` cannot fail
+        if let Some(operation_id) = &self.operation_id {
+            let _ = write!(summary, " ({})", operation_id);
+        }
+
+        if let Some(description) = &self.summary {
+            let _ = write!(summary, ": {}", description);
+        }
+
+        if !self.bindings.is_empty() {
+            let _ =
+                write!(summary, " [bindings: {}]", self.bindings.join(", "));
+        }
+
+        summary.push('\n');
+
+        for message in self.messages.iter() {
+            let _ = write!(
+                summary,
+                "    {}: {}",
+                message.name.as_deref().unwrap_or("message"),
+                message.payload
+            );
+
+            if let Some(headers) = &message.headers {
+                let _ = write!(summary, " (headers: {})", headers);
+            }
+
+            if !message.bindings.is_empty() {
+                let _ = write!(
+                    summary,
+                    " [bindings: {}]",
+                    message.bindings.join(", ")
+                );
+            }
+
+            summary.push('\n');
+        }
+
+        summary
+    }
+}
+
+#[wasm_bindgen]
+impl ChannelMessage {
+    #[wasm_bindgen(getter)]
+    pub fn name(&self) -> Option<JsString> {
+        self.name.clone().map(JsString::from)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn payload(&self) -> JsString {
+        self.payload.clone().into()
+    }
+}
+
+impl ChannelMessage {
+    /// Messages without a name are named after their `key`, or the key
+    /// they are referred to by.
+    fn new(root: &Value, key: Option<&str>, message: &Value) -> ChannelMessage {
+        let key = message
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|reference| reference.rsplit('/').next())
+            .or(key)
+            .map(String::from);
+
+        let message = resolve(root, message);
+
+        let name = message
+            .get("name")
+            .or_else(|| message.get("messageId"))
+            .and_then(scalar)
+            .or(key);
+
+        let payload = message
+            .get("payload")
+            .map(|payload| schema_type(root, resolve_payload(payload), 0))
+            .unwrap_or_else(|| String::from("any"));
+
+        let payload = match message.get("contentType").and_then(Value::as_str) {
+            Some(content_type) if !content_type.contains("json") => {
+                format!("{} {}", content_type, payload)
+            }
+            _ => payload,
+        };
+
+        ChannelMessage {
+            name,
+            payload,
+            headers: message
+                .get("headers")
+                .map(|headers| schema_type(root, resolve_payload(headers), 0)),
+            bindings: bindings(root, message.get("bindings")),
+        }
+    }
+}
+
+/// AsyncAPI 3 wraps schemas of other formats, e.g. Avro, in a multi-format
+/// schema holding the format and the schema itself.
+fn resolve_payload(payload: &Value) -> &Value {
+    match (payload.get("schemaFormat"), payload.get("schema")) {
+        (Some(_), Some(schema)) => schema,
+        _ => payload,
+    }
+}
+
+/// Renders the parameters of a channel address, e.g. `userId: string`.
+/// AsyncAPI 3 parameters have no schema, their values being strings.
+fn params(root: &Value, params: Option<&Value>) -> Vec<String> {
+    let params = match params {
+        Some(Value::Object(params)) => params,
+        _ => return Vec::new(),
+    };
+
+    params
+        .iter()
+        .map(|(name, param)| {
+            let param = resolve(root, param);
+
+            let ty = match param.get("schema") {
+                Some(schema) => schema_type(root, schema, 0),
+                None if param.get("enum").is_some() => {
+                    schema_type(root, param, 0)
+                }
+                None => String::from("string"),
+            };
+
+            format!("{}: {}", name, ty)
+        })
+        .collect()
+}
+
+/// Renders protocol bindings as the protocol followed by their scalar
+/// fields, e.g. `mqtt(qos: 1, retain: false)`. Nested fields, e.g. the
+/// schemas of Kafka keys, are rendered as compact types.
+fn bindings(root: &Value, bindings: Option<&Value>) -> Vec<String> {
+    let bindings = match bindings.map(|bindings| resolve(root, bindings)) {
+        Some(Value::Object(bindings)) => bindings,
+        _ => return Vec::new(),
+    };
+
+    bindings
+        .iter()
+        .map(|(protocol, binding)| {
+            let fields = match resolve(root, binding) {
+                Value::Object(fields) => fields
+                    .iter()
+                    .filter(|(name, _)| name.as_str() != "bindingVersion")
+                    .map(|(name, value)| {
+                        let value = scalar(value)
+                            .unwrap_or_else(|| schema_type(root, value, 0));
+
+                        format!("{}: {}", name, value)
+                    })
+                    .collect::<Vec<String>>(),
+                _ => Vec::new(),
+            };
+
+            match fields.is_empty() {
+                true => protocol.clone(),
+                false => format!("{}({})", protocol, fields.join(", ")),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    const ASYNCAPI_2: &str = r#"
+asyncapi: 2.6.0
+info:
+  title: Lights
+  version: 1.0.0
+servers:
+  production:
+    url: broker.lights.io:1883
+    protocol: mqtt
+channels:
+  lights/{lightId}/measured:
+    description: Lighting conditions of a street light.
+    parameters:
+      lightId:
+        schema:
+          type: string
+    publish:
+      operationId: onLightMeasured
+      message:
+        $ref: '#/components/messages/LightMeasured'
+  lights/{lightId}/turn:
+    parameters:
+      lightId:
+        schema:
+          type: string
+    subscribe:
+      operationId: turnLight
+      bindings:
+        mqtt:
+          qos: 1
+          bindingVersion: 0.2.0
+      message:
+        oneOf:
+          - $ref: '#/components/messages/TurnOn'
+          - name: TurnOff
+            payload:
+              type: object
+components:
+  messages:
+    LightMeasured:
+      payload:
+        $ref: '#/components/schemas/Measure'
+    TurnOn:
+      payload:
+        type: object
+        properties:
+          brightness:
+            type: integer
+  schemas:
+    Measure:
+      type: object
+      required: [lumens]
+      properties:
+        lumens:
+          type: integer
+        sentAt:
+          $ref: '#/components/schemas/SentAt'
+    SentAt:
+      type: string
+      format: date-time
+    Unused:
+      type: string
+"#;
+
+    const ASYNCAPI_3: &str = r#"
+asyncapi: 3.0.0
+info:
+  title: Chat
+  version: 1.0.0
+servers:
+  live:
+    host: chat.acme.com
+    pathname: /ws
+    protocol: wss
+channels:
+  room:
+    address: rooms/{roomId}
+    parameters:
+      roomId: {}
+    bindings:
+      ws:
+        method: GET
+    messages:
+      Said:
+        payload:
+          type: object
+          properties:
+            text:
+              type: string
+      Left:
+        contentType: text/plain
+        payload:
+          type: string
+operations:
+  say:
+    action: send
+    channel:
+      $ref: '#/channels/room'
+    messages:
+      - $ref: '#/channels/room/messages/Said'
+  listen:
+    action: receive
+    summary: Messages of the room.
+    channel:
+      $ref: '#/channels/room'
+"#;
+
+    #[wasm_bindgen_test]
+    fn parses_asyncapi_2() -> Result<()> {
+        let api = AsyncApi::from_spec_(ASYNCAPI_2)?;
+
+        assert_eq!(api.channels_with(SEND), vec!["lights/{lightId}/turn"]);
+        assert_eq!(
+            api.channels_with(RECEIVE),
+            vec!["lights/{lightId}/measured"]
+        );
+
+        assert_eq!(
+            api.summary(),
+            "API: Lights
+Server production: broker.lights.io:1883 (mqtt)
+
+Channel lights/{lightId}/measured: Lighting conditions of a street light.
+  param lightId: string
+  receive (onLightMeasured)
+    LightMeasured: Measure
+
+Channel lights/{lightId}/turn
+  param lightId: string
+  send (turnLight) [bindings: mqtt(qos: 1)]
+    TurnOn: {brightness?: integer}
+    TurnOff: object
+
+Schemas:
+  Measure {lumens: integer, sentAt?: SentAt}
+  SentAt string(date-time)
+"
+        );
+
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn parses_asyncapi_3() -> Result<()> {
+        let api = AsyncApi::from_spec_(ASYNCAPI_3)?;
+
+        assert_eq!(api.servers[0].url, "chat.acme.com/ws");
+
+        assert_eq!(
+            api.summary_of(Some("rooms/{roomId}")),
+            "API: Chat
+Server live: chat.acme.com/ws (wss)
+
+Channel rooms/{roomId} [bindings: ws(method: GET)]
+  param roomId: string
+  receive (listen): Messages of the room.
+    Left: text/plain string
+    Said: {text?: string}
+  send (say)
+    Said: {text?: string}
+"
+        );
+
+        assert!(AsyncApi::from_spec_("openapi: 3.0.0").is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,877 @@
+use anyhow::{anyhow, Result};
+use js_sys::JsString;
+use parser::parser::{csv::AsCsv, json::AsJson};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{collections::BTreeMap, fmt, iter::Peekable, str::Chars};
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use super::storage::FileType;
+use crate::JsError;
+
+/// Column or field schema of the data files of a `Storage` interface,
+/// inferred from a sample of its files: rows of CSV files, records of JSON
+/// and NDJSON files, Avro schemas, or the schemas of the footers of Parquet
+/// and ORC files as dumped by their tools.
+#[wasm_bindgen]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DataSchema {
+    pub(crate) fields: Vec<DataField>,
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DataField {
+    pub(crate) name: String,
+    /// Compact type of the field, e.g. `timestamp` or `[{id: integer}]`
+    pub(crate) data_type: String,
+    /// Whether the field may be missing or null
+    pub nullable: bool,
+}
+
+#[wasm_bindgen]
+impl DataSchema {
+    /// Infers the schema of a sample file of the given type.
+    #[wasm_bindgen(js_name = infer)]
+    pub fn infer(
+        file_type: FileType,
+        sample: &str,
+    ) -> Result<DataSchema, JsError> {
+        DataSchema::infer_(file_type, sample)
+            .map_err(|e| JsError::from_str(&e.to_string()))
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn fields(&self) -> Vec<DataField> {
+        self.fields.clone()
+    }
+
+    /// Renders the fields of the schema, one per line.
+    pub fn summary(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl DataSchema {
+    pub fn infer_(file_type: FileType, sample: &str) -> Result<DataSchema> {
+        let record = match file_type {
+            FileType::Csv => csv_record(sample)?,
+            FileType::Json => {
+                let json = sample.as_json()?;
+
+                // Hand-written schemas are not samples of the records
+                if json.get("$schema").is_some()
+                    || (json.get("type") == Some(&Value::from("object"))
+                        && json.get("properties").is_some())
+                {
+                    return Err(anyhow!(
+                        "The sample is a JSON Schema rather than a record"
+                    ));
+                }
+
+                match json {
+                    Value::Array(records) => {
+                        records.iter().map(json_type).fold(Type::Null, merge)
+                    }
+                    json => json_type(&json),
+                }
+            }
+            FileType::NdJson => sample
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| line.as_json().map(|json| json_type(&json)))
+                .collect::<Result<Vec<Type>, _>>()?
+                .into_iter()
+                .fold(Type::Null, merge),
+            FileType::Avro => avro_record(&sample.as_json()?)?,
+            // Avro schemas are printed by `parquet-cli schema`
+            FileType::Parquet => match sample.as_json() {
+                Ok(json) => avro_record(&json)?,
+                Err(_) => parquet_record(sample)?,
+            },
+            FileType::Orc => orc_record(sample)?,
+            FileType::ProtoBuf | FileType::Xml => {
+                return Err(anyhow!(
+                    "Schemas cannot be inferred from {} files",
+                    file_type
+                ))
+            }
+        };
+
+        let fields = match record {
+            Type::Record(fields) => fields,
+            _ => {
+                return Err(anyhow!(
+                    "The records of the {} sample are not objects",
+                    file_type
+                ))
+            }
+        };
+
+        Ok(DataSchema {
+            fields: fields
+                .into_iter()
+                .map(|field| DataField {
+                    name: field.name,
+                    data_type: field.ty.to_string(),
+                    nullable: field.nullable,
+                })
+                .collect(),
+        })
+    }
+}
+
+#[wasm_bindgen]
+impl DataField {
+    #[wasm_bindgen(getter)]
+    pub fn name(&self) -> JsString {
+        self.name.clone().into()
+    }
+
+    #[wasm_bindgen(getter, js_name = dataType)]
+    pub fn data_type(&self) -> JsString {
+        self.data_type.clone().into()
+    }
+}
+
+impl fmt::Display for DataSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for field in self.fields.iter() {
+            let optional = if field.nullable { "?" } else { "" };
+            writeln!(f, "{}{}: {}", field.name, optional, field.data_type)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Type of a field, as inferred from the values of the sample or declared
+/// by the schema of the file.
+#[derive(Debug, Clone, PartialEq)]
+enum Type {
+    /// No value seen but nulls, or empty cells
+    Null,
+    Boolean,
+    Integer,
+    Number,
+    String,
+    Date,
+    Timestamp,
+    Array(Box<Type>),
+    Record(Vec<Field>),
+    /// Type of the file format with no equivalent above, e.g. `binary` or
+    /// `decimal(10,2)`
+    Named(String),
+    /// Values of several types
+    Union(Vec<Type>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Field {
+    name: String,
+    ty: Type,
+    nullable: bool,
+}
+
+impl Field {
+    fn new(name: &str, ty: Type, nullable: bool) -> Field {
+        Field {
+            name: name.to_string(),
+            nullable: nullable || ty == Type::Null,
+            ty,
+        }
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Null => f.write_str("null"),
+            Type::Boolean => f.write_str("boolean"),
+            Type::Integer => f.write_str("integer"),
+            Type::Number => f.write_str("number"),
+            Type::String => f.write_str("string"),
+            Type::Date => f.write_str("date"),
+            Type::Timestamp => f.write_str("timestamp"),
+            Type::Array(items) => write!(f, "[{}]", items),
+            Type::Record(fields) => {
+                let fields = fields
+                    .iter()
+                    .map(|field| {
+                        let optional = if field.nullable { "?" } else { "" };
+                        format!("{}{}: {}", field.name, optional, field.ty)
+                    })
+                    .collect::<Vec<String>>();
+
+                write!(f, "{{{}}}", fields.join(", "))
+            }
+            Type::Named(name) => f.write_str(name),
+            Type::Union(types) => {
+                let types =
+                    types.iter().map(Type::to_string).collect::<Vec<_>>();
+
+                f.write_str(&types.join(" | "))
+            }
+        }
+    }
+}
+
+/// Merges the types of the values of a field across records. Fields of
+/// records missing from other records become nullable, and integers mixed
+/// with decimals become numbers.
+fn merge(left: Type, right: Type) -> Type {
+    match (left, right) {
+        (Type::Null, other) | (other, Type::Null) => other,
+        (left, right) if left == right => left,
+        (Type::Integer, Type::Number) | (Type::Number, Type::Integer) => {
+            Type::Number
+        }
+        (Type::Date, Type::Timestamp) | (Type::Timestamp, Type::Date) => {
+            Type::Timestamp
+        }
+        (Type::Array(left), Type::Array(right)) => {
+            Type::Array(Box::new(merge(*left, *right)))
+        }
+        (Type::Record(left), Type::Record(right)) => {
+            Type::Record(merge_fields(left, right))
+        }
+        (Type::Union(mut types), other) | (other, Type::Union(mut types)) => {
+            if !types.contains(&other) {
+                types.push(other);
+            }
+
+            Type::Union(types)
+        }
+        (left, right) => Type::Union(vec![left, right]),
+    }
+}
+
+fn merge_fields(left: Vec<Field>, mut right: Vec<Field>) -> Vec<Field> {
+    let mut fields = Vec::new();
+
+    for mut field in left {
+        match right.iter().position(|other| other.name == field.name) {
+            Some(index) => {
+                let other = right.remove(index);
+
+                field.nullable |= other.nullable;
+                field.ty = merge(field.ty, other.ty);
+            }
+            None => field.nullable = true,
+        }
+
+        fields.push(field);
+    }
+
+    for mut field in right {
+        field.nullable = true;
+        fields.push(field);
+    }
+
+    fields
+}
+
+/// Infers the type of a JSON value, strings holding dates or timestamps
+/// being typed as such.
+fn json_type(value: &Value) -> Type {
+    match value {
+        Value::Null => Type::Null,
+        Value::Bool(_) => Type::Boolean,
+        Value::Number(number) if number.is_f64() => Type::Number,
+        Value::Number(_) => Type::Integer,
+        Value::String(text) => match text_type(text) {
+            ty @ (Type::Date | Type::Timestamp) => ty,
+            _ => Type::String,
+        },
+        Value::Array(items) => Type::Array(Box::new(
+            items.iter().map(json_type).fold(Type::Null, merge),
+        )),
+        Value::Object(fields) => Type::Record(
+            fields
+                .iter()
+                .map(|(name, value)| Field::new(name, json_type(value), false))
+                .collect(),
+        ),
+    }
+}
+
+/// Infers the type of a CSV cell, empty cells being nulls.
+fn text_type(text: &str) -> Type {
+    let text = text.trim();
+    let bytes = text.as_bytes();
+
+    let is_date = |bytes: &[u8]| {
+        bytes.len() >= 10
+            && bytes[..10].iter().enumerate().all(|(i, byte)| match i {
+                4 | 7 => *byte == b'-',
+                _ => byte.is_ascii_digit(),
+            })
+    };
+
+    if text.is_empty() {
+        Type::Null
+    } else if text.eq_ignore_ascii_case("true")
+        || text.eq_ignore_ascii_case("false")
+    {
+        Type::Boolean
+    } else if text.parse::<i64>().is_ok() {
+        Type::Integer
+    } else if text.parse::<f64>().is_ok()
+        && text.contains(|c: char| c.is_ascii_digit())
+    {
+        Type::Number
+    } else if is_date(bytes) && bytes.len() == 10 {
+        Type::Date
+    } else if is_date(bytes)
+        && matches!(bytes.get(10), Some(b'T' | b' '))
+        && bytes.get(13) == Some(&b':')
+    {
+        Type::Timestamp
+    } else {
+        Type::String
+    }
+}
+
+/// Infers the columns of a CSV sample from its rows. Columns whose cells
+/// are of several types are strings.
+fn csv_record(sample: &str) -> Result<Type> {
+    let table = sample.as_csv()?;
+
+    let fields = table
+        .headers()
+        .iter()
+        .enumerate()
+        .map(|(index, name)| {
+            let cells = table
+                .iter()
+                .map(|row| text_type(row.get(index).unwrap_or_default()))
+                .collect::<Vec<Type>>();
+
+            let nullable = cells.contains(&Type::Null);
+
+            let ty = match cells.into_iter().fold(Type::Null, merge) {
+                Type::Union(_) => Type::String,
+                ty => ty,
+            };
+
+            Field::new(name, ty, nullable)
+        })
+        .collect();
+
+    Ok(Type::Record(fields))
+}
+
+/// Reads the record of an Avro schema, e.g. as printed by `avro-tools
+/// getschema`.
+fn avro_record(schema: &Value) -> Result<Type> {
+    let mut named = BTreeMap::new();
+
+    match avro_type(schema, &mut named) {
+        (record @ Type::Record(_), _) => Ok(record),
+        _ => Err(anyhow!("The Avro schema is not a record")),
+    }
+}
+
+/// Reads an Avro type, and whether it is nullable, i.e. a union with
+/// `null`. Named types are kept in `named`, such that later references to
+/// them are resolved.
+fn avro_type(
+    schema: &Value,
+    named: &mut BTreeMap<String, Type>,
+) -> (Type, bool) {
+    let name = match schema {
+        Value::String(name) => name.as_str(),
+        Value::Array(types) => {
+            let nullable = types.iter().any(|ty| ty == "null");
+
+            let types = types
+                .iter()
+                .filter(|ty| *ty != "null")
+                .map(|ty| avro_type(ty, named).0)
+                .collect::<Vec<Type>>();
+
+            let ty = match types.len() {
+                0 => Type::Null,
+                1 => types.into_iter().next().unwrap_or(Type::Null),
+                _ => Type::Union(types),
+            };
+
+            return (ty, nullable);
+        }
+        Value::Object(_) => {
+            let field = |name: &str| schema.get(name).and_then(Value::as_str);
+
+            let ty = match (field("logicalType"), field("type")) {
+                (Some("date"), _) => Type::Date,
+                (Some(logical), _) if logical.contains("timestamp") => {
+                    Type::Timestamp
+                }
+                (Some("decimal"), _) => Type::Named(format!(
+                    "decimal({},{})",
+                    schema.get("precision").unwrap_or(&Value::Null),
+                    schema.get("scale").unwrap_or(&Value::from(0))
+                )),
+                (_, Some("record")) => {
+                    let fields = schema
+                        .get("fields")
+                        .and_then(Value::as_array)
+                        .map(|fields| {
+                            fields
+                                .iter()
+                                .map(|field| {
+                                    let name = field
+                                        .get("name")
+                                        .and_then(Value::as_str)
+                                        .unwrap_or_default();
+
+                                    let (ty, nullable) = avro_type(
+                                        field
+                                            .get("type")
+                                            .unwrap_or(&Value::Null),
+                                        named,
+                                    );
+
+                                    Field::new(name, ty, nullable)
+                                })
+                                .collect()
+                        })
+                        .unwrap_or_default();
+
+                    Type::Record(fields)
+                }
+                (_, Some("enum")) => Type::Named(
+                    schema
+                        .get("symbols")
+                        .and_then(Value::as_array)
+                        .map(|symbols| {
+                            symbols
+                                .iter()
+                                .map(Value::to_string)
+                                .collect::<Vec<String>>()
+                                .join(" | ")
+                        })
+                        .unwrap_or_else(|| String::from("enum")),
+                ),
+                (_, Some("array")) => Type::Array(Box::new(
+                    avro_type(
+                        schema.get("items").unwrap_or(&Value::Null),
+                        named,
+                    )
+                    .0,
+                )),
+                (_, Some("map")) => Type::Named(format!(
+                    "map<string, {}>",
+                    avro_type(
+                        schema.get("values").unwrap_or(&Value::Null),
+                        named
+                    )
+                    .0
+                )),
+                (_, Some("fixed")) => Type::Named(String::from("binary")),
+                _ => {
+                    return avro_type(
+                        schema.get("type").unwrap_or(&Value::Null),
+                        named,
+                    )
+                }
+            };
+
+            if let Some(name) = field("name") {
+                named.insert(name.to_string(), ty.clone());
+            }
+
+            return (ty, false);
+        }
+        _ => "null",
+    };
+
+    let ty = match name {
+        "null" => return (Type::Null, true),
+        "boolean" => Type::Boolean,
+        "int" | "long" => Type::Integer,
+        "float" | "double" => Type::Number,
+        "string" => Type::String,
+        "bytes" => Type::Named(String::from("binary")),
+        // References to named types, possibly qualified by their namespace
+        name => named
+            .get(name)
+            .or_else(|| named.get(name.rsplit('.').next().unwrap_or(name)))
+            .cloned()
+            .unwrap_or_else(|| Type::Named(name.to_string())),
+    };
+
+    (ty, false)
+}
+
+/// Reads the message of a Parquet schema as printed by `parquet-tools
+/// schema`, one field per line, e.g. `optional binary name (STRING);`.
+fn parquet_record(sample: &str) -> Result<Type> {
+    // Groups being read, the message itself being the first of them
+    let mut groups: Vec<(String, String, String, Vec<Field>)> = Vec::new();
+
+    for line in sample.lines().map(str::trim) {
+        if line.starts_with("message ") {
+            groups.push(Default::default());
+            continue;
+        }
+
+        if groups.is_empty() || line.is_empty() {
+            continue;
+        }
+
+        if line == "}" {
+            let (name, repetition, annotation, fields) =
+                groups.pop().unwrap_or_default();
+
+            let parent = match groups.last_mut() {
+                Some((_, _, _, parent)) => parent,
+                // End of the message
+                None => return Ok(Type::Record(fields)),
+            };
+
+            let ty = parquet_group(&annotation, fields);
+            parent.push(parquet_field(&name, &repetition, ty));
+            continue;
+        }
+
+        let line = line.trim_end_matches([';', '{']).trim();
+        let mut tokens = line.splitn(4, ' ');
+
+        let repetition = tokens.next().unwrap_or_default().to_lowercase();
+        let primitive = tokens.next().unwrap_or_default().to_lowercase();
+        let name = tokens.next().unwrap_or_default().to_string();
+
+        // The annotation may be followed by the field id, e.g. `= 2`
+        let annotation = tokens
+            .next()
+            .unwrap_or_default()
+            .split('=')
+            .next()
+            .unwrap_or_default()
+            .trim();
+
+        let annotation = annotation
+            .strip_prefix('(')
+            .and_then(|annotation| annotation.strip_suffix(')'))
+            .unwrap_or(annotation)
+            .to_uppercase();
+
+        if primitive == "group" {
+            groups.push((name, repetition, annotation, Vec::new()));
+            continue;
+        }
+
+        let ty = parquet_type(&primitive, &annotation);
+
+        if let Some((_, _, _, fields)) = groups.last_mut() {
+            fields.push(parquet_field(&name, &repetition, ty));
+        }
+    }
+
+    Err(anyhow!(
+        "Not a Parquet schema, as printed by `parquet-tools schema`"
+    ))
+}
+
+fn parquet_field(name: &str, repetition: &str, ty: Type) -> Field {
+    match repetition {
+        "repeated" => Field::new(name, Type::Array(Box::new(ty)), false),
+        repetition => Field::new(name, ty, repetition == "optional"),
+    }
+}
+
+/// Type of a group, lists and maps being nested in a repeated group.
+fn parquet_group(annotation: &str, fields: Vec<Field>) -> Type {
+    // The repeated group of lists and maps, or the repeated field of
+    // legacy lists
+    let repeated = match fields.as_slice() {
+        [Field {
+            ty: Type::Array(items),
+            ..
+        }] => Some(items.as_ref()),
+        _ => None,
+    };
+
+    match (annotation, repeated) {
+        ("LIST", Some(Type::Record(items))) if items.len() == 1 => {
+            Type::Array(Box::new(items[0].ty.clone()))
+        }
+        ("LIST", Some(items)) => Type::Array(Box::new(items.clone())),
+        ("MAP" | "MAP_KEY_VALUE", Some(Type::Record(entries)))
+            if entries.len() == 2 =>
+        {
+            Type::Named(format!("map<{}, {}>", entries[0].ty, entries[1].ty))
+        }
+        _ => Type::Record(fields),
+    }
+}
+
+fn parquet_type(primitive: &str, annotation: &str) -> Type {
+    if annotation.starts_with("DECIMAL") {
+        return Type::Named(annotation.to_lowercase().replace(' ', ""));
+    }
+
+    match (primitive, annotation) {
+        ("boolean", _) => Type::Boolean,
+        (_, "DATE") => Type::Date,
+        (_, annotation) if annotation.starts_with("TIMESTAMP") => {
+            Type::Timestamp
+        }
+        ("int96", _) => Type::Timestamp,
+        (_, annotation) if annotation.starts_with("TIME") => {
+            Type::Named(String::from("time"))
+        }
+        ("int32" | "int64", _) => Type::Integer,
+        ("float" | "double", _) => Type::Number,
+        (_, "STRING" | "UTF8" | "ENUM" | "JSON" | "UUID") => Type::String,
+        _ => Type::Named(String::from("binary")),
+    }
+}
+
+/// Reads the `struct<...>` type of an ORC file, as printed by `orc-tools
+/// meta` or `hive --orcfiledump`.
+fn orc_record(sample: &str) -> Result<Type> {
+    let start = sample.find("struct<").ok_or_else(|| {
+        anyhow!("No `struct<...>` type found in the ORC file metadata")
+    })?;
+
+    orc_type(&mut sample[start..].chars().peekable())
+}
+
+fn orc_type(chars: &mut Peekable<Chars>) -> Result<Type> {
+    let name =
+        read_until(chars, |c| c == '<' || c == '(' || c == ',' || c == '>')
+            .to_lowercase();
+
+    let mut args = Vec::new();
+
+    match chars.peek() {
+        Some('<') => {
+            chars.next();
+
+            loop {
+                if name == "struct" {
+                    let field = read_until(chars, |c| c == ':' || c == '>');
+
+                    // Empty structs, i.e. `struct<>`
+                    if chars.next_if_eq(&':').is_none() {
+                        chars.next();
+                        break;
+                    }
+
+                    let ty = orc_type(chars)?;
+                    args.push(Field::new(&field, ty, false));
+                } else {
+                    args.push(Field::new("", orc_type(chars)?, false));
+                }
+
+                match chars.next() {
+                    Some(',') => continue,
+                    Some('>') => break,
+                    _ => return Err(anyhow!("Unbalanced ORC type {}", name)),
+                }
+            }
+        }
+        Some('(') => {
+            let size = read_until(chars, |c| c == ')');
+            chars.next();
+
+            return Ok(match name.as_str() {
+                "decimal" => Type::Named(format!("decimal{})", size)),
+                _ => Type::String,
+            });
+        }
+        _ => {}
+    }
+
+    let ty = match name.as_str() {
+        "struct" => Type::Record(args),
+        "array" => Type::Array(Box::new(
+            args.pop().map(|field| field.ty).unwrap_or(Type::Null),
+        )),
+        "map" if args.len() == 2 => {
+            Type::Named(format!("map<{}, {}>", args[0].ty, args[1].ty))
+        }
+        "uniontype" => {
+            Type::Union(args.into_iter().map(|field| field.ty).collect())
+        }
+        "boolean" => Type::Boolean,
+        "tinyint" | "smallint" | "int" | "bigint" => Type::Integer,
+        "float" | "double" => Type::Number,
+        "string" | "varchar" | "char" => Type::String,
+        "date" => Type::Date,
+        "timestamp" => Type::Timestamp,
+        name => Type::Named(name.to_string()),
+    };
+
+    Ok(ty)
+}
+
+/// Reads the characters up to the first one matching `end`, which is left
+/// unread, and trims them.
+fn read_until(
+    chars: &mut Peekable<Chars>,
+    end: impl Fn(char) -> bool,
+) -> String {
+    let mut text = String::new();
+
+    while let Some(c) = chars.next_if(|c| !end(*c)) {
+        text.push(c);
+    }
+
+    text.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn infers_csv_columns() -> Result<()> {
+        let sample = "id,name,score,active,joined,seen_at\n\
+                      1,Ada,9.5,true,2023-01-02,2023-01-02T10:00:00Z\n\
+                      2,,7,false,2023-02-03,\n\
+                      x3,Bob,8,true,2023-03-04,2023-03-04 08:30:00\n";
+
+        let schema = DataSchema::infer_(FileType::Csv, sample)?;
+
+        assert_eq!(
+            schema.to_string(),
+            "id: string
+name?: string
+score: number
+active: boolean
+joined: date
+seen_at?: timestamp
+"
+        );
+
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn merges_json_records() -> Result<()> {
+        let sample = r#"{"id": 1, "tags": ["a"], "address": {"city": "Lisbon"}, "note": null}
+{"id": 2.5, "tags": [], "address": {"city": "Porto", "zip": "4000"}, "extra": true}
+"#;
+
+        let schema = DataSchema::infer_(FileType::NdJson, sample)?;
+
+        assert_eq!(
+            schema.to_string(),
+            "address: {city: string, zip?: string}
+id: number
+note?: null
+tags: [string]
+extra?: boolean
+"
+        );
+
+        let array = format!("[{}]", sample.trim().replace('\n', ","));
+
+        assert_eq!(DataSchema::infer_(FileType::Json, &array)?, schema);
+        assert!(DataSchema::infer_(FileType::Json, "[1, 2]").is_err());
+        assert!(DataSchema::infer_(
+            FileType::Json,
+            r#"{"type": "object", "properties": {"id": {"type": "integer"}}}"#
+        )
+        .is_err());
+
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn reads_avro_schemas() -> Result<()> {
+        let sample = r#"{
+  "type": "record",
+  "name": "User",
+  "fields": [
+    {"name": "id", "type": "long"},
+    {"name": "email", "type": ["null", "string"], "default": null},
+    {"name": "created", "type": {"type": "long", "logicalType": "timestamp-millis"}},
+    {"name": "role", "type": {"type": "enum", "name": "Role", "symbols": ["ADMIN", "GUEST"]}},
+    {"name": "previous", "type": {"type": "array", "items": "Role"}},
+    {"name": "balance", "type": {"type": "bytes", "logicalType": "decimal", "precision": 10, "scale": 2}}
+  ]
+}"#;
+
+        let schema = DataSchema::infer_(FileType::Avro, sample)?;
+
+        assert_eq!(
+            schema.to_string(),
+            r#"id: integer
+email?: string
+created: timestamp
+role: "ADMIN" | "GUEST"
+previous: ["ADMIN" | "GUEST"]
+balance: decimal(10,2)
+"#
+        );
+
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn reads_parquet_and_orc_metadata() -> Result<()> {
+        let parquet = "message spark_schema {
+  required int64 id;
+  optional binary name (STRING);
+  optional int32 born (DATE);
+  optional int64 seen (TIMESTAMP(MILLIS,true));
+  optional group tags (LIST) {
+    repeated group list {
+      optional binary element (STRING);
+    }
+  }
+  optional group scores (MAP) {
+    repeated group key_value {
+      required binary key (STRING);
+      optional double value;
+    }
+  }
+  required group address {
+    optional binary city (UTF8) = 7;
+  }
+}
+";
+
+        let schema = DataSchema::infer_(FileType::Parquet, parquet)?;
+
+        assert_eq!(
+            schema.to_string(),
+            "id: integer
+name?: string
+born?: date
+seen?: timestamp
+tags?: [string]
+scores?: map<string, number>
+address: {city?: string}
+"
+        );
+
+        let orc = "Processing data file users.orc [length: 1024]
+Structure for users.orc
+File Version: 0.12 with ORC_135
+Rows: 2
+Compression: ZLIB
+Type: struct<id:bigint,name:string,tags:array<string>,price:decimal(10,2),attrs:map<string,int>>
+";
+
+        let schema = DataSchema::infer_(FileType::Orc, orc)?;
+
+        assert_eq!(
+            schema.to_string(),
+            "id: integer
+name: string
+tags: [string]
+price: decimal(10,2)
+attrs: map<string, integer>
+"
+        );
+
+        Ok(())
+    }
+}
@@ -1,12 +1,12 @@
-use super::{AsContext, SchemaFile};
+use super::{sql_schema::SqlSchema, AsContext, SchemaFile};
 use crate::{
-    openai::msg::{GptRole, OpenAIMsg},
-    typescript::ISchemas,
+    openai::msg::OpenAIMsg, prompts::PromptTemplates, typescript::ISchemas,
     JsError, WasmType,
 };
 use anyhow::Result;
 use js_sys::JsString;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use std::{
     collections::BTreeMap,
     fmt::{self, Display},
@@ -103,6 +103,12 @@ impl Database {
     pub fn set_host(&mut self, host: Option<String>) {
         self.host = host;
     }
+
+    /// Typed model of the SQL DDL of the schemas
+    #[wasm_bindgen(getter, js_name = sqlSchema)]
+    pub fn sql_schema(&self) -> SqlSchema {
+        self.sql_schema_()
+    }
 }
 
 impl Database {
@@ -120,6 +126,21 @@ impl Database {
             schemas,
         }
     }
+
+    /// Merges the SQL DDL of the schemas into a typed model. Schemas which
+    /// are not SQL DDL, e.g. the JSON schemas of document stores, are left
+    /// out.
+    pub fn sql_schema_(&self) -> SqlSchema {
+        let mut sql_schema = SqlSchema::default();
+
+        for schema in self.schemas.values() {
+            if let Ok(schema) = SqlSchema::from_ddl_(schema) {
+                sql_schema.merge(schema);
+            }
+        }
+
+        sql_schema
+    }
 }
 
 /// Enum documenting the type of Database/DataWarehouse interface.
@@ -232,40 +253,42 @@ pub enum DbType {
 }
 
 impl AsContext for Database {
-    fn add_context(&self, msg_sequence: &mut Vec<OpenAIMsg>) -> Result<()> {
-        let mut main_prompt = format!(
-            "
-Have in consideration the following {} Database:
-
-- database name: {}
-",
-            self.db_type, self.name
-        );
-
-        if let Some(port) = &self.port {
-            main_prompt =
-                format!("{}\n{} {}", main_prompt, "- database port:", port);
-        }
-
-        if let Some(host) = &self.host {
-            main_prompt =
-                format!("{}\n{} {}", main_prompt, "- database host:", host);
-        }
+    fn add_context(
+        &self,
+        templates: &PromptTemplates,
+        msg_sequence: &mut Vec<OpenAIMsg>,
+    ) -> Result<()> {
+        let main_prompt = templates.render(
+            "database_context",
+            &json!({
+                "db_type": self.db_type.to_string(),
+                "name": self.name,
+                "port": self.port,
+                "host": self.host,
+            }),
+        )?;
 
-        msg_sequence.push(OpenAIMsg {
-            role: GptRole::User,
-            content: main_prompt,
-        });
+        msg_sequence.push(OpenAIMsg::user(&main_prompt));
 
         for (schema_name, schema) in self.schemas.iter() {
-            let prompt = format!("
-Consider the following schema as part of the {} database. It's called `{}` and the schema is:\n```\n{}```
-            ", self.name, schema_name, schema);
-
-            msg_sequence.push(OpenAIMsg {
-                role: GptRole::User,
-                content: prompt,
-            });
+            // SQL DDL is summarised, which is more compact and spares the
+            // comments and statements irrelevant to the code
+            let summary = SqlSchema::from_ddl_(schema)
+                .ok()
+                .filter(|sql_schema| !sql_schema.is_empty())
+                .map(|sql_schema| sql_schema.summary());
+
+            let prompt = templates.render(
+                "database_schema",
+                &json!({
+                    "interface": self.name,
+                    "name": schema_name,
+                    "summary": summary.is_some(),
+                    "schema": summary.as_deref().unwrap_or(schema),
+                }),
+            )?;
+
+            msg_sequence.push(OpenAIMsg::user(&prompt));
         }
 
         Ok(())
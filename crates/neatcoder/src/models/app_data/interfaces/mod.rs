@@ -1,9 +1,16 @@
 pub mod apis;
+pub mod asyncapi;
+pub mod data_schema;
 pub mod dbs;
+pub mod openapi;
+pub mod sql_schema;
 pub mod storage;
 
 use self::{apis::Api, dbs::Database, storage::Storage};
-use crate::{openai::msg::OpenAIMsg, typescript::ISchemas, JsError};
+use crate::{
+    openai::msg::OpenAIMsg, prompts::PromptTemplates, typescript::ISchemas,
+    JsError,
+};
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
@@ -169,30 +176,38 @@ pub type SchemaFile = String;
 
 /// Trait that injects the context of interfaces onto the LLM Message Sequence.
 pub trait AsContext {
-    fn add_context(&self, msg_sequence: &mut Vec<OpenAIMsg>) -> Result<()>;
+    fn add_context(
+        &self,
+        templates: &PromptTemplates,
+        msg_sequence: &mut Vec<OpenAIMsg>,
+    ) -> Result<()>;
 }
 
 impl AsContext for Interface {
-    fn add_context(&self, msg_sequence: &mut Vec<OpenAIMsg>) -> Result<()> {
+    fn add_context(
+        &self,
+        templates: &PromptTemplates,
+        msg_sequence: &mut Vec<OpenAIMsg>,
+    ) -> Result<()> {
         match self.interface_type {
             InterfaceType::Database => self
                 .inner
                 .database
                 .as_ref()
                 .ok_or_else(|| anyhow!("Unable to retrieve inner Database :("))?
-                .add_context(msg_sequence),
+                .add_context(templates, msg_sequence),
             InterfaceType::Storage => self
                 .inner
-                .database
+                .storage
                 .as_ref()
                 .ok_or_else(|| anyhow!("Unable to retrieve inner Storage :("))?
-                .add_context(msg_sequence),
+                .add_context(templates, msg_sequence),
             InterfaceType::Api => self
                 .inner
-                .database
+                .api
                 .as_ref()
                 .ok_or_else(|| anyhow!("Unable to retrieve inner Api :("))?
-                .add_context(msg_sequence),
+                .add_context(templates, msg_sequence),
         }
     }
 }
@@ -299,6 +314,21 @@ impl Interface {
 }
 
 impl Interface {
+    /// Returns the schema files of the interface, by name.
+    pub(crate) fn schemas_(&self) -> Option<&BTreeMap<String, SchemaFile>> {
+        match self.interface_type {
+            InterfaceType::Database => {
+                self.inner.database.as_ref().map(|db| &db.schemas)
+            }
+            InterfaceType::Storage => {
+                self.inner.storage.as_ref().map(|storage| &storage.schemas)
+            }
+            InterfaceType::Api => {
+                self.inner.api.as_ref().map(|api| &api.schemas)
+            }
+        }
+    }
+
     fn schemas_mut(
         &mut self,
     ) -> Result<&mut BTreeMap<String, SchemaFile>, JsError> {
@@ -0,0 +1,995 @@
+use anyhow::{anyhow, Result};
+use js_sys::JsString;
+use parser::parser::{json::AsJson, yaml::AsYaml};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{collections::BTreeMap, fmt::Write};
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::JsError;
+
+/// HTTP methods of the operations of a path item.
+const METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Nesting depth up to which the properties of inline objects are rendered.
+const MAX_SCHEMA_DEPTH: usize = 2;
+
+/// Typed model of an OpenAPI 3.x or Swagger 2 specification, in JSON or
+/// YAML, restricted to what is needed to write code against the API: its
+/// endpoints, their parameters and payloads, and its authentication.
+#[wasm_bindgen]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenApi {
+    pub(crate) title: Option<String>,
+    /// Version of the specification format, e.g. `3.0.3` or `2.0`
+    pub(crate) spec_version: String,
+    pub(crate) base_url: Option<String>,
+    pub(crate) endpoints: Vec<Endpoint>,
+    pub(crate) auth: Vec<AuthScheme>,
+    /// Compact types of the reusable schemas, by name
+    pub(crate) schemas: BTreeMap<String, String>,
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Endpoint {
+    /// HTTP method, in uppercase
+    pub(crate) method: String,
+    pub(crate) path: String,
+    pub(crate) operation_id: Option<String>,
+    pub(crate) summary: Option<String>,
+    pub(crate) tags: Vec<String>,
+    pub(crate) params: Vec<EndpointParam>,
+    /// Compact type of the request body, prefixed by its media type unless
+    /// it is JSON
+    pub(crate) request_body: Option<String>,
+    pub(crate) responses: Vec<EndpointResponse>,
+    /// Names of the security schemes of the endpoint, any of which grants
+    /// access
+    pub(crate) auth: Vec<String>,
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EndpointParam {
+    pub(crate) name: String,
+    /// Location of the parameter, i.e. `path`, `query`, `header` or `cookie`
+    pub(crate) location: String,
+    pub required: bool,
+    pub(crate) schema: String,
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EndpointResponse {
+    /// Status code of the response, or `default`
+    pub(crate) status: String,
+    pub(crate) description: Option<String>,
+    pub(crate) schema: Option<String>,
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthScheme {
+    pub(crate) name: String,
+    /// Human readable kind of the scheme, e.g. `HTTP bearer` or
+    /// `API key in header X-API-Key`
+    pub(crate) kind: String,
+}
+
+#[wasm_bindgen]
+impl OpenApi {
+    /// Parses an OpenAPI 3.x or Swagger 2 specification, in JSON or YAML.
+    #[wasm_bindgen(js_name = fromSpec)]
+    pub fn from_spec(spec: &str) -> Result<OpenApi, JsError> {
+        OpenApi::from_spec_(spec).map_err(|e| JsError::from_str(&e.to_string()))
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn title(&self) -> Option<JsString> {
+        self.title.clone().map(JsString::from)
+    }
+
+    #[wasm_bindgen(getter, js_name = specVersion)]
+    pub fn spec_version(&self) -> JsString {
+        self.spec_version.clone().into()
+    }
+
+    #[wasm_bindgen(getter, js_name = baseUrl)]
+    pub fn base_url(&self) -> Option<JsString> {
+        self.base_url.clone().map(JsString::from)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn endpoints(&self) -> Vec<Endpoint> {
+        self.endpoints.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn auth(&self) -> Vec<AuthScheme> {
+        self.auth.clone()
+    }
+
+    /// Groups of endpoints, i.e. the first tag of each endpoint. Endpoints
+    /// without tags are grouped under the empty string.
+    #[wasm_bindgen(getter)]
+    pub fn groups(&self) -> Vec<String> {
+        let mut groups = Vec::new();
+
+        for endpoint in self.endpoints.iter() {
+            let group = endpoint.group().unwrap_or_default();
+
+            if !groups.contains(&group) {
+                groups.push(group);
+            }
+        }
+
+        groups
+    }
+
+    /// Renders the endpoints of the API compactly, followed by the reusable
+    /// schemas they refer to.
+    pub fn summary(&self) -> String {
+        self.summary_of(None)
+    }
+}
+
+impl OpenApi {
+    pub fn from_spec_(spec: &str) -> Result<OpenApi> {
+        let root = parse_document(spec)?;
+
+        let spec_version = root
+            .get("openapi")
+            .or_else(|| root.get("swagger"))
+            .and_then(scalar)
+            .ok_or_else(|| {
+                anyhow!("Not an OpenAPI or Swagger specification")
+            })?;
+
+        let is_swagger = root.get("swagger").is_some();
+
+        let mut open_api = OpenApi {
+            title: root.pointer("/info/title").and_then(scalar),
+            spec_version,
+            base_url: base_url(&root, is_swagger),
+            ..Default::default()
+        };
+
+        let (schemas, security_schemes) = if is_swagger {
+            ("/definitions", "/securityDefinitions")
+        } else {
+            ("/components/schemas", "/components/securitySchemes")
+        };
+
+        if let Some(Value::Object(schemas)) = root.pointer(schemas) {
+            for (name, schema) in schemas.iter() {
+                open_api
+                    .schemas
+                    .insert(name.clone(), schema_type(&root, schema, 0));
+            }
+        }
+
+        if let Some(Value::Object(schemes)) = root.pointer(security_schemes) {
+            for (name, scheme) in schemes.iter() {
+                open_api.auth.push(AuthScheme {
+                    name: name.clone(),
+                    kind: auth_kind(resolve(&root, scheme)),
+                });
+            }
+        }
+
+        let default_auth = security(root.get("security"));
+
+        if let Some(Value::Object(paths)) = root.get("paths") {
+            for (path, item) in paths.iter() {
+                let item = resolve(&root, item);
+
+                for method in METHODS {
+                    let operation = match item.get(method) {
+                        Some(operation) => operation,
+                        None => continue,
+                    };
+
+                    let endpoint = Endpoint::new(
+                        &root,
+                        path,
+                        method,
+                        item,
+                        operation,
+                        is_swagger,
+                        default_auth.as_ref(),
+                    );
+
+                    open_api.endpoints.push(endpoint);
+                }
+            }
+        }
+
+        Ok(open_api)
+    }
+
+    /// Appends the endpoints and schemas of `other`, e.g. of another file
+    /// of the same API.
+    pub fn merge(&mut self, other: OpenApi) {
+        self.title = self.title.take().or(other.title);
+        self.base_url = self.base_url.take().or(other.base_url);
+        self.endpoints.extend(other.endpoints);
+        self.schemas.extend(other.schemas);
+
+        for scheme in other.auth {
+            if !self.auth.contains(&scheme) {
+                self.auth.push(scheme);
+            }
+        }
+    }
+
+    /// Endpoints of the group `group`, as listed by `groups`.
+    pub fn endpoints_of(&self, group: &str) -> Vec<&Endpoint> {
+        self.endpoints
+            .iter()
+            .filter(|endpoint| endpoint.group().unwrap_or_default() == group)
+            .collect()
+    }
+
+    /// Renders the endpoints of `group`, or all of them, along with the
+    /// schemas they refer to. Schemas referred to by other schemas are
+    /// included as well.
+    pub fn summary_of(&self, group: Option<&str>) -> String {
+        let endpoints = match group {
+            Some(group) => self.endpoints_of(group),
+            None => self.endpoints.iter().collect(),
+        };
+
+        let mut summary = String::new();
+
+        // Writing to a `String` cannot fail
+        if let Some(title) = &self.title {
+            let _ = writeln!(summary, "API: {}", title);
+        }
+
+        if let Some(base_url) = &self.base_url {
+            let _ = writeln!(summary, "Base URL: {}", base_url);
+        }
+
+        if !self.auth.is_empty() {
+            let schemes = self
+                .auth
+                .iter()
+                .map(|scheme| format!("{} ({})", scheme.name, scheme.kind))
+                .collect::<Vec<String>>();
+
+            let _ = writeln!(summary, "Auth: {}", schemes.join(", "));
+        }
+
+        for endpoint in endpoints.iter() {
+            summary.push('\n');
+            summary.push_str(&endpoint.summary());
+        }
+
+        let used = self.used_schemas(&endpoints);
+
+        if !used.is_empty() {
+            summary.push_str("\nSchemas:\n");
+
+            for name in used {
+                let _ = writeln!(summary, "  {} {}", name, self.schemas[name]);
+            }
+        }
+
+        summary
+    }
+
+    /// Names of the reusable schemas referred to by `endpoints`, directly
+    /// or through other schemas.
+    fn used_schemas(&self, endpoints: &[&Endpoint]) -> Vec<&String> {
+        let mut types = Vec::new();
+
+        for endpoint in endpoints.iter() {
+            types.extend(endpoint.params.iter().map(|p| p.schema.as_str()));
+            types.extend(endpoint.request_body.as_deref());
+            types.extend(
+                endpoint
+                    .responses
+                    .iter()
+                    .filter_map(|response| response.schema.as_deref()),
+            );
+        }
+
+        used_schemas(&self.schemas, types)
+    }
+}
+
+#[wasm_bindgen]
+impl Endpoint {
+    #[wasm_bindgen(getter)]
+    pub fn method(&self) -> JsString {
+        self.method.clone().into()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn path(&self) -> JsString {
+        self.path.clone().into()
+    }
+
+    #[wasm_bindgen(getter, js_name = operationId)]
+    pub fn operation_id(&self) -> Option<JsString> {
+        self.operation_id.clone().map(JsString::from)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn tags(&self) -> Vec<String> {
+        self.tags.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn params(&self) -> Vec<EndpointParam> {
+        self.params.clone()
+    }
+
+    #[wasm_bindgen(getter, js_name = requestBody)]
+    pub fn request_body(&self) -> Option<JsString> {
+        self.request_body.clone().map(JsString::from)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn responses(&self) -> Vec<EndpointResponse> {
+        self.responses.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn auth(&self) -> Vec<String> {
+        self.auth.clone()
+    }
+}
+
+impl Endpoint {
+    fn new(
+        root: &Value,
+        path: &str,
+        method: &str,
+        item: &Value,
+        operation: &Value,
+        is_swagger: bool,
+        default_auth: Option<&Vec<String>>,
+    ) -> Endpoint {
+        // Parameters of the operation override the ones of the path
+        let mut params: Vec<&Value> = Vec::new();
+
+        let declared = [item.get("parameters"), operation.get("parameters")];
+
+        for param in declared.into_iter().flatten().filter_map(Value::as_array)
+        {
+            for param in param.iter().map(|param| resolve(root, param)) {
+                let key = (param.get("name"), param.get("in"));
+                params.retain(|p| (p.get("name"), p.get("in")) != key);
+                params.push(param);
+            }
+        }
+
+        let mut endpoint = Endpoint {
+            method: method.to_uppercase(),
+            path: path.to_string(),
+            operation_id: operation.get("operationId").and_then(scalar),
+            summary: operation
+                .get("summary")
+                .or_else(|| operation.get("description"))
+                .and_then(scalar)
+                .map(|summary| first_line(&summary)),
+            tags: operation
+                .get("tags")
+                .and_then(Value::as_array)
+                .map(|tags| tags.iter().filter_map(scalar).collect())
+                .unwrap_or_default(),
+            params: Vec::new(),
+            request_body: None,
+            responses: Vec::new(),
+            auth: security(operation.get("security"))
+                .or_else(|| default_auth.cloned())
+                .unwrap_or_default(),
+        };
+
+        let mut form = Vec::new();
+
+        for param in params {
+            let name = param.get("name").and_then(scalar).unwrap_or_default();
+            let location = param.get("in").and_then(scalar).unwrap_or_default();
+            let required = param
+                .get("required")
+                .and_then(Value::as_bool)
+                .unwrap_or(location == "path");
+
+            match location.as_str() {
+                // Swagger 2 bodies are parameters
+                "body" => {
+                    endpoint.request_body = param
+                        .get("schema")
+                        .map(|schema| schema_type(root, schema, 0));
+                }
+                "formData" => {
+                    let optional = if required { "" } else { "?" };
+                    form.push(format!(
+                        "{}{}: {}",
+                        name,
+                        optional,
+                        schema_type(root, param, 1)
+                    ));
+                }
+                _ => endpoint.params.push(EndpointParam {
+                    name,
+                    location,
+                    required,
+                    // Swagger 2 parameters are schemas themselves
+                    schema: match param.get("schema") {
+                        Some(schema) => schema_type(root, schema, 1),
+                        None if is_swagger => schema_type(root, param, 1),
+                        None => String::from("any"),
+                    },
+                }),
+            }
+        }
+
+        if !form.is_empty() {
+            endpoint.request_body = Some(format!(
+                "application/x-www-form-urlencoded {{{}}}",
+                form.join(", ")
+            ));
+        }
+
+        if let Some(body) = operation.get("requestBody") {
+            endpoint.request_body =
+                content_type(root, resolve(root, body).get("content"));
+        }
+
+        if let Some(Value::Object(responses)) = operation.get("responses") {
+            for (status, response) in responses.iter() {
+                let response = resolve(root, response);
+
+                let schema = match is_swagger {
+                    true => response
+                        .get("schema")
+                        .map(|schema| schema_type(root, schema, 0)),
+                    false => content_type(root, response.get("content")),
+                };
+
+                endpoint.responses.push(EndpointResponse {
+                    status: status.clone(),
+                    description: response
+                        .get("description")
+                        .and_then(scalar)
+                        .map(|description| first_line(&description))
+                        .filter(|description| !description.is_empty()),
+                    schema,
+                });
+            }
+        }
+
+        endpoint
+    }
+
+    /// First tag of the endpoint, under which stubs are grouped.
+    pub fn group(&self) -> Option<String> {
+        self.tags.first().cloned()
+    }
+
+    /// Renders the endpoint compactly, e.g.
+    ///
+    /// ```text
+    /// GET /pets/{id} (getPet): Find a pet [auth: bearer]
+    ///   path id: integer
+    ///   200: Pet
+    /// ```
+    pub fn summary(&self) -> String {
+        let mut summary = format!("{} {}", self.method, self.path);
+
+        if let Some(operation_id) = &self.operation_id {
+            summary.push_str(&format!(" ({})", operation_id));
+        }
+
+        if let Some(description) = &self.summary {
+            summary.push_str(&format!(": {}", description));
+        }
+
+        if !self.auth.is_empty() {
+            summary.push_str(&format!(" [auth: {}]", self.auth.join(" | ")));
+        }
+
+        summary.push('\n');
+
+        for param in self.params.iter() {
+            let optional = if param.required { "" } else { "?" };
+
+            let _ = writeln!(
+                summary,
+                "  {} {}{}: {}",
+                param.location, param.name, optional, param.schema
+            );
+        }
+
+        if let Some(body) = &self.request_body {
+            let _ = writeln!(summary, "  body: {}", body);
+        }
+
+        for response in self.responses.iter() {
+            let schema = match (&response.schema, &response.description) {
+                (Some(schema), _) => schema.clone(),
+                (None, Some(description)) => description.clone(),
+                (None, None) => String::from("no content"),
+            };
+
+            let _ = writeln!(summary, "  {}: {}", response.status, schema);
+        }
+
+        summary
+    }
+}
+
+#[wasm_bindgen]
+impl EndpointParam {
+    #[wasm_bindgen(getter)]
+    pub fn name(&self) -> JsString {
+        self.name.clone().into()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn location(&self) -> JsString {
+        self.location.clone().into()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn schema(&self) -> JsString {
+        self.schema.clone().into()
+    }
+}
+
+#[wasm_bindgen]
+impl EndpointResponse {
+    #[wasm_bindgen(getter)]
+    pub fn status(&self) -> JsString {
+        self.status.clone().into()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn schema(&self) -> Option<JsString> {
+        self.schema.clone().map(JsString::from)
+    }
+}
+
+#[wasm_bindgen]
+impl AuthScheme {
+    #[wasm_bindgen(getter)]
+    pub fn name(&self) -> JsString {
+        self.name.clone().into()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn kind(&self) -> JsString {
+        self.kind.clone().into()
+    }
+}
+
+/// Parses a specification document, in JSON or YAML, into a JSON value.
+pub(crate) fn parse_document(spec: &str) -> Result<Value> {
+    match spec.as_json() {
+        Ok(json) => Ok(json),
+        Err(_) => {
+            let yaml = spec.as_yaml().map_err(|e| {
+                anyhow!("The specification is neither JSON nor YAML: {}", e)
+            })?;
+
+            Ok(serde_json::to_value(yaml)?)
+        }
+    }
+}
+
+/// Follows the local `$ref` of `value`, if any. References which cannot be
+/// resolved, e.g. to other files, are left as is.
+pub(crate) fn resolve<'a>(root: &'a Value, value: &'a Value) -> &'a Value {
+    let mut value = value;
+
+    // Bounded, in case of cyclic references
+    for _ in 0..8 {
+        let target = value
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|reference| reference.strip_prefix('#'))
+            .and_then(|pointer| root.pointer(pointer));
+
+        match target {
+            Some(target) => value = target,
+            None => break,
+        }
+    }
+
+    value
+}
+
+/// Renders a JSON schema as a compact type, e.g. `{id: integer, tags?:
+/// [string]}`. References to reusable schemas are rendered as their name.
+pub(crate) fn schema_type(
+    root: &Value,
+    schema: &Value,
+    depth: usize,
+) -> String {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        return reference
+            .rsplit('/')
+            .next()
+            .unwrap_or(reference)
+            .to_string();
+    }
+
+    for (keyword, separator) in
+        [("oneOf", " | "), ("anyOf", " | "), ("allOf", " & ")]
+    {
+        if let Some(Value::Array(schemas)) = schema.get(keyword) {
+            return schemas
+                .iter()
+                .map(|schema| schema_type(root, schema, depth))
+                .collect::<Vec<String>>()
+                .join(separator);
+        }
+    }
+
+    if let Some(Value::Array(values)) = schema.get("enum") {
+        return values
+            .iter()
+            .map(Value::to_string)
+            .collect::<Vec<String>>()
+            .join(" | ");
+    }
+
+    let ty = match schema.get("type") {
+        Some(Value::String(ty)) => ty.clone(),
+        // OpenAPI 3.1 allows lists of types, e.g. `[string, "null"]`
+        Some(Value::Array(types)) => types
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<&str>>()
+            .join(" | "),
+        _ if schema.get("properties").is_some() => String::from("object"),
+        _ => String::from("any"),
+    };
+
+    let mut rendered = match ty.as_str() {
+        "array" => format!(
+            "[{}]",
+            schema
+                .get("items")
+                .map(|items| schema_type(root, items, depth))
+                .unwrap_or_else(|| String::from("any"))
+        ),
+        "object" => object_type(root, schema, depth),
+        _ => match schema.get("format").and_then(Value::as_str) {
+            Some(format) => format!("{}({})", ty, format),
+            None => ty,
+        },
+    };
+
+    if schema.get("nullable").and_then(Value::as_bool) == Some(true) {
+        rendered.push_str(" | null");
+    }
+
+    rendered
+}
+
+fn object_type(root: &Value, schema: &Value, depth: usize) -> String {
+    let properties = match schema.get("properties") {
+        Some(Value::Object(properties)) if depth <= MAX_SCHEMA_DEPTH => {
+            properties
+        }
+        Some(Value::Object(_)) => return String::from("object"),
+        _ => {
+            return match schema.get("additionalProperties") {
+                Some(values) if values.is_object() => {
+                    format!("map<string, {}>", schema_type(root, values, depth))
+                }
+                _ => String::from("object"),
+            }
+        }
+    };
+
+    let required = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|required| required.iter().filter_map(scalar).collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    let fields = properties
+        .iter()
+        .map(|(name, property)| {
+            let optional = if required.contains(name) { "" } else { "?" };
+
+            format!(
+                "{}{}: {}",
+                name,
+                optional,
+                schema_type(root, property, depth + 1)
+            )
+        })
+        .collect::<Vec<String>>();
+
+    format!("{{{}}}", fields.join(", "))
+}
+
+/// Renders the schema of the preferred media type of an OpenAPI 3 `content`
+/// object, JSON being preferred. Other media types prefix the type.
+fn content_type(root: &Value, content: Option<&Value>) -> Option<String> {
+    let content = match content {
+        Some(Value::Object(content)) => content,
+        _ => return None,
+    };
+
+    let (media_type, media) = content
+        .iter()
+        .find(|(media_type, _)| media_type.contains("json"))
+        .or_else(|| content.iter().next())?;
+
+    let ty = media
+        .get("schema")
+        .map(|schema| schema_type(root, schema, 0))
+        .unwrap_or_else(|| String::from("any"));
+
+    match media_type.contains("json") {
+        true => Some(ty),
+        false => Some(format!("{} {}", media_type, ty)),
+    }
+}
+
+fn base_url(root: &Value, is_swagger: bool) -> Option<String> {
+    if !is_swagger {
+        return root.pointer("/servers/0/url").and_then(scalar);
+    }
+
+    let host = root.get("host").and_then(scalar)?;
+    let base_path = root.get("basePath").and_then(scalar).unwrap_or_default();
+    let scheme = root
+        .pointer("/schemes/0")
+        .and_then(scalar)
+        .unwrap_or_else(|| String::from("https"));
+
+    Some(format!("{}://{}{}", scheme, host, base_path))
+}
+
+fn auth_kind(scheme: &Value) -> String {
+    let field = |name: &str| scheme.get(name).and_then(scalar);
+
+    match field("type").as_deref() {
+        Some("http") => {
+            format!("HTTP {}", field("scheme").unwrap_or_default())
+        }
+        Some("basic") => String::from("HTTP basic"),
+        Some("apiKey") => format!(
+            "API key in {} {}",
+            field("in").unwrap_or_default(),
+            field("name").unwrap_or_default()
+        ),
+        Some("oauth2") => String::from("OAuth 2"),
+        Some("openIdConnect") => String::from("OpenID Connect"),
+        Some(other) => other.to_string(),
+        None => String::from("unknown"),
+    }
+}
+
+/// Names of the schemes of a security requirement, `Some` of an empty list
+/// meaning that no authentication is needed.
+fn security(requirements: Option<&Value>) -> Option<Vec<String>> {
+    let requirements = requirements?.as_array()?;
+
+    let mut names = Vec::new();
+
+    for requirement in requirements.iter().filter_map(Value::as_object) {
+        let requirement = requirement
+            .keys()
+            .cloned()
+            .collect::<Vec<String>>()
+            .join(" + ");
+
+        if !requirement.is_empty() && !names.contains(&requirement) {
+            names.push(requirement);
+        }
+    }
+
+    Some(names)
+}
+
+/// Names of the reusable `schemas` referred to by the compact `types`,
+/// directly or through other schemas.
+pub(crate) fn used_schemas<'a: 'b, 'b>(
+    schemas: &'a BTreeMap<String, String>,
+    mut types: Vec<&'b str>,
+) -> Vec<&'a String> {
+    let mut used = Vec::new();
+
+    while let Some(ty) = types.pop() {
+        for name in schemas.keys() {
+            if !used.contains(&name) && mentions(ty, name) {
+                used.push(name);
+                types.push(&schemas[name]);
+            }
+        }
+    }
+
+    used.sort();
+    used
+}
+
+/// Whether the compact type `ty` refers to the schema `name`.
+fn mentions(ty: &str, name: &str) -> bool {
+    ty.match_indices(name).any(|(start, _)| {
+        let end = start + name.len();
+        let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+
+        !ty[..start].ends_with(is_ident) && !ty[end..].starts_with(is_ident)
+    })
+}
+
+pub(crate) fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(string) => Some(string.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Bool(boolean) => Some(boolean.to_string()),
+        _ => None,
+    }
+}
+
+pub(crate) fn first_line(text: &str) -> String {
+    text.lines().next().unwrap_or_default().trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    const OPENAPI: &str = r#"
+openapi: 3.0.3
+info:
+  title: Petstore
+  version: 1.0.0
+servers:
+  - url: https://petstore.io/v1
+security:
+  - bearer: []
+paths:
+  /pets/{petId}:
+    parameters:
+      - name: petId
+        in: path
+        schema:
+          type: integer
+          format: int64
+    get:
+      operationId: getPet
+      summary: Info for a specific pet
+      tags: [pets]
+      parameters:
+        - $ref: '#/components/parameters/Fields'
+      responses:
+        200:
+          description: The pet
+          content:
+            application/json:
+              schema:
+                $ref: '#/components/schemas/Pet'
+        404:
+          description: Not found
+  /health:
+    get:
+      security: []
+      responses:
+        '204':
+          description: ''
+components:
+  parameters:
+    Fields:
+      name: fields
+      in: query
+      schema:
+        type: array
+        items:
+          type: string
+  securitySchemes:
+    bearer:
+      type: http
+      scheme: bearer
+  schemas:
+    Pet:
+      type: object
+      required: [id, name]
+      properties:
+        id:
+          type: integer
+        name:
+          type: string
+        owner:
+          $ref: '#/components/schemas/Owner'
+    Owner:
+      type: object
+      properties:
+        name:
+          type: string
+    Unused:
+      type: string
+"#;
+
+    const SWAGGER: &str = r##"{
+  "swagger": "2.0",
+  "host": "api.acme.com",
+  "basePath": "/v2",
+  "securityDefinitions": {
+    "key": { "type": "apiKey", "in": "header", "name": "X-API-Key" }
+  },
+  "paths": {
+    "/users": {
+      "post": {
+        "security": [{ "key": [] }],
+        "parameters": [
+          { "name": "body", "in": "body", "schema": { "$ref": "#/definitions/User" } },
+          { "name": "dryRun", "in": "query", "type": "boolean" }
+        ],
+        "responses": { "201": { "description": "Created", "schema": { "$ref": "#/definitions/User" } } }
+      }
+    }
+  },
+  "definitions": {
+    "User": { "properties": { "email": { "type": "string", "format": "email" } } }
+  }
+}"##;
+
+    #[wasm_bindgen_test]
+    fn parses_openapi() -> Result<()> {
+        let api = OpenApi::from_spec_(OPENAPI)?;
+
+        assert_eq!(api.spec_version, "3.0.3");
+        assert_eq!(api.groups(), vec!["", "pets"]);
+
+        assert_eq!(
+            api.summary(),
+            "API: Petstore
+Base URL: https://petstore.io/v1
+Auth: bearer (HTTP bearer)
+
+GET /health
+  204: no content
+
+GET /pets/{petId} (getPet): Info for a specific pet [auth: bearer]
+  path petId: integer(int64)
+  query fields?: [string]
+  200: Pet
+  404: Not found
+
+Schemas:
+  Owner {name?: string}
+  Pet {id: integer, name: string, owner?: Owner}
+"
+        );
+
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn parses_swagger() -> Result<()> {
+        let api = OpenApi::from_spec_(SWAGGER)?;
+
+        assert_eq!(api.base_url.as_deref(), Some("https://api.acme.com/v2"));
+        assert_eq!(api.auth[0].kind, "API key in header X-API-Key");
+
+        let endpoint = &api.endpoints[0];
+
+        assert_eq!(endpoint.request_body.as_deref(), Some("User"));
+        assert_eq!(endpoint.params[0].schema, "boolean");
+        assert_eq!(endpoint.auth, vec!["key"]);
+        assert_eq!(api.schemas["User"], "{email?: string(email)}");
+
+        assert!(OpenApi::from_spec_("name: not an api").is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,882 @@
+use anyhow::{anyhow, Result};
+use js_sys::JsString;
+use parser::parser::sql::{
+    ast::{
+        visit_expressions, visit_relations, AlterTableOperation, ColumnDef,
+        ColumnOption, Expr, Ident, ObjectName, Query, SelectItem, SetExpr,
+        Statement, TableConstraint, TableFactor, TableWithJoins,
+    },
+    AsSql,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+    ops::ControlFlow,
+};
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::JsError;
+
+/// Typed model of the SQL DDL of a `Database` interface, built from its
+/// `CREATE TABLE`, `ALTER TABLE` and `CREATE INDEX` statements. Other
+/// statements of the schema files are ignored.
+#[wasm_bindgen]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SqlSchema {
+    pub(crate) tables: Vec<SqlTable>,
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SqlTable {
+    /// Name of the table, qualified by its schema if the DDL does so
+    pub(crate) name: String,
+    pub(crate) columns: Vec<SqlColumn>,
+    pub(crate) primary_key: Vec<String>,
+    pub(crate) foreign_keys: Vec<SqlForeignKey>,
+    pub(crate) indexes: Vec<SqlIndex>,
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SqlColumn {
+    pub(crate) name: String,
+    /// Type of the column, as written in the DDL
+    pub(crate) data_type: String,
+    pub nullable: bool,
+    pub unique: bool,
+    pub(crate) default: Option<String>,
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SqlForeignKey {
+    pub(crate) columns: Vec<String>,
+    pub(crate) foreign_table: String,
+    pub(crate) referred_columns: Vec<String>,
+    pub(crate) on_delete: Option<String>,
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SqlIndex {
+    pub(crate) name: Option<String>,
+    pub(crate) columns: Vec<String>,
+    pub unique: bool,
+}
+
+#[wasm_bindgen]
+impl SqlSchema {
+    /// Parses the DDL statements of `ddl`.
+    #[wasm_bindgen(js_name = fromDdl)]
+    pub fn from_ddl(ddl: &str) -> Result<SqlSchema, JsError> {
+        SqlSchema::from_ddl_(ddl).map_err(|e| JsError::from_str(&e.to_string()))
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn tables(&self) -> Vec<SqlTable> {
+        self.tables.clone()
+    }
+
+    #[wasm_bindgen(js_name = isEmpty)]
+    pub fn is_empty(&self) -> bool {
+        self.tables.is_empty()
+    }
+
+    /// Renders the schema compactly, one line per table followed by its
+    /// foreign keys and indexes, e.g.
+    ///
+    /// ```text
+    /// users(id INT PK, email TEXT NOT NULL UNIQUE, team_id INT)
+    ///   FK (team_id) -> teams(id) ON DELETE CASCADE
+    /// ```
+    pub fn summary(&self) -> String {
+        let mut summary = String::new();
+
+        for table in self.tables.iter() {
+            let columns = table
+                .columns
+                .iter()
+                .map(|column| {
+                    let mut line =
+                        format!("{} {}", column.name, column.data_type);
+
+                    if table.is_primary_key(&column.name) {
+                        line.push_str(" PK");
+                    } else if !column.nullable {
+                        line.push_str(" NOT NULL");
+                    }
+
+                    if column.unique {
+                        line.push_str(" UNIQUE");
+                    }
+
+                    if let Some(default) = &column.default {
+                        line.push_str(&format!(" DEFAULT {}", default));
+                    }
+
+                    line
+                })
+                .collect::<Vec<String>>();
+
+            // Writing to a `String` cannot fail
+            let _ = writeln!(summary, "{}({})", table.name, columns.join(", "));
+
+            if table.primary_key.len() > 1 {
+                let _ = writeln!(
+                    summary,
+                    "  PK ({})",
+                    table.primary_key.join(", ")
+                );
+            }
+
+            for fk in table.foreign_keys.iter() {
+                let _ = write!(
+                    summary,
+                    "  FK ({}) -> {}({})",
+                    fk.columns.join(", "),
+                    fk.foreign_table,
+                    fk.referred_columns.join(", ")
+                );
+
+                match &fk.on_delete {
+                    Some(action) => {
+                        let _ = writeln!(summary, " ON DELETE {}", action);
+                    }
+                    None => summary.push('\n'),
+                }
+            }
+
+            for index in table.indexes.iter() {
+                let _ = writeln!(
+                    summary,
+                    "  {}INDEX {}({})",
+                    if index.unique { "UNIQUE " } else { "" },
+                    index
+                        .name
+                        .as_ref()
+                        .map(|name| format!("{} ", name))
+                        .unwrap_or_default(),
+                    index.columns.join(", ")
+                );
+            }
+        }
+
+        summary
+    }
+
+    /// Checks that the tables and columns referred to by the DML statements
+    /// of `sql` exist in the schema, and returns the issues found.
+    #[wasm_bindgen(js_name = validateQuery)]
+    pub fn validate_query(&self, sql: &str) -> Result<Vec<String>, JsError> {
+        self.validate_query_(sql)
+            .map_err(|e| JsError::from_str(&e.to_string()))
+    }
+}
+
+impl SqlSchema {
+    pub fn from_ddl_(ddl: &str) -> Result<SqlSchema> {
+        let ddl = ddl.replace("\r\n", "\n");
+
+        let sql = ddl.as_str().as_sql().map_err(|e| {
+            anyhow!("Failed to parse the schema as SQL DDL: {}", e)
+        })?;
+
+        let mut schema = SqlSchema::default();
+
+        for statement in sql.iter() {
+            schema.apply(&statement.stmt);
+        }
+
+        Ok(schema)
+    }
+
+    /// Looks up a table by name, ignoring the case and, unless both names
+    /// are qualified, the schema of the table.
+    pub fn table(&self, name: &str) -> Option<&SqlTable> {
+        self.tables
+            .iter()
+            .find(|table| names_match(&table.name, name))
+    }
+
+    fn table_mut(&mut self, name: &str) -> Option<&mut SqlTable> {
+        self.tables
+            .iter_mut()
+            .find(|table| names_match(&table.name, name))
+    }
+
+    /// Adds the tables of `other`, replacing the tables of the same name.
+    pub fn merge(&mut self, other: SqlSchema) {
+        for table in other.tables {
+            self.tables.retain(|existing| existing.name != table.name);
+            self.tables.push(table);
+        }
+    }
+
+    fn apply(&mut self, statement: &Statement) {
+        match statement {
+            Statement::CreateTable {
+                name,
+                columns,
+                constraints,
+                ..
+            } => {
+                let mut table = SqlTable {
+                    name: object_name(name),
+                    columns: Vec::new(),
+                    primary_key: Vec::new(),
+                    foreign_keys: Vec::new(),
+                    indexes: Vec::new(),
+                };
+
+                for column in columns.iter() {
+                    table.add_column(column);
+                }
+
+                for constraint in constraints.iter() {
+                    table.add_constraint(constraint);
+                }
+
+                self.tables.retain(|existing| existing.name != table.name);
+                self.tables.push(table);
+            }
+            Statement::AlterTable { name, operation } => {
+                let table = match self.table_mut(&object_name(name)) {
+                    Some(table) => table,
+                    None => return,
+                };
+
+                match operation {
+                    AlterTableOperation::AddColumn { column_def, .. } => {
+                        table.add_column(column_def)
+                    }
+                    AlterTableOperation::AddConstraint(constraint) => {
+                        table.add_constraint(constraint)
+                    }
+                    AlterTableOperation::DropColumn { column_name, .. } => {
+                        table.columns.retain(|column| {
+                            !column
+                                .name
+                                .eq_ignore_ascii_case(&column_name.value)
+                        });
+                    }
+                    AlterTableOperation::RenameColumn {
+                        old_column_name,
+                        new_column_name,
+                    } => {
+                        for column in table.columns.iter_mut() {
+                            if column
+                                .name
+                                .eq_ignore_ascii_case(&old_column_name.value)
+                            {
+                                column.name = new_column_name.value.clone();
+                            }
+                        }
+                    }
+                    AlterTableOperation::RenameTable { table_name } => {
+                        table.name = object_name(table_name);
+                    }
+                    _ => {}
+                }
+            }
+            Statement::CreateIndex {
+                name,
+                table_name,
+                columns,
+                unique,
+                ..
+            } => {
+                if let Some(table) = self.table_mut(&object_name(table_name)) {
+                    table.indexes.push(SqlIndex {
+                        name: Some(object_name(name)),
+                        columns: columns
+                            .iter()
+                            .map(|column| column.expr.to_string())
+                            .collect(),
+                        unique: *unique,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn validate_query_(&self, sql: &str) -> Result<Vec<String>> {
+        let sql = sql
+            .as_sql()
+            .map_err(|e| anyhow!("Failed to parse the query: {}", e))?;
+
+        let mut issues = Vec::new();
+
+        for statement in sql.iter() {
+            self.validate_statement(&statement.stmt, &mut issues);
+        }
+
+        Ok(issues)
+    }
+
+    fn validate_statement(
+        &self,
+        statement: &Statement,
+        issues: &mut Vec<String>,
+    ) {
+        let mut scope = Scope::default();
+
+        match statement {
+            Statement::Query(query) => scope.add_query(query),
+            Statement::Insert {
+                table_name,
+                columns,
+                source,
+                ..
+            } => {
+                let name = object_name(table_name);
+
+                if let Some(table) = self.table(&name) {
+                    for column in columns.iter() {
+                        if !table.has_column(&column.value) {
+                            issues.push(unknown_column(&column.value, &name));
+                        }
+                    }
+                }
+
+                scope.add_relation(table_name, None);
+                scope.add_query(source);
+            }
+            Statement::Update {
+                table,
+                assignments,
+                from,
+                ..
+            } => {
+                if let TableFactor::Table { name, .. } = &table.relation {
+                    let name = object_name(name);
+
+                    if let Some(table) = self.table(&name) {
+                        for assignment in assignments.iter() {
+                            let column = match assignment.id.last() {
+                                Some(column) => &column.value,
+                                None => continue,
+                            };
+
+                            if !table.has_column(column) {
+                                issues.push(unknown_column(column, &name));
+                            }
+                        }
+                    }
+                }
+
+                scope.add_tables(table);
+
+                if let Some(from) = from {
+                    scope.add_tables(from);
+                }
+            }
+            Statement::Delete { from, using, .. } => {
+                for tables in from.iter().chain(using.iter().flatten()) {
+                    scope.add_tables(tables);
+                }
+            }
+            // Only the statements querying data are validated
+            _ => return,
+        }
+
+        // Subqueries of the conditions may read from other tables, which
+        // their unqualified columns belong to
+        let _ = visit_expressions(statement, |expr| {
+            match expr {
+                Expr::InSubquery { subquery, .. }
+                | Expr::Exists { subquery, .. }
+                | Expr::Subquery(subquery) => scope.add_query(subquery),
+                _ => {}
+            }
+
+            ControlFlow::<()>::Continue(())
+        });
+
+        let _ = visit_relations(statement, |relation| {
+            let name = object_name(relation);
+
+            if self.table(&name).is_none() && !scope.is_cte(&name) {
+                issues.push(format!("Unknown table `{}`", name));
+            }
+
+            ControlFlow::<()>::Continue(())
+        });
+
+        // Tables of the schema which the statement queries, by alias
+        let tables = scope
+            .relations
+            .iter()
+            .filter_map(|(alias, name)| {
+                self.table(name).map(|table| (alias.as_str(), table))
+            })
+            .collect::<BTreeMap<&str, &SqlTable>>();
+
+        // Unqualified columns can only be checked if all the tables they
+        // may belong to are known
+        let resolved = !scope.opaque
+            && !tables.is_empty()
+            && tables.len() == scope.relations.len();
+
+        let _ = visit_expressions(statement, |expr| {
+            match expr {
+                Expr::Identifier(ident) if resolved => {
+                    let known = scope.aliases.contains(&ident.value)
+                        || tables.values().any(|t| t.has_column(&ident.value));
+
+                    if !known {
+                        issues
+                            .push(format!("Unknown column `{}`", ident.value));
+                    }
+                }
+                Expr::CompoundIdentifier(idents) if idents.len() >= 2 => {
+                    let column = &idents[idents.len() - 1].value;
+                    let qualifier = &idents[idents.len() - 2].value;
+
+                    let table = tables.iter().find_map(|(alias, table)| {
+                        alias.eq_ignore_ascii_case(qualifier).then_some(table)
+                    });
+
+                    if let Some(table) = table {
+                        if !table.has_column(column) {
+                            issues.push(unknown_column(column, &table.name));
+                        }
+                    }
+                }
+                _ => {}
+            }
+
+            ControlFlow::<()>::Continue(())
+        });
+
+        issues.dedup();
+    }
+}
+
+#[wasm_bindgen]
+impl SqlTable {
+    #[wasm_bindgen(getter)]
+    pub fn name(&self) -> JsString {
+        self.name.clone().into()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn columns(&self) -> Vec<SqlColumn> {
+        self.columns.clone()
+    }
+
+    #[wasm_bindgen(getter, js_name = primaryKey)]
+    pub fn primary_key(&self) -> Vec<String> {
+        self.primary_key.clone()
+    }
+
+    #[wasm_bindgen(getter, js_name = foreignKeys)]
+    pub fn foreign_keys(&self) -> Vec<SqlForeignKey> {
+        self.foreign_keys.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn indexes(&self) -> Vec<SqlIndex> {
+        self.indexes.clone()
+    }
+
+    #[wasm_bindgen(js_name = hasColumn)]
+    pub fn has_column(&self, name: &str) -> bool {
+        self.columns
+            .iter()
+            .any(|column| column.name.eq_ignore_ascii_case(name))
+    }
+}
+
+impl SqlTable {
+    fn is_primary_key(&self, column: &str) -> bool {
+        self.primary_key
+            .iter()
+            .any(|key| key.eq_ignore_ascii_case(column))
+    }
+
+    fn column_mut(&mut self, name: &str) -> Option<&mut SqlColumn> {
+        self.columns
+            .iter_mut()
+            .find(|column| column.name.eq_ignore_ascii_case(name))
+    }
+
+    fn add_column(&mut self, column: &ColumnDef) {
+        let name = column.name.value.clone();
+
+        let mut sql_column = SqlColumn {
+            name: name.clone(),
+            data_type: column.data_type.to_string(),
+            nullable: true,
+            unique: false,
+            default: None,
+        };
+
+        for option in column.options.iter() {
+            match &option.option {
+                ColumnOption::Null => sql_column.nullable = true,
+                ColumnOption::NotNull => sql_column.nullable = false,
+                ColumnOption::Default(expr) => {
+                    sql_column.default = Some(expr.to_string())
+                }
+                ColumnOption::Unique { is_primary: true } => {
+                    sql_column.nullable = false;
+                    self.primary_key = vec![name.clone()];
+                }
+                ColumnOption::Unique { is_primary: false } => {
+                    sql_column.unique = true
+                }
+                ColumnOption::ForeignKey {
+                    foreign_table,
+                    referred_columns,
+                    on_delete,
+                    ..
+                } => self.foreign_keys.push(SqlForeignKey {
+                    columns: vec![name.clone()],
+                    foreign_table: object_name(foreign_table),
+                    referred_columns: idents(referred_columns),
+                    on_delete: on_delete.map(|action| action.to_string()),
+                }),
+                _ => {}
+            }
+        }
+
+        self.columns.push(sql_column);
+    }
+
+    fn add_constraint(&mut self, constraint: &TableConstraint) {
+        match constraint {
+            TableConstraint::Unique {
+                columns,
+                is_primary: true,
+                ..
+            } => {
+                self.primary_key = idents(columns);
+
+                for key in columns.iter() {
+                    if let Some(column) = self.column_mut(&key.value) {
+                        column.nullable = false;
+                    }
+                }
+            }
+            TableConstraint::Unique {
+                name,
+                columns,
+                is_primary: false,
+            } => {
+                match (columns.as_slice(), name) {
+                    // Unnamed single-column constraints are shown inline
+                    ([key], None) if self.column_mut(&key.value).is_some() => {
+                        if let Some(column) = self.column_mut(&key.value) {
+                            column.unique = true;
+                        }
+                    }
+                    _ => self.indexes.push(SqlIndex {
+                        name: name.as_ref().map(|name| name.value.clone()),
+                        columns: idents(columns),
+                        unique: true,
+                    }),
+                }
+            }
+            TableConstraint::ForeignKey {
+                columns,
+                foreign_table,
+                referred_columns,
+                on_delete,
+                ..
+            } => self.foreign_keys.push(SqlForeignKey {
+                columns: idents(columns),
+                foreign_table: object_name(foreign_table),
+                referred_columns: idents(referred_columns),
+                on_delete: on_delete.map(|action| action.to_string()),
+            }),
+            TableConstraint::Index { name, columns, .. } => {
+                self.indexes.push(SqlIndex {
+                    name: name.as_ref().map(|name| name.value.clone()),
+                    columns: idents(columns),
+                    unique: false,
+                })
+            }
+            _ => {}
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl SqlColumn {
+    #[wasm_bindgen(getter)]
+    pub fn name(&self) -> JsString {
+        self.name.clone().into()
+    }
+
+    #[wasm_bindgen(getter, js_name = dataType)]
+    pub fn data_type(&self) -> JsString {
+        self.data_type.clone().into()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn default(&self) -> Option<JsString> {
+        self.default.clone().map(JsString::from)
+    }
+}
+
+#[wasm_bindgen]
+impl SqlForeignKey {
+    #[wasm_bindgen(getter)]
+    pub fn columns(&self) -> Vec<String> {
+        self.columns.clone()
+    }
+
+    #[wasm_bindgen(getter, js_name = foreignTable)]
+    pub fn foreign_table(&self) -> JsString {
+        self.foreign_table.clone().into()
+    }
+
+    #[wasm_bindgen(getter, js_name = referredColumns)]
+    pub fn referred_columns(&self) -> Vec<String> {
+        self.referred_columns.clone()
+    }
+
+    #[wasm_bindgen(getter, js_name = onDelete)]
+    pub fn on_delete(&self) -> Option<JsString> {
+        self.on_delete.clone().map(JsString::from)
+    }
+}
+
+#[wasm_bindgen]
+impl SqlIndex {
+    #[wasm_bindgen(getter)]
+    pub fn name(&self) -> Option<JsString> {
+        self.name.clone().map(JsString::from)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn columns(&self) -> Vec<String> {
+        self.columns.clone()
+    }
+}
+
+/// Tables a statement reads from or writes to, along with the names it
+/// defines itself, i.e. the common table expressions and the aliases of the
+/// selected expressions.
+#[derive(Default)]
+struct Scope {
+    /// Names of the tables, by alias or by name if they have no alias
+    relations: BTreeMap<String, String>,
+    ctes: BTreeSet<String>,
+    aliases: BTreeSet<String>,
+    /// Whether the statement reads from subqueries or table functions, whose
+    /// columns are unknown
+    opaque: bool,
+}
+
+impl Scope {
+    fn is_cte(&self, name: &str) -> bool {
+        self.ctes.iter().any(|cte| cte.eq_ignore_ascii_case(name))
+    }
+
+    fn add_relation(&mut self, name: &ObjectName, alias: Option<&Ident>) {
+        let name = object_name(name);
+
+        if self.is_cte(&name) {
+            self.opaque = true;
+        }
+
+        let alias = match alias {
+            Some(alias) => alias.value.clone(),
+            // Columns can be qualified with the table name without its
+            // schema
+            None => unqualified(&name).to_string(),
+        };
+
+        self.relations.insert(alias, name);
+    }
+
+    fn add_query(&mut self, query: &Query) {
+        if let Some(with) = &query.with {
+            for cte in with.cte_tables.iter() {
+                self.ctes.insert(cte.alias.name.value.clone());
+            }
+
+            for cte in with.cte_tables.iter() {
+                self.add_query(&cte.query);
+            }
+        }
+
+        self.add_set_expr(&query.body);
+    }
+
+    fn add_set_expr(&mut self, body: &SetExpr) {
+        match body {
+            SetExpr::Select(select) => {
+                for item in select.projection.iter() {
+                    if let SelectItem::ExprWithAlias { alias, .. } = item {
+                        self.aliases.insert(alias.value.clone());
+                    }
+                }
+
+                for tables in select.from.iter() {
+                    self.add_tables(tables);
+                }
+            }
+            SetExpr::Query(query) => self.add_query(query),
+            SetExpr::SetOperation { left, right, .. } => {
+                self.add_set_expr(left);
+                self.add_set_expr(right);
+            }
+            _ => {}
+        }
+    }
+
+    fn add_tables(&mut self, tables: &TableWithJoins) {
+        let relations = std::iter::once(&tables.relation)
+            .chain(tables.joins.iter().map(|join| &join.relation));
+
+        for relation in relations {
+            match relation {
+                TableFactor::Table { name, alias, .. } => self.add_relation(
+                    name,
+                    alias.as_ref().map(|alias| &alias.name),
+                ),
+                TableFactor::Derived { subquery, .. } => {
+                    self.opaque = true;
+                    self.add_query(subquery);
+                }
+                TableFactor::NestedJoin {
+                    table_with_joins, ..
+                } => self.add_tables(table_with_joins),
+                _ => self.opaque = true,
+            }
+        }
+    }
+}
+
+fn object_name(name: &ObjectName) -> String {
+    idents(&name.0).join(".")
+}
+
+fn idents(idents: &[Ident]) -> Vec<String> {
+    idents.iter().map(|ident| ident.value.clone()).collect()
+}
+
+/// Whether the table names `a` and `b` match. The schema qualifying a name
+/// is only compared if both names are qualified.
+fn names_match(a: &str, b: &str) -> bool {
+    if a.contains('.') && b.contains('.') {
+        return a.eq_ignore_ascii_case(b);
+    }
+
+    unqualified(a).eq_ignore_ascii_case(unqualified(b))
+}
+
+fn unqualified(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or(name)
+}
+
+fn unknown_column(column: &str, table: &str) -> String {
+    format!("Unknown column `{}` in table `{}`", column, table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    const DDL: &str = "-- Accounts of the app
+CREATE TABLE teams (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR(100) NOT NULL UNIQUE
+);
+
+CREATE TABLE users (
+    id INT NOT NULL,
+    email TEXT NOT NULL,
+    team_id INT REFERENCES teams(id) ON DELETE CASCADE,
+    created_at TIMESTAMP DEFAULT now(),
+    PRIMARY KEY (id)
+);
+
+ALTER TABLE users ADD COLUMN nickname TEXT;
+
+CREATE UNIQUE INDEX users_email ON users (email);
+";
+
+    #[wasm_bindgen_test]
+    fn parses_ddl() -> Result<()> {
+        let schema = SqlSchema::from_ddl_(DDL)?;
+
+        assert_eq!(schema.tables.len(), 2);
+
+        let users = schema.table("public.users").unwrap();
+
+        assert_eq!(users.primary_key, vec!["id"]);
+        assert!(users.has_column("NICKNAME"));
+        assert!(!users.columns[0].nullable);
+        assert_eq!(users.foreign_keys[0].foreign_table, "teams");
+        assert_eq!(users.indexes[0].name.as_deref(), Some("users_email"));
+
+        assert_eq!(
+            schema.summary(),
+            "teams(id SERIAL PK, name VARCHAR(100) NOT NULL UNIQUE)
+users(id INT PK, email TEXT NOT NULL, team_id INT, created_at TIMESTAMP DEFAULT now(), nickname TEXT)
+  FK (team_id) -> teams(id) ON DELETE CASCADE
+  UNIQUE INDEX users_email (email)
+"
+        );
+
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn validates_queries() -> Result<()> {
+        let schema = SqlSchema::from_ddl_(DDL)?;
+
+        let valid = [
+            "SELECT u.email, t.name AS team FROM users u JOIN teams t ON u.team_id = t.id WHERE t.id = $1 ORDER BY team;",
+            "INSERT INTO users (id, email) VALUES (?, ?);",
+            "UPDATE users SET nickname = 'neo' WHERE id = 1;",
+            "WITH recent AS (SELECT id FROM users) SELECT anything FROM recent;",
+            "SELECT t.name FROM teams t WHERE t.id IN (SELECT team_id FROM users WHERE nickname = 'neo');",
+            "SELECT name FROM teams WHERE EXISTS (SELECT 1 FROM users WHERE team_id = teams.id);",
+        ];
+
+        for query in valid {
+            assert!(schema.validate_query_(query)?.is_empty(), "{}", query);
+        }
+
+        assert_eq!(
+            schema
+                .validate_query_("SELECT u.mail FROM users u WHERE age > 3;")?,
+            vec![
+                "Unknown column `mail` in table `users`",
+                "Unknown column `age`",
+            ]
+        );
+
+        assert_eq!(
+            schema.validate_query_(
+                "INSERT INTO accounts (id) VALUES (1);\nUPDATE teams SET label = 'a';"
+            )?,
+            vec![
+                "Unknown table `accounts`",
+                "Unknown column `label` in table `teams`",
+            ]
+        );
+
+        assert_eq!(
+            schema.validate_query_(
+                "SELECT name FROM teams WHERE id IN (SELECT team FROM users);"
+            )?,
+            vec!["Unknown column `team`"]
+        );
+
+        Ok(())
+    }
+}
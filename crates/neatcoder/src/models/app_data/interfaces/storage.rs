@@ -1,18 +1,19 @@
 use crate::{
-    openai::msg::{GptRole, OpenAIMsg},
-    typescript::ISchemas,
+    openai::msg::OpenAIMsg, prompts::PromptTemplates, typescript::ISchemas,
     JsError, WasmType,
 };
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use js_sys::JsString;
+use parser::parser::proto::AsProto;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use std::{
     collections::BTreeMap,
     fmt::{self, Display},
 };
 use wasm_bindgen::prelude::wasm_bindgen;
 
-use super::{AsContext, SchemaFile};
+use super::{data_schema::DataSchema, AsContext, SchemaFile};
 
 /// Struct documenting a Data storage interface. This refers to more raw storage
 /// solutions that usually provide a direct interface to a file or object-store
@@ -51,8 +52,8 @@ pub enum StorageType {
 #[wasm_bindgen]
 pub enum FileType {
     // === Data Store Formats ===
-    /// A simple CSV file with a few rows should allow the LLM
-    /// to infer the columns and the types
+    /// A simple CSV file with a few rows, from which the columns and their
+    /// types are inferred
     Csv,
     /// A free format file that can be acquired via:
     /// `parquet-tools schema /path/to/your/file.parquet`
@@ -138,39 +139,72 @@ impl Storage {
     pub fn set_region(&mut self, host: Option<String>) {
         self.region = host;
     }
+
+    /// Infers the schema of the sample file `name` of the schemas, as sent
+    /// to the LLM.
+    #[wasm_bindgen(js_name = inferSchema)]
+    pub fn infer_schema(&self, name: &str) -> Result<DataSchema, JsError> {
+        self.infer_schema_(name)
+            .map_err(|e| JsError::from_str(&e.to_string()))
+    }
+}
+
+impl Storage {
+    pub fn infer_schema_(&self, name: &str) -> Result<DataSchema> {
+        let sample = self.schemas.get(name).ok_or_else(|| {
+            anyhow!("No schema named {} in {}", name, self.name)
+        })?;
+
+        DataSchema::infer_(self.file_type, sample)
+    }
 }
 
 impl AsContext for Storage {
-    fn add_context(&self, msg_sequence: &mut Vec<OpenAIMsg>) -> Result<()> {
-        let mut main_prompt = format!(
-            "
-Have in consideration the following {} data storage:
-
-- datastore name: {}
-- file type: {}
-",
-            self.storage_type, self.name, self.file_type
-        );
-
-        if let Some(region) = &self.region {
-            main_prompt =
-                format!("{}\n{} {}", main_prompt, "- region:", region);
-        }
+    fn add_context(
+        &self,
+        templates: &PromptTemplates,
+        msg_sequence: &mut Vec<OpenAIMsg>,
+    ) -> Result<()> {
+        let main_prompt = templates.render(
+            "storage_context",
+            &json!({
+                "storage_type": self.storage_type.to_string(),
+                "name": self.name,
+                "file_type": self.file_type.to_string(),
+                "region": self.region,
+            }),
+        )?;
 
-        msg_sequence.push(OpenAIMsg {
-            role: GptRole::User,
-            content: main_prompt,
-        });
+        msg_sequence.push(OpenAIMsg::user(&main_prompt));
 
         for (schema_name, schema) in self.schemas.iter() {
-            let prompt = format!("
-Consider the following {} schema as part of the {} data storage. It's called `{}` and the schema is:\n```\n{}```
-            ", self.file_type, self.name, schema_name, schema);
-
-            msg_sequence.push(OpenAIMsg {
-                role: GptRole::User,
-                content: prompt,
-            });
+            // `.proto` files are summarised as their messages and enums,
+            // without comments and options, and sample files as the schema
+            // inferred from them. Hand-written schemas are sent as is.
+            let summary = match self.file_type {
+                FileType::ProtoBuf => {
+                    schema.as_str().as_proto().ok().map(|p| p.to_string())
+                }
+                FileType::Xml => None,
+                file_type => DataSchema::infer_(file_type, schema)
+                    .ok()
+                    .filter(|inferred| !inferred.fields.is_empty())
+                    .map(|inferred| inferred.to_string()),
+            };
+
+            let prompt = templates.render(
+                "storage_schema",
+                &json!({
+                    "interface": self.name,
+                    "name": schema_name,
+                    "file_type": self.file_type.to_string(),
+                    "summary": summary.is_some(),
+                    "inferred": !matches!(self.file_type, FileType::ProtoBuf),
+                    "schema": summary.as_deref().unwrap_or(schema),
+                }),
+            )?;
+
+            msg_sequence.push(OpenAIMsg::user(&prompt));
         }
 
         Ok(())
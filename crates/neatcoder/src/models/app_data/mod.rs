@@ -1,28 +1,45 @@
 pub mod interfaces;
 pub mod language;
+pub mod scaffold;
 pub mod task_pool;
 
 use crate::{
+    consts::MAX_REPAIR_ROUNDS,
     endpoints::{
-        scaffold_project::scaffold_project,
+        edit_code::{edit_code, CodeEdit, CodeEditParams},
+        gen_api_stubs::{gen_api_stubs, stub_groups, ApiStubParams, StubKind},
+        gen_config::{
+            gen_config, is_config_file, manifest_files, ConfigGenParams,
+        },
+        gen_docs::{gen_docs, scaffold_modules, DocGenParams, DocKind},
+        gen_tests::{gen_tests, TestGenParams},
+        repair_code::{repair_code, CodeValidation},
+        review_code::{review_code, ReviewFinding, ReviewParams},
+        scaffold_project::{
+            rescaffold_project, scaffold_project, Files, RescaffoldParams,
+        },
         stream_code::{stream_code, CodeGenParams},
     },
-    openai::params::OpenAIParams,
+    openai::{budget::ContextBudget, params::OpenAIParams},
+    prompts::PromptTemplates,
+    providers::Provider,
     typescript::{ICodebase, IInterfaces, ITasksVec},
     JsError, WasmType,
 };
 use anyhow::{anyhow, Result};
 use js_sys::JsString;
-use js_sys::{Error, Function};
+use js_sys::{Date, Error, Function};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use wasm_bindgen::prelude::{wasm_bindgen, JsValue};
 
 use self::{
-    interfaces::{Interface, SchemaFile},
+    interfaces::{sql_schema::SqlSchema, Interface, SchemaFile},
     language::Language,
+    scaffold::{Scaffold, ScaffoldDiff},
     task_pool::{
-        task::Task,
+        scheduler::Scheduler,
+        task::{Task, TokenUsage},
         task_params::{TaskParams, TaskType},
         Pipeline, TaskPool,
     },
@@ -52,8 +69,8 @@ pub struct AppData {
     pub(crate) language: Option<Language>,
     /// Initial prompt containing the specifications of the project
     pub(crate) specs: Option<String>,
-    /// JSON String containing the File System Scaffold
-    /// Example:
+    /// File System Scaffold of the project, serialized as follows (see
+    /// `Scaffold`):
     /// ```json
     /// {
     ///     "src": {
@@ -77,11 +94,20 @@ pub struct AppData {
     ///     }
     ///   }
     /// ```
-    pub(crate) scaffold: Option<String>,
+    pub(crate) scaffold: Option<Scaffold>,
     /// Vector of strings containing the interface config files (e.g. SQL DLLs,
     /// etc.) The BTreeMap represents BTreeMap<Interface Name, Interface>
     pub(crate) interfaces: BTreeMap<String, Interface>,
     pub(crate) task_pool: TaskPool,
+    /// LLM backend used to run the tasks of the project
+    #[serde(default)]
+    pub(crate) provider: Provider,
+    /// Overrides of the default prompt templates
+    #[serde(default, skip_serializing_if = "PromptTemplates::is_empty")]
+    pub(crate) prompts: PromptTemplates,
+    /// Whether a `TestGen` task is queued after each `CodeGen` task
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) auto_tests: bool,
 }
 
 #[wasm_bindgen]
@@ -90,7 +116,7 @@ impl AppData {
     pub fn new(
         language: Option<Language>,
         specs: Option<String>,
-        scaffold: Option<String>,
+        scaffold: Option<Scaffold>,
         interfaces: IInterfaces,
         task_pool: TaskPool,
     ) -> Result<AppData, JsValue> {
@@ -101,6 +127,9 @@ impl AppData {
             scaffold,
             interfaces,
             task_pool,
+            provider: Provider::default(),
+            prompts: PromptTemplates::default(),
+            auto_tests: false,
         })
     }
 
@@ -112,6 +141,12 @@ impl AppData {
         Ok(json.into())
     }
 
+    /// Returns a copy of the application state, on which long running
+    /// requests can be made while the state itself is updated.
+    pub fn snapshot(&self) -> AppData {
+        self.clone()
+    }
+
     #[wasm_bindgen(js_name = castFromString)]
     pub fn cast_from_string(json: String) -> Result<AppData, JsError> {
         let app_state = serde_json::from_str(&json)
@@ -127,6 +162,9 @@ impl AppData {
             scaffold: None,
             interfaces: BTreeMap::new(),
             task_pool: TaskPool::empty(),
+            provider: Provider::default(),
+            prompts: PromptTemplates::default(),
+            auto_tests: false,
         }
     }
 
@@ -144,11 +182,14 @@ impl AppData {
     }
 
     #[wasm_bindgen(getter)]
-    pub fn scaffold(&self) -> Option<JsString> {
-        match &self.scaffold {
-            Some(scaffold) => Some(scaffold.clone().into()),
-            None => None,
-        }
+    pub fn scaffold(&self) -> Option<Scaffold> {
+        self.scaffold.clone()
+    }
+
+    /// Replaces the scaffold, e.g. after files were added, moved or removed.
+    #[wasm_bindgen(setter)]
+    pub fn set_scaffold(&mut self, scaffold: Option<Scaffold>) {
+        self.scaffold = scaffold;
     }
 
     #[wasm_bindgen(getter)]
@@ -156,6 +197,31 @@ impl AppData {
         BTreeMap::to_extern(self.interfaces.clone())
     }
 
+    #[wasm_bindgen(getter)]
+    pub fn provider(&self) -> Provider {
+        self.provider.clone()
+    }
+
+    #[wasm_bindgen(js_name = setProvider)]
+    pub fn set_provider(&mut self, provider: Provider) {
+        self.provider = provider;
+    }
+
+    #[wasm_bindgen(js_name = setPromptTemplates)]
+    pub fn set_prompt_templates(&mut self, prompts: &PromptTemplates) {
+        self.prompts = prompts.clone();
+    }
+
+    #[wasm_bindgen(getter, js_name = autoTests)]
+    pub fn auto_tests(&self) -> bool {
+        self.auto_tests
+    }
+
+    #[wasm_bindgen(js_name = setAutoTests)]
+    pub fn set_auto_tests(&mut self, auto_tests: bool) {
+        self.auto_tests = auto_tests;
+    }
+
     #[wasm_bindgen(getter, js_name = taskPool)]
     pub fn task_pool(&self) -> TaskPool {
         self.task_pool.clone()
@@ -175,6 +241,186 @@ impl AppData {
         Vec::to_extern(tasks)
     }
 
+    /// Returns the to-do tasks whose dependencies are all done.
+    #[wasm_bindgen(js_name = getReadyTasks)]
+    pub fn get_ready_tasks(&self) -> Result<ITasksVec, JsError> {
+        let tasks: Vec<_> =
+            self.task_pool.ready_tasks().into_iter().cloned().collect();
+
+        Vec::to_extern(tasks)
+    }
+
+    #[wasm_bindgen(js_name = addDependency)]
+    pub fn add_dependency(
+        &mut self,
+        task_id: usize,
+        dependency_id: usize,
+    ) -> Result<(), JsError> {
+        self.task_pool
+            .add_dependency(task_id, dependency_id)
+            .map_err(|e| JsError::from_str(&e.to_string()))
+    }
+
+    #[wasm_bindgen(js_name = removeDependency)]
+    pub fn remove_dependency(&mut self, task_id: usize, dependency_id: usize) {
+        self.task_pool.remove_dependency(task_id, dependency_id)
+    }
+
+    /// Starts a new attempt of a to-do or failed task.
+    #[wasm_bindgen(js_name = startTask)]
+    pub fn start_task(
+        &mut self,
+        task_id: usize,
+        ai_params: &OpenAIParams,
+    ) -> Result<(), JsError> {
+        self.task_pool
+            .start_task(task_id, &ai_params.model.as_string(), Date::now())
+            .map_err(|e| JsError::from_str(&e.to_string()))
+    }
+
+    #[wasm_bindgen(js_name = completeTask)]
+    pub fn complete_task(
+        &mut self,
+        task_id: usize,
+        usage: Option<TokenUsage>,
+    ) -> Result<(), JsError> {
+        self.task_pool
+            .complete_task(task_id, usage, Date::now())
+            .map_err(|e| JsError::from_str(&e.to_string()))?;
+
+        self.mark_generated(task_id);
+
+        Ok(())
+    }
+
+    #[wasm_bindgen(js_name = failTask)]
+    pub fn fail_task(
+        &mut self,
+        task_id: usize,
+        error: String,
+        usage: Option<TokenUsage>,
+    ) -> Result<(), JsError> {
+        self.task_pool
+            .fail_task(task_id, &error, usage, Date::now())
+            .map_err(|e| JsError::from_str(&e.to_string()))
+    }
+
+    #[wasm_bindgen(js_name = skipTask)]
+    pub fn skip_task(&mut self, task_id: usize) -> Result<(), JsError> {
+        self.task_pool
+            .skip_task(task_id)
+            .map_err(|e| JsError::from_str(&e.to_string()))
+    }
+
+    /// Moves a done or skipped task back to the to-do tasks.
+    #[wasm_bindgen(js_name = reopenTask)]
+    pub fn reopen_task(&mut self, task_id: usize) -> Result<(), JsError> {
+        self.task_pool
+            .reopen_task(task_id)
+            .map_err(|e| JsError::from_str(&e.to_string()))
+    }
+
+    /// Starts the next batch of tasks which can run concurrently within the
+    /// limits of the `scheduler`, and returns them.
+    #[wasm_bindgen(js_name = scheduleTasks)]
+    pub fn schedule_tasks(
+        &mut self,
+        scheduler: &mut Scheduler,
+        ai_params: &OpenAIParams,
+    ) -> Result<ITasksVec, JsError> {
+        let budget = ContextBudget::new(ai_params);
+
+        // Rough estimate, as the codebase sent as context is unknown here
+        let scaffold = self.scaffold.as_ref().map(Scaffold::render);
+
+        let context_tokens = [&self.specs, &scaffold]
+            .iter()
+            .filter_map(|text| text.as_ref())
+            .map(|text| budget.encoding.count_tokens(text))
+            .sum::<usize>();
+
+        let estimate = |task: &Task| {
+            let tokens = context_tokens
+                + budget.encoding.count_tokens(&task.description)
+                + budget.completion_tokens;
+
+            tokens.min(budget.context_window) as u32
+        };
+
+        let batch = scheduler
+            .next_batch(
+                &mut self.task_pool,
+                &ai_params.model.as_string(),
+                Date::now(),
+                estimate,
+            )
+            .map_err(|e| JsError::from_str(&e.to_string()))?;
+
+        let tasks: Vec<_> = batch
+            .iter()
+            .filter_map(|id| self.task_pool.todo.tasks.get(id))
+            .cloned()
+            .collect();
+
+        Vec::to_extern(tasks)
+    }
+
+    #[wasm_bindgen(js_name = completeScheduledTask)]
+    pub fn complete_scheduled_task(
+        &mut self,
+        scheduler: &mut Scheduler,
+        task_id: usize,
+        usage: Option<TokenUsage>,
+    ) -> Result<(), JsError> {
+        scheduler
+            .complete(&mut self.task_pool, task_id, usage, Date::now())
+            .map_err(|e| JsError::from_str(&e.to_string()))?;
+
+        self.mark_generated(task_id);
+
+        Ok(())
+    }
+
+    /// Marks a scheduled task as failed, and returns whether it was queued
+    /// again to be retried.
+    #[wasm_bindgen(js_name = failScheduledTask)]
+    pub fn fail_scheduled_task(
+        &mut self,
+        scheduler: &mut Scheduler,
+        task_id: usize,
+        error: String,
+        usage: Option<TokenUsage>,
+    ) -> Result<bool, JsError> {
+        scheduler
+            .fail(&mut self.task_pool, task_id, &error, usage, Date::now())
+            .map_err(|e| JsError::from_str(&e.to_string()))
+    }
+
+    #[wasm_bindgen(js_name = getFailedTasks)]
+    pub fn get_failed_tasks(&self) -> Result<ITasksVec, JsError> {
+        let tasks: Vec<_> =
+            self.task_pool.failed_tasks().into_iter().cloned().collect();
+
+        Vec::to_extern(tasks)
+    }
+
+    /// Returns the tasks in progress since more than `timeout_ms`
+    /// milliseconds.
+    #[wasm_bindgen(js_name = getStuckTasks)]
+    pub fn get_stuck_tasks(
+        &self,
+        timeout_ms: f64,
+    ) -> Result<ITasksVec, JsError> {
+        let tasks: Vec<_> = self
+            .task_pool
+            .stuck_tasks(Date::now(), timeout_ms)
+            .into_iter()
+            .cloned()
+            .collect();
+
+        Vec::to_extern(tasks)
+    }
+
     #[wasm_bindgen(js_name = getDoneTasks)]
     pub fn get_done_tasks(&self) -> Result<ITasksVec, JsError> {
         let tasks: Vec<_> = self
@@ -287,10 +533,12 @@ impl AppData {
             JsError::from_str("Failed to retrieve a language")
         })?;
 
-        let (scaffold_json, files) = scaffold_project(
+        let (scaffold, files, configs) = scaffold_project(
+            &self.provider,
             language,
             ai_params,
             task_params,
+            &self.prompts,
             request_callback,
         )
         .await
@@ -314,11 +562,56 @@ impl AppData {
                 .add_todo(&file.name, &file.description, task_params);
         }
 
-        self.scaffold = Some(scaffold_json.to_string());
+        // Files are generated after the modules they build upon
+        self.task_pool.infer_dependencies();
+
+        if self.auto_tests {
+            self.add_test_tasks()
+                .map_err(|e| JsError::from_str(&e.to_string()))?;
+        }
+
+        let configs = configs
+            .iter()
+            .map(|file| {
+                ConfigGenParams::for_scaffold_file(
+                    &file.name,
+                    &file.description,
+                )
+            })
+            .collect();
+
+        self.add_config_tasks_(configs)
+            .map_err(|e| JsError::from_str(&e.to_string()))?;
+
+        self.scaffold = Some(scaffold);
 
         Ok(())
     }
 
+    /// Extends the existing project with the change request of
+    /// `task_params`. The scaffold is updated with the resulting diff, whose
+    /// added and edited files are turned into tasks. The diff is returned
+    /// such that the removed and renamed files are updated on disk.
+    #[wasm_bindgen(js_name = rescaffoldProject)]
+    pub async fn rescaffold_project(
+        &mut self,
+        ai_params: &OpenAIParams,
+        task_params: RescaffoldParams,
+        codebase: ICodebase,
+        request_callback: &Function,
+    ) -> Result<ScaffoldDiff, JsError> {
+        let codebase = BTreeMap::from_extern(codebase)?;
+
+        self.rescaffold_project_(
+            ai_params,
+            &task_params,
+            codebase,
+            request_callback,
+        )
+        .await
+        .map_err(|e| JsError::from_str(&e.to_string()))
+    }
+
     #[wasm_bindgen(js_name = streamCode)]
     pub fn stream_code(
         &mut self,
@@ -338,13 +631,355 @@ impl AppData {
 
         Ok(req_body)
     }
+
+    /// Adds a task modifying the existing file `filename` according to
+    /// `instruction`, and returns its ID.
+    #[wasm_bindgen(js_name = addEditTask)]
+    pub fn add_edit_task(
+        &mut self,
+        filename: String,
+        instruction: String,
+    ) -> Result<usize, JsError> {
+        let description = instruction.clone();
+
+        let task_params = TaskParams::new_(
+            TaskType::CodeEdit,
+            Box::new(CodeEditParams {
+                filename: filename.clone(),
+                instruction,
+            }),
+        )
+        .map_err(|e| JsError::from_str(&e.to_string()))?;
+
+        Ok(self
+            .task_pool
+            .add_todo(&filename, &description, task_params))
+    }
+
+    /// Adds a task writing the unit tests of the existing file `filename`,
+    /// and returns its ID.
+    #[wasm_bindgen(js_name = addTestTask)]
+    pub fn add_test_task(
+        &mut self,
+        filename: String,
+    ) -> Result<usize, JsError> {
+        let language = self
+            .language_()
+            .map_err(|e| JsError::from_str(&e.to_string()))?;
+
+        let test_gen = TestGenParams::for_file(&filename, language)
+            .ok_or_else(|| {
+                JsError::from_str(&format!(
+                    "Unit tests are not supported for {}",
+                    language.name()
+                ))
+            })?;
+
+        self.add_test_task_(test_gen)
+            .map_err(|e| JsError::from_str(&e.to_string()))
+    }
+
+    /// Writes the unit tests of the file of a `TestGen` task, whose current
+    /// content is `content`, and returns their code.
+    #[wasm_bindgen(js_name = genTests)]
+    pub async fn gen_tests(
+        &self,
+        ai_params: &OpenAIParams,
+        task_params: TaskParams,
+        content: String,
+        request_callback: &Function,
+    ) -> Result<String, JsError> {
+        let task_params = task_params.test_gen_().ok_or_else(|| {
+            JsError::from_str("No TestGen field. This error should not occur.")
+        })?;
+
+        gen_tests(self, ai_params, task_params, &content, request_callback)
+            .await
+            .map_err(|e| JsError::from_str(&e.to_string()))
+    }
+
+    /// Adds a task writing the docs of `subject`, i.e. the folder of a
+    /// `Module` or the interface of an `ApiReference`, and returns its ID.
+    #[wasm_bindgen(js_name = addDocTask)]
+    pub fn add_doc_task(
+        &mut self,
+        kind: DocKind,
+        subject: Option<String>,
+    ) -> Result<usize, JsError> {
+        let language = self
+            .language_()
+            .map_err(|e| JsError::from_str(&e.to_string()))?;
+
+        let doc_gen =
+            DocGenParams::for_subject(kind, subject.as_deref(), language)
+                .ok_or_else(|| JsError::from_str("No subject to document"))?;
+
+        self.add_doc_task_(doc_gen)
+            .map_err(|e| JsError::from_str(&e.to_string()))
+    }
+
+    /// Queues the tasks writing the configuration files of the scaffold and
+    /// the manifests of the language, and returns their IDs. Files already
+    /// written are updated, e.g. with the dependencies of new code.
+    #[wasm_bindgen(js_name = addConfigTasks)]
+    pub fn add_config_tasks(&mut self) -> Result<Vec<usize>, JsError> {
+        let language = self
+            .language_()
+            .map_err(|e| JsError::from_str(&e.to_string()))?;
+
+        let configs = match &self.scaffold {
+            Some(scaffold) => Files::from_scaffold(scaffold)
+                .cleanup(language)
+                .map_err(|e| JsError::from_str(&e.to_string()))?
+                .iter()
+                .map(|file| {
+                    ConfigGenParams::for_scaffold_file(
+                        &file.name,
+                        &file.description,
+                    )
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+
+        self.add_config_tasks_(configs)
+            .map_err(|e| JsError::from_str(&e.to_string()))
+    }
+
+    /// Writes the configuration file of a `ConfigGen` task, and returns its
+    /// content. The `existing` file, if any, is updated.
+    #[wasm_bindgen(js_name = genConfig)]
+    pub async fn gen_config(
+        &self,
+        ai_params: &OpenAIParams,
+        task_params: TaskParams,
+        codebase: ICodebase,
+        existing: Option<String>,
+        request_callback: &Function,
+    ) -> Result<String, JsError> {
+        let task_params = task_params.config_gen_().ok_or_else(|| {
+            JsError::from_str(
+                "No ConfigGen field. This error should not occur.",
+            )
+        })?;
+
+        let codebase = BTreeMap::from_extern(codebase)?;
+
+        gen_config(
+            self,
+            ai_params,
+            task_params,
+            codebase,
+            existing.as_deref(),
+            request_callback,
+        )
+        .await
+        .map_err(|e| JsError::from_str(&e.to_string()))
+    }
+
+    /// Queues the tasks writing the client or the handlers of the API
+    /// `interface`, or the producers or consumers of its channels if it is
+    /// event-driven, one per group of operations, and returns their IDs.
+    #[wasm_bindgen(js_name = addApiStubTasks)]
+    pub fn add_api_stub_tasks(
+        &mut self,
+        interface: String,
+        kind: StubKind,
+    ) -> Result<Vec<usize>, JsError> {
+        self.add_api_stub_tasks_(&interface, kind)
+            .map_err(|e| JsError::from_str(&e.to_string()))
+    }
+
+    /// Writes the stubs of an `ApiStubGen` task, and returns their content
+    /// after the repair rounds, if any. The `existing` stubs, if any, are
+    /// updated.
+    #[wasm_bindgen(js_name = genApiStubs)]
+    pub async fn gen_api_stubs(
+        &self,
+        ai_params: &OpenAIParams,
+        task_params: TaskParams,
+        existing: Option<String>,
+        request_callback: &Function,
+    ) -> Result<CodeValidation, JsError> {
+        let task_params = task_params.api_stub_gen_().ok_or_else(|| {
+            JsError::from_str(
+                "No ApiStubGen field. This error should not occur.",
+            )
+        })?;
+
+        gen_api_stubs(
+            self,
+            ai_params,
+            task_params,
+            existing.as_deref(),
+            request_callback,
+        )
+        .await
+        .map_err(|e| JsError::from_str(&e.to_string()))
+    }
+
+    /// Queues the tasks writing the README, the docs of the modules of the
+    /// scaffold and the reference of each interface, and returns their IDs.
+    /// Docs already written are updated, such that the tasks can be queued
+    /// again whenever the docs are stale.
+    #[wasm_bindgen(js_name = addDocTasks)]
+    pub fn add_doc_tasks(&mut self) -> Result<Vec<usize>, JsError> {
+        self.add_doc_tasks_()
+            .map_err(|e| JsError::from_str(&e.to_string()))
+    }
+
+    /// Writes the docs of a `DocGen` task, and returns their Markdown. The
+    /// `existing` docs, if any, are updated.
+    #[wasm_bindgen(js_name = genDocs)]
+    pub async fn gen_docs(
+        &self,
+        ai_params: &OpenAIParams,
+        task_params: TaskParams,
+        codebase: ICodebase,
+        existing: Option<String>,
+        request_callback: &Function,
+    ) -> Result<String, JsError> {
+        let task_params = task_params.doc_gen_().ok_or_else(|| {
+            JsError::from_str("No DocGen field. This error should not occur.")
+        })?;
+
+        let codebase = BTreeMap::from_extern(codebase)?;
+
+        gen_docs(
+            self,
+            ai_params,
+            task_params,
+            codebase,
+            existing.as_deref(),
+            request_callback,
+        )
+        .await
+        .map_err(|e| JsError::from_str(&e.to_string()))
+    }
+
+    /// Adds a task reviewing the existing file `filename`, or only its
+    /// uncommitted changes if `changes_only` is set, and returns its ID.
+    #[wasm_bindgen(js_name = addReviewTask)]
+    pub fn add_review_task(
+        &mut self,
+        filename: String,
+        changes_only: bool,
+    ) -> Result<usize, JsError> {
+        let description = match changes_only {
+            true => format!("Review of the changes to {}", filename),
+            false => format!("Review of {}", filename),
+        };
+
+        let task_params = TaskParams::new_(
+            TaskType::Review,
+            Box::new(ReviewParams {
+                filename: filename.clone(),
+                changes_only,
+            }),
+        )
+        .map_err(|e| JsError::from_str(&e.to_string()))?;
+
+        Ok(self
+            .task_pool
+            .add_todo(&filename, &description, task_params))
+    }
+
+    /// Reviews the file of a `Review` task, whose current content is
+    /// `content`, and returns the issues found. If `diff` is given, only
+    /// the changes of the diff are reviewed.
+    #[wasm_bindgen(js_name = reviewCode)]
+    pub async fn review_code(
+        &self,
+        ai_params: &OpenAIParams,
+        task_params: TaskParams,
+        content: String,
+        diff: Option<String>,
+        request_callback: &Function,
+    ) -> Result<Vec<ReviewFinding>, JsError> {
+        let task_params = task_params.review_().ok_or_else(|| {
+            JsError::from_str("No Review field. This error should not occur.")
+        })?;
+
+        review_code(
+            self,
+            ai_params,
+            task_params,
+            &content,
+            diff.as_deref(),
+            request_callback,
+        )
+        .await
+        .map_err(|e| JsError::from_str(&e.to_string()))
+    }
+
+    /// Stores the findings of a `Review` task on the task, such that they
+    /// can be shown again later on.
+    #[wasm_bindgen(js_name = setFindings)]
+    pub fn set_findings(
+        &mut self,
+        task_id: usize,
+        findings: Vec<ReviewFinding>,
+    ) -> Result<(), JsError> {
+        self.task_pool
+            .set_findings(task_id, findings)
+            .map_err(|e| JsError::from_str(&e.to_string()))
+    }
+
+    /// Modifies the file of a `CodeEdit` task, whose current content is
+    /// `content`, with a unified diff generated by the LLM.
+    #[wasm_bindgen(js_name = editCode)]
+    pub async fn edit_code(
+        &self,
+        ai_params: &OpenAIParams,
+        task_params: TaskParams,
+        content: String,
+        request_callback: &Function,
+    ) -> Result<CodeEdit, JsError> {
+        let task_params = task_params.code_edit_().ok_or_else(|| {
+            JsError::from_str("No CodeEdit field. This error should not occur.")
+        })?;
+
+        edit_code(self, ai_params, task_params, &content, request_callback)
+            .await
+            .map_err(|e| JsError::from_str(&e.to_string()))
+    }
+
+    /// Checks the syntax of the code generated for `filename` and asks the
+    /// LLM to repair it if it is invalid.
+    #[wasm_bindgen(js_name = validateCode)]
+    pub async fn validate_code(
+        &self,
+        ai_params: &OpenAIParams,
+        filename: String,
+        code: String,
+        request_callback: &Function,
+    ) -> Result<CodeValidation, JsError> {
+        let language = self
+            .language_()
+            .map_err(|e| JsError::from_str(&e.to_string()))?;
+
+        repair_code(
+            &self.provider,
+            ai_params,
+            &self.prompts,
+            language,
+            &filename,
+            &code,
+            Some(&self.sql_schema_()),
+            None,
+            MAX_REPAIR_ROUNDS,
+            request_callback,
+        )
+        .await
+        .map_err(|e| JsError::from_str(&e.to_string()))
+    }
 }
 
 impl AppData {
     pub fn new_(
         language: Option<Language>,
         specs: Option<String>,
-        scaffold: Option<String>,
+        scaffold: Option<Scaffold>,
         interfaces: BTreeMap<String, Interface>,
         task_pool: TaskPool,
     ) -> Self {
@@ -354,6 +989,50 @@ impl AppData {
             scaffold,
             interfaces,
             task_pool,
+            provider: Provider::default(),
+            prompts: PromptTemplates::default(),
+            auto_tests: false,
+        }
+    }
+
+    /// Programming language of the project, which the tasks generating code
+    /// require.
+    pub fn language_(&self) -> Result<&Language> {
+        self.language
+            .as_ref()
+            .ok_or_else(|| anyhow!("No programming language specified"))
+    }
+
+    /// Merges the SQL schemas of the database interfaces, against which the
+    /// queries of the generated code are checked.
+    pub fn sql_schema_(&self) -> SqlSchema {
+        let mut sql_schema = SqlSchema::default();
+
+        for interface in self.interfaces.values() {
+            if let Some(database) = &interface.inner.database {
+                sql_schema.merge(database.sql_schema_());
+            }
+        }
+
+        sql_schema
+    }
+
+    /// Flags the file of the done `CodeGen` task `task_id` as generated in
+    /// the scaffold.
+    fn mark_generated(&mut self, task_id: usize) {
+        let filename = self
+            .task_pool
+            .done
+            .tasks
+            .get(&task_id)
+            .and_then(|task| task.task_params.stream_code_())
+            .map(|params| format!("src/{}", params.filename));
+
+        if let (Some(scaffold), Some(filename)) =
+            (self.scaffold.as_mut(), filename)
+        {
+            // Files which are not part of the scaffold are left out
+            let _ = scaffold.set_generated_(&filename, true);
         }
     }
 
@@ -432,6 +1111,319 @@ impl AppData {
 
         Ok(())
     }
+
+    pub async fn rescaffold_project_(
+        &mut self,
+        ai_params: &OpenAIParams,
+        task_params: &RescaffoldParams,
+        codebase: BTreeMap<String, String>,
+        request_callback: &Function,
+    ) -> Result<ScaffoldDiff> {
+        let language = self.language_()?;
+
+        let mut scaffold = self.scaffold.clone().unwrap_or_default();
+
+        // Files written since the project was scaffolded are part of it
+        scaffold.add_codebase_files(codebase.keys())?;
+
+        let diff = rescaffold_project(
+            &self.provider,
+            language,
+            ai_params,
+            task_params,
+            &scaffold,
+            self.specs.as_deref(),
+            &self.prompts,
+            request_callback,
+        )
+        .await?;
+
+        diff.apply(&mut scaffold)?;
+
+        let mut code_gen_ids = Vec::new();
+        let mut configs = Vec::new();
+
+        for file in diff.added.iter() {
+            if is_config_file(&file.path) {
+                if !file.path.ends_with(".md") {
+                    configs.push(ConfigGenParams::for_scaffold_file(
+                        &file.path,
+                        &file.description,
+                    ));
+                }
+                continue;
+            }
+
+            let task_params = TaskParams::new_(
+                TaskType::CodeGen,
+                Box::new(CodeGenParams {
+                    filename: file.path.clone(),
+                    description: file.description.clone(),
+                }),
+            )?;
+
+            code_gen_ids.push(self.task_pool.add_todo(
+                &file.path,
+                &file.description,
+                task_params,
+            ));
+        }
+
+        for edit in diff.edited.iter() {
+            let task_params = TaskParams::new_(
+                TaskType::CodeEdit,
+                Box::new(CodeEditParams {
+                    filename: edit.path.clone(),
+                    instruction: edit.instruction.clone(),
+                }),
+            )?;
+
+            let edit_id = self.task_pool.add_todo(
+                &edit.path,
+                &edit.instruction,
+                task_params,
+            );
+
+            // Existing files are edited to use the added ones
+            for code_gen_id in code_gen_ids.iter() {
+                self.task_pool.add_dependency(edit_id, *code_gen_id)?;
+            }
+        }
+
+        self.task_pool.infer_dependencies();
+
+        if self.auto_tests {
+            self.add_test_tasks_for(&code_gen_ids)?;
+        }
+
+        // New code may import new packages
+        if !code_gen_ids.is_empty() || !configs.is_empty() {
+            self.add_config_tasks_(configs)?;
+        }
+
+        self.scaffold = Some(scaffold);
+
+        Ok(diff)
+    }
+
+    pub fn add_test_task_(&mut self, test_gen: TestGenParams) -> Result<usize> {
+        let name = test_gen.test_filename.clone();
+        let description = format!("Unit tests of {}", test_gen.filename);
+
+        let task_params =
+            TaskParams::new_(TaskType::TestGen, Box::new(test_gen))?;
+
+        Ok(self.task_pool.add_todo(&name, &description, task_params))
+    }
+
+    /// Adds the `ConfigGen` tasks of `configs` and of the manifests of the
+    /// language, leaving out the files which are already queued, and
+    /// returns their IDs. The tasks run after the `CodeGen` tasks, such that
+    /// the packages imported by the code are declared as dependencies.
+    pub fn add_config_tasks_(
+        &mut self,
+        mut configs: Vec<ConfigGenParams>,
+    ) -> Result<Vec<usize>> {
+        let language = self.language_()?;
+
+        for (filename, description) in manifest_files(language) {
+            if !configs.iter().any(|config| config.filename == filename) {
+                configs.push(ConfigGenParams::new(
+                    filename.to_string(),
+                    description.to_string(),
+                ));
+            }
+        }
+
+        let code_gen_ids = self
+            .task_pool
+            .todo
+            .tasks
+            .values()
+            .filter(|task| task.task_params.stream_code_().is_some())
+            .map(|task| task.id)
+            .collect::<Vec<usize>>();
+
+        let mut ids = Vec::new();
+
+        for config in configs {
+            // The file is already queued
+            let queued = self.task_pool.todo.tasks.values().any(|task| {
+                task.task_params
+                    .config_gen_()
+                    .is_some_and(|queued| queued.filename == config.filename)
+            });
+
+            if queued {
+                continue;
+            }
+
+            let name = config.filename.clone();
+            let description = config.description.clone();
+
+            let task_params =
+                TaskParams::new_(TaskType::ConfigGen, Box::new(config))?;
+
+            let id = self.task_pool.add_todo(&name, &description, task_params);
+
+            for code_gen_id in code_gen_ids.iter() {
+                self.task_pool.add_dependency(id, *code_gen_id)?;
+            }
+
+            ids.push(id);
+        }
+
+        Ok(ids)
+    }
+
+    /// Adds the `ApiStubGen` tasks of the groups of operations of the API
+    /// `interface`, leaving out the files which are already queued, and
+    /// returns their IDs.
+    pub fn add_api_stub_tasks_(
+        &mut self,
+        interface: &str,
+        kind: StubKind,
+    ) -> Result<Vec<usize>> {
+        let language = self.language_()?;
+
+        let api = self
+            .interfaces
+            .get(interface)
+            .and_then(|interface| interface.inner.api.as_ref())
+            .ok_or_else(|| {
+                anyhow!("The interface {} is not an API", interface)
+            })?;
+
+        let stubs = stub_groups(api, kind)?
+            .iter()
+            .map(|group| {
+                ApiStubParams::for_group(
+                    interface,
+                    kind,
+                    group.as_deref(),
+                    language,
+                )
+            })
+            .collect::<Vec<ApiStubParams>>();
+
+        let mut ids = Vec::new();
+
+        for stub in stubs {
+            // The file is already queued
+            let queued = self.task_pool.todo.tasks.values().any(|task| {
+                task.task_params
+                    .api_stub_gen_()
+                    .is_some_and(|queued| queued.filename == stub.filename)
+            });
+
+            if queued {
+                continue;
+            }
+
+            let name = stub.filename.clone();
+            let description = stub.description();
+
+            let task_params =
+                TaskParams::new_(TaskType::ApiStubGen, Box::new(stub))?;
+
+            ids.push(self.task_pool.add_todo(&name, &description, task_params));
+        }
+
+        Ok(ids)
+    }
+
+    pub fn add_doc_task_(&mut self, doc_gen: DocGenParams) -> Result<usize> {
+        let name = doc_gen.filename.clone();
+        let description = doc_gen.description();
+
+        let task_params =
+            TaskParams::new_(TaskType::DocGen, Box::new(doc_gen))?;
+
+        Ok(self.task_pool.add_todo(&name, &description, task_params))
+    }
+
+    pub fn add_doc_tasks_(&mut self) -> Result<Vec<usize>> {
+        let language = self.language_()?;
+
+        let modules = self
+            .scaffold
+            .as_ref()
+            .map(scaffold_modules)
+            .unwrap_or_default();
+
+        let subjects = std::iter::once((DocKind::Readme, None))
+            .chain(modules.into_iter().map(|m| (DocKind::Module, Some(m))))
+            .chain(
+                self.interfaces
+                    .keys()
+                    .map(|name| (DocKind::ApiReference, Some(name.clone()))),
+            );
+
+        let mut docs = Vec::new();
+
+        for (kind, subject) in subjects {
+            let doc_gen =
+                DocGenParams::for_subject(kind, subject.as_deref(), language)
+                    .ok_or_else(|| anyhow!("No subject to document"))?;
+
+            // The docs are already queued
+            let queued = self.task_pool.todo.tasks.values().any(|task| {
+                task.task_params
+                    .doc_gen_()
+                    .is_some_and(|queued| queued.filename == doc_gen.filename)
+            });
+
+            if !queued {
+                docs.push(doc_gen);
+            }
+        }
+
+        let ids = docs
+            .into_iter()
+            .map(|doc_gen| self.add_doc_task_(doc_gen))
+            .collect::<Result<Vec<usize>>>()?;
+
+        Ok(ids)
+    }
+
+    /// Queues a `TestGen` task after each `CodeGen` task of the "to-do"
+    /// pipeline, for languages with unit tests.
+    pub fn add_test_tasks(&mut self) -> Result<()> {
+        let task_ids = self
+            .task_pool
+            .todo
+            .tasks
+            .keys()
+            .copied()
+            .collect::<Vec<usize>>();
+
+        self.add_test_tasks_for(&task_ids)
+    }
+
+    /// Adds the `TestGen` tasks of the `CodeGen` tasks `task_ids`, each of
+    /// them depending on its `CodeGen` task.
+    pub fn add_test_tasks_for(&mut self, task_ids: &[usize]) -> Result<()> {
+        let language = self.language_()?;
+
+        let files = task_ids
+            .iter()
+            .filter_map(|task_id| self.task_pool.todo.tasks.get(task_id))
+            .filter_map(|task| {
+                let filename = &task.task_params.stream_code_()?.filename;
+                let test_gen = TestGenParams::for_file(filename, language)?;
+
+                Some((task.id, test_gen))
+            })
+            .collect::<Vec<(usize, TestGenParams)>>();
+
+        for (code_gen_id, test_gen) in files {
+            let test_gen_id = self.add_test_task_(test_gen)?;
+
+            self.task_pool.add_dependency(test_gen_id, code_gen_id)?;
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -558,7 +1550,10 @@ pub mod tests {
         let app_state = AppData::new_(
             Some(Language::new(LanguageType::Rust)),
             Some(String::from("specs")),
-            Some(String::from("scaffold")),
+            Scaffold::from_value(&serde_json::json!({
+                "src": { "main.rs": "Entry point" }
+            }))
+            .ok(),
             interfaces,
             task_pool,
         );
@@ -569,7 +1564,7 @@ pub mod tests {
             .unwrap();
 
         let expected = String::from(
-            r#"{"language":{"language":"Rust","custom":null},"specs":"specs","scaffold":"scaffold","interfaces":{"MyApi":{"interfaceType":"Api","inner":{"database":null,"storage":null,"api":{"name":"MyApi","apiType":"RestfulApi","customType":null,"port":null,"host":null,"schemas":{"MySchema":"schema"}}}},"MyDB":{"interfaceType":"Database","inner":{"database":{"name":"MyDB","dbType":"MySql","customType":null,"port":null,"host":null,"schemas":{"MySchema":"schema"}},"storage":null,"api":null}}},"taskPool":{"counter":3,"todo":{"tasks":{"2":{"id":2,"name":"Task2","description": "Description2","taskParams":{"taskType":"CodeGen","inner":{"scaffoldProject":null,"streamCode":{"filename":"filename.rs"}}},"status":"Todo"}},"order":[2]},"done":{"tasks":{"1":{"id":1,"name":"Task1","description": "Description1", "taskParams":{"taskType":"ScaffoldProject","inner":{"scaffoldProject":{"specs":"specs"},"streamCode":null}},"status":"Todo"}},"order":[1]}}}"#,
+            r#"{"language":{"language":"Rust","custom":null},"specs":"specs","scaffold":{"src":{"main.rs":"Entry point"}},"interfaces":{"MyApi":{"interfaceType":"Api","inner":{"database":null,"storage":null,"api":{"name":"MyApi","apiType":"RestfulApi","customType":null,"port":null,"host":null,"schemas":{"MySchema":"schema"}}}},"MyDB":{"interfaceType":"Database","inner":{"database":{"name":"MyDB","dbType":"MySql","customType":null,"port":null,"host":null,"schemas":{"MySchema":"schema"}},"storage":null,"api":null}}},"taskPool":{"counter":3,"todo":{"tasks":{"2":{"id":2,"name":"Task2","description": "Description2","taskParams":{"taskType":"CodeGen","inner":{"scaffoldProject":null,"streamCode":{"filename":"filename.rs"}}},"status":"Todo"}},"order":[2]},"done":{"tasks":{"1":{"id":1,"name":"Task1","description": "Description1", "taskParams":{"taskType":"ScaffoldProject","inner":{"scaffoldProject":{"specs":"specs"},"streamCode":null}},"status":"Todo"}},"order":[1]}},"provider":{"providerType":"OpenAI","uri":null,"model":null}}"#,
         );
 
         assert_eq!(actual, expected);
@@ -615,7 +1610,7 @@ pub mod tests {
             .unwrap();
 
         let expected = String::from(
-            r#"{"language":{"language":"Rust","custom":null},"specs":null,"scaffold":null,"interfaces":{"aaa":{"interfaceType":"Database","inner":{"database":{"name":"aaa","dbType":"ClickHouse","customType":null,"port":null,"host":null,"schemas":{}},"storage":null,"api":null}}},"taskPool":{"counter":0,"todo":{"tasks":{},"order":[]},"done":{"tasks":{},"order":[]}}}"#,
+            r#"{"language":{"language":"Rust","custom":null},"specs":null,"scaffold":null,"interfaces":{"aaa":{"interfaceType":"Database","inner":{"database":{"name":"aaa","dbType":"ClickHouse","customType":null,"port":null,"host":null,"schemas":{}},"storage":null,"api":null}}},"taskPool":{"counter":0,"todo":{"tasks":{},"order":[]},"done":{"tasks":{},"order":[]}},"provider":{"providerType":"OpenAI","uri":null,"model":null}}"#,
         );
 
         assert_eq!(actual, expected);
@@ -636,7 +1631,7 @@ pub mod tests {
             JsValue::from_str(&serde_json::to_string(&app_state_x).unwrap());
 
         let expected = JsValue::from_str(
-            r#"{"language":null,"specs":null,"scaffold":null,"interfaces":{},"taskPool":{"counter":0,"todo":{"tasks":{},"order":[]},"done":{"tasks":{},"order":[]}}}"#,
+            r#"{"language":null,"specs":null,"scaffold":null,"interfaces":{},"taskPool":{"counter":0,"todo":{"tasks":{},"order":[]},"done":{"tasks":{},"order":[]}},"provider":{"providerType":"OpenAI","uri":null,"model":null}}"#,
         );
 
         assert_eq!(actual, expected);
@@ -0,0 +1,906 @@
+//! This module defines the scaffold of the project, i.e. the tree of its
+//! folders and files along with the description of each file.
+//!
+//! The scaffold is serialized as nested JSON objects, in which folders are
+//! objects and files are their description:
+//!
+//! ```json
+//! {
+//!     "src": {
+//!         "main.rs": "Entry point of the API server",
+//!         "models": {
+//!             "user.rs": { "description": "User model", "generated": true }
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! Files whose code was generated are serialized as an object holding their
+//! description and the `generated` flag. Scaffolds saved by previous
+//! versions as a JSON string are deserialized as well.
+
+use anyhow::{anyhow, Result};
+use js_sys::JsString;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::JsError;
+
+/// Tree of the folders and files of the project. Paths are relative to the
+/// root of the project and separated by `/`, the code of the project being
+/// in the `src` folder.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Scaffold {
+    pub(crate) root: BTreeMap<String, ScaffoldNode>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScaffoldNode {
+    Folder(BTreeMap<String, ScaffoldNode>),
+    File(ScaffoldFile),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScaffoldFile {
+    pub description: String,
+    /// Whether the code of the file was generated
+    pub generated: bool,
+}
+
+/// File of the scaffold, as listed by `Scaffold::files`.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScaffoldEntry {
+    pub(crate) path: String,
+    pub(crate) description: String,
+    #[serde(default)]
+    pub generated: bool,
+}
+
+#[wasm_bindgen]
+impl ScaffoldEntry {
+    #[wasm_bindgen(getter)]
+    pub fn path(&self) -> JsString {
+        self.path.clone().into()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn description(&self) -> JsString {
+        self.description.clone().into()
+    }
+}
+
+#[wasm_bindgen]
+impl Scaffold {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Scaffold {
+        Scaffold::default()
+    }
+
+    #[wasm_bindgen(js_name = castToString)]
+    pub fn cast_to_string(&self) -> Result<JsString, JsError> {
+        let json = serde_json::to_string(self)
+            .map_err(|e| JsError::from_str(&e.to_string()))?;
+
+        Ok(json.into())
+    }
+
+    #[wasm_bindgen(js_name = castFromString)]
+    pub fn cast_from_string(json: String) -> Result<Scaffold, JsError> {
+        let scaffold = serde_json::from_str(&json)
+            .map_err(|e| JsError::from_str(&e.to_string()))?;
+
+        Ok(scaffold)
+    }
+
+    /// Files of the scaffold, in depth-first order.
+    #[wasm_bindgen(getter)]
+    pub fn files(&self) -> Vec<ScaffoldEntry> {
+        fn walk(
+            folder: &BTreeMap<String, ScaffoldNode>,
+            path: &str,
+            acc: &mut Vec<ScaffoldEntry>,
+        ) {
+            for (name, node) in folder.iter() {
+                let path = join(path, name);
+
+                match node {
+                    ScaffoldNode::Folder(children) => {
+                        walk(children, &path, acc)
+                    }
+                    ScaffoldNode::File(file) => acc.push(ScaffoldEntry {
+                        path,
+                        description: file.description.clone(),
+                        generated: file.generated,
+                    }),
+                }
+            }
+        }
+
+        let mut files = Vec::new();
+        walk(&self.root, "", &mut files);
+
+        files
+    }
+
+    /// Folders of the scaffold, in depth-first order.
+    #[wasm_bindgen(getter)]
+    pub fn folders(&self) -> Vec<String> {
+        fn walk(
+            folder: &BTreeMap<String, ScaffoldNode>,
+            path: &str,
+            acc: &mut Vec<String>,
+        ) {
+            for (name, node) in folder.iter() {
+                if let ScaffoldNode::Folder(children) = node {
+                    let path = join(path, name);
+                    acc.push(path.clone());
+                    walk(children, &path, acc);
+                }
+            }
+        }
+
+        let mut folders = Vec::new();
+        walk(&self.root, "", &mut folders);
+
+        folders
+    }
+
+    /// Adds the file `path`, creating its parent folders if needed. If the
+    /// file already exists, its description is updated.
+    #[wasm_bindgen(js_name = addFile)]
+    pub fn add_file(
+        &mut self,
+        path: &str,
+        description: String,
+    ) -> Result<(), JsError> {
+        self.add_file_(path, description)
+            .map_err(|e| JsError::from_str(&e.to_string()))
+    }
+
+    /// Adds the folder `path`, creating its parent folders if needed.
+    #[wasm_bindgen(js_name = addFolder)]
+    pub fn add_folder(&mut self, path: &str) -> Result<(), JsError> {
+        self.add_folder_(path)
+            .map_err(|e| JsError::from_str(&e.to_string()))
+    }
+
+    /// Moves the file or folder `from` to `to`.
+    #[wasm_bindgen(js_name = moveNode)]
+    pub fn move_node(&mut self, from: &str, to: &str) -> Result<(), JsError> {
+        self.move_node_(from, to)
+            .map_err(|e| JsError::from_str(&e.to_string()))
+    }
+
+    /// Removes the file or folder `path`, along with its content.
+    #[wasm_bindgen(js_name = removeNode)]
+    pub fn remove_node(&mut self, path: &str) -> Result<(), JsError> {
+        self.remove_node_(path)
+            .map(|_| ())
+            .map_err(|e| JsError::from_str(&e.to_string()))
+    }
+
+    #[wasm_bindgen(js_name = setGenerated)]
+    pub fn set_generated(
+        &mut self,
+        path: &str,
+        generated: bool,
+    ) -> Result<(), JsError> {
+        self.set_generated_(path, generated)
+            .map_err(|e| JsError::from_str(&e.to_string()))
+    }
+
+    /// Renders the scaffold as a text tree, which is how it is sent to the
+    /// LLM:
+    ///
+    /// ```text
+    /// src/
+    /// ├── main.rs: Entry point of the API server
+    /// └── models/
+    ///     └── user.rs: User model
+    /// ```
+    pub fn render(&self) -> String {
+        fn walk(
+            folder: &BTreeMap<String, ScaffoldNode>,
+            prefix: &str,
+            top_level: bool,
+            acc: &mut Vec<String>,
+        ) {
+            let count = folder.len();
+
+            for (i, (name, node)) in folder.iter().enumerate() {
+                let last = i + 1 == count;
+
+                let (branch, indent) = match (top_level, last) {
+                    (true, _) => ("", ""),
+                    (false, false) => ("├── ", "│   "),
+                    (false, true) => ("└── ", "    "),
+                };
+
+                match node {
+                    ScaffoldNode::Folder(children) => {
+                        acc.push(format!("{}{}{}/", prefix, branch, name));
+
+                        let prefix = format!("{}{}", prefix, indent);
+                        walk(children, &prefix, false, acc);
+                    }
+                    ScaffoldNode::File(file) if file.description.is_empty() => {
+                        acc.push(format!("{}{}{}", prefix, branch, name));
+                    }
+                    ScaffoldNode::File(file) => acc.push(format!(
+                        "{}{}{}: {}",
+                        prefix, branch, name, file.description
+                    )),
+                }
+            }
+        }
+
+        let mut lines = Vec::new();
+        walk(&self.root, "", true, &mut lines);
+
+        lines.join("\n")
+    }
+}
+
+impl Scaffold {
+    pub fn is_empty(&self) -> bool {
+        self.root.is_empty()
+    }
+
+    /// Builds the scaffold from its JSON value, which is either the tree
+    /// itself or, as saved by previous versions, a string holding it.
+    pub fn from_value(value: &Value) -> Result<Scaffold> {
+        match value {
+            Value::Object(map) => Ok(Scaffold {
+                root: parse_folder(map)?,
+            }),
+            Value::String(json) => {
+                let value = serde_json::from_str::<Value>(json)
+                    .map_err(|e| anyhow!("Invalid scaffold: {}", e))?;
+
+                match value {
+                    Value::String(_) => {
+                        Err(anyhow!("Invalid scaffold: expected an object"))
+                    }
+                    value => Scaffold::from_value(&value),
+                }
+            }
+            _ => Err(anyhow!("Invalid scaffold: expected an object")),
+        }
+    }
+
+    pub fn to_value(&self) -> Value {
+        folder_to_value(&self.root)
+    }
+
+    pub fn get(&self, path: &str) -> Option<&ScaffoldNode> {
+        let components = components(path).ok()?;
+        let (name, parents) = components.split_last()?;
+
+        let mut folder = &self.root;
+
+        for parent in parents.iter() {
+            match folder.get(*parent)? {
+                ScaffoldNode::Folder(children) => folder = children,
+                ScaffoldNode::File(_) => return None,
+            }
+        }
+
+        folder.get(*name)
+    }
+
+    /// Returns the file `path`, if it exists and is a file.
+    pub fn file(&self, path: &str) -> Option<&ScaffoldFile> {
+        match self.get(path)? {
+            ScaffoldNode::File(file) => Some(file),
+            ScaffoldNode::Folder(_) => None,
+        }
+    }
+
+    /// Returns the files of the folder `path`, relative to it, e.g. the
+    /// files of the `src` folder for which code is generated.
+    pub fn files_in(&self, path: &str) -> Vec<ScaffoldEntry> {
+        match self.get(path) {
+            Some(ScaffoldNode::Folder(children)) => Scaffold {
+                root: children.clone(),
+            }
+            .files(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Adds the files of the `src` folder of the codebase which are not in
+    /// the scaffold, e.g. files written by hand, as generated files.
+    pub fn add_codebase_files<'a>(
+        &mut self,
+        paths: impl IntoIterator<Item = &'a String>,
+    ) -> Result<()> {
+        for path in paths {
+            let path = in_src(path);
+
+            if self.get(&path).is_none() {
+                self.add_file_(&path, String::new())?;
+                self.set_generated_(&path, true)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn add_file_(&mut self, path: &str, description: String) -> Result<()> {
+        let generated = self.file(path).is_some_and(|file| file.generated);
+
+        let file = ScaffoldNode::File(ScaffoldFile {
+            description,
+            generated,
+        });
+
+        insert(&mut self.root, &components(path)?, file)
+    }
+
+    pub fn add_folder_(&mut self, path: &str) -> Result<()> {
+        let folder = ScaffoldNode::Folder(BTreeMap::new());
+
+        insert(&mut self.root, &components(path)?, folder)
+    }
+
+    pub fn move_node_(&mut self, from: &str, to: &str) -> Result<()> {
+        let source = components(from)?;
+        let target = components(to)?;
+
+        if target.starts_with(&source) {
+            return Err(anyhow!("Cannot move {} into itself", from));
+        }
+
+        if self.get(to).is_some() {
+            return Err(anyhow!("{} already exists", to));
+        }
+
+        let node = self.remove_node_(from)?;
+
+        if let Err(e) = insert(&mut self.root, &target, node.clone()) {
+            // Puts the node back where it was
+            insert(&mut self.root, &source, node)?;
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    pub fn remove_node_(&mut self, path: &str) -> Result<ScaffoldNode> {
+        let components = components(path)?;
+        let (name, parents) =
+            components.split_last().expect("Path is not empty"); // Checked by `components`
+
+        let mut folder = &mut self.root;
+
+        for parent in parents.iter() {
+            match folder.get_mut(*parent) {
+                Some(ScaffoldNode::Folder(children)) => folder = children,
+                _ => return Err(anyhow!("{} does not exist", path)),
+            }
+        }
+
+        folder
+            .remove(*name)
+            .ok_or_else(|| anyhow!("{} does not exist", path))
+    }
+
+    pub fn set_generated_(
+        &mut self,
+        path: &str,
+        generated: bool,
+    ) -> Result<()> {
+        let components = components(path)?;
+
+        let mut node = None;
+        let mut folder = &mut self.root;
+
+        for (i, name) in components.iter().enumerate() {
+            match folder.get_mut(*name) {
+                Some(ScaffoldNode::Folder(children))
+                    if i + 1 < components.len() =>
+                {
+                    folder = children
+                }
+                Some(ScaffoldNode::File(file)) if i + 1 == components.len() => {
+                    node = Some(file);
+                    break;
+                }
+                _ => break,
+            }
+        }
+
+        let file = node.ok_or_else(|| anyhow!("No file {}", path))?;
+        file.generated = generated;
+
+        Ok(())
+    }
+}
+
+/// Changes to the scaffold of an existing project, requested to extend it.
+/// Paths are relative to the `src` folder, as the filenames of the tasks.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScaffoldDiff {
+    pub(crate) added: Vec<ScaffoldEntry>,
+    pub(crate) removed: Vec<String>,
+    pub(crate) renamed: Vec<ScaffoldRename>,
+    /// Existing files to change, e.g. to use the added files
+    pub(crate) edited: Vec<ScaffoldEdit>,
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScaffoldRename {
+    pub(crate) from: String,
+    pub(crate) to: String,
+    /// New description of the file, if it changes
+    #[serde(default)]
+    pub(crate) description: Option<String>,
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScaffoldEdit {
+    pub(crate) path: String,
+    pub(crate) instruction: String,
+}
+
+#[wasm_bindgen]
+impl ScaffoldDiff {
+    #[wasm_bindgen(getter)]
+    pub fn added(&self) -> Vec<ScaffoldEntry> {
+        self.added.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn removed(&self) -> Vec<String> {
+        self.removed.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn renamed(&self) -> Vec<ScaffoldRename> {
+        self.renamed.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn edited(&self) -> Vec<ScaffoldEdit> {
+        self.edited.clone()
+    }
+
+    #[wasm_bindgen(js_name = isEmpty)]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.renamed.is_empty()
+            && self.edited.is_empty()
+    }
+}
+
+#[wasm_bindgen]
+impl ScaffoldRename {
+    #[wasm_bindgen(getter)]
+    pub fn from(&self) -> JsString {
+        self.from.clone().into()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn to(&self) -> JsString {
+        self.to.clone().into()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn description(&self) -> Option<JsString> {
+        self.description.clone().map(JsString::from)
+    }
+}
+
+#[wasm_bindgen]
+impl ScaffoldEdit {
+    #[wasm_bindgen(getter)]
+    pub fn path(&self) -> JsString {
+        self.path.clone().into()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn instruction(&self) -> JsString {
+        self.instruction.clone().into()
+    }
+}
+
+impl ScaffoldDiff {
+    /// Normalises the paths of the diff and drops the changes which do not
+    /// apply to `scaffold`, such as removals of files which do not exist or
+    /// additions of files which already do.
+    pub fn normalise(&mut self, scaffold: &Scaffold) {
+        let exists = |path: &str| scaffold.file(&in_src(path)).is_some();
+
+        for file in self.added.iter_mut() {
+            file.path = relative(&file.path);
+            file.generated = false;
+        }
+
+        for path in self.removed.iter_mut() {
+            *path = relative(path);
+        }
+
+        for rename in self.renamed.iter_mut() {
+            rename.from = relative(&rename.from);
+            rename.to = relative(&rename.to);
+        }
+
+        for edit in self.edited.iter_mut() {
+            edit.path = relative(&edit.path);
+        }
+
+        self.added.retain(|file| {
+            !file.path.is_empty() && scaffold.get(&in_src(&file.path)).is_none()
+        });
+        self.removed.retain(|path| exists(path));
+        self.renamed.retain(|rename| {
+            exists(&rename.from)
+                && !rename.to.is_empty()
+                && scaffold.get(&in_src(&rename.to)).is_none()
+        });
+
+        let removed = self.removed.clone();
+        let renamed = self.renamed.clone();
+        let added = self.added.clone();
+
+        // Edited files are either existing files, which are not removed nor
+        // renamed, or renamed files under their new path
+        self.edited.retain(|edit| {
+            let existing = exists(&edit.path)
+                && !removed.contains(&edit.path)
+                && !renamed.iter().any(|rename| rename.from == edit.path);
+
+            let moved = renamed.iter().any(|rename| rename.to == edit.path);
+            let new = added.iter().any(|file| file.path == edit.path);
+
+            (existing || moved) && !new
+        });
+    }
+
+    /// Applies the diff to `scaffold`.
+    pub fn apply(&self, scaffold: &mut Scaffold) -> Result<()> {
+        for path in self.removed.iter() {
+            scaffold.remove_node_(&in_src(path))?;
+        }
+
+        for rename in self.renamed.iter() {
+            scaffold.move_node_(&in_src(&rename.from), &in_src(&rename.to))?;
+
+            if let Some(description) = &rename.description {
+                scaffold.add_file_(&in_src(&rename.to), description.clone())?;
+            }
+        }
+
+        for file in self.added.iter() {
+            scaffold
+                .add_file_(&in_src(&file.path), file.description.clone())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Serialize for Scaffold {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        self.to_value().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Scaffold {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+
+        Scaffold::from_value(&value).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Splits `path` into its components, ignoring leading and trailing `/`.
+fn components(path: &str) -> Result<Vec<&str>> {
+    let components = path
+        .split('/')
+        .filter(|name| !name.is_empty() && *name != ".")
+        .collect::<Vec<&str>>();
+
+    if components.is_empty() {
+        return Err(anyhow!("Invalid scaffold path: `{}`", path));
+    }
+
+    Ok(components)
+}
+
+/// Path relative to the `src` folder, without leading `/`, `./` or `src/`.
+fn relative(path: &str) -> String {
+    let path = components(path).unwrap_or_default();
+
+    match path.split_first() {
+        Some((&"src", rest)) => rest.join("/"),
+        _ => path.join("/"),
+    }
+}
+
+/// Path of the scaffold of the file `path` of the `src` folder.
+fn in_src(path: &str) -> String {
+    join("src", &relative(path))
+}
+
+fn join(parent: &str, name: &str) -> String {
+    match parent.is_empty() {
+        true => name.to_string(),
+        false => format!("{}/{}", parent, name),
+    }
+}
+
+/// Inserts `node` at the path `components` of `folder`, creating the
+/// missing folders. Folders inserted over existing folders are merged.
+fn insert(
+    folder: &mut BTreeMap<String, ScaffoldNode>,
+    components: &[&str],
+    node: ScaffoldNode,
+) -> Result<()> {
+    let (name, parents) = components
+        .split_first()
+        .ok_or_else(|| anyhow!("Invalid scaffold path"))?;
+
+    if !parents.is_empty() {
+        let parent = folder
+            .entry(name.to_string())
+            .or_insert_with(|| ScaffoldNode::Folder(BTreeMap::new()));
+
+        return match parent {
+            ScaffoldNode::Folder(children) => insert(children, parents, node),
+            ScaffoldNode::File(_) => {
+                Err(anyhow!("{} is a file, not a folder", name))
+            }
+        };
+    }
+
+    match (folder.get_mut(*name), node) {
+        (Some(ScaffoldNode::Folder(existing)), ScaffoldNode::Folder(new)) => {
+            for (child, node) in new.into_iter() {
+                insert(existing, &[&child], node)?;
+            }
+        }
+        (Some(ScaffoldNode::Folder(_)), ScaffoldNode::File(_)) => {
+            return Err(anyhow!("{} is a folder, not a file", name));
+        }
+        (Some(ScaffoldNode::File(_)), ScaffoldNode::Folder(_)) => {
+            return Err(anyhow!("{} is a file, not a folder", name));
+        }
+        (_, node) => {
+            folder.insert(name.to_string(), node);
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses a folder of the JSON scaffold. Keys holding a path, such as
+/// `models/user.rs`, are nested in their folders, and keys ending with `/`
+/// are folders.
+fn parse_folder(
+    map: &Map<String, Value>,
+) -> Result<BTreeMap<String, ScaffoldNode>> {
+    let mut folder = BTreeMap::new();
+
+    for (key, value) in map.iter() {
+        // The key is the folder itself
+        if components(key).is_err() {
+            if let Value::Object(map) = value {
+                for (name, node) in parse_folder(map)? {
+                    insert(&mut folder, &[&name], node)?;
+                }
+            }
+            continue;
+        }
+
+        let node = match value {
+            Value::Object(map) => match file_object(map) {
+                Some(file) => ScaffoldNode::File(file),
+                None => ScaffoldNode::Folder(parse_folder(map)?),
+            },
+            _ if key.ends_with('/') => ScaffoldNode::Folder(BTreeMap::new()),
+            Value::String(description) => ScaffoldNode::File(ScaffoldFile {
+                description: description.clone(),
+                generated: false,
+            }),
+            Value::Null => ScaffoldNode::File(ScaffoldFile {
+                description: String::new(),
+                generated: false,
+            }),
+            value => ScaffoldNode::File(ScaffoldFile {
+                description: value.to_string(),
+                generated: false,
+            }),
+        };
+
+        insert(&mut folder, &components(key)?, node)?;
+    }
+
+    Ok(folder)
+}
+
+/// Parses a file serialized with its `generated` flag. Folders cannot be
+/// mistaken for such files, as the files of a folder are strings.
+fn file_object(map: &Map<String, Value>) -> Option<ScaffoldFile> {
+    if map.len() != 2 {
+        return None;
+    }
+
+    Some(ScaffoldFile {
+        description: map.get("description")?.as_str()?.to_string(),
+        generated: map.get("generated")?.as_bool()?,
+    })
+}
+
+fn folder_to_value(folder: &BTreeMap<String, ScaffoldNode>) -> Value {
+    let map = folder
+        .iter()
+        .map(|(name, node)| {
+            let value = match node {
+                ScaffoldNode::Folder(children) => folder_to_value(children),
+                ScaffoldNode::File(file) if file.generated => {
+                    let mut map = Map::new();
+                    map.insert(
+                        String::from("description"),
+                        Value::String(file.description.clone()),
+                    );
+                    map.insert(String::from("generated"), Value::Bool(true));
+
+                    Value::Object(map)
+                }
+                ScaffoldNode::File(file) => {
+                    Value::String(file.description.clone())
+                }
+            };
+
+            (name.clone(), value)
+        })
+        .collect::<Map<String, Value>>();
+
+    Value::Object(map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn deserializes_legacy_scaffolds() -> Result<()> {
+        let legacy = json!({
+            "scaffold": r#"{"src": {
+                "main.rs": "Entry point",
+                "handlers/": "Request handlers",
+                "models/user.rs": "User model",
+                "models": { "order.rs": "Order model" }
+            }}"#
+        });
+
+        let scaffold = Scaffold::from_value(&legacy["scaffold"])?;
+
+        assert_eq!(
+            scaffold.folders(),
+            vec!["src", "src/handlers", "src/models"]
+        );
+        assert_eq!(
+            scaffold
+                .files()
+                .iter()
+                .map(|file| file.path.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["src/main.rs", "src/models/order.rs", "src/models/user.rs"]
+        );
+
+        // Generated files are kept as such once saved
+        let mut scaffold = scaffold;
+        scaffold.set_generated_("src/main.rs", true)?;
+
+        let json = serde_json::to_string(&scaffold)?;
+        assert_eq!(serde_json::from_str::<Scaffold>(&json)?, scaffold);
+        assert!(scaffold.file("src/main.rs").unwrap().generated);
+
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn edits_and_renders_the_tree() -> Result<()> {
+        let mut scaffold = Scaffold::new();
+
+        scaffold.add_file_("src/main.rs", String::from("Entry point"))?;
+        scaffold.add_file_("src/models/user.rs", String::from("User model"))?;
+        scaffold.add_folder_("src/handlers")?;
+
+        assert!(scaffold.add_file_("src/models", String::new()).is_err());
+        assert!(scaffold.move_node_("src/models", "src/models/db").is_err());
+        assert!(scaffold.move_node_("src/main.rs", "src/models").is_err());
+
+        scaffold.move_node_("src/models/user.rs", "src/handlers/user.rs")?;
+        scaffold.remove_node_("src/models")?;
+
+        assert!(scaffold.remove_node_("src/models").is_err());
+
+        assert_eq!(
+            scaffold.render(),
+            "src/\n├── handlers/\n│   └── user.rs: User model\n└── main.rs: Entry point"
+        );
+
+        let files = scaffold.files_in("src");
+        assert_eq!(files[0].path, "handlers/user.rs");
+        assert_eq!(files[1].path, "main.rs");
+
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn applies_diffs() -> Result<()> {
+        let mut scaffold = Scaffold::from_value(&json!({
+            "src": {
+                "main.rs": "Entry point",
+                "db.rs": "Database connection",
+                "utils.rs": "Helpers",
+            }
+        }))?;
+
+        let mut diff: ScaffoldDiff = serde_json::from_value(json!({
+            "added": [
+                { "path": "src/routes/orders.rs", "description": "Order routes" },
+                { "path": "main.rs", "description": "Already exists" },
+            ],
+            "removed": ["utils.rs", "missing.rs"],
+            "renamed": [
+                { "from": "/db.rs", "to": "store/db.rs", "description": null },
+            ],
+            "edited": [
+                { "path": "main.rs", "instruction": "Mount the order routes" },
+                { "path": "utils.rs", "instruction": "Removed anyway" },
+                { "path": "store/db.rs", "instruction": "Fix imports" },
+            ],
+        }))?;
+
+        diff.normalise(&scaffold);
+
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].path, "routes/orders.rs");
+        assert_eq!(diff.removed, vec!["utils.rs"]);
+        assert_eq!(diff.renamed[0].from, "db.rs");
+        assert_eq!(
+            diff.edited
+                .iter()
+                .map(|edit| edit.path.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["main.rs", "store/db.rs"]
+        );
+
+        diff.apply(&mut scaffold)?;
+
+        assert_eq!(
+            scaffold
+                .files_in("src")
+                .iter()
+                .map(|file| file.path.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["main.rs", "routes/orders.rs", "store/db.rs"]
+        );
+        assert_eq!(
+            scaffold.file("src/store/db.rs").unwrap().description,
+            "Database connection"
+        );
+
+        Ok(())
+    }
+}
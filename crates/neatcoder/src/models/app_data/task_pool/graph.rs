@@ -0,0 +1,446 @@
+//! This module adds dependencies between the tasks of the task pool.
+//!
+//! Tasks declare the IDs of the tasks they depend on, which together form a
+//! directed acyclic graph. A task is ready to run once all its dependencies
+//! are done, and dependencies on tasks which no longer exist are considered
+//! fulfilled.
+//!
+//! Dependencies between `CodeGen` tasks can also be inferred from the paths
+//! of the files of the scaffold:
+//!
+//! - Module roots (`mod.rs`, `lib.rs`, `main.rs`, `__init__.py`, `index.ts`,
+//!   etc.) run after the files of their folder and the roots of their
+//!   subfolders. The same applies to a file named after a sibling folder,
+//!   such as `handlers.rs` next to `handlers/`;
+//! - Files of higher architectural layers run after the files of lower
+//!   ones, e.g. handlers after services, services after models.
+
+use anyhow::{anyhow, Result};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+use super::{
+    task::{Task, TaskStatus},
+    TaskPool,
+};
+
+/// File stems of module roots, which import the files of their folder. Roots
+/// of the same folder import the ones preceding them.
+const MODULE_ROOTS: [&str; 6] =
+    ["mod", "__init__", "index", "lib", "main", "app"];
+
+/// Architectural layers, from the lowest to the highest, and the path
+/// components of their files.
+const LAYERS: [&[&str]; 5] = [
+    &[
+        "model", "models", "entity", "entities", "schema", "schemas", "types",
+        "domain", "dto", "dtos",
+    ],
+    &[
+        "db",
+        "database",
+        "repository",
+        "repositories",
+        "repo",
+        "dao",
+        "store",
+        "storage",
+    ],
+    &["service", "services", "logic", "usecases", "use_cases"],
+    &[
+        "handler",
+        "handlers",
+        "controller",
+        "controllers",
+        "view",
+        "views",
+        "resolvers",
+        "middleware",
+        "middlewares",
+    ],
+    &[
+        "route", "routes", "router", "routers", "api", "urls", "server",
+    ],
+];
+
+impl TaskPool {
+    /// Declares that the task `task_id` depends on the task `dependency_id`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either task does not exist, or if the dependency
+    /// would introduce a cycle.
+    pub fn add_dependency(
+        &mut self,
+        task_id: usize,
+        dependency_id: usize,
+    ) -> Result<()> {
+        if self.get_task(dependency_id).is_none() {
+            return Err(anyhow!(
+                "Failed to find dependency id: {}",
+                dependency_id
+            ));
+        }
+
+        if self.get_task(task_id).is_none() {
+            return Err(anyhow!("Failed to find task id: {}", task_id));
+        }
+
+        // A cycle appears if the task is already reachable from the
+        // dependency
+        if task_id == dependency_id || self.depends_on(dependency_id, task_id) {
+            return Err(anyhow!(
+                "Task {} cannot depend on task {} as it would create a cycle",
+                task_id,
+                dependency_id
+            ));
+        }
+
+        self.get_task_mut(task_id)
+            .expect("Task exists") // Checked above
+            .dependencies
+            .insert(dependency_id);
+
+        Ok(())
+    }
+
+    /// Removes the dependency of the task `task_id` on `dependency_id`.
+    pub fn remove_dependency(&mut self, task_id: usize, dependency_id: usize) {
+        if let Some(task) = self.get_task_mut(task_id) {
+            task.dependencies.remove(&dependency_id);
+        }
+    }
+
+    /// Whether the task `task_id` depends, directly or transitively, on the
+    /// task `dependency_id`.
+    pub fn depends_on(&self, task_id: usize, dependency_id: usize) -> bool {
+        let mut visited = BTreeSet::new();
+        let mut stack = vec![task_id];
+
+        while let Some(id) = stack.pop() {
+            if !visited.insert(id) {
+                continue;
+            }
+
+            if let Some(task) = self.get_task(id) {
+                if task.dependencies.contains(&dependency_id) {
+                    return true;
+                }
+
+                stack.extend(task.dependencies.iter());
+            }
+        }
+
+        false
+    }
+
+    /// Returns a cycle of dependencies if there is any, which can only occur
+    /// if the pool was deserialized from a corrupted state.
+    pub fn find_cycle(&self) -> Option<Vec<usize>> {
+        #[derive(Clone, Copy, PartialEq)]
+        enum Mark {
+            Visiting,
+            Visited,
+        }
+
+        fn visit(
+            pool: &TaskPool,
+            id: usize,
+            marks: &mut BTreeMap<usize, Mark>,
+            path: &mut Vec<usize>,
+        ) -> Option<Vec<usize>> {
+            match marks.get(&id) {
+                Some(Mark::Visited) => return None,
+                Some(Mark::Visiting) => {
+                    let start = path.iter().position(|&p| p == id)?;
+                    return Some(path[start..].to_vec());
+                }
+                None => {}
+            }
+
+            marks.insert(id, Mark::Visiting);
+            path.push(id);
+
+            if let Some(task) = pool.get_task(id) {
+                for dependency in task.dependencies.iter() {
+                    if let Some(cycle) = visit(pool, *dependency, marks, path) {
+                        return Some(cycle);
+                    }
+                }
+            }
+
+            path.pop();
+            marks.insert(id, Mark::Visited);
+
+            None
+        }
+
+        let mut marks = BTreeMap::new();
+
+        self.todo
+            .tasks
+            .keys()
+            .chain(self.done.tasks.keys())
+            .find_map(|id| visit(self, *id, &mut marks, &mut Vec::new()))
+    }
+
+    /// Returns the IDs of the "to-do" tasks in topological order, such that
+    /// each task comes after its dependencies. Ties are broken by the order
+    /// of the "to-do" pipeline.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the dependencies contain a cycle.
+    pub fn topological_order(&self) -> Result<Vec<usize>> {
+        // Number of pending dependencies of each task
+        let mut pending: BTreeMap<usize, usize> = BTreeMap::new();
+        let mut dependents: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+
+        for id in self.todo.order.iter() {
+            let task = &self.todo.tasks[id];
+
+            let todo_dependencies = task
+                .dependencies
+                .iter()
+                .filter(|dependency| self.todo.tasks.contains_key(dependency))
+                .collect::<Vec<&usize>>();
+
+            pending.insert(*id, todo_dependencies.len());
+
+            for dependency in todo_dependencies {
+                dependents.entry(*dependency).or_default().push(*id);
+            }
+        }
+
+        let mut queue = self
+            .todo
+            .order
+            .iter()
+            .filter(|id| pending[id] == 0)
+            .cloned()
+            .collect::<VecDeque<usize>>();
+
+        let mut order = Vec::new();
+
+        while let Some(id) = queue.pop_front() {
+            order.push(id);
+
+            for dependent in dependents.remove(&id).unwrap_or_default() {
+                let count = pending.get_mut(&dependent).unwrap(); // Safe
+                *count -= 1;
+
+                if *count == 0 {
+                    queue.push_back(dependent);
+                }
+            }
+        }
+
+        if order.len() < self.todo.order.len() {
+            let blocked = self
+                .todo
+                .order
+                .iter()
+                .filter(|id| !order.contains(id))
+                .collect::<Vec<&usize>>();
+
+            return Err(anyhow!(
+                "Cyclic dependencies between the tasks {:?}",
+                blocked
+            ));
+        }
+
+        Ok(order)
+    }
+
+    /// Returns the `Todo` tasks whose dependencies are all done, in the
+    /// order of the "to-do" pipeline. Failed tasks are only run again when
+    /// retried explicitly.
+    pub fn ready_tasks(&self) -> Vec<&Task> {
+        self.todo
+            .order
+            .iter()
+            .filter_map(|id| self.todo.tasks.get(id))
+            .filter(|task| task.status == TaskStatus::Todo)
+            .filter(|task| {
+                task.dependencies
+                    .iter()
+                    .all(|dependency| !self.todo.tasks.contains_key(dependency))
+            })
+            .collect()
+    }
+
+    /// Infers the dependencies between the `CodeGen` tasks from the paths
+    /// of their files. Inferred dependencies which would introduce a cycle
+    /// are skipped.
+    pub fn infer_dependencies(&mut self) {
+        let files = self
+            .todo
+            .tasks
+            .values()
+            .chain(self.done.tasks.values())
+            .filter_map(|task| {
+                task.task_params
+                    .stream_code_()
+                    .map(|params| (task.id, params.filename.clone()))
+            })
+            .collect::<Vec<(usize, String)>>();
+
+        let paths = files
+            .iter()
+            .map(|(_, path)| path.as_str())
+            .collect::<Vec<&str>>();
+
+        for (task, dependency) in infer_dependencies(&paths) {
+            // Skips dependencies which would create a cycle
+            let _ = self.add_dependency(files[task].0, files[dependency].0);
+        }
+    }
+
+    fn get_task(&self, task_id: usize) -> Option<&Task> {
+        self.todo
+            .tasks
+            .get(&task_id)
+            .or_else(|| self.done.tasks.get(&task_id))
+    }
+
+    fn get_task_mut(&mut self, task_id: usize) -> Option<&mut Task> {
+        match self.todo.tasks.get_mut(&task_id) {
+            Some(task) => Some(task),
+            None => self.done.tasks.get_mut(&task_id),
+        }
+    }
+}
+
+/// Infers dependencies between files from their paths, returned as pairs of
+/// indices `(file, dependency)` into `paths`. Dependencies of module roots
+/// come first, such that they take precedence over the layers in case of
+/// conflicts.
+pub fn infer_dependencies(paths: &[&str]) -> Vec<(usize, usize)> {
+    let paths = paths.iter().map(Path::new).collect::<Vec<&Path>>();
+
+    let mut module_dependencies = Vec::new();
+    let mut layer_dependencies = Vec::new();
+
+    for (i, path) in paths.iter().enumerate() {
+        let module_folder = module_folder(path, &paths);
+
+        for (j, other) in paths.iter().enumerate() {
+            if i == j {
+                continue;
+            }
+
+            let imports = match (&module_folder, other.parent()) {
+                (Some(folder), Some(parent)) if parent == folder => {
+                    // Among roots of the same folder, e.g. `lib.rs` and
+                    // `main.rs`, the entry point imports the library
+                    root_rank(other).is_none()
+                        || root_rank(other) < root_rank(path)
+                }
+                (Some(folder), Some(parent)) => {
+                    parent.parent() == Some(folder.as_path())
+                        && root_rank(other).is_some()
+                }
+                _ => false,
+            };
+
+            if imports {
+                module_dependencies.push((i, j));
+            } else if let (Some(layer), Some(other_layer)) =
+                (layer(path), layer(other))
+            {
+                if other_layer < layer {
+                    layer_dependencies.push((i, j));
+                }
+            }
+        }
+    }
+
+    module_dependencies.extend(layer_dependencies);
+
+    module_dependencies
+}
+
+/// Folder whose files are imported by the file, if it is a module root.
+fn module_folder(path: &Path, paths: &[&Path]) -> Option<PathBuf> {
+    let folder = path.parent().unwrap_or(Path::new(""));
+
+    if root_rank(path).is_some() {
+        return Some(folder.to_path_buf());
+    }
+
+    let sibling = folder.join(path.file_stem()?);
+
+    paths
+        .iter()
+        .any(|other| other.parent() == Some(sibling.as_path()))
+        .then_some(sibling)
+}
+
+/// Rank of the file among the module roots, if it is one.
+fn root_rank(path: &Path) -> Option<usize> {
+    let stem = path.file_stem().and_then(|stem| stem.to_str())?;
+
+    MODULE_ROOTS.iter().position(|root| *root == stem)
+}
+
+/// Architectural layer of the file, from its innermost matching component.
+fn layer(path: &Path) -> Option<usize> {
+    path.iter().rev().find_map(|component| {
+        let component = component.to_str()?;
+        let component = component.split('.').next()?.to_lowercase();
+
+        LAYERS
+            .iter()
+            .position(|layer| layer.contains(&component.as_str()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::app_data::task_pool::test_utils::add_file;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn orders_tasks_by_dependencies() -> Result<()> {
+        let mut pool = TaskPool::empty();
+
+        let main = add_file(&mut pool, "main.rs");
+        let handlers = add_file(&mut pool, "handlers/mod.rs");
+        let order = add_file(&mut pool, "handlers/order.rs");
+        let models = add_file(&mut pool, "models/mod.rs");
+        let user = add_file(&mut pool, "models/user.rs");
+        let config = add_file(&mut pool, "config.rs");
+
+        pool.infer_dependencies();
+
+        assert!(pool.depends_on(main, handlers));
+        assert!(pool.depends_on(main, config));
+        assert!(pool.depends_on(handlers, order));
+        assert!(pool.depends_on(order, user));
+        assert!(pool.depends_on(models, user));
+        assert!(!pool.depends_on(user, models));
+        assert!(!pool.depends_on(config, main));
+        assert!(pool.find_cycle().is_none());
+
+        let ready = pool.ready_tasks();
+        let ready = ready.iter().map(|task| task.id).collect::<Vec<usize>>();
+        assert_eq!(ready, vec![user, config]);
+
+        assert_eq!(
+            pool.topological_order()?,
+            vec![user, config, models, order, handlers, main]
+        );
+
+        assert!(pool.add_dependency(user, main).is_err());
+
+        let task = pool.pop_todo(user).unwrap();
+        pool.add_done(task);
+
+        let ready = pool.ready_tasks();
+        let ready = ready.iter().map(|task| task.id).collect::<Vec<usize>>();
+        assert_eq!(ready, vec![models, config]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,338 @@
+//! This module defines the lifecycle of the tasks of the task pool.
+//!
+//! Tasks which are not finished stay in the "to-do" pipeline, with the
+//! status `Todo`, `InProgress` or `Failed`. Finished tasks are moved to the
+//! "done" pipeline, with the status `Done` or `Skipped`:
+//!
+//! ```text
+//! Todo -> InProgress -> Done
+//!   |         |
+//!   |         v
+//!   |       Failed -> InProgress (retry)
+//!   v
+//! Skipped
+//! ```
+//!
+//! Each run of a task is recorded as a `TaskAttempt`, and a task can be
+//! attempted at most `MAX_TASK_ATTEMPTS` times.
+
+use anyhow::{anyhow, Result};
+
+use super::{
+    task::{Task, TaskAttempt, TaskStatus, TokenUsage},
+    task_params::TaskType,
+    TaskPool,
+};
+use crate::endpoints::review_code::ReviewFinding;
+
+impl TaskPool {
+    /// Starts a new attempt of the task `task_id`, run with `model` at
+    /// `now`, in milliseconds since the Unix epoch.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the task is not a `Todo` or `Failed` task of the
+    /// "to-do" pipeline, or if it has no attempts left.
+    pub fn start_task(
+        &mut self,
+        task_id: usize,
+        model: &str,
+        now: f64,
+    ) -> Result<()> {
+        let task = self.todo_task_mut(task_id)?;
+
+        if !matches!(task.status, TaskStatus::Todo | TaskStatus::Failed) {
+            return Err(anyhow!(
+                "Task {} cannot be started as it is {:?}",
+                task_id,
+                task.status
+            ));
+        }
+
+        if !task.can_retry() {
+            return Err(anyhow!(
+                "Task {} already failed {} times",
+                task_id,
+                task.attempts.len()
+            ));
+        }
+
+        task.status = TaskStatus::InProgress;
+        task.attempts.push(TaskAttempt {
+            started_at: now,
+            finished_at: None,
+            model: model.to_string(),
+            error: None,
+            usage: None,
+        });
+
+        Ok(())
+    }
+
+    /// Marks the task `task_id` as done and moves it to the "done" pipeline.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the task is not in progress.
+    pub fn complete_task(
+        &mut self,
+        task_id: usize,
+        usage: Option<TokenUsage>,
+        now: f64,
+    ) -> Result<()> {
+        let attempt = self.current_attempt_mut(task_id)?;
+
+        attempt.finished_at = Some(now);
+        attempt.usage = usage;
+
+        let mut task = self.todo.remove(task_id).expect("Task exists"); // Checked above
+        task.complete();
+
+        self.done.push_back(task);
+
+        Ok(())
+    }
+
+    /// Marks the task `task_id` as failed with `error`. The task stays in the
+    /// "to-do" pipeline such that it can be retried.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the task is not in progress.
+    pub fn fail_task(
+        &mut self,
+        task_id: usize,
+        error: &str,
+        usage: Option<TokenUsage>,
+        now: f64,
+    ) -> Result<()> {
+        let attempt = self.current_attempt_mut(task_id)?;
+
+        attempt.finished_at = Some(now);
+        attempt.error = Some(error.to_string());
+        attempt.usage = usage;
+
+        self.todo_task_mut(task_id)?.status = TaskStatus::Failed;
+
+        Ok(())
+    }
+
+    /// Puts the failed task `task_id` back in the queue, such that it is
+    /// returned again by `ready_tasks`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the task has not failed, or if it has no attempts
+    /// left.
+    pub fn requeue_task(&mut self, task_id: usize) -> Result<()> {
+        let task = self.todo_task_mut(task_id)?;
+
+        if task.status != TaskStatus::Failed {
+            return Err(anyhow!("Task {} has not failed", task_id));
+        }
+
+        if !task.can_retry() {
+            return Err(anyhow!(
+                "Task {} already failed {} times",
+                task_id,
+                task.attempts.len()
+            ));
+        }
+
+        task.status = TaskStatus::Todo;
+
+        Ok(())
+    }
+
+    /// Skips the task `task_id` and moves it to the "done" pipeline, such
+    /// that the tasks depending on it can run.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the task is not in the "to-do" pipeline or is in
+    /// progress.
+    pub fn skip_task(&mut self, task_id: usize) -> Result<()> {
+        let task = self.todo_task_mut(task_id)?;
+
+        if task.status == TaskStatus::InProgress {
+            return Err(anyhow!(
+                "Task {} cannot be skipped as it is in progress",
+                task_id
+            ));
+        }
+
+        let mut task = self.todo.remove(task_id).expect("Task exists"); // Checked above
+        task.status = TaskStatus::Skipped;
+
+        self.done.push_back(task);
+
+        Ok(())
+    }
+
+    /// Moves the finished task `task_id` back to the "to-do" pipeline, such
+    /// that it can be run again.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the task is not in the "done" pipeline, or if it
+    /// has no attempts left.
+    pub fn reopen_task(&mut self, task_id: usize) -> Result<()> {
+        let task = self.done.tasks.get(&task_id).ok_or_else(|| {
+            anyhow!("Failed to retrieve done task by id: {}", task_id)
+        })?;
+
+        if !task.can_retry() {
+            return Err(anyhow!(
+                "Task {} was already attempted {} times",
+                task_id,
+                task.attempts.len()
+            ));
+        }
+
+        let mut task = self.done.remove(task_id).expect("Task exists"); // Checked above
+        task.status = TaskStatus::Todo;
+
+        self.todo.push_back(task);
+
+        Ok(())
+    }
+
+    /// Replaces the findings of the `Review` task `task_id`, which is either
+    /// still in the "to-do" pipeline or already done.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the task does not exist or is not a `Review` task.
+    pub fn set_findings(
+        &mut self,
+        task_id: usize,
+        findings: Vec<ReviewFinding>,
+    ) -> Result<()> {
+        let task = self
+            .todo
+            .tasks
+            .get_mut(&task_id)
+            .or_else(|| self.done.tasks.get_mut(&task_id))
+            .ok_or_else(|| {
+                anyhow!("Failed to retrieve task by id: {}", task_id)
+            })?;
+
+        if !matches!(task.task_type(), TaskType::Review) {
+            return Err(anyhow!("Task {} is not a review", task_id));
+        }
+
+        task.findings = findings;
+
+        Ok(())
+    }
+
+    /// Returns the tasks whose latest attempt failed, in the order of the
+    /// "to-do" pipeline.
+    pub fn failed_tasks(&self) -> Vec<&Task> {
+        self.todo_tasks_with_status(TaskStatus::Failed)
+    }
+
+    /// Returns the tasks in progress since more than `timeout_ms`
+    /// milliseconds at `now`, e.g. because the editor was closed while they
+    /// ran.
+    pub fn stuck_tasks(&self, now: f64, timeout_ms: f64) -> Vec<&Task> {
+        self.todo_tasks_with_status(TaskStatus::InProgress)
+            .into_iter()
+            .filter(|task| {
+                task.attempts
+                    .last()
+                    .is_none_or(|attempt| now - attempt.started_at > timeout_ms)
+            })
+            .collect()
+    }
+
+    fn todo_tasks_with_status(&self, status: TaskStatus) -> Vec<&Task> {
+        self.todo
+            .order
+            .iter()
+            .filter_map(|id| self.todo.tasks.get(id))
+            .filter(|task| task.status == status)
+            .collect()
+    }
+
+    fn todo_task_mut(&mut self, task_id: usize) -> Result<&mut Task> {
+        self.todo.tasks.get_mut(&task_id).ok_or_else(|| {
+            anyhow!("Failed to retrieve to-do task by id: {}", task_id)
+        })
+    }
+
+    fn current_attempt_mut(
+        &mut self,
+        task_id: usize,
+    ) -> Result<&mut TaskAttempt> {
+        let task = self.todo_task_mut(task_id)?;
+
+        if task.status != TaskStatus::InProgress {
+            return Err(anyhow!("Task {} is not in progress", task_id));
+        }
+
+        task.attempts
+            .last_mut()
+            .ok_or_else(|| anyhow!("Task {} has no attempt", task_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        consts::MAX_TASK_ATTEMPTS,
+        models::app_data::task_pool::test_utils::add_file,
+    };
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn records_attempts_and_limits_retries() -> Result<()> {
+        let mut pool = TaskPool::empty();
+
+        let id = add_file(&mut pool, "main.rs");
+
+        assert!(pool.complete_task(id, None, 0.0).is_err());
+
+        for attempt in 0..MAX_TASK_ATTEMPTS {
+            let now = attempt as f64 * 1000.0;
+
+            pool.start_task(id, "gpt-4", now)?;
+            assert!(pool.start_task(id, "gpt-4", now).is_err());
+
+            pool.fail_task(id, "Timeout", None, now + 10.0)?;
+        }
+
+        assert_eq!(pool.failed_tasks().len(), 1);
+        assert!(pool.start_task(id, "gpt-4", 5000.0).is_err());
+
+        let task = &pool.todo.tasks[&id];
+        assert_eq!(task.retries(), MAX_TASK_ATTEMPTS - 1);
+        assert_eq!(task.attempts[0].finished_at, Some(10.0));
+        assert_eq!(task.attempts[0].error.as_deref(), Some("Timeout"));
+
+        // Skipped tasks are finished, but cannot be reopened after too many
+        // attempts
+        pool.skip_task(id)?;
+        assert_eq!(pool.done.tasks[&id].status, TaskStatus::Skipped);
+        assert!(pool.reopen_task(id).is_err());
+
+        let id = add_file(&mut pool, "lib.rs");
+
+        pool.start_task(id, "gpt-4", 0.0)?;
+        assert!(pool.stuck_tasks(1000.0, 5000.0).is_empty());
+        assert_eq!(pool.stuck_tasks(10_000.0, 5000.0).len(), 1);
+
+        pool.complete_task(id, Some(TokenUsage::new(120, 30)), 2000.0)?;
+
+        let task = &pool.done.tasks[&id];
+        assert_eq!(task.status, TaskStatus::Done);
+        assert_eq!(task.attempts[0].usage.unwrap().total_tokens(), 150);
+
+        pool.reopen_task(id)?;
+        assert_eq!(pool.todo.tasks[&id].status, TaskStatus::Todo);
+        assert_eq!(pool.todo.tasks[&id].attempts.len(), 1);
+
+        Ok(())
+    }
+}
@@ -2,6 +2,9 @@
 //!
 //! The task pool manages tasks and their parameters in both "to-do" and "done" states.
 
+pub mod graph;
+pub mod lifecycle;
+pub mod scheduler;
 pub mod task;
 pub mod task_params;
 use self::{task::Task, task_params::TaskParams};
@@ -276,3 +279,23 @@ impl Pipeline {
         PipelineIterator { tasks, order }
     }
 }
+
+#[cfg(test)]
+pub(crate) mod test_utils {
+    use super::{
+        task_params::{TaskParams, TaskType},
+        TaskPool,
+    };
+    use crate::endpoints::stream_code::CodeGenParams;
+
+    /// Adds a to-do task writing the code of `filename`, and returns its ID.
+    pub(crate) fn add_file(pool: &mut TaskPool, filename: &str) -> usize {
+        let task_params = TaskParams::new_(
+            TaskType::CodeGen,
+            Box::new(CodeGenParams::new(filename.into(), String::new())),
+        )
+        .unwrap();
+
+        pool.add_todo(filename, "", task_params)
+    }
+}
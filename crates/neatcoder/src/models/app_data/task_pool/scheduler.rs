@@ -0,0 +1,351 @@
+//! This module schedules the tasks of the task pool to run concurrently.
+//!
+//! The `Scheduler` hands out batches of ready tasks, i.e. tasks whose
+//! dependencies are done, such that at most `max_concurrency` tasks are in
+//! flight and the tokens spent over the last minute stay within
+//! `tokens_per_minute`. The tokens of a task are estimated when it starts
+//! and replaced by its actual usage when it finishes, if known.
+//!
+//! Scaffolding the project updates the application state in place, hence
+//! `ScaffoldProject` tasks always run alone.
+
+use anyhow::Result;
+use std::collections::{BTreeSet, VecDeque};
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use super::{
+    task::{Task, TokenUsage},
+    task_params::TaskType,
+    TaskPool,
+};
+
+/// Length of the window of the token budget, in milliseconds
+const WINDOW_MS: f64 = 60_000.0;
+
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct Scheduler {
+    /// Maximum number of tasks in flight
+    pub(crate) max_concurrency: usize,
+    /// Maximum number of tokens spent per minute
+    pub(crate) tokens_per_minute: u32,
+    /// IDs of the tasks in flight
+    pub(crate) in_flight: BTreeSet<usize>,
+    /// Tokens spent over the last minute, from the oldest to the latest
+    pub(crate) spent: VecDeque<Spend>,
+}
+
+/// Tokens spent by a task.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Spend {
+    pub(crate) task_id: usize,
+    /// Start of the task, in milliseconds since the Unix epoch
+    pub(crate) at: f64,
+    pub(crate) tokens: u32,
+}
+
+#[wasm_bindgen]
+impl Scheduler {
+    #[wasm_bindgen(constructor)]
+    pub fn new(max_concurrency: usize, tokens_per_minute: u32) -> Scheduler {
+        Scheduler {
+            max_concurrency: max_concurrency.max(1),
+            tokens_per_minute,
+            in_flight: BTreeSet::new(),
+            spent: VecDeque::new(),
+        }
+    }
+
+    /// Returns the IDs of the tasks in flight.
+    #[wasm_bindgen(getter, js_name = inFlight)]
+    pub fn in_flight(&self) -> Vec<usize> {
+        self.in_flight.iter().cloned().collect()
+    }
+
+    #[wasm_bindgen(js_name = isIdle)]
+    pub fn is_idle(&self) -> bool {
+        self.in_flight.is_empty()
+    }
+
+    /// Returns the number of milliseconds after `now` until the oldest
+    /// tokens spent leave the budget window.
+    #[wasm_bindgen(js_name = waitMs)]
+    pub fn wait_ms(&self, now: f64) -> f64 {
+        self.spent
+            .front()
+            .map(|spend| (spend.at + WINDOW_MS - now).max(0.0))
+            .unwrap_or(0.0)
+    }
+}
+
+impl Scheduler {
+    /// Starts the next ready tasks of `pool` with `model` at `now`, and
+    /// returns their IDs. Tasks are taken in the order of the "to-do"
+    /// pipeline, until the concurrency limit or the token budget is reached.
+    /// `estimate` returns the tokens a task is expected to spend.
+    ///
+    /// A task exceeding the whole budget is only started once no tokens
+    /// were spent over the last minute, such that it does not block the
+    /// queue forever. A `ScaffoldProject` task is only started once no task
+    /// is in flight, and no other task is started alongside it.
+    pub fn next_batch<F>(
+        &mut self,
+        pool: &mut TaskPool,
+        model: &str,
+        now: f64,
+        estimate: F,
+    ) -> Result<Vec<usize>>
+    where
+        F: Fn(&Task) -> u32,
+    {
+        self.spent.retain(|spend| now - spend.at < WINDOW_MS);
+
+        if self
+            .in_flight
+            .iter()
+            .any(|task_id| is_exclusive(pool, *task_id))
+        {
+            return Ok(Vec::new());
+        }
+
+        let mut spent = self.spent_tokens();
+
+        let ready = pool
+            .ready_tasks()
+            .into_iter()
+            .map(|task| (task.id, estimate(task)))
+            .collect::<Vec<(usize, u32)>>();
+
+        let mut batch = Vec::new();
+
+        for (task_id, tokens) in ready {
+            if self.in_flight.len() >= self.max_concurrency {
+                break;
+            }
+
+            let exclusive = is_exclusive(pool, task_id);
+
+            if exclusive && !self.in_flight.is_empty() {
+                break;
+            }
+
+            let within_budget = spent.saturating_add(tokens)
+                <= self.tokens_per_minute
+                || self.spent.is_empty();
+
+            if !within_budget {
+                break;
+            }
+
+            pool.start_task(task_id, model, now)?;
+
+            self.in_flight.insert(task_id);
+            self.spent.push_back(Spend {
+                task_id,
+                at: now,
+                tokens,
+            });
+
+            spent = spent.saturating_add(tokens);
+            batch.push(task_id);
+
+            if exclusive {
+                break;
+            }
+        }
+
+        Ok(batch)
+    }
+
+    /// Marks the task `task_id` in flight as done at `now`.
+    pub fn complete(
+        &mut self,
+        pool: &mut TaskPool,
+        task_id: usize,
+        usage: Option<TokenUsage>,
+        now: f64,
+    ) -> Result<()> {
+        pool.complete_task(task_id, usage, now)?;
+
+        self.land(task_id, usage);
+
+        Ok(())
+    }
+
+    /// Marks the task `task_id` in flight as failed at `now`, and requeues
+    /// it if it has attempts left. Returns whether the task was requeued.
+    pub fn fail(
+        &mut self,
+        pool: &mut TaskPool,
+        task_id: usize,
+        error: &str,
+        usage: Option<TokenUsage>,
+        now: f64,
+    ) -> Result<bool> {
+        pool.fail_task(task_id, error, usage, now)?;
+
+        self.land(task_id, usage);
+
+        Ok(pool.requeue_task(task_id).is_ok())
+    }
+
+    /// Removes the task from the tasks in flight, replacing its estimated
+    /// tokens by the actual ones if known.
+    fn land(&mut self, task_id: usize, usage: Option<TokenUsage>) {
+        self.in_flight.remove(&task_id);
+
+        let spend = self
+            .spent
+            .iter_mut()
+            .rev()
+            .find(|spend| spend.task_id == task_id);
+
+        if let (Some(spend), Some(usage)) = (spend, usage) {
+            spend.tokens = usage.total_tokens();
+        }
+    }
+
+    fn spent_tokens(&self) -> u32 {
+        self.spent
+            .iter()
+            .fold(0, |total, spend| total.saturating_add(spend.tokens))
+    }
+}
+
+/// Returns whether the to-do task `task_id` must run alone.
+fn is_exclusive(pool: &TaskPool, task_id: usize) -> bool {
+    pool.todo.tasks.get(&task_id).is_some_and(|task| {
+        matches!(task.task_type(), TaskType::ScaffoldProject)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        endpoints::scaffold_project::ScaffoldParams,
+        models::app_data::task_pool::{
+            task::TaskStatus, task_params::TaskParams, test_utils::add_file,
+        },
+    };
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn schedules_batches_within_limits() -> Result<()> {
+        let mut pool = TaskPool::empty();
+
+        let a = add_file(&mut pool, "a.rs");
+        let b = add_file(&mut pool, "b.rs");
+        let c = add_file(&mut pool, "c.rs");
+        let d = add_file(&mut pool, "d.rs");
+        let main = add_file(&mut pool, "main.rs");
+
+        for dependency in [a, b, c, d] {
+            pool.add_dependency(main, dependency)?;
+        }
+
+        let mut scheduler = Scheduler::new(2, 1000);
+        let estimate = |_: &Task| 400;
+
+        // Limited by the concurrency
+        assert_eq!(
+            scheduler.next_batch(&mut pool, "gpt-4", 0.0, estimate)?,
+            [a, b]
+        );
+        assert_eq!(pool.todo.tasks[&a].status, TaskStatus::InProgress);
+        assert!(scheduler
+            .next_batch(&mut pool, "gpt-4", 0.0, estimate)?
+            .is_empty());
+
+        // Limited by the token budget, as 800 tokens were spent
+        scheduler.complete(&mut pool, a, None, 100.0)?;
+        assert!(scheduler
+            .next_batch(&mut pool, "gpt-4", 100.0, estimate)?
+            .is_empty());
+        assert_eq!(scheduler.wait_ms(100.0), 59_900.0);
+
+        // Failed tasks are requeued and the actual usage is accounted for
+        let requeued = scheduler.fail(
+            &mut pool,
+            b,
+            "Timeout",
+            Some(TokenUsage::new(50, 0)),
+            200.0,
+        )?;
+        assert!(requeued);
+        assert_eq!(
+            scheduler.next_batch(&mut pool, "gpt-4", 200.0, estimate)?,
+            [b]
+        );
+
+        // Tokens are released after a minute
+        assert_eq!(
+            scheduler.next_batch(&mut pool, "gpt-4", 60_000.0, estimate)?,
+            [c]
+        );
+        assert_eq!(scheduler.in_flight(), [b, c]);
+
+        for task_id in [b, c] {
+            scheduler.complete(&mut pool, task_id, None, 61_000.0)?;
+        }
+
+        // A task exceeding the budget runs alone once the window is empty
+        let expensive = |_: &Task| 5000;
+        assert!(scheduler
+            .next_batch(&mut pool, "gpt-4", 61_000.0, expensive)?
+            .is_empty());
+        assert_eq!(
+            scheduler.next_batch(&mut pool, "gpt-4", 121_000.0, expensive)?,
+            [d]
+        );
+        assert!(!scheduler.is_idle());
+
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn runs_scaffolding_alone() -> Result<()> {
+        let mut pool = TaskPool::empty();
+
+        let a = add_file(&mut pool, "a.rs");
+        let scaffold = pool.add_todo(
+            "Scaffold project",
+            "",
+            TaskParams::new_(
+                TaskType::ScaffoldProject,
+                Box::new(ScaffoldParams::new(String::new())),
+            )?,
+        );
+        let b = add_file(&mut pool, "b.rs");
+
+        let mut scheduler = Scheduler::new(3, 100_000);
+        let estimate = |_: &Task| 400;
+
+        // Scaffolding waits for the tasks in flight
+        assert_eq!(
+            scheduler.next_batch(&mut pool, "gpt-4", 0.0, estimate)?,
+            [a]
+        );
+        assert!(scheduler
+            .next_batch(&mut pool, "gpt-4", 0.0, estimate)?
+            .is_empty());
+
+        // And no task starts alongside it
+        scheduler.complete(&mut pool, a, None, 100.0)?;
+        assert_eq!(
+            scheduler.next_batch(&mut pool, "gpt-4", 100.0, estimate)?,
+            [scaffold]
+        );
+        assert!(scheduler
+            .next_batch(&mut pool, "gpt-4", 100.0, estimate)?
+            .is_empty());
+
+        scheduler.complete(&mut pool, scaffold, None, 200.0)?;
+        assert_eq!(
+            scheduler.next_batch(&mut pool, "gpt-4", 200.0, estimate)?,
+            [b]
+        );
+
+        Ok(())
+    }
+}
@@ -1,11 +1,13 @@
 //! This module defines the structure and behavior of tasks.
 //!
 //! It provides a `Task` struct that represents an individual task,
-//! with associated task parameters and status.
+//! with associated task parameters, status and history of attempts.
 
 use super::task_params::{TaskParams, TaskType};
+use crate::{consts::MAX_TASK_ATTEMPTS, endpoints::review_code::ReviewFinding};
 use js_sys::JsString;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 use wasm_bindgen::prelude::wasm_bindgen;
 
 /// Represents a task with associated parameters and status.
@@ -21,6 +23,15 @@ pub struct Task {
     pub(crate) description: String,
     pub(crate) task_params: TaskParams,
     pub status: TaskStatus,
+    /// IDs of the tasks which must be done before this one
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub(crate) dependencies: BTreeSet<usize>,
+    /// Runs of the task, from the oldest to the latest
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) attempts: Vec<TaskAttempt>,
+    /// Issues raised by the latest run of a `Review` task
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) findings: Vec<ReviewFinding>,
 }
 
 /// Represents the possible statuses a task can have.
@@ -28,7 +39,35 @@ pub struct Task {
 #[derive(Debug, PartialEq, Deserialize, Serialize, Clone, Copy)]
 pub enum TaskStatus {
     Todo,
+    InProgress,
     Done,
+    Failed,
+    Skipped,
+}
+
+/// Record of a run of a task.
+#[wasm_bindgen]
+#[derive(Debug, PartialEq, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskAttempt {
+    /// Start of the run, in milliseconds since the Unix epoch
+    pub(crate) started_at: f64,
+    /// End of the run, in milliseconds since the Unix epoch
+    pub(crate) finished_at: Option<f64>,
+    /// Model used to run the task
+    pub(crate) model: String,
+    /// Error message, if the run failed
+    pub(crate) error: Option<String>,
+    pub(crate) usage: Option<TokenUsage>,
+}
+
+/// Tokens consumed by a run of a task.
+#[wasm_bindgen]
+#[derive(Debug, PartialEq, Deserialize, Serialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenUsage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
 }
 
 #[wasm_bindgen]
@@ -49,6 +88,9 @@ impl Task {
             description: description.to_string(),
             task_params,
             status: TaskStatus::Todo,
+            dependencies: BTreeSet::new(),
+            attempts: Vec::new(),
+            findings: Vec::new(),
         }
     }
 
@@ -75,9 +117,93 @@ impl Task {
         self.task_params.clone()
     }
 
+    /// Returns the IDs of the tasks this task depends on.
+    #[wasm_bindgen(getter)]
+    pub fn dependencies(&self) -> Vec<usize> {
+        self.dependencies.iter().cloned().collect()
+    }
+
+    /// Returns the runs of the task, from the oldest to the latest.
+    #[wasm_bindgen(getter)]
+    pub fn attempts(&self) -> Vec<TaskAttempt> {
+        self.attempts.clone()
+    }
+
+    /// Returns the issues raised by the latest run of a `Review` task.
+    #[wasm_bindgen(getter)]
+    pub fn findings(&self) -> Vec<ReviewFinding> {
+        self.findings.clone()
+    }
+
+    /// Returns the number of times the task was run again after its first
+    /// attempt.
+    #[wasm_bindgen(getter)]
+    pub fn retries(&self) -> usize {
+        self.attempts.len().saturating_sub(1)
+    }
+
+    /// Whether the task can be attempted again.
+    #[wasm_bindgen(getter, js_name = canRetry)]
+    pub fn can_retry(&self) -> bool {
+        self.attempts.len() < MAX_TASK_ATTEMPTS
+    }
+
+    /// Returns the error message of the latest attempt, if it failed.
+    #[wasm_bindgen(getter, js_name = lastError)]
+    pub fn last_error(&self) -> Option<JsString> {
+        self.attempts
+            .last()
+            .and_then(|attempt| attempt.error.clone())
+            .map(JsString::from)
+    }
+
     /// Returns the type of the task from the associated task parameters.
     #[wasm_bindgen(js_name = taskType)]
     pub fn task_type(&self) -> TaskType {
         self.task_params.task_type
     }
 }
+
+#[wasm_bindgen]
+impl TaskAttempt {
+    #[wasm_bindgen(getter, js_name = startedAt)]
+    pub fn started_at(&self) -> f64 {
+        self.started_at
+    }
+
+    #[wasm_bindgen(getter, js_name = finishedAt)]
+    pub fn finished_at(&self) -> Option<f64> {
+        self.finished_at
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn model(&self) -> JsString {
+        self.model.clone().into()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn error(&self) -> Option<JsString> {
+        self.error.clone().map(JsString::from)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn usage(&self) -> Option<TokenUsage> {
+        self.usage
+    }
+}
+
+#[wasm_bindgen]
+impl TokenUsage {
+    #[wasm_bindgen(constructor)]
+    pub fn new(prompt_tokens: u32, completion_tokens: u32) -> TokenUsage {
+        TokenUsage {
+            prompt_tokens,
+            completion_tokens,
+        }
+    }
+
+    #[wasm_bindgen(js_name = totalTokens)]
+    pub fn total_tokens(&self) -> u32 {
+        self.prompt_tokens + self.completion_tokens
+    }
+}
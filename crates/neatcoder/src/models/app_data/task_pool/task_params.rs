@@ -4,14 +4,18 @@
 //! including the task type and associated inner parameters.
 
 use crate::{
-    endpoints::{scaffold_project::ScaffoldParams, stream_code::CodeGenParams},
+    endpoints::{
+        edit_code::CodeEditParams, gen_api_stubs::ApiStubParams,
+        gen_config::ConfigGenParams, gen_docs::DocGenParams,
+        gen_tests::TestGenParams, review_code::ReviewParams,
+        scaffold_project::ScaffoldParams, stream_code::CodeGenParams,
+    },
     utils::log,
-    JsError,
 };
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 use std::any::Any;
-use wasm_bindgen::prelude::{wasm_bindgen, JsValue};
+use wasm_bindgen::prelude::wasm_bindgen;
 
 /// Represents parameters for a task.
 ///
@@ -32,99 +36,159 @@ pub struct TaskParams {
 pub enum TaskType {
     ScaffoldProject,
     CodeGen,
+    /// Modifies an existing file with a unified diff
+    CodeEdit,
+    /// Writes the unit tests of a file
+    TestGen,
+    /// Reviews a file, or the changes made to it
+    Review,
+    /// Writes Markdown documentation
+    DocGen,
+    /// Writes a configuration or manifest file of the project
+    ConfigGen,
+    /// Writes the client or the handlers of the operations of an API
+    ApiStubGen,
 }
 
 /// Holds the actual parameters for the task based on its type.
 ///
-/// Depending on the task type, one of the fields will be populated.
-#[wasm_bindgen]
+/// It serializes to an object with a single field named after the variant,
+/// e.g. `{ "streamCode": { ... } }`, and also deserializes from the former
+/// layout, where the fields of the other task types were `null`.
 #[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase", try_from = "TaskParamsFields")]
+pub enum TaskParamsInner {
+    ScaffoldProject(ScaffoldParams),
+    StreamCode(CodeGenParams),
+    CodeEdit(CodeEditParams),
+    TestGen(TestGenParams),
+    Review(ReviewParams),
+    DocGen(DocGenParams),
+    ConfigGen(ConfigGenParams),
+    ApiStubGen(ApiStubParams),
+}
+
+/// Former layout of `TaskParamsInner`, with one optional field per task type.
+#[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct TaskParamsInner {
-    pub(crate) scaffold_project: Option<ScaffoldParams>,
-    pub(crate) stream_code: Option<CodeGenParams>,
+struct TaskParamsFields {
+    #[serde(default)]
+    scaffold_project: Option<ScaffoldParams>,
+    #[serde(default)]
+    stream_code: Option<CodeGenParams>,
+    #[serde(default)]
+    code_edit: Option<CodeEditParams>,
+    #[serde(default)]
+    test_gen: Option<TestGenParams>,
+    #[serde(default)]
+    review: Option<ReviewParams>,
+    #[serde(default)]
+    doc_gen: Option<DocGenParams>,
+    #[serde(default)]
+    config_gen: Option<ConfigGenParams>,
+    #[serde(default)]
+    api_stub_gen: Option<ApiStubParams>,
 }
 
-#[wasm_bindgen]
-impl TaskParams {
-    /// Creates a new `TaskParams` with the given task type and inner parameters.
-    #[wasm_bindgen(constructor)]
-    pub fn new(task_type: TaskType, inner: TaskParamsInner) -> TaskParams {
-        Self { task_type, inner }
+impl TryFrom<TaskParamsFields> for TaskParamsInner {
+    type Error = String;
+
+    fn try_from(fields: TaskParamsFields) -> Result<Self, Self::Error> {
+        let mut params = [
+            fields
+                .scaffold_project
+                .map(TaskParamsInner::ScaffoldProject),
+            fields.stream_code.map(TaskParamsInner::StreamCode),
+            fields.code_edit.map(TaskParamsInner::CodeEdit),
+            fields.test_gen.map(TaskParamsInner::TestGen),
+            fields.review.map(TaskParamsInner::Review),
+            fields.doc_gen.map(TaskParamsInner::DocGen),
+            fields.config_gen.map(TaskParamsInner::ConfigGen),
+            fields.api_stub_gen.map(TaskParamsInner::ApiStubGen),
+        ]
+        .into_iter()
+        .flatten();
+
+        match (params.next(), params.next()) {
+            (Some(inner), None) => Ok(inner),
+            (None, _) => Err(String::from("No task parameters provided.")),
+            (Some(_), Some(_)) => {
+                Err(String::from("Cannot accept multiple parameter types."))
+            }
+        }
     }
+}
 
-    /// Returns the inner parameters of the task.
-    #[wasm_bindgen(getter)]
-    pub fn inner(&self) -> TaskParamsInner {
-        self.inner.clone()
+#[wasm_bindgen]
+impl TaskParams {
+    /// Creates the parameters of a `ScaffoldProject` task, the only task
+    /// which is not created by `AppData` itself.
+    #[wasm_bindgen(js_name = newScaffoldProject)]
+    pub fn new_scaffold_project(scaffold: ScaffoldParams) -> TaskParams {
+        Self {
+            task_type: TaskType::ScaffoldProject,
+            inner: TaskParamsInner::ScaffoldProject(scaffold),
+        }
     }
 
     /// Retrieves the scaffold project parameters if the task type is `ScaffoldProject`.
     #[wasm_bindgen(getter, js_name = scaffoldProject)]
     pub fn scaffold_project(&self) -> Option<ScaffoldParams> {
-        match self.task_type {
-            TaskType::ScaffoldProject => self.inner.scaffold_project.clone(),
-            _ => None,
-        }
+        self.scaffold_project_().cloned()
     }
 
     /// Retrieves the stream code parameters if the task type is `CodeGen`.
     #[wasm_bindgen(getter, js_name = streamCode)]
     pub fn stream_code(&self) -> Option<CodeGenParams> {
-        match self.task_type {
-            TaskType::CodeGen => {
-                log(&format!(
-                    "RETURNING: {:?}",
-                    self.inner.stream_code.clone(),
-                ));
-
-                self.inner.stream_code.clone()
-            }
-            _ => None,
+        let stream_code = self.stream_code_().cloned();
+
+        if stream_code.is_some() {
+            log(&format!("RETURNING: {:?}", stream_code));
         }
+
+        stream_code
     }
 
-    /// Returns the type of the task.
-    #[wasm_bindgen(getter, js_name = taskType)]
-    pub fn task_type(&self) -> TaskType {
-        self.task_type
+    /// Retrieves the code edit parameters if the task type is `CodeEdit`.
+    #[wasm_bindgen(getter, js_name = codeEdit)]
+    pub fn code_edit(&self) -> Option<CodeEditParams> {
+        self.code_edit_().cloned()
     }
-}
 
-#[wasm_bindgen]
-impl TaskParamsInner {
-    /// Creates a new `TaskParamsInner` with the given scaffold project and stream code parameters.
-    ///
-    /// This constructor ensures that only one set of parameters is provided.
-    ///
-    /// # Errors
-    ///
-    /// Returns an error if both `scaffold_project` and `stream_code` parameters are provided.
-    #[wasm_bindgen(constructor)]
-    pub fn new(
-        scaffold_project: Option<ScaffoldParams>,
-        stream_code: Option<CodeGenParams>,
-    ) -> Result<TaskParamsInner, JsValue> {
-        if scaffold_project.is_some() && stream_code.is_some() {
-            return Err(anyhow!("Cannot accept multiple parameter types."))
-                .map_err(|e| JsError::from_str(&e.to_string()));
-        }
-        Ok(Self {
-            scaffold_project,
-            stream_code,
-        })
+    /// Retrieves the test generation parameters if the task type is `TestGen`.
+    #[wasm_bindgen(getter, js_name = testGen)]
+    pub fn test_gen(&self) -> Option<TestGenParams> {
+        self.test_gen_().cloned()
     }
 
-    /// Returns the scaffold project parameters if they exist.
-    #[wasm_bindgen(getter, js_name = scaffoldProject)]
-    pub fn scaffold_project(&self) -> Option<ScaffoldParams> {
-        self.scaffold_project.clone()
+    /// Retrieves the review parameters if the task type is `Review`.
+    #[wasm_bindgen(getter)]
+    pub fn review(&self) -> Option<ReviewParams> {
+        self.review_().cloned()
     }
 
-    /// Returns the stream code parameters if they exist.
-    #[wasm_bindgen(getter, js_name = streamCode)]
-    pub fn stream_code(&self) -> Option<CodeGenParams> {
-        self.stream_code.clone()
+    /// Retrieves the documentation generation parameters if the task type is `DocGen`.
+    #[wasm_bindgen(getter, js_name = docGen)]
+    pub fn doc_gen(&self) -> Option<DocGenParams> {
+        self.doc_gen_().cloned()
+    }
+
+    /// Retrieves the config generation parameters if the task type is `ConfigGen`.
+    #[wasm_bindgen(getter, js_name = configGen)]
+    pub fn config_gen(&self) -> Option<ConfigGenParams> {
+        self.config_gen_().cloned()
+    }
+
+    /// Retrieves the API stub generation parameters if the task type is `ApiStubGen`.
+    #[wasm_bindgen(getter, js_name = apiStubGen)]
+    pub fn api_stub_gen(&self) -> Option<ApiStubParams> {
+        self.api_stub_gen_().cloned()
+    }
+
+    /// Returns the type of the task.
+    #[wasm_bindgen(getter, js_name = taskType)]
+    pub fn task_type(&self) -> TaskType {
+        self.task_type
     }
 }
 
@@ -137,48 +201,105 @@ impl TaskParams {
     ///
     /// Returns an error if the downcast fails for the provided task type.
     pub fn new_(task_type: TaskType, inner: Box<dyn Any>) -> Result<Self> {
-        match task_type {
-            TaskType::ScaffoldProject => {
-                if let Some(scaffold) = inner.downcast_ref::<ScaffoldParams>() {
-                    Ok(TaskParams {
-                        task_type,
-                        inner: TaskParamsInner {
-                            scaffold_project: Some(scaffold.clone()),
-                            stream_code: None,
-                        },
-                    })
-                } else {
-                    Err(anyhow!("Failed to downcast to ScaffoldProject"))
-                }
-            }
-            TaskType::CodeGen => {
-                if let Some(code_gen) = inner.downcast_ref::<CodeGenParams>() {
-                    Ok(TaskParams {
-                        task_type,
-                        inner: TaskParamsInner {
-                            scaffold_project: None,
-                            stream_code: Some(code_gen.clone()),
-                        },
-                    })
-                } else {
-                    Err(anyhow!("Failed to downcast to CodeGen"))
-                }
-            }
+        let inner = match task_type {
+            TaskType::ScaffoldProject => inner
+                .downcast_ref::<ScaffoldParams>()
+                .cloned()
+                .map(TaskParamsInner::ScaffoldProject),
+            TaskType::CodeGen => inner
+                .downcast_ref::<CodeGenParams>()
+                .cloned()
+                .map(TaskParamsInner::StreamCode),
+            TaskType::CodeEdit => inner
+                .downcast_ref::<CodeEditParams>()
+                .cloned()
+                .map(TaskParamsInner::CodeEdit),
+            TaskType::TestGen => inner
+                .downcast_ref::<TestGenParams>()
+                .cloned()
+                .map(TaskParamsInner::TestGen),
+            TaskType::Review => inner
+                .downcast_ref::<ReviewParams>()
+                .cloned()
+                .map(TaskParamsInner::Review),
+            TaskType::DocGen => inner
+                .downcast_ref::<DocGenParams>()
+                .cloned()
+                .map(TaskParamsInner::DocGen),
+            TaskType::ConfigGen => inner
+                .downcast_ref::<ConfigGenParams>()
+                .cloned()
+                .map(TaskParamsInner::ConfigGen),
+            TaskType::ApiStubGen => inner
+                .downcast_ref::<ApiStubParams>()
+                .cloned()
+                .map(TaskParamsInner::ApiStubGen),
         }
+        .ok_or_else(|| anyhow!("Failed to downcast to {:?}", task_type))?;
+
+        Ok(TaskParams { task_type, inner })
     }
 
     /// Retrieves a reference to the scaffold project parameters if the task type is `ScaffoldProject`.
     pub fn scaffold_project_(&self) -> Option<&ScaffoldParams> {
-        match self.task_type {
-            TaskType::ScaffoldProject => self.inner.scaffold_project.as_ref(),
+        match &self.inner {
+            TaskParamsInner::ScaffoldProject(params) => Some(params),
             _ => None,
         }
     }
 
     /// Retrieves a reference to the stream code parameters if the task type is `CodeGen`.
     pub fn stream_code_(&self) -> Option<&CodeGenParams> {
-        match self.task_type {
-            TaskType::CodeGen => self.inner.stream_code.as_ref(),
+        match &self.inner {
+            TaskParamsInner::StreamCode(params) => Some(params),
+            _ => None,
+        }
+    }
+
+    /// Retrieves a reference to the code edit parameters if the task type is `CodeEdit`.
+    pub fn code_edit_(&self) -> Option<&CodeEditParams> {
+        match &self.inner {
+            TaskParamsInner::CodeEdit(params) => Some(params),
+            _ => None,
+        }
+    }
+
+    /// Retrieves a reference to the test generation parameters if the task type is `TestGen`.
+    pub fn test_gen_(&self) -> Option<&TestGenParams> {
+        match &self.inner {
+            TaskParamsInner::TestGen(params) => Some(params),
+            _ => None,
+        }
+    }
+
+    /// Retrieves a reference to the review parameters if the task type is `Review`.
+    pub fn review_(&self) -> Option<&ReviewParams> {
+        match &self.inner {
+            TaskParamsInner::Review(params) => Some(params),
+            _ => None,
+        }
+    }
+
+    /// Retrieves a reference to the documentation generation parameters if the task type is `DocGen`.
+    pub fn doc_gen_(&self) -> Option<&DocGenParams> {
+        match &self.inner {
+            TaskParamsInner::DocGen(params) => Some(params),
+            _ => None,
+        }
+    }
+
+    /// Retrieves a reference to the config generation parameters if the task type is `ConfigGen`.
+    pub fn config_gen_(&self) -> Option<&ConfigGenParams> {
+        match &self.inner {
+            TaskParamsInner::ConfigGen(params) => Some(params),
+            _ => None,
+        }
+    }
+
+    /// Retrieves a reference to the API stub generation parameters if the task type is `ApiStubGen`.
+    pub fn api_stub_gen_(&self) -> Option<&ApiStubParams> {
+        match &self.inner {
+            TaskParamsInner::ApiStubGen(params) => Some(params),
             _ => None,
         }
     }
@@ -7,7 +7,12 @@ use wasm_bindgen::prelude::{wasm_bindgen, JsValue};
 
 use crate::{
     endpoints::get_chat_title::get_chat_title,
-    openai::{msg::OpenAIMsg, params::OpenAIModels},
+    openai::{
+        msg::OpenAIMsg,
+        params::{OpenAIModels, OpenAIParams},
+    },
+    prompts::PromptTemplates,
+    providers::{LlmProvider, Provider},
     typescript::{IMessages, IModels},
     JsError, WasmType,
 };
@@ -67,6 +72,12 @@ pub struct Chat {
     pub(crate) title: String,
     pub(crate) models: HashMap<String, Model>,
     pub(crate) messages: Vec<Message>,
+    /// LLM backend used by the chat
+    #[serde(default)]
+    pub(crate) provider: Provider,
+    /// Overrides of the default prompt templates
+    #[serde(default, skip_serializing_if = "PromptTemplates::is_empty")]
+    pub(crate) prompts: PromptTemplates,
 }
 
 #[wasm_bindgen]
@@ -78,6 +89,8 @@ impl Chat {
             title,
             models: HashMap::new(),
             messages: Vec::new(),
+            provider: Provider::default(),
+            prompts: PromptTemplates::default(),
         }
     }
 
@@ -101,6 +114,21 @@ impl Chat {
         Vec::to_extern(self.messages.clone())
     }
 
+    #[wasm_bindgen(getter)]
+    pub fn provider(&self) -> Provider {
+        self.provider.clone()
+    }
+
+    #[wasm_bindgen(js_name = setProvider)]
+    pub fn set_provider(&mut self, provider: Provider) {
+        self.provider = provider;
+    }
+
+    #[wasm_bindgen(js_name = setPromptTemplates)]
+    pub fn set_prompt_templates(&mut self, prompts: &PromptTemplates) {
+        self.prompts = prompts.clone();
+    }
+
     #[wasm_bindgen(js_name = setTitle)]
     pub async fn set_title(
         &mut self,
@@ -109,10 +137,14 @@ impl Chat {
         if !self.messages.is_empty() {
             // Get the first element using indexing (index 0)
             let first_msg = self.messages[0].clone();
-            let title =
-                get_chat_title(&first_msg.payload.content, request_callback)
-                    .await
-                    .map_err(|e| JsError::from_str(&e.to_string()))?;
+            let title = get_chat_title(
+                &self.provider,
+                &first_msg.payload.content,
+                &self.prompts,
+                request_callback,
+            )
+            .await
+            .map_err(|e| JsError::from_str(&e.to_string()))?;
 
             self.title = title;
 
@@ -141,7 +173,33 @@ impl Chat {
     #[wasm_bindgen(js_name = addModel)]
     pub fn add_model(&mut self, model: OpenAIModels) {
         let model_id = model.as_string();
-        self.models.insert(model_id.clone(), Model::new(model_id));
+        let model = Model {
+            uri: self.provider.uri(),
+            interface: self.provider.provider_type.to_string(),
+            ..Model::new(model_id.clone())
+        };
+        self.models.insert(model_id, model);
+    }
+
+    /// Builds the body of the request prompting the chat's provider with the
+    /// messages of the chat.
+    #[wasm_bindgen(js_name = requestBody)]
+    pub fn request_body(
+        &self,
+        ai_params: &OpenAIParams,
+        stream: bool,
+    ) -> Result<JsString, JsError> {
+        let msgs: Vec<&OpenAIMsg> =
+            self.messages.iter().map(|msg| &msg.payload).collect();
+
+        let body = self
+            .provider
+            .request_body(ai_params, &msgs, &[], stream)
+            .map_err(|e| JsError::from_str(&e.to_string()))?;
+
+        serde_json::to_string(&body)
+            .map(JsString::from)
+            .map_err(|e| JsError::from_str(&e.to_string()))
     }
 
     #[wasm_bindgen(js_name = castFromString)]
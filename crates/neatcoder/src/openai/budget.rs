@@ -0,0 +1,200 @@
+//! This module fits prompts into the context window of the model.
+//!
+//! Messages are assembled with a `Priority`. When the prompt does not fit in
+//! the context window, minus the tokens reserved for the completion, the
+//! lowest priority messages are trimmed first and dropped if not enough of
+//! them would remain. `Required` messages are never touched.
+
+use anyhow::{anyhow, Result};
+
+use super::{
+    msg::OpenAIMsg,
+    params::OpenAIParams,
+    tokenizer::{Encoding, TOKENS_PER_REPLY},
+};
+
+/// Tokens reserved for the completion when `max_tokens` is not set
+pub const DEFAULT_COMPLETION_TOKENS: usize = 1024;
+/// Trimmed messages keeping fewer tokens than this are dropped instead
+const MIN_TRIMMED_TOKENS: usize = 64;
+/// Appended to the content of trimmed messages
+const TRIMMED_MARKER: &str = "\n[...truncated to fit the context window]";
+
+/// Priority of a message when trimming the prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    Low,
+    Medium,
+    High,
+    /// Never trimmed, e.g. the system prompt and the task itself
+    Required,
+}
+
+/// Token budget of a prompt.
+#[derive(Debug, Clone, Copy)]
+pub struct ContextBudget {
+    pub encoding: Encoding,
+    /// Size of the context window of the model
+    pub context_window: usize,
+    /// Tokens reserved for the completion
+    pub completion_tokens: usize,
+}
+
+impl ContextBudget {
+    pub fn new(ai_params: &OpenAIParams) -> Self {
+        Self {
+            encoding: ai_params.model.encoding(),
+            context_window: ai_params.model.context_window(),
+            completion_tokens: ai_params
+                .max_tokens
+                .map(|max_tokens| max_tokens as usize)
+                .unwrap_or(DEFAULT_COMPLETION_TOKENS),
+        }
+    }
+
+    /// Tokens available to the prompt.
+    pub fn prompt_tokens(&self) -> usize {
+        self.context_window.saturating_sub(self.completion_tokens)
+    }
+
+    /// Trims the lowest priority messages until the prompt fits in the
+    /// budget, preserving the order of the remaining messages.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `Required` messages alone exceed the budget.
+    pub fn fit(
+        &self,
+        msgs: Vec<(OpenAIMsg, Priority)>,
+    ) -> Result<Vec<OpenAIMsg>> {
+        let mut msgs = msgs
+            .into_iter()
+            .map(|(msg, priority)| {
+                let tokens = self.encoding.count_msg_tokens(&msg);
+                Some((msg, priority, tokens))
+            })
+            .collect::<Vec<Option<(OpenAIMsg, Priority, usize)>>>();
+
+        let total = |msgs: &[Option<(OpenAIMsg, Priority, usize)>]| {
+            msgs.iter()
+                .flatten()
+                .map(|(_, _, tokens)| tokens)
+                .sum::<usize>()
+                + TOKENS_PER_REPLY
+        };
+
+        let budget = self.prompt_tokens();
+
+        loop {
+            let excess = total(&msgs).saturating_sub(budget);
+
+            if excess == 0 {
+                break;
+            }
+
+            // Picks the earliest message of lowest priority
+            let candidate = msgs
+                .iter()
+                .enumerate()
+                .filter_map(|(i, msg)| msg.as_ref().map(|msg| (i, msg)))
+                .filter(|(_, (_, priority, _))| *priority != Priority::Required)
+                .min_by_key(|(_, (_, priority, _))| *priority)
+                .map(|(i, _)| i);
+
+            let i = candidate.ok_or_else(|| {
+                anyhow!(
+                    "The prompt requires {} tokens whereas the budget is of {}",
+                    total(&msgs),
+                    budget
+                )
+            })?;
+
+            let (msg, _, tokens) = msgs[i].as_mut().unwrap();
+            let keep = tokens.saturating_sub(excess);
+
+            if keep >= MIN_TRIMMED_TOKENS {
+                let overhead = self.encoding.count_msg_tokens(&OpenAIMsg::new(
+                    msg.role,
+                    TRIMMED_MARKER,
+                ));
+
+                let content = msg
+                    .content
+                    .strip_suffix(TRIMMED_MARKER)
+                    .unwrap_or(&msg.content);
+
+                let content = format!(
+                    "{}{}",
+                    self.encoding
+                        .truncate(content, keep.saturating_sub(overhead))?,
+                    TRIMMED_MARKER
+                );
+
+                let trimmed = OpenAIMsg::new(msg.role, &content);
+                let trimmed_tokens = self.encoding.count_msg_tokens(&trimmed);
+
+                // Ensures progress, given that re-encoding the truncated
+                // content may not yield exactly the expected count
+                if trimmed_tokens < *tokens {
+                    *msg = trimmed;
+                    *tokens = trimmed_tokens;
+                    continue;
+                }
+            }
+
+            msgs[i] = None;
+        }
+
+        Ok(msgs.into_iter().flatten().map(|(msg, _, _)| msg).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::openai::params::OpenAIModels;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn trims_lowest_priority_messages_first() -> Result<()> {
+        let ai_params = OpenAIParams::empty(OpenAIModels::Gpt4).max_tokens(0);
+        let budget = ContextBudget {
+            context_window: 300,
+            ..ContextBudget::new(&ai_params)
+        };
+
+        let code = "fn main() {}\n".repeat(100);
+
+        let msgs = vec![
+            (
+                OpenAIMsg::system("You are a Rust engineer."),
+                Priority::Required,
+            ),
+            (OpenAIMsg::user("Build a CLI."), Priority::High),
+            (OpenAIMsg::user(&code), Priority::Low),
+            (OpenAIMsg::user(&code), Priority::Medium),
+            (OpenAIMsg::user("Write `main.rs`."), Priority::Required),
+        ];
+
+        let fitted = budget.fit(msgs)?;
+        let tokens = budget
+            .encoding
+            .count_prompt_tokens(&fitted.iter().collect::<Vec<_>>());
+
+        assert!(tokens <= 300);
+        assert_eq!(fitted.len(), 4);
+        assert!(fitted[2].content.ends_with("context window]"));
+        assert_eq!(fitted[3].content, "Write `main.rs`.");
+
+        let budget = ContextBudget {
+            context_window: 10,
+            ..budget
+        };
+
+        assert!(budget
+            .fit(vec![(OpenAIMsg::user(&code), Priority::Required)])
+            .is_err());
+
+        Ok(())
+    }
+}
@@ -1,7 +1,12 @@
 pub mod assistant;
+pub mod budget;
 ///< Client for interacting with the OpenAI API.
 pub mod msg;
 pub mod params;
 pub mod request;
 pub mod response;
+pub mod retry;
+pub mod stream;
+pub mod tokenizer;
+pub mod tools;
 pub mod utils;
@@ -3,11 +3,22 @@ use js_sys::JsString;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use wasm_bindgen::prelude::wasm_bindgen;
 
+use super::tools::ToolCall;
+
 #[wasm_bindgen]
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct OpenAIMsg {
     pub(crate) role: GptRole,
+    /// The content is `null` in assistant messages that only contain tool
+    /// calls, in which case it deserializes to an empty string
+    #[serde(deserialize_with = "null_as_empty")]
     pub(crate) content: String,
+    /// Tool calls requested by the model in assistant messages
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) tool_calls: Option<Vec<ToolCall>>,
+    /// Id of the tool call that a `tool` message is responding to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) tool_call_id: Option<String>,
 }
 
 #[wasm_bindgen]
@@ -16,31 +27,47 @@ pub enum GptRole {
     System,
     User,
     Assistant,
+    Tool,
 }
 
 impl OpenAIMsg {
-    pub fn user(content: &str) -> Self {
+    pub fn new(role: GptRole, content: &str) -> Self {
         Self {
-            role: GptRole::User,
+            role,
             content: String::from(content),
+            tool_calls: None,
+            tool_call_id: None,
         }
     }
 
+    pub fn user(content: &str) -> Self {
+        Self::new(GptRole::User, content)
+    }
+
     pub fn system(content: &str) -> Self {
-        Self {
-            role: GptRole::System,
-            content: String::from(content),
-        }
+        Self::new(GptRole::System, content)
     }
 
     pub fn assistant(content: &str) -> Self {
+        Self::new(GptRole::Assistant, content)
+    }
+
+    /// Creates a message with the output of the tool call `tool_call_id`.
+    pub fn tool(tool_call_id: &str, content: &str) -> Self {
         Self {
-            role: GptRole::Assistant,
-            content: String::from(content),
+            tool_call_id: Some(String::from(tool_call_id)),
+            ..Self::new(GptRole::Tool, content)
         }
     }
 }
 
+fn null_as_empty<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::<String>::deserialize(deserializer)?.unwrap_or_default())
+}
+
 #[wasm_bindgen]
 impl OpenAIMsg {
     #[wasm_bindgen(getter)]
@@ -63,6 +90,7 @@ impl Serialize for GptRole {
             GptRole::System => serializer.serialize_str("system"),
             GptRole::User => serializer.serialize_str("user"),
             GptRole::Assistant => serializer.serialize_str("assistant"),
+            GptRole::Tool => serializer.serialize_str("tool"),
         }
     }
 }
@@ -78,6 +106,7 @@ impl<'de> Deserialize<'de> for GptRole {
             "system" => Ok(GptRole::System),
             "user" => Ok(GptRole::User),
             "assistant" => Ok(GptRole::Assistant),
+            "tool" => Ok(GptRole::Tool),
             _ => panic!("Invalid variant `{:?}`", s.as_str()),
         }
     }
@@ -89,6 +118,7 @@ impl GptRole {
             "system" => GptRole::System,
             "user" => GptRole::User,
             "assistant" => GptRole::Assistant,
+            "tool" => GptRole::Tool,
             _ => return Err(anyhow!(format!("Invalid role {}", role))),
         };
 
@@ -100,6 +130,7 @@ impl GptRole {
             GptRole::System => "system",
             GptRole::User => "user",
             GptRole::Assistant => "assistant",
+            GptRole::Tool => "tool",
         }
     }
 }
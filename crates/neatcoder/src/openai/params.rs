@@ -12,7 +12,12 @@ use crate::{
     JsError,
 };
 
-use super::utils::{Bounded, Scale01, Scale100s, Scale22};
+use super::{
+    retry::RetryPolicy,
+    tokenizer::Encoding,
+    tools::ToolDef,
+    utils::{Bounded, Scale01, Scale100s, Scale22},
+};
 
 #[wasm_bindgen]
 #[derive(Debug, Serialize, Clone, Copy)]
@@ -95,6 +100,12 @@ pub struct OpenAIParams {
     /// to monitor and detect abuse. You can read more at:
     /// https://platform.openai.com/docs/guides/safety-best-practices/end-user-ids
     pub(crate) user: Option<String>,
+    /// A list of tools the model may call. Currently, only functions are
+    /// supported as a tool.
+    pub(crate) tools: Vec<ToolDef>,
+    /// How failed requests are retried. Not part of the request body.
+    #[serde(skip)]
+    pub(crate) retry_policy: RetryPolicy,
 }
 
 #[wasm_bindgen]
@@ -150,6 +161,8 @@ impl OpenAIParams {
             stream,
             logit_bias,
             user,
+            tools: Vec::new(),
+            retry_policy: RetryPolicy::default(),
         })
     }
 
@@ -165,6 +178,8 @@ impl OpenAIParams {
             stream: false,
             logit_bias: HashMap::new(),
             user: None,
+            tools: Vec::new(),
+            retry_policy: RetryPolicy::default(),
         }
     }
 
@@ -219,6 +234,40 @@ impl OpenAIParams {
         self.user = Some(user);
         self
     }
+
+    /// Adds a function tool, where `parameters` is a JSON-schema object
+    /// describing the arguments of the function.
+    #[wasm_bindgen(js_name = addTool)]
+    pub fn add_tool(
+        mut self,
+        name: String,
+        description: Option<String>,
+        parameters: JsValue,
+    ) -> Result<OpenAIParams, JsError> {
+        let parameters = serde_wasm_bindgen::from_value(parameters)
+            .map_err(|e| JsError::from_str(&e.to_string()))?;
+
+        self.tools.push(ToolDef::function(
+            &name,
+            description.as_deref(),
+            parameters,
+        ));
+
+        Ok(self)
+    }
+
+    #[wasm_bindgen(js_name = retryPolicy)]
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+}
+
+impl OpenAIParams {
+    pub fn with_tools(mut self, tools: Vec<ToolDef>) -> Self {
+        self.tools = tools;
+        self
+    }
 }
 
 impl OpenAIModels {
@@ -246,6 +295,31 @@ impl OpenAIModels {
             OpenAIModels::Gpt41106Preview => String::from("gpt-4-1106-preview"),
         }
     }
+
+    /// Size of the context window in tokens, shared by the prompt and the
+    /// completion.
+    pub fn context_window(&self) -> usize {
+        match self {
+            OpenAIModels::Gpt432k => 32_768,
+            OpenAIModels::Gpt4 => 8_192,
+            OpenAIModels::Gpt35Turbo => 4_096,
+            OpenAIModels::Gpt35Turbo16k => 16_385,
+            OpenAIModels::Gpt35Turbo1106 => 16_385,
+            OpenAIModels::Gpt41106Preview => 128_000,
+        }
+    }
+
+    /// BPE encoding used by the model.
+    pub fn encoding(&self) -> Encoding {
+        match self {
+            OpenAIModels::Gpt432k
+            | OpenAIModels::Gpt4
+            | OpenAIModels::Gpt35Turbo
+            | OpenAIModels::Gpt35Turbo16k
+            | OpenAIModels::Gpt35Turbo1106
+            | OpenAIModels::Gpt41106Preview => Encoding::Cl100k,
+        }
+    }
 }
 
 impl<'de> Deserialize<'de> for OpenAIModels {
@@ -298,6 +372,8 @@ impl Default for OpenAIParams {
             stream: false,
             logit_bias: HashMap::new(),
             user: None,
+            tools: Vec::new(),
+            retry_policy: RetryPolicy::default(),
         }
     }
 }
@@ -1,9 +1,18 @@
-use super::{msg::OpenAIMsg, params::OpenAIParams, response::ResponseBody};
-use crate::{typescript::IOpenAIMsg, utils::log, JsError, WasmType};
+use super::{
+    msg::OpenAIMsg,
+    params::OpenAIParams,
+    response::ResponseBody,
+    retry::{parse_body, ErrorKind, RequestError},
+};
+use crate::{
+    providers::{LlmProvider, RequestTarget},
+    typescript::IOpenAIMsg,
+    utils::log,
+    JsError, WasmType,
+};
 use anyhow::{anyhow, Result};
 use js_sys::{Function, Promise};
 use serde_json::{json, Value};
-use serde_wasm_bindgen::from_value;
 use std::ops::Deref;
 use wasm_bindgen::{prelude::wasm_bindgen, JsCast, JsValue};
 use wasm_bindgen_futures::JsFuture;
@@ -42,14 +51,14 @@ pub fn request_body_(
 
 pub async fn chat(
     request_callback: &Function,
+    provider: &dyn LlmProvider,
     ai_params: impl Deref<Target = OpenAIParams>,
     msgs: &[&OpenAIMsg],
-    funcs: &[&String],
     stop_seq: &[String],
 ) -> Result<String> {
     log("[DEBUG] Getting Chat Raw...");
     let chat =
-        chat_raw(request_callback, ai_params, msgs, funcs, stop_seq).await?;
+        chat_raw(request_callback, provider, ai_params, msgs, stop_seq).await?;
 
     log("[DEBUG] Got answer.");
 
@@ -64,63 +73,81 @@ pub async fn chat(
     Ok(String::from(answer))
 }
 
+/// Prompts the LLM, retrying failed requests according to the retry policy
+/// of `ai_params`.
 pub async fn chat_raw(
     request_callback: &Function,
+    provider: &dyn LlmProvider,
     ai_params: impl Deref<Target = OpenAIParams>,
     msgs: &[&OpenAIMsg],
-    funcs: &[&String],
     stop_seq: &[String],
 ) -> Result<ResponseBody> {
-    let req_body = request_body(ai_params, msgs, funcs, stop_seq, false)?;
+    let req_body = provider.request_body(&ai_params, msgs, stop_seq, false)?;
 
     let body_json = serde_json::to_string(&req_body)?;
 
+    ai_params
+        .retry_policy
+        .run(|| request(request_callback, provider, &body_json))
+        .await
+}
+
+async fn request(
+    request_callback: &Function,
+    provider: &dyn LlmProvider,
+    body_json: &str,
+) -> Result<ResponseBody, RequestError> {
+    let target = serde_json::to_string(&RequestTarget::of(provider))
+        .map_err(|e| RequestError::new(ErrorKind::Fatal, &e.to_string()))?;
+
     log("[DEBUG] Getting promise...");
     let js_promise: Promise = request_callback
-        .call1(&JsValue::NULL, &JsValue::from_str(&body_json))
-        .map_err(|e| anyhow!("Error performing request callback: {:?}", e))?
+        .call2(
+            &JsValue::NULL,
+            &JsValue::from_str(body_json),
+            &JsValue::from_str(&target),
+        )
+        .map_err(|e| RequestError::from_js(&e))?
         .dyn_into()
         .map_err(|e| {
-            anyhow!("Error processing request callback promise: {:?}", e)
+            RequestError::new(
+                ErrorKind::Fatal,
+                &format!("Error processing request callback promise: {:?}", e),
+            )
         })?;
     log("[INFO] Prompting the LLM...");
-    let res_js_value: JsValue =
-        JsFuture::from(js_promise).await.map_err(|e| {
-            anyhow!("Error processing request callback result: {:?}", e)
-        })?;
+    let res_js_value: JsValue = JsFuture::from(js_promise)
+        .await
+        .map_err(|e| RequestError::from_js(&e))?;
 
     log("[INFO] Receive response from LLM..");
     log(&format!("LLM response body: {:?}", res_js_value));
 
-    let body: Result<ResponseBody, _> = from_value(res_js_value);
+    let body = parse_body(res_js_value)?;
 
-    match body {
-        Ok(body) => Ok(body),
-        Err(e) => {
-            Err(anyhow!("Could not convert JsValue to Response: {:?}", e))
-        }
-    }
+    provider
+        .parse_response(body)
+        .map_err(|e| RequestError::malformed(&e.to_string()))
 }
 
 pub fn request_stream(
+    provider: &dyn LlmProvider,
     ai_params: impl Deref<Target = OpenAIParams>,
     msgs: &[&OpenAIMsg],
-    funcs: &[&String],
     stop_seq: &[String],
 ) -> Result<String> {
-    let req_body = request_body(ai_params, msgs, funcs, stop_seq, true)?;
+    let req_body = provider.request_body(&ai_params, msgs, stop_seq, true)?;
 
     let body_json = serde_json::to_string(&req_body)?;
 
     Ok(body_json)
 }
 
+/// Builds the request body in the OpenAI Chat Completions wire format.
 pub fn request_body(
-    job: impl Deref<Target = OpenAIParams>,
+    job: &OpenAIParams,
     msgs: &[&OpenAIMsg],
     // TODO: Add to OpenAIParams
-    funcs: &[&String],
-    // TODO: Add to OpenAIParams
     stop_seq: &[String],
     stream: bool,
 ) -> Result<Value> {
@@ -138,8 +165,8 @@ pub fn request_body(
         data["top_p"] = serde_json::to_value(top_p)?;
     }
 
-    if !funcs.is_empty() {
-        data["functions"] = serde_json::to_value(funcs)?;
+    if !job.tools.is_empty() {
+        data["tools"] = serde_json::to_value(&job.tools)?;
     }
 
     if !stop_seq.is_empty() {
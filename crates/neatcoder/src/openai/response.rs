@@ -48,7 +48,28 @@ pub struct ResponseBody {
 pub struct Choice {
     pub index: i32,
     pub message: OpenAIMsg,
-    pub finish_reason: String,
+    /// Missing when the backend does not report why it stopped
+    #[serde(default)]
+    pub finish_reason: Option<FinishReason>,
+}
+
+/// The reason the model stopped generating tokens.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FinishReason {
+    /// Natural stop point or provided stop sequence
+    Stop,
+    /// Maximum number of tokens reached
+    Length,
+    /// The model called one or more tools
+    ToolCalls,
+    /// Content omitted due to a flag from the content filters
+    ContentFilter,
+    /// Deprecated counterpart of `ToolCalls`
+    FunctionCall,
+    /// Reason not known to this version of the crate
+    #[serde(other)]
+    Unknown,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
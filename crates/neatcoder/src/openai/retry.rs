@@ -0,0 +1,558 @@
+//! This module provides the retry policy used when prompting the LLM.
+//!
+//! Requests are performed by the JS request callback, hence failures reach
+//! us as rejected promises. These are classified into retryable errors (rate
+//! limits, server errors and timeouts) and fatal ones, malformed bodies
+//! included since sending the same request again rarely fixes them.
+//! Retryable errors are retried with exponential backoff and jitter, waiting
+//! at least until the rate limits reset when the API tells us when that is.
+
+use anyhow::{anyhow, Result};
+use js_sys::{Function, Promise, Reflect};
+use serde::Serialize;
+use serde_json::Value;
+use std::{collections::HashMap, fmt};
+use wasm_bindgen::{prelude::wasm_bindgen, JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+use super::response::ResponseHeaders;
+use crate::utils::log;
+
+/// Configures how failed requests to the LLM are retried.
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Maximum number of retries after the first attempt
+    pub max_retries: u32,
+    /// Delay before the first retry, in milliseconds
+    pub initial_backoff_ms: u32,
+    /// Upper bound of the exponential backoff, in milliseconds. Rate limit
+    /// resets communicated by the API are not capped by it.
+    pub max_backoff_ms: u32,
+    /// Total time after which we give up retrying, in milliseconds
+    pub max_elapsed_ms: u32,
+    /// Factor by which the backoff grows after each attempt
+    pub multiplier: f64,
+    /// Whether to randomise the delays such that concurrent requests do not
+    /// retry in lockstep
+    pub jitter: bool,
+    /// JS callback receiving a `RetryAttempt` whenever a request fails
+    on_attempt: Option<Function>,
+}
+
+/// Classification of the errors returned when prompting the LLM.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+pub enum ErrorKind {
+    /// HTTP 429, the rate limits have been exceeded
+    RateLimited,
+    /// HTTP 5xx, the API failed to process an otherwise valid request
+    ServerError,
+    /// The request timed out or the connection dropped
+    Timeout,
+    /// The response body could not be parsed. It is not retried.
+    MalformedBody,
+    /// Any other error, such as an invalid request or API key
+    Fatal,
+}
+
+/// Error returned by a request to the LLM.
+#[derive(Debug, Clone)]
+pub struct RequestError {
+    pub kind: ErrorKind,
+    /// HTTP status code, if known
+    pub status: Option<u16>,
+    /// Time until the rate limits reset, in milliseconds
+    pub reset_ms: Option<u64>,
+    pub message: String,
+}
+
+/// Report of a failed attempt sent to the `on_attempt` callback.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RetryAttempt {
+    /// Number of the attempt that failed, starting at 1
+    pub attempt: u32,
+    pub kind: ErrorKind,
+    pub status: Option<u16>,
+    pub message: String,
+    /// Delay before the next attempt, or `None` if we gave up
+    pub delay_ms: Option<u64>,
+}
+
+#[wasm_bindgen]
+impl RetryPolicy {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        max_retries: u32,
+        initial_backoff_ms: u32,
+        max_backoff_ms: u32,
+        max_elapsed_ms: u32,
+        multiplier: f64,
+        jitter: bool,
+    ) -> RetryPolicy {
+        Self {
+            max_retries,
+            initial_backoff_ms,
+            max_backoff_ms,
+            max_elapsed_ms,
+            multiplier,
+            jitter,
+            on_attempt: None,
+        }
+    }
+
+    /// Policy that does not retry at all.
+    pub fn never() -> RetryPolicy {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the callback called with a `RetryAttempt` whenever a request
+    /// fails.
+    #[wasm_bindgen(js_name = onAttempt)]
+    pub fn on_attempt(mut self, callback: Function) -> Self {
+        self.on_attempt = Some(callback);
+        self
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            initial_backoff_ms: 1_000,
+            max_backoff_ms: 60_000,
+            max_elapsed_ms: 300_000,
+            multiplier: 2.,
+            jitter: true,
+            on_attempt: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Computes the delay before retrying after the given attempt (starting
+    /// at 1) failed, where `random` is a sample of `[0; 1)` used for jitter.
+    ///
+    /// The delay never falls short of the rate limit reset communicated by
+    /// the API.
+    pub fn delay_ms(
+        &self,
+        attempt: u32,
+        error: &RequestError,
+        random: f64,
+    ) -> u64 {
+        let exponent = attempt.saturating_sub(1) as i32;
+
+        let backoff = (self.initial_backoff_ms as f64
+            * self.multiplier.powi(exponent))
+        .min(self.max_backoff_ms as f64);
+
+        // Equal jitter: keeps at least half of the backoff
+        let backoff = if self.jitter {
+            backoff / 2. + backoff / 2. * random
+        } else {
+            backoff
+        };
+
+        (backoff as u64).max(error.reset_ms.unwrap_or(0))
+    }
+
+    /// Runs `request` until it succeeds, fails with a non-retryable error or
+    /// the retry budget of the policy runs out.
+    pub async fn run<T, F, Fut>(&self, mut request: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<T, RequestError>>,
+    {
+        let start = js_sys::Date::now();
+        let mut attempt = 0;
+
+        loop {
+            attempt += 1;
+
+            let error = match request().await {
+                Ok(result) => return Ok(result),
+                Err(error) => error,
+            };
+
+            let elapsed = js_sys::Date::now() - start;
+            let delay = self.delay_ms(attempt, &error, js_sys::Math::random());
+
+            let give_up = !error.kind.is_retryable()
+                || attempt > self.max_retries
+                || elapsed + delay as f64 > self.max_elapsed_ms as f64;
+
+            self.report(RetryAttempt {
+                attempt,
+                kind: error.kind,
+                status: error.status,
+                message: error.message.clone(),
+                delay_ms: (!give_up).then_some(delay),
+            });
+
+            if give_up {
+                return Err(anyhow!(error));
+            }
+
+            log(&format!(
+                "[INFO] {}. Retrying in {}ms (attempt {} of {})...",
+                error,
+                delay,
+                attempt + 1,
+                self.max_retries + 1
+            ));
+
+            sleep(delay).await?;
+        }
+    }
+
+    fn report(&self, attempt: RetryAttempt) {
+        let callback = match &self.on_attempt {
+            Some(callback) => callback,
+            None => return,
+        };
+
+        let result = serde_wasm_bindgen::to_value(&attempt)
+            .map_err(JsValue::from)
+            .and_then(|attempt| callback.call1(&JsValue::NULL, &attempt));
+
+        if let Err(e) = result {
+            log(&format!("[WARN] Failed to report retry attempt: {:?}", e));
+        }
+    }
+}
+
+impl ErrorKind {
+    pub fn is_retryable(&self) -> bool {
+        !matches!(self, ErrorKind::Fatal | ErrorKind::MalformedBody)
+    }
+
+    pub fn from_status(status: u16) -> Self {
+        match status {
+            429 => ErrorKind::RateLimited,
+            408 => ErrorKind::Timeout,
+            500..=599 => ErrorKind::ServerError,
+            _ => ErrorKind::Fatal,
+        }
+    }
+}
+
+impl RequestError {
+    pub fn new(kind: ErrorKind, message: &str) -> Self {
+        Self {
+            kind,
+            status: None,
+            reset_ms: None,
+            message: String::from(message),
+        }
+    }
+
+    /// Error for response bodies that could not be parsed.
+    pub fn malformed(message: &str) -> Self {
+        Self::new(ErrorKind::MalformedBody, message)
+    }
+
+    /// Classifies the value with which the request callback rejected.
+    ///
+    /// The callback may reject with an `Error` whose message mentions the
+    /// HTTP status (e.g. `HTTP error! status: 429`) and which optionally
+    /// carries `status` and `headers` properties.
+    pub fn from_js(error: &JsValue) -> Self {
+        let message = error
+            .as_string()
+            .or_else(|| get_property(error, "message")?.as_string())
+            .unwrap_or_else(|| format!("{:?}", error));
+
+        let status = get_property(error, "status")
+            .and_then(|status| status.as_f64())
+            .map(|status| status as u16);
+
+        let headers: HashMap<String, String> = get_property(error, "headers")
+            .and_then(|headers| serde_wasm_bindgen::from_value(headers).ok())
+            .unwrap_or_default();
+
+        Self::classify(&message, status, &headers)
+    }
+
+    /// Classifies an error from its message, status and response headers.
+    /// If the status is not given, it is parsed from the message.
+    pub fn classify(
+        message: &str,
+        status: Option<u16>,
+        headers: &HashMap<String, String>,
+    ) -> Self {
+        let status = status.or_else(|| parse_status(message));
+
+        let kind = match status {
+            Some(status) => ErrorKind::from_status(status),
+            None => {
+                let lowercase = message.to_lowercase();
+
+                if ["timeout", "timed out", "etimedout", "econnreset"]
+                    .iter()
+                    .any(|pattern| lowercase.contains(pattern))
+                {
+                    ErrorKind::Timeout
+                } else {
+                    ErrorKind::Fatal
+                }
+            }
+        };
+
+        let header = |name: &str| {
+            headers
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.as_str())
+        };
+
+        let reset_ms = rate_limit_reset_ms(
+            header("x-ratelimit-remaining-requests"),
+            header("x-ratelimit-reset-requests"),
+            header("x-ratelimit-remaining-tokens"),
+            header("x-ratelimit-reset-tokens"),
+        )
+        .or_else(|| {
+            header("retry-after")
+                .and_then(|secs| secs.trim().parse::<f64>().ok())
+                .map(|secs| (secs * 1_000.) as u64)
+        });
+
+        Self {
+            kind,
+            status,
+            reset_ms,
+            message: String::from(message),
+        }
+    }
+}
+
+impl fmt::Display for RequestError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.status {
+            Some(status) => write!(
+                f,
+                "LLM request failed ({:?}, status {}): {}",
+                self.kind, status, self.message
+            ),
+            None => write!(
+                f,
+                "LLM request failed ({:?}): {}",
+                self.kind, self.message
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RequestError {}
+
+impl ResponseHeaders {
+    /// Time until the rate limits reset, in milliseconds.
+    pub fn rate_limit_reset_ms(&self) -> Option<u64> {
+        rate_limit_reset_ms(
+            Some(&self.x_ratelimit_remaining_requests),
+            Some(&self.x_ratelimit_reset_requests),
+            Some(&self.x_ratelimit_remaining_tokens),
+            Some(&self.x_ratelimit_reset_tokens),
+        )
+    }
+}
+
+/// Time until the exhausted rate limits reset. If neither limit is reported
+/// as exhausted, the longest of both resets is returned.
+fn rate_limit_reset_ms(
+    remaining_requests: Option<&str>,
+    reset_requests: Option<&str>,
+    remaining_tokens: Option<&str>,
+    reset_tokens: Option<&str>,
+) -> Option<u64> {
+    let is_exhausted = |remaining: Option<&str>| {
+        remaining.map(|remaining| remaining.trim() == "0") == Some(true)
+    };
+
+    let requests = reset_requests.and_then(parse_duration_ms);
+    let tokens = reset_tokens.and_then(parse_duration_ms);
+
+    match (
+        is_exhausted(remaining_requests),
+        is_exhausted(remaining_tokens),
+    ) {
+        (true, false) => requests,
+        (false, true) => tokens,
+        _ => requests.max(tokens),
+    }
+}
+
+/// Parses the durations used by the `x-ratelimit-reset-*` headers, such as
+/// `20ms`, `1s` or `6m0s`, into milliseconds.
+pub fn parse_duration_ms(value: &str) -> Option<u64> {
+    let value = value.trim();
+
+    if value.is_empty() {
+        return None;
+    }
+
+    let mut total = 0.;
+    let mut rest = value;
+
+    while !rest.is_empty() {
+        let number_end = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+
+        let number: f64 = rest[..number_end].parse().ok()?;
+        rest = &rest[number_end..];
+
+        let unit_end = rest
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(rest.len());
+
+        let factor = match &rest[..unit_end] {
+            "h" => 3_600_000.,
+            "m" => 60_000.,
+            "s" => 1_000.,
+            "ms" => 1.,
+            _ => return None,
+        };
+
+        total += number * factor;
+        rest = &rest[unit_end..];
+    }
+
+    Some(total.ceil() as u64)
+}
+
+fn parse_status(message: &str) -> Option<u16> {
+    let message = message.to_lowercase();
+    let (_, rest) = message.split_once("status")?;
+
+    rest.trim_start_matches(|c: char| c == ':' || c.is_whitespace())
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect::<String>()
+        .parse()
+        .ok()
+}
+
+fn get_property(value: &JsValue, key: &str) -> Option<JsValue> {
+    if !value.is_object() {
+        return None;
+    }
+
+    Reflect::get(value, &JsValue::from_str(key))
+        .ok()
+        .filter(|property| !property.is_undefined() && !property.is_null())
+}
+
+/// Resolves after `ms` milliseconds, using the `setTimeout` of the JS
+/// runtime.
+async fn sleep(ms: u64) -> Result<()> {
+    let promise = Promise::new(&mut |resolve, reject| {
+        let result =
+            Reflect::get(&js_sys::global(), &JsValue::from_str("setTimeout"))
+                .and_then(|set_timeout| set_timeout.dyn_into::<Function>())
+                .and_then(|set_timeout| {
+                    set_timeout.call2(
+                        &JsValue::NULL,
+                        &resolve,
+                        &JsValue::from_f64(ms as f64),
+                    )
+                });
+
+        if let Err(e) = result {
+            let _ = reject.call1(&JsValue::NULL, &e);
+        }
+    });
+
+    JsFuture::from(promise)
+        .await
+        .map_err(|e| anyhow!("Failed to wait before retrying: {:?}", e))?;
+
+    Ok(())
+}
+
+/// Parses the response body, classifying failures as malformed bodies.
+pub fn parse_body(value: JsValue) -> Result<Value, RequestError> {
+    serde_wasm_bindgen::from_value(value).map_err(|e| {
+        RequestError::malformed(&format!(
+            "Could not convert JsValue to Response: {:?}",
+            e
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn classifies_errors_and_honours_rate_limit_resets() {
+        let headers = HashMap::from([
+            (
+                String::from("x-ratelimit-remaining-requests"),
+                String::from("59"),
+            ),
+            (
+                String::from("x-ratelimit-reset-requests"),
+                String::from("1s"),
+            ),
+            (
+                String::from("x-ratelimit-remaining-tokens"),
+                String::from("0"),
+            ),
+            (
+                String::from("x-ratelimit-reset-tokens"),
+                String::from("6m0s"),
+            ),
+        ]);
+
+        let error =
+            RequestError::classify("HTTP error! status: 429", None, &headers);
+
+        assert_eq!(error.kind, ErrorKind::RateLimited);
+        assert_eq!(error.status, Some(429));
+        assert_eq!(error.reset_ms, Some(360_000));
+
+        let server_error = RequestError::classify(
+            "HTTP error! status: 503",
+            None,
+            &HashMap::new(),
+        );
+        assert_eq!(server_error.kind, ErrorKind::ServerError);
+
+        let timeout = RequestError::classify(
+            "request to https://api.openai.com failed, reason: ETIMEDOUT",
+            None,
+            &HashMap::new(),
+        );
+        assert_eq!(timeout.kind, ErrorKind::Timeout);
+
+        let unauthorized = RequestError::classify(
+            "HTTP error! status: 401",
+            None,
+            &HashMap::new(),
+        );
+        assert!(!unauthorized.kind.is_retryable());
+        assert!(!RequestError::malformed("EOF").kind.is_retryable());
+
+        assert_eq!(parse_duration_ms("20ms"), Some(20));
+        assert_eq!(parse_duration_ms("1m30.5s"), Some(90_500));
+        assert_eq!(parse_duration_ms("soon"), None);
+
+        let policy = RetryPolicy::default();
+
+        // The backoff doubles up to `max_backoff_ms`, while jitter keeps at
+        // least half of it
+        assert_eq!(policy.delay_ms(1, &server_error, 1.), 1_000);
+        assert_eq!(policy.delay_ms(3, &server_error, 1.), 4_000);
+        assert_eq!(policy.delay_ms(10, &server_error, 1.), 60_000);
+        assert_eq!(policy.delay_ms(3, &server_error, 0.), 2_000);
+
+        // Rate limit resets take precedence over shorter backoffs
+        assert_eq!(policy.delay_ms(1, &error, 0.5), 360_000);
+    }
+}
@@ -0,0 +1,278 @@
+//! This module provides an incremental decoder for streamed chat completions.
+//!
+//! When `stream` is set, the chat endpoints answer with server-sent events
+//! whose `data:` fields carry JSON chunks with partial deltas of the message,
+//! terminated by `data: [DONE]`. The chunks received by the client do not
+//! necessarily align with event boundaries, hence the decoder buffers
+//! incomplete lines until the rest of the frame arrives.
+
+use anyhow::{anyhow, Result};
+use js_sys::JsString;
+use serde_json::Value;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use super::msg::{GptRole, OpenAIMsg};
+use crate::JsError;
+
+/// Incremental decoder that reassembles a streamed chat completion into an
+/// `OpenAIMsg`.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default)]
+pub struct StreamDecoder {
+    /// Incomplete line carried over from the previous chunk
+    buffer: String,
+    /// Name of the event currently being decoded, if any
+    event: Option<String>,
+    /// Data lines of the event currently being decoded
+    data: Vec<String>,
+    role: Option<GptRole>,
+    content: String,
+    finish_reason: Option<String>,
+    done: bool,
+}
+
+#[wasm_bindgen]
+impl StreamDecoder {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> StreamDecoder {
+        Self::default()
+    }
+
+    /// Consumes a raw chunk of the response stream and returns the content
+    /// that it added to the message.
+    #[wasm_bindgen(js_name = push)]
+    pub fn push_js(&mut self, chunk: &str) -> Result<JsString, JsError> {
+        self.push(chunk)
+            .map(|delta| delta.into())
+            .map_err(|e| JsError::from_str(&e.to_string()))
+    }
+
+    /// Flushes any buffered event. To be called once the stream is closed,
+    /// in case the last event was not followed by a blank line.
+    #[wasm_bindgen(js_name = finish)]
+    pub fn finish_js(&mut self) -> Result<JsString, JsError> {
+        self.finish()
+            .map(|delta| delta.into())
+            .map_err(|e| JsError::from_str(&e.to_string()))
+    }
+
+    #[wasm_bindgen(getter, js_name = isDone)]
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn content(&self) -> JsString {
+        self.content.clone().into()
+    }
+
+    #[wasm_bindgen(getter, js_name = finishReason)]
+    pub fn finish_reason(&self) -> Option<JsString> {
+        self.finish_reason.clone().map(|reason| reason.into())
+    }
+
+    /// Returns the message reassembled so far.
+    #[wasm_bindgen(getter)]
+    pub fn message(&self) -> OpenAIMsg {
+        OpenAIMsg::new(self.role.unwrap_or(GptRole::Assistant), &self.content)
+    }
+}
+
+impl StreamDecoder {
+    /// Consumes a raw chunk of the response stream and returns the content
+    /// that it added to the message.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stream carries an error event or if a data
+    /// field does not contain valid JSON.
+    pub fn push(&mut self, chunk: &str) -> Result<String> {
+        self.buffer.push_str(chunk);
+
+        let mut delta = String::new();
+
+        while let Some(line_end) = self.buffer.find('\n') {
+            let line: String = self.buffer.drain(..=line_end).collect();
+            let line = line.trim_end_matches(['\n', '\r']);
+
+            delta.push_str(&self.process_line(line)?);
+        }
+
+        Ok(delta)
+    }
+
+    /// Flushes any buffered event and returns the content it added to the
+    /// message.
+    pub fn finish(&mut self) -> Result<String> {
+        let mut delta = String::new();
+
+        if !self.buffer.is_empty() {
+            let line = std::mem::take(&mut self.buffer);
+            delta.push_str(&self.process_line(line.trim_end_matches('\r'))?);
+        }
+
+        delta.push_str(&self.dispatch()?);
+
+        Ok(delta)
+    }
+
+    /// Consumes the decoder and returns the reassembled message.
+    pub fn into_msg(self) -> OpenAIMsg {
+        self.message()
+    }
+
+    fn process_line(&mut self, line: &str) -> Result<String> {
+        // A blank line marks the end of an event
+        if line.is_empty() {
+            return self.dispatch();
+        }
+
+        // Lines starting with a colon are comments (e.g. keep-alives)
+        if line.starts_with(':') {
+            return Ok(String::new());
+        }
+
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => {
+                (field, value.strip_prefix(' ').unwrap_or(value))
+            }
+            None => (line, ""),
+        };
+
+        match field {
+            "data" => self.data.push(value.to_string()),
+            "event" => self.event = Some(value.to_string()),
+            // Fields such as `id` and `retry` are irrelevant to us
+            _ => {}
+        }
+
+        Ok(String::new())
+    }
+
+    fn dispatch(&mut self) -> Result<String> {
+        let event = self.event.take();
+
+        if self.data.is_empty() {
+            return Ok(String::new());
+        }
+
+        let data = std::mem::take(&mut self.data).join("\n");
+
+        if data.trim() == "[DONE]" {
+            self.done = true;
+            return Ok(String::new());
+        }
+
+        let json: Value = serde_json::from_str(&data).map_err(|e| {
+            anyhow!("Failed to parse stream chunk `{}`: {}", data, e)
+        })?;
+
+        if event.as_deref() == Some("error") || json.get("error").is_some() {
+            let error = json.get("error").unwrap_or(&json);
+
+            let message = error
+                .get("message")
+                .and_then(Value::as_str)
+                .map(String::from)
+                .unwrap_or_else(|| error.to_string());
+
+            return Err(anyhow!("LLM stream returned an error: {}", message));
+        }
+
+        self.apply_chunk(&json)
+    }
+
+    fn apply_chunk(&mut self, json: &Value) -> Result<String> {
+        // Anthropic's Messages API streams typed events instead of choices
+        if let Some(event_type) = json.get("type").and_then(Value::as_str) {
+            match event_type {
+                "content_block_delta" => {
+                    let text = json["delta"]["text"].as_str().unwrap_or("");
+                    self.content.push_str(text);
+                    return Ok(text.to_string());
+                }
+                "message_delta" => {
+                    if let Some(reason) = json["delta"]["stop_reason"].as_str()
+                    {
+                        self.finish_reason = Some(String::from(reason));
+                    }
+                }
+                "message_stop" => self.done = true,
+                _ => {}
+            }
+
+            return Ok(String::new());
+        }
+
+        let choice = match json
+            .get("choices")
+            .and_then(Value::as_array)
+            .and_then(|choices| choices.first())
+        {
+            Some(choice) => choice,
+            None => return Ok(String::new()),
+        };
+
+        if let Some(reason) = choice["finish_reason"].as_str() {
+            self.finish_reason = Some(String::from(reason));
+        }
+
+        let delta = &choice["delta"];
+
+        if let Some(role) = delta["role"].as_str() {
+            self.role = Some(GptRole::new(role)?);
+        }
+
+        let text = delta["content"].as_str().unwrap_or("");
+        self.content.push_str(text);
+
+        Ok(text.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn reassembles_split_frames() -> Result<()> {
+        let mut decoder = StreamDecoder::new();
+
+        let chunks = [
+            "data: {\"choices\":[{\"index\":0,\"delta\":{\"role\":\"assistant\",\"content\":\"\"}}]}\n\n",
+            "data: {\"choices\":[{\"index\":0,\"delta\":{\"con",
+            "tent\":\"fn main\"}}]}\r\n\r\n: ping\n\n",
+            "data: {\"choices\":[{\"index\":0,\"delta\":{\"content\":\"() {}\"},\"finish_reason\":\"stop\"}]}\n\ndata: [DONE]\n\n",
+        ];
+
+        let deltas = chunks
+            .iter()
+            .map(|chunk| decoder.push(chunk))
+            .collect::<Result<Vec<String>>>()?;
+
+        assert_eq!(deltas, vec!["", "", "fn main", "() {}"]);
+        assert!(decoder.is_done());
+        assert_eq!(decoder.finish_reason, Some(String::from("stop")));
+
+        let msg = decoder.into_msg();
+        assert_eq!(msg.role.as_str(), "assistant");
+        assert_eq!(msg.content, "fn main() {}");
+
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn surfaces_error_events() {
+        let mut decoder = StreamDecoder::new();
+
+        let result = decoder.push(
+            "data: {\"error\":{\"message\":\"Rate limit reached\",\"type\":\"requests\"}}\n\n",
+        );
+
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Rate limit reached"));
+    }
+}
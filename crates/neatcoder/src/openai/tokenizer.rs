@@ -0,0 +1,128 @@
+//! This module provides token counting for prompts.
+//!
+//! The BPE ranks of the `cl100k_base` and `o200k_base` encodings are compiled
+//! into the crate, such that prompts can be measured against the context
+//! window of the model before being sent.
+
+use anyhow::{anyhow, Result};
+use tiktoken_rs::{cl100k_base_singleton, o200k_base_singleton};
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use super::{msg::OpenAIMsg, params::OpenAIModels};
+
+/// Tokens added by the chat format around each message
+const TOKENS_PER_MSG: usize = 3;
+/// Tokens priming the reply of the assistant
+pub(crate) const TOKENS_PER_REPLY: usize = 3;
+
+/// BPE encoding used by a model.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Encoding {
+    /// Encoding of the GPT-3.5 and GPT-4 models
+    Cl100k,
+    /// Encoding of the GPT-4o models
+    O200k,
+}
+
+impl Encoding {
+    pub fn encode(&self, text: &str) -> Vec<usize> {
+        let bpe = match self {
+            Encoding::Cl100k => cl100k_base_singleton(),
+            Encoding::O200k => o200k_base_singleton(),
+        };
+
+        let bpe = bpe.lock();
+        bpe.encode_with_special_tokens(text)
+    }
+
+    pub fn decode(&self, tokens: Vec<usize>) -> Result<String> {
+        let bpe = match self {
+            Encoding::Cl100k => cl100k_base_singleton(),
+            Encoding::O200k => o200k_base_singleton(),
+        };
+
+        let bpe = bpe.lock();
+        bpe.decode(tokens)
+            .map_err(|e| anyhow!("Failed to decode tokens: {}", e))
+    }
+
+    pub fn count_tokens(&self, text: &str) -> usize {
+        self.encode(text).len()
+    }
+
+    /// Returns the longest prefix of `text` that fits in `max_tokens`.
+    pub fn truncate(&self, text: &str, max_tokens: usize) -> Result<String> {
+        let mut tokens = self.encode(text);
+
+        if tokens.len() <= max_tokens {
+            return Ok(String::from(text));
+        }
+
+        tokens.truncate(max_tokens);
+
+        // Cutting through a multi-byte character yields invalid UTF-8, in
+        // which case we drop tokens until the prefix decodes again
+        while !tokens.is_empty() {
+            if let Ok(prefix) = self.decode(tokens.clone()) {
+                return Ok(prefix);
+            }
+
+            tokens.pop();
+        }
+
+        Ok(String::new())
+    }
+
+    /// Counts the tokens of a message, including the overhead of the chat
+    /// format.
+    pub fn count_msg_tokens(&self, msg: &OpenAIMsg) -> usize {
+        let mut count = TOKENS_PER_MSG
+            + self.count_tokens(msg.role.as_str())
+            + self.count_tokens(&msg.content);
+
+        if let Some(tool_calls) = &msg.tool_calls {
+            for tool_call in tool_calls.iter() {
+                count += self.count_tokens(&tool_call.function.name)
+                    + self.count_tokens(&tool_call.function.arguments);
+            }
+        }
+
+        count
+    }
+
+    /// Counts the tokens of a prompt, including the tokens priming the reply.
+    pub fn count_prompt_tokens(&self, msgs: &[&OpenAIMsg]) -> usize {
+        msgs.iter()
+            .map(|msg| self.count_msg_tokens(msg))
+            .sum::<usize>()
+            + TOKENS_PER_REPLY
+    }
+}
+
+#[wasm_bindgen(js_name = countTokens)]
+pub fn count_tokens(model: OpenAIModels, text: &str) -> usize {
+    model.encoding().count_tokens(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn counts_and_truncates_tokens() -> Result<()> {
+        let encoding = OpenAIModels::Gpt4.encoding();
+
+        assert_eq!(encoding.count_tokens("hello world"), 2);
+        assert_eq!(encoding.truncate("hello world", 1)?, "hello");
+
+        // "hello world" + "user" + 3 tokens per message + 3 for the reply
+        let msg = OpenAIMsg::user("hello world");
+        assert_eq!(encoding.count_prompt_tokens(&[&msg]), 9);
+
+        assert_eq!(Encoding::O200k.truncate("héllo", 100)?, "héllo");
+
+        Ok(())
+    }
+}
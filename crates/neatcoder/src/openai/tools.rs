@@ -0,0 +1,264 @@
+//! This module provides function calling (i.e. tools) for chat completions.
+//!
+//! Tools are declared on `OpenAIParams` with a JSON-schema describing their
+//! parameters. When the model decides to call them, the response message
+//! carries `tool_calls` which are dispatched to the Rust handlers registered
+//! in a `ToolRegistry`. Their outputs are sent back as `GptRole::Tool`
+//! messages until the model answers with a regular message.
+
+use anyhow::{anyhow, Result};
+use js_sys::Function;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use super::{
+    msg::OpenAIMsg,
+    params::OpenAIParams,
+    request::chat_raw,
+    response::{FinishReason, ResponseBody},
+};
+use crate::{providers::LlmProvider, utils::log};
+
+/// Definition of a tool the model may call.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ToolDef {
+    /// Currently only `function` is supported by the API
+    #[serde(rename = "type")]
+    pub tool_type: String,
+    pub function: FunctionDef,
+}
+
+/// Definition of a function the model may call.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FunctionDef {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// JSON-schema object describing the parameters of the function
+    pub parameters: Value,
+}
+
+/// Tool call requested by the model in a response message.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ToolCall {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub call_type: String,
+    pub function: FunctionCall,
+}
+
+/// Name and arguments of the function the model wants to call.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FunctionCall {
+    pub name: String,
+    /// JSON encoded arguments. Note that the model does not always generate
+    /// valid JSON and may hallucinate parameters not defined by the schema.
+    pub arguments: String,
+}
+
+impl ToolDef {
+    pub fn function(
+        name: &str,
+        description: Option<&str>,
+        parameters: Value,
+    ) -> Self {
+        Self {
+            tool_type: String::from("function"),
+            function: FunctionDef {
+                name: String::from(name),
+                description: description.map(String::from),
+                parameters,
+            },
+        }
+    }
+}
+
+type ToolHandler = Box<dyn Fn(Value) -> Result<String>>;
+
+/// Registry mapping tool names to their definitions and Rust handlers.
+#[derive(Default)]
+pub struct ToolRegistry {
+    tools: BTreeMap<String, (ToolDef, ToolHandler)>,
+}
+
+impl ToolRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a function tool. The handler receives the parsed arguments
+    /// and returns the content of the `tool` message sent back to the model.
+    pub fn register<F>(
+        &mut self,
+        name: &str,
+        description: &str,
+        parameters: Value,
+        handler: F,
+    ) where
+        F: Fn(Value) -> Result<String> + 'static,
+    {
+        let def = ToolDef::function(name, Some(description), parameters);
+
+        self.tools
+            .insert(String::from(name), (def, Box::new(handler)));
+    }
+
+    /// Returns the definitions of all registered tools.
+    pub fn defs(&self) -> Vec<ToolDef> {
+        self.tools.values().map(|(def, _)| def.clone()).collect()
+    }
+
+    /// Dispatches a tool call to its handler.
+    ///
+    /// Handler errors are returned as the tool output rather than failing,
+    /// such that the model gets the chance to correct its call.
+    pub fn dispatch(&self, tool_call: &ToolCall) -> OpenAIMsg {
+        let FunctionCall { name, arguments } = &tool_call.function;
+
+        let output = match self.tools.get(name) {
+            Some((_, handler)) => serde_json::from_str(arguments)
+                .map_err(|e| anyhow!("Invalid JSON arguments: {}", e))
+                .and_then(handler),
+            None => Err(anyhow!("Unknown tool `{}`", name)),
+        };
+
+        let content = output.unwrap_or_else(|e| format!("Error: {}", e));
+
+        OpenAIMsg::tool(&tool_call.id, &content)
+    }
+}
+
+/// Runs a chat completion, dispatching the tool calls of the model to the
+/// handlers in `registry` until the model produces a final answer or
+/// `max_rounds` is reached.
+///
+/// Returns the final response along with the full message sequence,
+/// including the assistant tool calls and the tool outputs.
+pub async fn chat_with_tools(
+    request_callback: &Function,
+    provider: &dyn LlmProvider,
+    ai_params: &OpenAIParams,
+    msgs: Vec<OpenAIMsg>,
+    registry: &ToolRegistry,
+    max_rounds: usize,
+) -> Result<(ResponseBody, Vec<OpenAIMsg>)> {
+    let ai_params = ai_params.clone().with_tools(registry.defs());
+    let mut msgs = msgs;
+
+    for _ in 0..max_rounds {
+        let prompts = msgs.iter().collect::<Vec<&OpenAIMsg>>();
+
+        let response =
+            chat_raw(request_callback, provider, &ai_params, &prompts, &[])
+                .await?;
+
+        let choice = response
+            .choices
+            .first()
+            .ok_or_else(|| anyhow!("LLM Respose seems to be empty :("))?;
+
+        let tool_calls = match &choice.message.tool_calls {
+            Some(tool_calls)
+                if choice.finish_reason == Some(FinishReason::ToolCalls)
+                    && !tool_calls.is_empty() =>
+            {
+                tool_calls.clone()
+            }
+            _ => return Ok((response, msgs)),
+        };
+
+        msgs.push(choice.message.clone());
+
+        for tool_call in tool_calls.iter() {
+            log(&format!(
+                "[INFO] Calling tool `{}`...",
+                tool_call.function.name
+            ));
+            msgs.push(registry.dispatch(tool_call));
+        }
+    }
+
+    Err(anyhow!(
+        "Maximum number of tool call rounds reached: {}",
+        max_rounds
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::openai::msg::GptRole;
+    use serde_json::json;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn dispatches_tool_calls_to_handlers() {
+        let mut registry = ToolRegistry::new();
+
+        registry.register(
+            "read_file",
+            "Reads a file from the codebase",
+            json!({
+                "type": "object",
+                "properties": { "path": { "type": "string" } },
+                "required": ["path"]
+            }),
+            |args| Ok(format!("contents of {}", args["path"])),
+        );
+
+        let response = json!({
+            "id": "chatcmpl-123",
+            "object": "chat.completion",
+            "created": 1699896916,
+            "model": "gpt-4-1106-preview",
+            "choices": [{
+                "index": 0,
+                "message": {
+                    "role": "assistant",
+                    "content": null,
+                    "tool_calls": [{
+                        "id": "call_abc123",
+                        "type": "function",
+                        "function": {
+                            "name": "read_file",
+                            "arguments": "{\"path\":\"src/main.rs\"}"
+                        }
+                    }]
+                },
+                "finish_reason": "tool_calls"
+            }],
+            "usage": {
+                "prompt_tokens": 82,
+                "completion_tokens": 17,
+                "total_tokens": 99
+            }
+        });
+
+        let response: ResponseBody = serde_json::from_value(response).unwrap();
+        let choice = &response.choices[0];
+
+        assert_eq!(choice.finish_reason, Some(FinishReason::ToolCalls));
+
+        let tool_call = &choice.message.tool_calls.as_ref().unwrap()[0];
+        let msg = registry.dispatch(tool_call);
+
+        assert!(matches!(msg.role, GptRole::Tool));
+        assert_eq!(msg.tool_call_id.as_deref(), Some("call_abc123"));
+        assert_eq!(msg.content, "contents of \"src/main.rs\"");
+
+        let unknown = ToolCall {
+            id: String::from("call_def456"),
+            call_type: String::from("function"),
+            function: FunctionCall {
+                name: String::from("list_files"),
+                arguments: String::from("{}"),
+            },
+        };
+
+        assert_eq!(
+            registry.dispatch(&unknown).content,
+            "Error: Unknown tool `list_files`"
+        );
+    }
+}
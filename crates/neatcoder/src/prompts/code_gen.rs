@@ -0,0 +1,328 @@
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::{collections::BTreeMap, path::Path};
+
+use super::PromptTemplates;
+use crate::models::app_data::{
+    interfaces::Interface,
+    language::{Language, LanguageType},
+};
+
+/// Conventions of a programming language that the model should follow when
+/// writing a file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LanguageTemplate {
+    pub name: String,
+    pub file_naming: String,
+    pub module_system: String,
+    pub idioms: String,
+    pub test_framework: String,
+}
+
+impl LanguageTemplate {
+    pub fn new(language: &Language) -> Self {
+        let name = language.name();
+
+        let conventions = match language.language {
+            LanguageType::Python => Some((
+                "snake_case module names ending in `.py`",
+                "packages are folders with an `__init__.py`; use absolute imports",
+                "follow PEP 8, add type hints and docstrings",
+                "pytest, with tests in `tests/test_<module>.py`",
+            )),
+            LanguageType::Java => Some((
+                "one public class per file, named after the class in PascalCase",
+                "declare the `package` matching the folder structure",
+                "favour interfaces, immutability and checked exceptions",
+                "JUnit 5 in `src/test/java`",
+            )),
+            LanguageType::JavaScript => Some((
+                "camelCase or kebab-case file names ending in `.js`",
+                "ES modules with `import` and `export`",
+                "use `const`/`let`, async/await and strict equality",
+                "Jest, with tests in `<module>.test.js`",
+            )),
+            LanguageType::TypeScript => Some((
+                "camelCase or kebab-case file names ending in `.ts`",
+                "ES modules with `import` and `export`",
+                "strict typing, avoid `any`, prefer interfaces for shapes",
+                "Jest, with tests in `<module>.test.ts`",
+            )),
+            LanguageType::Ruby => Some((
+                "snake_case file names ending in `.rb`",
+                "`require_relative` for project files and modules as namespaces",
+                "follow the Ruby style guide, prefer blocks and enumerables",
+                "RSpec, with specs in `spec/<file>_spec.rb`",
+            )),
+            LanguageType::PHP => Some((
+                "one class per file, named after the class in PascalCase",
+                "PSR-4 autoloading with namespaces matching the folders",
+                "follow PSR-12 and declare strict types",
+                "PHPUnit in `tests/`",
+            )),
+            LanguageType::C => Some((
+                "snake_case `.c` files with a matching `.h` header",
+                "headers with include guards declare the public interface",
+                "check return values and free what you allocate",
+                "Unity or CTest",
+            )),
+            LanguageType::CPlusPLus => Some((
+                "snake_case `.cpp` files with a matching `.hpp` header",
+                "headers with `#pragma once` and namespaces",
+                "RAII, smart pointers and the standard library over raw memory",
+                "GoogleTest",
+            )),
+            LanguageType::CSharp => Some((
+                "one type per file, named after the type in PascalCase",
+                "namespaces matching the folder structure",
+                "follow .NET naming conventions, use async/await and LINQ",
+                "xUnit in a separate test project",
+            )),
+            LanguageType::ObjectiveC => Some((
+                "PascalCase `.m` files with a matching `.h` header",
+                "`#import` headers and prefix class names",
+                "follow Cocoa conventions and use ARC",
+                "XCTest",
+            )),
+            LanguageType::Swift => Some((
+                "PascalCase file names named after the main type",
+                "Swift packages and modules, access control with `public`",
+                "prefer value types, optionals and protocol extensions",
+                "XCTest in `Tests/`",
+            )),
+            LanguageType::Go => Some((
+                "lowercase file names ending in `.go`",
+                "declare the `package` of the folder and import by module path",
+                "return errors explicitly and keep interfaces small",
+                "the `testing` package, with tests in `<file>_test.go`",
+            )),
+            LanguageType::Rust => Some((
+                "snake_case module names ending in `.rs`",
+                "declare modules with `mod` and import them with `use`",
+                "handle errors with `Result` and avoid `unwrap` outside tests",
+                "`#[cfg(test)]` modules with `#[test]` functions",
+            )),
+            LanguageType::Perl => Some((
+                "PascalCase `.pm` modules and `.pl` scripts",
+                "packages matching the file path, loaded with `use`",
+                "`use strict` and `use warnings`",
+                "Test::More, with tests in `t/`",
+            )),
+            LanguageType::Shell => Some((
+                "kebab-case script names ending in `.sh`",
+                "`source` shared scripts",
+                "start with `set -euo pipefail` and quote variables",
+                "Bats",
+            )),
+            LanguageType::Scala => Some((
+                "PascalCase file names named after the main type",
+                "packages matching the folder structure",
+                "prefer immutability, case classes and pattern matching",
+                "ScalaTest in `src/test/scala`",
+            )),
+            LanguageType::Kotlin => Some((
+                "PascalCase file names named after the main type",
+                "packages matching the folder structure",
+                "prefer `val`, data classes and null safety",
+                "JUnit 5 or Kotest in `src/test/kotlin`",
+            )),
+            LanguageType::Lua => Some((
+                "snake_case file names ending in `.lua`",
+                "modules returning a table, loaded with `require`",
+                "use `local` variables",
+                "busted",
+            )),
+            LanguageType::Haskell => Some((
+                "PascalCase module names matching the file path",
+                "declare `module` with an explicit export list",
+                "keep functions pure and push effects to `IO`",
+                "Hspec",
+            )),
+            LanguageType::HTML => Some((
+                "kebab-case file names ending in `.html`",
+                "link stylesheets and scripts relative to the page",
+                "semantic and accessible markup",
+                "none",
+            )),
+            LanguageType::MATLAB => Some((
+                "one function per file, named after the function",
+                "functions on the path and packages in `+folders`",
+                "vectorise operations",
+                "the MATLAB unit testing framework",
+            )),
+            LanguageType::R => Some((
+                "snake_case file names ending in `.R`",
+                "packages with `library` and functions sourced with `source`",
+                "follow the tidyverse style guide",
+                "testthat",
+            )),
+            LanguageType::Custom => None,
+        };
+
+        match conventions {
+            Some((file_naming, module_system, idioms, test_framework)) => {
+                Self {
+                    name,
+                    file_naming: String::from(file_naming),
+                    module_system: String::from(module_system),
+                    idioms: String::from(idioms),
+                    test_framework: String::from(test_framework),
+                }
+            }
+            // Generic template for languages we know nothing about
+            None => Self {
+                file_naming: format!("the file naming conventions of {}", name),
+                module_system: format!("the module system of {}", name),
+                idioms: format!("idiomatic {}", name),
+                test_framework: format!(
+                    "the standard test framework of {}",
+                    name
+                ),
+                name,
+            },
+        }
+    }
+}
+
+/// Renders the main prompt asking the model to write the file `filename`,
+/// from the `code_gen` template.
+///
+/// If the filename has no extension, the default extension of the language
+/// is appended.
+pub fn code_gen_prompt(
+    templates: &PromptTemplates,
+    language: &Language,
+    filename: &str,
+    description: &str,
+    interfaces: &BTreeMap<String, Interface>,
+) -> Result<String> {
+    let template = LanguageTemplate::new(language);
+
+    let filename = match (
+        Path::new(filename).extension(),
+        language.language.default_extension(),
+    ) {
+        (None, Some(extension)) => format!("{}{}", filename, extension),
+        _ => String::from(filename),
+    };
+
+    let interfaces = interfaces
+        .values()
+        .map(|interface| {
+            json!({ "name": interface.name(), "itype": interface.itype() })
+        })
+        .collect::<Vec<Value>>();
+
+    templates.render(
+        "code_gen",
+        &json!({
+            "name": template.name,
+            "filename": filename,
+            "description": description,
+            "file_naming": template.file_naming,
+            "module_system": template.module_system,
+            "idioms": template.idioms,
+            "test_framework": template.test_framework,
+            "interfaces": interfaces,
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn renders_prompt_per_language() -> Result<()> {
+        let templates = PromptTemplates::default();
+        let interfaces = BTreeMap::new();
+
+        let expected = [
+            (LanguageType::Python, "main.py", "pytest"),
+            (LanguageType::Java, "Main.java", "JUnit 5"),
+            (LanguageType::JavaScript, "main.js", "<module>.test.js"),
+            (LanguageType::TypeScript, "main.ts", "<module>.test.ts"),
+            (LanguageType::Ruby, "main.rb", "RSpec"),
+            (LanguageType::PHP, "main.php", "PHPUnit"),
+            (LanguageType::C, "main.c", "CTest"),
+            (LanguageType::CPlusPLus, "main.cpp", "GoogleTest"),
+            (LanguageType::CSharp, "Main.cs", "xUnit"),
+            (LanguageType::ObjectiveC, "main.m", "XCTest"),
+            (LanguageType::Swift, "Main.swift", "XCTest"),
+            (LanguageType::Go, "main.go", "<file>_test.go"),
+            (LanguageType::Rust, "main.rs", "#[cfg(test)]"),
+            (LanguageType::Perl, "main.pl", "Test::More"),
+            (LanguageType::Shell, "main.sh", "Bats"),
+            (LanguageType::Scala, "Main.scala", "ScalaTest"),
+            (LanguageType::Kotlin, "Main.kt", "Kotest"),
+            (LanguageType::Lua, "main.lua", "busted"),
+            (LanguageType::Haskell, "Main.hs", "Hspec"),
+            (LanguageType::HTML, "main.html", "- Tests: none"),
+            (LanguageType::MATLAB, "main.m", "MATLAB unit testing"),
+            (LanguageType::R, "main.r", "testthat"),
+        ];
+
+        for (language_type, filename, test_framework) in expected {
+            let language = Language::new(language_type);
+            let stem = filename.split('.').next().unwrap();
+
+            let prompt = code_gen_prompt(
+                &templates,
+                &language,
+                stem,
+                "Entry point",
+                &interfaces,
+            )?;
+
+            assert!(prompt.starts_with(&format!(
+                "You are a {} engineer",
+                language.name()
+            )));
+            assert!(
+                prompt.contains(&format!("write the file `{}`", filename)),
+                "{}",
+                prompt
+            );
+            assert!(prompt.contains(test_framework), "{}", prompt);
+        }
+
+        let prompt = code_gen_prompt(
+            &templates,
+            &Language::new(LanguageType::Python),
+            "app/models.py",
+            "Database models",
+            &interfaces,
+        )?;
+
+        assert_eq!(
+            prompt,
+            "You are a Python engineer and you're assigned to build the project
+defined in the previous prompts.
+
+Your current task is to write the file `app/models.py`.
+Consider the description of the file: Database models
+
+Follow the conventions of Python:
+- File naming: snake_case module names ending in `.py`
+- Module system: packages are folders with an `__init__.py`; use absolute imports
+- Idioms: follow PEP 8, add type hints and docstrings
+- Tests: pytest, with tests in `tests/test_<module>.py`
+"
+        );
+
+        let prompt = code_gen_prompt(
+            &templates,
+            &Language::new_custom(String::from("Zig")),
+            "main.zig",
+            "Entry point",
+            &interfaces,
+        )?;
+
+        assert!(prompt.contains("write the file `main.zig`"));
+        assert!(prompt.contains("- Module system: the module system of Zig"));
+        assert!(prompt.contains("- Tests: the standard test framework of Zig"));
+
+        Ok(())
+    }
+}
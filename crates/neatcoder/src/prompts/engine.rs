@@ -0,0 +1,375 @@
+//! Minimal template engine for prompts.
+//!
+//! The syntax is a subset of Handlebars:
+//!
+//! - `{{name}}` renders a variable, `{{interface.name}}` a nested field;
+//! - `{{#if name}}...{{else}}...{{/if}}` renders a branch depending on
+//!   whether the variable is truthy, i.e. not null, false, empty or zero;
+//! - `{{#each items}}...{{/each}}` renders its body for each element of an
+//!   array or each value of an object. Inside the loop, the fields of the
+//!   element are in scope, along with `this`, `@index` and `@key`.
+//!
+//! Block tags standing alone on their line do not leave a blank line behind.
+//! Unknown variables are errors, such that typos in custom templates do not
+//! go unnoticed.
+
+use anyhow::{anyhow, Result};
+use serde_json::{Map, Value};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Text(String),
+    Var(String),
+    If {
+        cond: String,
+        then: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+    Each {
+        path: String,
+        body: Vec<Node>,
+    },
+}
+
+/// Parsed template, ready to be rendered.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+enum Tag {
+    Var(String),
+    If(String),
+    Each(String),
+    Else,
+    End(String),
+}
+
+impl Template {
+    pub fn parse(source: &str) -> Result<Self> {
+        let tokens = tokenize(source)?;
+        let mut tokens = tokens.into_iter();
+
+        let (nodes, end) = parse_nodes(&mut tokens)?;
+
+        match end {
+            None => Ok(Self { nodes }),
+            Some(Tag::Else) => Err(anyhow!("Unexpected `{{{{else}}}}`")),
+            Some(Tag::End(block)) => {
+                Err(anyhow!("Unexpected `{{{{/{}}}}}`", block))
+            }
+            Some(_) => unreachable!(),
+        }
+    }
+
+    pub fn render(&self, context: &Value) -> Result<String> {
+        let mut output = String::new();
+        let scopes = vec![context.clone()];
+
+        render_nodes(&self.nodes, &scopes, &mut output)?;
+
+        Ok(output)
+    }
+}
+
+/// Parses and renders `source` in one go.
+pub fn render(source: &str, context: &Value) -> Result<String> {
+    Template::parse(source)?.render(context)
+}
+
+enum Token {
+    Text(String),
+    Tag(Tag),
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut rest = source;
+    // Whether `rest` starts at the beginning of a line
+    let mut at_line_begin = true;
+
+    while let Some(start) = rest.find("{{") {
+        let end = rest[start..]
+            .find("}}")
+            .map(|end| start + end)
+            .ok_or_else(|| anyhow!("Unclosed tag in template"))?;
+
+        let mut text = &rest[..start];
+        let content = rest[start + 2..end].trim();
+        let mut after = &rest[end + 2..];
+
+        let tag = parse_tag(content)?;
+
+        let mut standalone = false;
+
+        // Standalone block tags swallow their line
+        if !matches!(tag, Tag::Var(_)) {
+            let line_start = text.rfind('\n').map(|i| i + 1);
+            let at_line_start =
+                text[line_start.unwrap_or(0)..].trim().is_empty()
+                    && (line_start.is_some() || at_line_begin);
+
+            let line_end = after.find('\n');
+            let at_line_end = match line_end {
+                Some(i) => after[..i].trim().is_empty(),
+                None => after.trim().is_empty(),
+            };
+
+            if at_line_start && at_line_end {
+                standalone = true;
+                text = &text[..line_start.unwrap_or(0)];
+                after = match line_end {
+                    Some(i) => &after[i + 1..],
+                    None => "",
+                };
+            }
+        }
+
+        at_line_begin = standalone;
+
+        if !text.is_empty() {
+            tokens.push(Token::Text(String::from(text)));
+        }
+
+        tokens.push(Token::Tag(tag));
+        rest = after;
+    }
+
+    if !rest.is_empty() {
+        tokens.push(Token::Text(String::from(rest)));
+    }
+
+    Ok(tokens)
+}
+
+fn parse_tag(content: &str) -> Result<Tag> {
+    let arg = |keyword: &str| {
+        let arg = content[keyword.len()..].trim();
+
+        if arg.is_empty() {
+            Err(anyhow!("Missing argument in `{{{{{}}}}}`", content))
+        } else {
+            Ok(String::from(arg))
+        }
+    };
+
+    if content.starts_with("#if") {
+        Ok(Tag::If(arg("#if")?))
+    } else if content.starts_with("#each") {
+        Ok(Tag::Each(arg("#each")?))
+    } else if content == "else" {
+        Ok(Tag::Else)
+    } else if let Some(block) = content.strip_prefix('/') {
+        Ok(Tag::End(String::from(block.trim())))
+    } else if content.is_empty() || content.starts_with('#') {
+        Err(anyhow!("Invalid tag `{{{{{}}}}}`", content))
+    } else {
+        Ok(Tag::Var(String::from(content)))
+    }
+}
+
+/// Parses nodes until the end of the tokens or a closing tag, which is
+/// returned along with the nodes.
+fn parse_nodes(
+    tokens: &mut impl Iterator<Item = Token>,
+) -> Result<(Vec<Node>, Option<Tag>)> {
+    let mut nodes = Vec::new();
+
+    while let Some(token) = tokens.next() {
+        match token {
+            Token::Text(text) => nodes.push(Node::Text(text)),
+            Token::Tag(Tag::Var(path)) => nodes.push(Node::Var(path)),
+            Token::Tag(Tag::If(cond)) => {
+                let (then, end) = parse_nodes(tokens)?;
+
+                let otherwise = match end {
+                    Some(Tag::Else) => {
+                        let (otherwise, end) = parse_nodes(tokens)?;
+                        expect_end(end, "if")?;
+                        otherwise
+                    }
+                    end => {
+                        expect_end(end, "if")?;
+                        Vec::new()
+                    }
+                };
+
+                nodes.push(Node::If {
+                    cond,
+                    then,
+                    otherwise,
+                });
+            }
+            Token::Tag(Tag::Each(path)) => {
+                let (body, end) = parse_nodes(tokens)?;
+                expect_end(end, "each")?;
+
+                nodes.push(Node::Each { path, body });
+            }
+            Token::Tag(tag) => return Ok((nodes, Some(tag))),
+        }
+    }
+
+    Ok((nodes, None))
+}
+
+fn expect_end(end: Option<Tag>, block: &str) -> Result<()> {
+    match end {
+        Some(Tag::End(name)) if name == block => Ok(()),
+        Some(Tag::End(name)) => Err(anyhow!(
+            "Expected `{{{{/{}}}}}` but found `{{{{/{}}}}}`",
+            block,
+            name
+        )),
+        _ => Err(anyhow!("Missing `{{{{/{}}}}}`", block)),
+    }
+}
+
+fn render_nodes(
+    nodes: &[Node],
+    scopes: &[Value],
+    output: &mut String,
+) -> Result<()> {
+    for node in nodes.iter() {
+        match node {
+            Node::Text(text) => output.push_str(text),
+            Node::Var(path) => match lookup(scopes, path)? {
+                Value::String(string) => output.push_str(&string),
+                Value::Null => {}
+                value => output.push_str(&value.to_string()),
+            },
+            Node::If {
+                cond,
+                then,
+                otherwise,
+            } => {
+                let branch = match is_truthy(&lookup(scopes, cond)?) {
+                    true => then,
+                    false => otherwise,
+                };
+
+                render_nodes(branch, scopes, output)?;
+            }
+            Node::Each { path, body } => {
+                let items: Vec<(Value, Value)> = match lookup(scopes, path)? {
+                    Value::Array(items) => items
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, item)| (Value::from(i), item))
+                        .collect(),
+                    Value::Object(items) => items
+                        .into_iter()
+                        .map(|(key, item)| (Value::String(key), item))
+                        .collect(),
+                    Value::Null => Vec::new(),
+                    _ => {
+                        return Err(anyhow!(
+                            "Cannot iterate over `{}` in template",
+                            path
+                        ))
+                    }
+                };
+
+                for (index, (key, item)) in items.into_iter().enumerate() {
+                    let mut scope = Map::new();
+                    scope.insert(String::from("@index"), Value::from(index));
+                    scope.insert(String::from("@key"), key);
+                    scope.insert(String::from("this"), item);
+
+                    let mut scopes = scopes.to_vec();
+                    scopes.push(Value::Object(scope));
+
+                    render_nodes(body, &scopes, output)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Resolves a dotted path, starting from the innermost scope.
+fn lookup(scopes: &[Value], path: &str) -> Result<Value> {
+    let mut segments = path.split('.');
+    let first = segments.next().unwrap_or_default();
+
+    let root = scopes.iter().rev().find_map(|scope| {
+        scope.get(first).or_else(|| {
+            // Fields of the loop element are in scope
+            scope.get("this").and_then(|this| this.get(first))
+        })
+    });
+
+    let mut value =
+        root.ok_or_else(|| anyhow!("Unknown variable `{}` in template", path))?;
+
+    for segment in segments {
+        value = value.get(segment).ok_or_else(|| {
+            anyhow!("Unknown variable `{}` in template", path)
+        })?;
+    }
+
+    Ok(value.clone())
+}
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(value) => *value,
+        Value::Number(number) => number.as_f64() != Some(0.),
+        Value::String(string) => !string.is_empty(),
+        Value::Array(array) => !array.is_empty(),
+        Value::Object(object) => !object.is_empty(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn renders_variables_conditionals_and_loops() -> Result<()> {
+        let template = "\
+Write `{{filename}}`.
+{{#if interfaces}}
+Consider the following interfaces:
+{{#each interfaces}}
+{{@index}}. {{name}} ({{kind.label}})
+{{/each}}
+{{else}}
+No interfaces.
+{{/if}}
+Done.";
+
+        let context = json!({
+            "filename": "main.rs",
+            "interfaces": [
+                { "name": "users", "kind": { "label": "Database" } },
+                { "name": "payments", "kind": { "label": "Api" } },
+            ],
+        });
+
+        assert_eq!(
+            render(template, &context)?,
+            "Write `main.rs`.
+Consider the following interfaces:
+0. users (Database)
+1. payments (Api)
+Done."
+        );
+
+        let context = json!({ "filename": "main.rs", "interfaces": [] });
+
+        assert_eq!(
+            render(template, &context)?,
+            "Write `main.rs`.\nNo interfaces.\nDone."
+        );
+
+        assert!(render("{{missing}}", &context).is_err());
+        assert!(Template::parse("{{#if filename}}unclosed").is_err());
+
+        Ok(())
+    }
+}
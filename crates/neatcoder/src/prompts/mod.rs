@@ -0,0 +1,180 @@
+///< Prompt templates used to instruct the LLM.
+pub mod code_gen;
+pub mod engine;
+
+use anyhow::{anyhow, Result};
+use js_sys::JsString;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use self::engine::Template;
+use crate::JsError;
+
+/// Default templates compiled into the crate, keyed by name.
+pub const DEFAULT_TEMPLATES: [(&str, &str); 21] = [
+    (
+        "code_gen_system",
+        include_str!("templates/code_gen_system.txt"),
+    ),
+    ("code_gen", include_str!("templates/code_gen.txt")),
+    ("code_repair", include_str!("templates/code_repair.txt")),
+    ("code_edit", include_str!("templates/code_edit.txt")),
+    ("test_gen", include_str!("templates/test_gen.txt")),
+    (
+        "code_review_system",
+        include_str!("templates/code_review_system.txt"),
+    ),
+    ("code_review", include_str!("templates/code_review.txt")),
+    ("doc_gen", include_str!("templates/doc_gen.txt")),
+    ("config_gen", include_str!("templates/config_gen.txt")),
+    ("api_stubs", include_str!("templates/api_stubs.txt")),
+    (
+        "scaffold_system",
+        include_str!("templates/scaffold_system.txt"),
+    ),
+    ("scaffold", include_str!("templates/scaffold.txt")),
+    ("rescaffold", include_str!("templates/rescaffold.txt")),
+    (
+        "chat_title_system",
+        include_str!("templates/chat_title_system.txt"),
+    ),
+    ("chat_title", include_str!("templates/chat_title.txt")),
+    (
+        "database_context",
+        include_str!("templates/database_context.txt"),
+    ),
+    (
+        "database_schema",
+        include_str!("templates/database_schema.txt"),
+    ),
+    (
+        "storage_context",
+        include_str!("templates/storage_context.txt"),
+    ),
+    (
+        "storage_schema",
+        include_str!("templates/storage_schema.txt"),
+    ),
+    ("api_context", include_str!("templates/api_context.txt")),
+    ("api_schema", include_str!("templates/api_schema.txt")),
+];
+
+/// Prompt templates, where teams can override the defaults compiled into the
+/// crate, e.g. with the files of the `prompts/` folder of their project.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+#[serde(transparent)]
+pub struct PromptTemplates {
+    overrides: BTreeMap<String, String>,
+}
+
+#[wasm_bindgen]
+impl PromptTemplates {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> PromptTemplates {
+        Self::default()
+    }
+
+    /// Overrides the template `name`. The template must be known and valid.
+    #[wasm_bindgen(js_name = setTemplate)]
+    pub fn set_template_js(
+        &mut self,
+        name: String,
+        template: String,
+    ) -> Result<(), JsError> {
+        self.set_template(&name, &template)
+            .map_err(|e| JsError::from_str(&e.to_string()))
+    }
+
+    /// Restores the default of the template `name`.
+    #[wasm_bindgen(js_name = resetTemplate)]
+    pub fn reset_template(&mut self, name: String) {
+        self.overrides.remove(&name);
+    }
+
+    /// Returns the names of all the templates.
+    #[wasm_bindgen(js_name = templateNames)]
+    pub fn template_names() -> Vec<JsString> {
+        DEFAULT_TEMPLATES
+            .iter()
+            .map(|(name, _)| JsString::from(*name))
+            .collect()
+    }
+}
+
+impl PromptTemplates {
+    pub fn is_empty(&self) -> bool {
+        self.overrides.is_empty()
+    }
+
+    pub fn set_template(&mut self, name: &str, template: &str) -> Result<()> {
+        default_template(name)?;
+
+        Template::parse(template).map_err(|e| {
+            anyhow!("Invalid override of the template `{}`: {}", name, e)
+        })?;
+
+        self.overrides
+            .insert(String::from(name), String::from(template));
+
+        Ok(())
+    }
+
+    /// Returns the source of the template `name`, favouring overrides.
+    pub fn get(&self, name: &str) -> Result<&str> {
+        match self.overrides.get(name) {
+            Some(template) => Ok(template),
+            None => default_template(name),
+        }
+    }
+
+    /// Renders the template `name` with the given variables.
+    pub fn render(&self, name: &str, context: &Value) -> Result<String> {
+        Template::parse(self.get(name)?)?
+            .render(context)
+            .map_err(|e| anyhow!("Failed to render template `{}`: {}", name, e))
+    }
+}
+
+fn default_template(name: &str) -> Result<&'static str> {
+    DEFAULT_TEMPLATES
+        .iter()
+        .find(|(default, _)| *default == name)
+        .map(|(_, template)| *template)
+        .ok_or_else(|| anyhow!("Unknown prompt template `{}`", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn overrides_default_templates() -> Result<()> {
+        let mut templates = PromptTemplates::new();
+        let context = json!({ "language": "Rust" });
+
+        assert_eq!(
+            templates.render("code_gen_system", &context)?,
+            "You are a software engineer who is specialised in Rust.\n"
+        );
+
+        templates.set_template(
+            "code_gen_system",
+            "You write {{language}} at ACME. Use our internal crates.",
+        )?;
+
+        assert_eq!(
+            templates.render("code_gen_system", &context)?,
+            "You write Rust at ACME. Use our internal crates."
+        );
+
+        assert!(templates.set_template("unknown", "Hello").is_err());
+        assert!(templates.set_template("scaffold", "{{#if specs}}").is_err());
+
+        Ok(())
+    }
+}
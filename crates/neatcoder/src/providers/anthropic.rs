@@ -0,0 +1,514 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+use super::LlmProvider;
+use crate::openai::{
+    msg::{GptRole, OpenAIMsg},
+    params::OpenAIParams,
+    response::{Choice, FinishReason, ResponseBody, Usage},
+    tools::{FunctionCall, ToolCall},
+};
+
+pub const ANTHROPIC_MESSAGES_URI: &str =
+    "https://api.anthropic.com/v1/messages";
+pub const ANTHROPIC_DEFAULT_MODEL: &str = "claude-2.1";
+/// Version of the Messages API the request bodies are written against
+pub const ANTHROPIC_VERSION: &str = "2023-06-01";
+/// The Messages API requires `max_tokens` to be set, whereas OpenAI
+/// defaults to the remaining context window.
+pub const ANTHROPIC_DEFAULT_MAX_TOKENS: u64 = 4096;
+
+/// Backend for the Anthropic Messages API.
+///
+/// The Messages API differs from OpenAI's in that the system prompt is a
+/// top-level field and that consecutive messages must alternate between the
+/// `user` and `assistant` roles. Tool calls and their outputs are content
+/// blocks, respectively `tool_use` blocks in assistant messages and
+/// `tool_result` blocks in user messages.
+#[derive(Debug, Clone, Default)]
+pub struct Anthropic {
+    uri: Option<String>,
+    model: Option<String>,
+}
+
+impl Anthropic {
+    pub fn new(uri: Option<String>, model: Option<String>) -> Self {
+        Self { uri, model }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct AnthropicResponse {
+    id: String,
+    model: String,
+    content: Vec<AnthropicContent>,
+    stop_reason: Option<String>,
+    usage: AnthropicUsage,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct AnthropicContent {
+    #[serde(rename = "type")]
+    content_type: String,
+    #[serde(default)]
+    text: String,
+    /// Id, name and input of `tool_use` blocks
+    #[serde(default)]
+    id: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    input: Value,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct AnthropicUsage {
+    input_tokens: i32,
+    output_tokens: i32,
+}
+
+impl LlmProvider for Anthropic {
+    fn uri(&self) -> String {
+        self.uri
+            .clone()
+            .unwrap_or_else(|| String::from(ANTHROPIC_MESSAGES_URI))
+    }
+
+    fn headers(&self) -> BTreeMap<String, String> {
+        BTreeMap::from([
+            (
+                String::from("Content-Type"),
+                String::from("application/json"),
+            ),
+            (
+                String::from("anthropic-version"),
+                String::from(ANTHROPIC_VERSION),
+            ),
+        ])
+    }
+
+    fn auth_header(&self) -> Option<(String, String)> {
+        Some((String::from("x-api-key"), String::new()))
+    }
+
+    fn model_name(&self, _ai_params: &OpenAIParams) -> String {
+        self.model
+            .clone()
+            .unwrap_or_else(|| String::from(ANTHROPIC_DEFAULT_MODEL))
+    }
+
+    fn request_body(
+        &self,
+        ai_params: &OpenAIParams,
+        msgs: &[&OpenAIMsg],
+        stop_seq: &[String],
+        stream: bool,
+    ) -> Result<Value> {
+        let system = msgs
+            .iter()
+            .filter(|msg| matches!(msg.role, GptRole::System))
+            .map(|msg| msg.content.as_str())
+            .collect::<Vec<&str>>()
+            .join("\n\n");
+
+        // Merges consecutive messages of the same role since the API
+        // requires the roles to alternate
+        let mut messages: Vec<(&str, Vec<Value>)> = Vec::new();
+
+        for msg in msgs.iter() {
+            let (role, blocks) = match msg.role {
+                GptRole::System => continue,
+                GptRole::User => ("user", text_block(&msg.content)),
+                GptRole::Assistant => ("assistant", assistant_blocks(msg)?),
+                // Tool outputs are sent back in user messages
+                GptRole::Tool => ("user", vec![tool_result_block(msg)?]),
+            };
+
+            match messages.last_mut() {
+                Some((last_role, content)) if *last_role == role => {
+                    content.extend(blocks)
+                }
+                _ => messages.push((role, blocks)),
+            }
+        }
+
+        let messages = messages
+            .into_iter()
+            .map(|(role, blocks)| {
+                json!({ "role": role, "content": message_content(blocks) })
+            })
+            .collect::<Vec<Value>>();
+
+        let mut data = json!({
+            "model": self.model_name(ai_params),
+            "messages": messages,
+            "max_tokens": ai_params
+                .max_tokens
+                .unwrap_or(ANTHROPIC_DEFAULT_MAX_TOKENS),
+        });
+
+        if !system.is_empty() {
+            data["system"] = Value::String(system);
+        }
+
+        if let Some(temperature) = ai_params.temperature {
+            data["temperature"] = serde_json::to_value(temperature)?;
+        }
+
+        if let Some(top_p) = ai_params.top_p {
+            data["top_p"] = serde_json::to_value(top_p)?;
+        }
+
+        if !ai_params.tools.is_empty() {
+            let tools = ai_params
+                .tools
+                .iter()
+                .map(|tool| {
+                    let mut tool_json = json!({
+                        "name": tool.function.name,
+                        "input_schema": tool.function.parameters,
+                    });
+
+                    if let Some(description) = &tool.function.description {
+                        tool_json["description"] = json!(description);
+                    }
+
+                    tool_json
+                })
+                .collect::<Vec<Value>>();
+
+            data["tools"] = Value::Array(tools);
+        }
+
+        if !stop_seq.is_empty() {
+            data["stop_sequences"] = serde_json::to_value(stop_seq)?;
+        }
+
+        if let Some(user) = &ai_params.user {
+            data["metadata"] = json!({ "user_id": user });
+        }
+
+        if stream {
+            data["stream"] = Value::Bool(true);
+        }
+
+        Ok(data)
+    }
+
+    fn parse_response(&self, response: Value) -> Result<ResponseBody> {
+        let response: AnthropicResponse = serde_json::from_value(response)
+            .map_err(|e| {
+                anyhow!(
+                    "Could not convert Anthropic response to Response: {:?}",
+                    e
+                )
+            })?;
+
+        let content = response
+            .content
+            .iter()
+            .filter(|block| block.content_type == "text")
+            .map(|block| block.text.as_str())
+            .collect::<String>();
+
+        let tool_calls = response
+            .content
+            .iter()
+            .filter(|block| block.content_type == "tool_use")
+            .map(|block| ToolCall {
+                id: block.id.clone(),
+                call_type: String::from("function"),
+                function: FunctionCall {
+                    name: block.name.clone(),
+                    arguments: block.input.to_string(),
+                },
+            })
+            .collect::<Vec<ToolCall>>();
+
+        let finish_reason =
+            response.stop_reason.as_deref().map(|reason| match reason {
+                "end_turn" | "stop_sequence" => FinishReason::Stop,
+                "max_tokens" => FinishReason::Length,
+                "tool_use" => FinishReason::ToolCalls,
+                _ => FinishReason::Unknown,
+            });
+
+        let mut message = OpenAIMsg::assistant(&content);
+
+        if !tool_calls.is_empty() {
+            message.tool_calls = Some(tool_calls);
+        }
+
+        let usage = Usage {
+            prompt_tokens: response.usage.input_tokens,
+            completion_tokens: response.usage.output_tokens,
+            total_tokens: response.usage.input_tokens
+                + response.usage.output_tokens,
+        };
+
+        Ok(ResponseBody {
+            id: response.id,
+            object: String::from("chat.completion"),
+            // The Messages API does not return a creation timestamp
+            created: 0,
+            model: response.model,
+            choices: vec![Choice {
+                index: 0,
+                message,
+                finish_reason,
+            }],
+            usage,
+        })
+    }
+}
+
+/// Text content block, if the text is not empty since the API rejects empty
+/// text blocks.
+fn text_block(text: &str) -> Vec<Value> {
+    if text.is_empty() {
+        return Vec::new();
+    }
+
+    vec![json!({ "type": "text", "text": text })]
+}
+
+/// Content blocks of an assistant message, with its tool calls as `tool_use`
+/// blocks.
+fn assistant_blocks(msg: &OpenAIMsg) -> Result<Vec<Value>> {
+    let mut blocks = text_block(&msg.content);
+
+    for tool_call in msg.tool_calls.iter().flatten() {
+        let input: Value = serde_json::from_str(&tool_call.function.arguments)
+            .map_err(|e| {
+                anyhow!(
+                    "Invalid JSON arguments in tool call `{}`: {}",
+                    tool_call.id,
+                    e
+                )
+            })?;
+
+        blocks.push(json!({
+            "type": "tool_use",
+            "id": tool_call.id,
+            "name": tool_call.function.name,
+            "input": input,
+        }));
+    }
+
+    Ok(blocks)
+}
+
+/// `tool_result` content block with the output of a tool call.
+fn tool_result_block(msg: &OpenAIMsg) -> Result<Value> {
+    let tool_use_id = msg
+        .tool_call_id
+        .as_ref()
+        .ok_or_else(|| anyhow!("Tool message without a tool call id"))?;
+
+    Ok(json!({
+        "type": "tool_result",
+        "tool_use_id": tool_use_id,
+        "content": msg.content,
+    }))
+}
+
+/// Content of a message. Messages made of text only are sent as a plain
+/// string, joining the merged messages.
+fn message_content(blocks: Vec<Value>) -> Value {
+    if !blocks.iter().all(|block| block["type"] == "text") {
+        return Value::Array(blocks);
+    }
+
+    let text = blocks
+        .iter()
+        .filter_map(|block| block["text"].as_str())
+        .collect::<Vec<&str>>()
+        .join("\n\n");
+
+    Value::String(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        openai::{params::OpenAIModels, tools::ToolDef},
+        providers::{Provider, ProviderType, RequestTarget},
+    };
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn merges_messages_and_lifts_system_prompt() {
+        let provider = Anthropic::new(None, None);
+        let ai_params = OpenAIParams::empty(OpenAIModels::Gpt4);
+
+        let system = OpenAIMsg::system("You are a Rust engineer.");
+        let specs = OpenAIMsg::user("Build a CLI.");
+        let task = OpenAIMsg::user("Write `main.rs`.");
+
+        let body = provider
+            .request_body(&ai_params, &[&system, &specs, &task], &[], true)
+            .unwrap();
+
+        assert_eq!(body["system"], "You are a Rust engineer.");
+        assert_eq!(body["model"], ANTHROPIC_DEFAULT_MODEL);
+        assert_eq!(body["max_tokens"], ANTHROPIC_DEFAULT_MAX_TOKENS);
+        assert_eq!(body["stream"], true);
+        assert_eq!(body["messages"].as_array().unwrap().len(), 1);
+        assert_eq!(
+            body["messages"][0]["content"],
+            "Build a CLI.\n\nWrite `main.rs`."
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn converts_response_to_openai_format() {
+        let raw_response = json!({
+            "id": "msg_01XFDUDYJgAACzvnptvVoYEL",
+            "type": "message",
+            "role": "assistant",
+            "content": [{ "type": "text", "text": "Hello!" }],
+            "model": "claude-2.1",
+            "stop_reason": "max_tokens",
+            "usage": { "input_tokens": 12, "output_tokens": 6 }
+        });
+
+        let body = Anthropic::default().parse_response(raw_response).unwrap();
+
+        assert_eq!(body.choices[0].message.content, "Hello!");
+        assert_eq!(body.choices[0].finish_reason, Some(FinishReason::Length));
+        assert_eq!(body.usage.total_tokens, 18);
+    }
+
+    #[wasm_bindgen_test]
+    fn builds_request_end_to_end() {
+        let provider = Provider::new(
+            ProviderType::Anthropic,
+            None,
+            Some(String::from("claude-instant-1.2")),
+        );
+        let ai_params = OpenAIParams::empty(OpenAIModels::Gpt4);
+
+        let target = RequestTarget::of(&provider);
+        let headers = target.headers_with("sk-ant-key");
+
+        assert_eq!(target.uri, ANTHROPIC_MESSAGES_URI);
+        assert_eq!(headers["x-api-key"], "sk-ant-key");
+        assert_eq!(headers["anthropic-version"], ANTHROPIC_VERSION);
+        assert_eq!(headers["Content-Type"], "application/json");
+        assert!(!headers.contains_key("Authorization"));
+
+        let system = OpenAIMsg::system("You are a Rust engineer.");
+        let task = OpenAIMsg::user("Write `main.rs`.");
+
+        let body = provider
+            .request_body(&ai_params, &[&system, &task], &[], false)
+            .unwrap();
+
+        assert_eq!(body["model"], "claude-instant-1.2");
+        assert_eq!(body["system"], "You are a Rust engineer.");
+        assert_eq!(body["messages"][0]["role"], "user");
+        assert!(body.get("stream").is_none());
+
+        let raw_response = json!({
+            "id": "msg_013Zva2CMHLNnXjNJJKqJ2EF",
+            "type": "message",
+            "role": "assistant",
+            "content": [{ "type": "text", "text": "fn main() {}" }],
+            "model": "claude-instant-1.2",
+            "stop_reason": "end_turn",
+            "usage": { "input_tokens": 20, "output_tokens": 4 }
+        });
+
+        let response = provider.parse_response(raw_response).unwrap();
+
+        assert_eq!(response.choices[0].message.content, "fn main() {}");
+        assert_eq!(response.choices[0].finish_reason, Some(FinishReason::Stop));
+    }
+
+    #[wasm_bindgen_test]
+    fn translates_tool_calls_to_content_blocks() {
+        let tool = ToolDef::function(
+            "read_file",
+            Some("Reads a file of the codebase"),
+            json!({
+                "type": "object",
+                "properties": { "path": { "type": "string" } }
+            }),
+        );
+        let ai_params =
+            OpenAIParams::empty(OpenAIModels::Gpt4).with_tools(vec![tool]);
+
+        let task = OpenAIMsg::user("Document `main.rs`.");
+        let call = OpenAIMsg {
+            tool_calls: Some(vec![ToolCall {
+                id: String::from("toolu_01"),
+                call_type: String::from("function"),
+                function: FunctionCall {
+                    name: String::from("read_file"),
+                    arguments: String::from("{\"path\":\"main.rs\"}"),
+                },
+            }]),
+            ..OpenAIMsg::assistant("")
+        };
+        let output = OpenAIMsg::tool("toolu_01", "fn main() {}");
+
+        let body = Anthropic::default()
+            .request_body(&ai_params, &[&task, &call, &output], &[], false)
+            .unwrap();
+
+        assert_eq!(body["tools"][0]["name"], "read_file");
+        assert_eq!(body["tools"][0]["input_schema"]["type"], "object");
+
+        let messages = body["messages"].as_array().unwrap();
+
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[0]["content"], "Document `main.rs`.");
+        assert_eq!(
+            messages[1]["content"],
+            json!([{
+                "type": "tool_use",
+                "id": "toolu_01",
+                "name": "read_file",
+                "input": { "path": "main.rs" }
+            }])
+        );
+        assert_eq!(messages[2]["role"], "user");
+        assert_eq!(
+            messages[2]["content"],
+            json!([{
+                "type": "tool_result",
+                "tool_use_id": "toolu_01",
+                "content": "fn main() {}"
+            }])
+        );
+
+        let raw_response = json!({
+            "id": "msg_01",
+            "model": "claude-2.1",
+            "content": [
+                { "type": "text", "text": "Reading it." },
+                {
+                    "type": "tool_use",
+                    "id": "toolu_02",
+                    "name": "read_file",
+                    "input": { "path": "lib.rs" }
+                }
+            ],
+            "stop_reason": "tool_use",
+            "usage": { "input_tokens": 30, "output_tokens": 12 }
+        });
+
+        let response =
+            Anthropic::default().parse_response(raw_response).unwrap();
+        let choice = &response.choices[0];
+        let tool_calls = choice.message.tool_calls.as_ref().unwrap();
+
+        assert_eq!(choice.finish_reason, Some(FinishReason::ToolCalls));
+        assert_eq!(choice.message.content, "Reading it.");
+        assert_eq!(tool_calls[0].id, "toolu_02");
+        assert_eq!(tool_calls[0].function.arguments, "{\"path\":\"lib.rs\"}");
+    }
+}
@@ -0,0 +1,82 @@
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use super::LlmProvider;
+use crate::openai::{
+    msg::OpenAIMsg, params::OpenAIParams, request::request_body,
+    response::ResponseBody,
+};
+
+/// Default chat endpoint of Ollama. llama.cpp's server exposes the same path
+/// on port 8080.
+pub const LOCAL_CHAT_URI: &str = "http://localhost:11434/v1/chat/completions";
+
+/// Backend for local servers exposing an OpenAI-compatible API, such as
+/// Ollama or the llama.cpp server.
+///
+/// These servers speak the OpenAI wire format but host arbitrary models,
+/// hence the model name is taken from `model` whenever present.
+#[derive(Debug, Clone, Default)]
+pub struct OpenAICompatible {
+    uri: Option<String>,
+    model: Option<String>,
+}
+
+impl OpenAICompatible {
+    pub fn new(uri: Option<String>, model: Option<String>) -> Self {
+        Self { uri, model }
+    }
+}
+
+impl LlmProvider for OpenAICompatible {
+    fn uri(&self) -> String {
+        self.uri
+            .clone()
+            .unwrap_or_else(|| String::from(LOCAL_CHAT_URI))
+    }
+
+    fn headers(&self) -> BTreeMap<String, String> {
+        BTreeMap::from([(
+            String::from("Content-Type"),
+            String::from("application/json"),
+        )])
+    }
+
+    /// Local servers do not check the API key, if any
+    fn auth_header(&self) -> Option<(String, String)> {
+        None
+    }
+
+    fn model_name(&self, ai_params: &OpenAIParams) -> String {
+        self.model
+            .clone()
+            .unwrap_or_else(|| ai_params.model.as_string())
+    }
+
+    fn request_body(
+        &self,
+        ai_params: &OpenAIParams,
+        msgs: &[&OpenAIMsg],
+        stop_seq: &[String],
+        stream: bool,
+    ) -> Result<Value> {
+        let mut data = request_body(ai_params, msgs, stop_seq, stream)?;
+
+        data["model"] = Value::String(self.model_name(ai_params));
+
+        // Local servers tend to reject fields they do not know about
+        if let Some(obj) = data.as_object_mut() {
+            obj.remove("logit_bias");
+            obj.remove("user");
+        }
+
+        Ok(data)
+    }
+
+    fn parse_response(&self, response: Value) -> Result<ResponseBody> {
+        serde_json::from_value(response).map_err(|e| {
+            anyhow!("Could not convert local response to Response: {:?}", e)
+        })
+    }
+}
@@ -0,0 +1,240 @@
+//! This module provides the LLM backends that Neatcoder can talk to.
+//!
+//! All prompt assembly in the crate is written against `OpenAIMsg` and
+//! `OpenAIParams`. Each backend is responsible for translating those into its
+//! own wire format and for translating its responses back into a
+//! `ResponseBody`, such that endpoints do not need to know which backend they
+//! are running against.
+
+pub mod anthropic;
+pub mod local;
+pub mod openai;
+
+use self::{anthropic::Anthropic, local::OpenAICompatible, openai::OpenAI};
+use crate::{
+    openai::{msg::OpenAIMsg, params::OpenAIParams, response::ResponseBody},
+    JsError,
+};
+use anyhow::Result;
+use js_sys::JsString;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+};
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// Trait implemented by every LLM backend.
+pub trait LlmProvider {
+    /// The URI of the chat endpoint the request body should be sent to.
+    fn uri(&self) -> String;
+
+    /// Headers the requests are sent with, besides the API key.
+    fn headers(&self) -> BTreeMap<String, String>;
+
+    /// Header carrying the API key and the prefix of its value, e.g.
+    /// `Authorization` and `Bearer `. Returns `None` if the backend does not
+    /// authenticate requests.
+    fn auth_header(&self) -> Option<(String, String)>;
+
+    /// Name of the model as expected by the backend.
+    fn model_name(&self, ai_params: &OpenAIParams) -> String;
+
+    /// Builds the request body in the wire format of the backend.
+    fn request_body(
+        &self,
+        ai_params: &OpenAIParams,
+        msgs: &[&OpenAIMsg],
+        stop_seq: &[String],
+        stream: bool,
+    ) -> Result<Value>;
+
+    /// Converts the raw response of the backend into a `ResponseBody`.
+    fn parse_response(&self, response: Value) -> Result<ResponseBody>;
+}
+
+/// Enum documenting the supported LLM backends.
+#[wasm_bindgen]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+pub enum ProviderType {
+    /// OpenAI Chat Completions API
+    #[default]
+    OpenAI,
+    /// Anthropic Messages API
+    Anthropic,
+    /// Local servers exposing an OpenAI-compatible API, such as Ollama or
+    /// llama.cpp
+    OpenAICompatible,
+}
+
+/// Where and how the request callback sends the request bodies built by a
+/// backend. It is handed to the callback as JSON, the callback adding the
+/// API key under `auth_header` since it is not known to the crate.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestTarget {
+    pub uri: String,
+    pub headers: BTreeMap<String, String>,
+    pub auth_header: Option<String>,
+    pub auth_prefix: String,
+}
+
+impl RequestTarget {
+    pub fn of(provider: &dyn LlmProvider) -> RequestTarget {
+        let (auth_header, auth_prefix) = match provider.auth_header() {
+            Some((header, prefix)) => (Some(header), prefix),
+            None => (None, String::new()),
+        };
+
+        RequestTarget {
+            uri: provider.uri(),
+            headers: provider.headers(),
+            auth_header,
+            auth_prefix,
+        }
+    }
+
+    /// Headers of the request, authenticated with `api_key`.
+    pub fn headers_with(&self, api_key: &str) -> BTreeMap<String, String> {
+        let mut headers = self.headers.clone();
+
+        if let Some(header) = &self.auth_header {
+            headers.insert(
+                header.clone(),
+                format!("{}{}", self.auth_prefix, api_key),
+            );
+        }
+
+        headers
+    }
+}
+
+/// Configuration of the LLM backend selected by the user. This is the struct
+/// stored in `AppData` and in `Chat` and it dispatches to the concrete
+/// `LlmProvider` implementation.
+#[wasm_bindgen]
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Provider {
+    pub provider_type: ProviderType,
+    /// Overrides the default endpoint of the backend
+    pub(crate) uri: Option<String>,
+    /// Overrides the model name derived from `OpenAIParams`. This is required
+    /// for backends whose models are not part of `OpenAIModels`.
+    pub(crate) model: Option<String>,
+}
+
+#[wasm_bindgen]
+impl Provider {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        provider_type: ProviderType,
+        uri: Option<String>,
+        model: Option<String>,
+    ) -> Provider {
+        Self {
+            provider_type,
+            uri,
+            model,
+        }
+    }
+
+    #[wasm_bindgen(js_name = openAI)]
+    pub fn open_ai() -> Provider {
+        Self::default()
+    }
+
+    #[wasm_bindgen(getter, js_name = uri)]
+    pub fn uri_(&self) -> JsString {
+        self.uri().into()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn model(&self) -> Option<JsString> {
+        self.model.clone().map(|model| model.into())
+    }
+
+    /// Serialized `RequestTarget` of the backend, for the requests which are
+    /// not sent through the request callback, e.g. streamed ones.
+    #[wasm_bindgen(js_name = requestTarget)]
+    pub fn request_target(&self) -> Result<JsString, JsError> {
+        serde_json::to_string(&RequestTarget::of(self))
+            .map(JsString::from)
+            .map_err(|e| JsError::from_str(&e.to_string()))
+    }
+}
+
+impl Provider {
+    fn backend(&self) -> Box<dyn LlmProvider> {
+        let uri = self.uri.clone();
+        let model = self.model.clone();
+
+        match self.provider_type {
+            ProviderType::OpenAI => Box::new(OpenAI::new(uri)),
+            ProviderType::Anthropic => Box::new(Anthropic::new(uri, model)),
+            ProviderType::OpenAICompatible => {
+                Box::new(OpenAICompatible::new(uri, model))
+            }
+        }
+    }
+}
+
+impl LlmProvider for Provider {
+    fn uri(&self) -> String {
+        self.backend().uri()
+    }
+
+    fn headers(&self) -> BTreeMap<String, String> {
+        self.backend().headers()
+    }
+
+    fn auth_header(&self) -> Option<(String, String)> {
+        self.backend().auth_header()
+    }
+
+    fn model_name(&self, ai_params: &OpenAIParams) -> String {
+        self.backend().model_name(ai_params)
+    }
+
+    fn request_body(
+        &self,
+        ai_params: &OpenAIParams,
+        msgs: &[&OpenAIMsg],
+        stop_seq: &[String],
+        stream: bool,
+    ) -> Result<Value> {
+        self.backend()
+            .request_body(ai_params, msgs, stop_seq, stream)
+    }
+
+    fn parse_response(&self, response: Value) -> Result<ResponseBody> {
+        self.backend().parse_response(response)
+    }
+}
+
+impl Display for ProviderType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tag = match self {
+            ProviderType::OpenAI => "OpenAI",
+            ProviderType::Anthropic => "Anthropic",
+            ProviderType::OpenAICompatible => "OpenAI Compatible",
+        };
+
+        f.write_str(tag)
+    }
+}
+
+// This is implemented outside the impl block because abstract data structs
+// are not supported in javascript
+#[wasm_bindgen(js_name = providerTypeFromFriendlyUX)]
+pub fn provider_type_from_friendly_ux(provider: String) -> ProviderType {
+    let provider = match provider.as_str() {
+        "Anthropic" => ProviderType::Anthropic,
+        "OpenAI Compatible" | "Ollama" | "llama.cpp" => {
+            ProviderType::OpenAICompatible
+        }
+        _ => ProviderType::OpenAI,
+    };
+    provider
+}
@@ -0,0 +1,62 @@
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use super::LlmProvider;
+use crate::openai::{
+    msg::OpenAIMsg, params::OpenAIParams, request::request_body,
+    response::ResponseBody,
+};
+
+pub const OPENAI_CHAT_URI: &str = "https://api.openai.com/v1/chat/completions";
+
+/// Backend for the OpenAI Chat Completions API.
+#[derive(Debug, Clone, Default)]
+pub struct OpenAI {
+    uri: Option<String>,
+}
+
+impl OpenAI {
+    pub fn new(uri: Option<String>) -> Self {
+        Self { uri }
+    }
+}
+
+impl LlmProvider for OpenAI {
+    fn uri(&self) -> String {
+        self.uri
+            .clone()
+            .unwrap_or_else(|| String::from(OPENAI_CHAT_URI))
+    }
+
+    fn headers(&self) -> BTreeMap<String, String> {
+        BTreeMap::from([(
+            String::from("Content-Type"),
+            String::from("application/json"),
+        )])
+    }
+
+    fn auth_header(&self) -> Option<(String, String)> {
+        Some((String::from("Authorization"), String::from("Bearer ")))
+    }
+
+    fn model_name(&self, ai_params: &OpenAIParams) -> String {
+        ai_params.model.as_string()
+    }
+
+    fn request_body(
+        &self,
+        ai_params: &OpenAIParams,
+        msgs: &[&OpenAIMsg],
+        stop_seq: &[String],
+        stream: bool,
+    ) -> Result<Value> {
+        request_body(ai_params, msgs, stop_seq, stream)
+    }
+
+    fn parse_response(&self, response: Value) -> Result<ResponseBody> {
+        serde_json::from_value(response).map_err(|e| {
+            anyhow!("Could not convert OpenAI response to Response: {:?}", e)
+        })
+    }
+}
@@ -0,0 +1,70 @@
+use std::collections::HashMap;
+
+/// Term frequency saturation
+const K1: f64 = 1.2;
+/// Document length normalisation
+const B: f64 = 0.75;
+
+/// Okapi BM25 index over a set of tokenized documents.
+#[derive(Debug, Clone, Default)]
+pub struct Bm25 {
+    term_freqs: Vec<HashMap<String, usize>>,
+    lengths: Vec<usize>,
+    avg_length: f64,
+    doc_freqs: HashMap<String, usize>,
+}
+
+impl Bm25 {
+    pub fn new(docs: &[Vec<String>]) -> Self {
+        let mut term_freqs = Vec::with_capacity(docs.len());
+        let mut doc_freqs: HashMap<String, usize> = HashMap::new();
+
+        for doc in docs.iter() {
+            let mut freqs: HashMap<String, usize> = HashMap::new();
+
+            for term in doc.iter() {
+                *freqs.entry(term.clone()).or_default() += 1;
+            }
+
+            for term in freqs.keys() {
+                *doc_freqs.entry(term.clone()).or_default() += 1;
+            }
+
+            term_freqs.push(freqs);
+        }
+
+        let lengths = docs.iter().map(Vec::len).collect::<Vec<usize>>();
+
+        let avg_length = if docs.is_empty() {
+            0.
+        } else {
+            lengths.iter().sum::<usize>() as f64 / docs.len() as f64
+        };
+
+        Self {
+            term_freqs,
+            lengths,
+            avg_length,
+            doc_freqs,
+        }
+    }
+
+    /// Scores the document at index `doc` against the query terms.
+    pub fn score(&self, query: &[String], doc: usize) -> f64 {
+        let n = self.term_freqs.len() as f64;
+        let length = self.lengths[doc] as f64;
+
+        query
+            .iter()
+            .filter_map(|term| {
+                let tf = *self.term_freqs[doc].get(term)? as f64;
+                let df = *self.doc_freqs.get(term)? as f64;
+
+                let idf = (1. + (n - df + 0.5) / (df + 0.5)).ln();
+                let norm = 1. - B + B * length / self.avg_length.max(1.);
+
+                Some(idf * tf * (K1 + 1.) / (tf + K1 * norm))
+            })
+            .sum()
+    }
+}
@@ -0,0 +1,341 @@
+//! This module selects the files of the codebase relevant to a code
+//! generation task.
+//!
+//! Sending the whole codebase with every prompt is costly and dilutes the
+//! attention of the model on large projects. Instead, files are ranked by
+//! combining three signals:
+//!
+//! - BM25 over the contents and scaffold descriptions of the files;
+//! - Overlap between the identifiers of the target and of the file path;
+//! - Proximity in the import graph, i.e. files importing the target, files
+//!   imported by it and files in the same folder.
+//!
+//! The top files are included verbatim whereas the next ones are reduced to
+//! their signatures.
+
+pub mod bm25;
+pub mod signatures;
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+use self::{bm25::Bm25, signatures::signatures};
+use crate::models::app_data::scaffold::Scaffold;
+
+const BM25_WEIGHT: f64 = 0.5;
+const OVERLAP_WEIGHT: f64 = 0.3;
+const GRAPH_WEIGHT: f64 = 0.2;
+
+const STOPWORDS: [&str; 24] = [
+    "the", "and", "of", "to", "in", "for", "is", "on", "be", "it", "as", "an",
+    "or", "by", "with", "this", "that", "from", "at", "are", "will", "src",
+    "mod", "rs",
+];
+
+/// File of the codebase selected as context.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextFile {
+    pub path: String,
+    /// Full content of the file, or its signatures if `summarised`
+    pub content: String,
+    pub summarised: bool,
+    pub score: f64,
+}
+
+/// Ranks the files of `codebase` by relevance to the file `target` described
+/// by `description`, from the most to the least relevant.
+///
+/// The file descriptions of the `scaffold` of the project enrich the
+/// documents being ranked.
+pub fn rank_files(
+    codebase: &BTreeMap<String, String>,
+    scaffold: Option<&Scaffold>,
+    target: &str,
+    description: &str,
+) -> Vec<(String, f64)> {
+    let descriptions = scaffold.map(Scaffold::files).unwrap_or_default();
+
+    let describe = |path: &str| {
+        descriptions
+            .iter()
+            .find(|file| same_file(&file.path, path))
+            .map(|file| file.description.as_str())
+            .unwrap_or("")
+    };
+
+    let mut query = terms(target);
+    query.extend(terms(description));
+    query.extend(terms(describe(target)));
+
+    let paths = codebase
+        .keys()
+        .filter(|path| !same_file(path, target))
+        .collect::<Vec<&String>>();
+
+    let docs = paths
+        .iter()
+        .map(|path| {
+            let mut doc = terms(&codebase[*path]);
+            doc.extend(terms(describe(path)));
+            doc
+        })
+        .collect::<Vec<Vec<String>>>();
+
+    let bm25 = Bm25::new(&docs);
+    let bm25_scores = (0..docs.len())
+        .map(|i| bm25.score(&query, i))
+        .collect::<Vec<f64>>();
+    let max_bm25 = bm25_scores.iter().cloned().fold(0., f64::max);
+
+    let target_stem = stem(target);
+    let target_imports = codebase
+        .iter()
+        .find(|(path, _)| same_file(path, target))
+        .map(|(_, code)| imports(code))
+        .unwrap_or_default();
+
+    let query_terms = query.iter().collect::<HashSet<&String>>();
+
+    let mut ranked = paths
+        .iter()
+        .enumerate()
+        .map(|(i, path)| {
+            let bm25 = if max_bm25 > 0. {
+                bm25_scores[i] / max_bm25
+            } else {
+                0.
+            };
+
+            let path_terms = terms(path);
+            let overlap = if path_terms.is_empty() {
+                0.
+            } else {
+                path_terms
+                    .iter()
+                    .filter(|term| query_terms.contains(term))
+                    .count() as f64
+                    / path_terms.len() as f64
+            };
+
+            let graph = if imports(&codebase[*path]).contains(&target_stem)
+                || target_imports.contains(&stem(path))
+            {
+                1.
+            } else if parent(path) == parent(target) {
+                0.5
+            } else {
+                0.
+            };
+
+            let score = BM25_WEIGHT * bm25
+                + OVERLAP_WEIGHT * overlap.min(1.)
+                + GRAPH_WEIGHT * graph;
+
+            (path.to_string(), score)
+        })
+        .collect::<Vec<(String, f64)>>();
+
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    ranked
+}
+
+/// Selects at most `max_files` relevant files, of which the `full_files`
+/// most relevant are included verbatim and the remaining ones as
+/// signature-only summaries. Files without any relevance are left out.
+pub fn select_context(
+    codebase: &BTreeMap<String, String>,
+    scaffold: Option<&Scaffold>,
+    target: &str,
+    description: &str,
+    max_files: usize,
+    full_files: usize,
+) -> Vec<ContextFile> {
+    rank_files(codebase, scaffold, target, description)
+        .into_iter()
+        .filter(|(_, score)| *score > 0.)
+        .take(max_files)
+        .enumerate()
+        .map(|(rank, (path, score))| {
+            let code = &codebase[&path];
+            let summarised = rank >= full_files;
+
+            let content = if summarised {
+                signatures(code)
+            } else {
+                code.clone()
+            };
+
+            ContextFile {
+                path,
+                content,
+                summarised,
+                score,
+            }
+        })
+        .collect()
+}
+
+/// Splits text into lowercase terms, breaking identifiers on `snake_case`
+/// and `camelCase` boundaries. Plurals are naively reduced to their singular
+/// such that `orders` matches `order`.
+pub fn terms(text: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+
+    for word in text.split(|c: char| !c.is_alphanumeric()) {
+        let mut term = String::new();
+        let mut prev_lower = false;
+
+        for c in word.chars() {
+            if c.is_uppercase() && prev_lower {
+                terms.push(std::mem::take(&mut term));
+            }
+
+            prev_lower = c.is_lowercase() || c.is_ascii_digit();
+            term.extend(c.to_lowercase());
+        }
+
+        terms.push(term);
+    }
+
+    terms
+        .into_iter()
+        .filter(|term| term.len() > 1 && !STOPWORDS.contains(&term.as_str()))
+        .map(|term| match term.strip_suffix('s') {
+            Some(singular)
+                if singular.len() > 2 && !singular.ends_with('s') =>
+            {
+                String::from(singular)
+            }
+            _ => term,
+        })
+        .collect()
+}
+
+/// Names of the modules referenced by the import statements of `code`.
+fn imports(code: &str) -> HashSet<String> {
+    const KEYWORDS: [&str; 6] =
+        ["use ", "mod ", "import ", "from ", "#include", "require("];
+
+    code.lines()
+        .map(str::trim_start)
+        .map(|line| {
+            line.trim_start_matches("pub ")
+                .trim_start_matches("export ")
+        })
+        .filter(|line| KEYWORDS.iter().any(|keyword| line.starts_with(keyword)))
+        .flat_map(|line| {
+            line.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .map(str::to_lowercase)
+                .collect::<Vec<String>>()
+        })
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// Compares paths regardless of a leading `src/` or `/`.
+fn same_file(a: &str, b: &str) -> bool {
+    let normalise = |path: &str| {
+        let path = path.trim_start_matches('/');
+        path.strip_prefix("src/").unwrap_or(path).to_string()
+    };
+
+    normalise(a) == normalise(b)
+}
+
+fn stem(path: &str) -> String {
+    let path = Path::new(path);
+
+    // `mod.rs`, `index.ts` and `__init__.py` are named after their folder
+    let stem = match path.file_stem().and_then(|stem| stem.to_str()) {
+        Some("mod") | Some("index") | Some("__init__") => path
+            .parent()
+            .and_then(|parent| parent.file_name())
+            .and_then(|name| name.to_str()),
+        stem => stem,
+    };
+
+    stem.unwrap_or("").to_lowercase()
+}
+
+fn parent(path: &str) -> String {
+    let path = path.trim_start_matches('/');
+    let path = path.strip_prefix("src/").unwrap_or(path);
+
+    Path::new(path)
+        .parent()
+        .map(|parent| parent.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn ranks_related_files_first() {
+        let codebase = BTreeMap::from([
+            (
+                String::from("src/main.rs"),
+                String::from("mod config;\nmod handlers;\n\nfn main() {}"),
+            ),
+            (
+                String::from("src/handlers/order_handler.rs"),
+                String::from(
+                    "pub fn create_order(order: Order) -> Result<()> {\n    todo!()\n}",
+                ),
+            ),
+            (
+                String::from("src/models.rs"),
+                String::from(
+                    "pub struct Order {\n    pub id: u64,\n}\n\npub struct Product;",
+                ),
+            ),
+            (
+                String::from("src/utils.rs"),
+                String::from("pub fn slugify(s: &str) -> String {\n    s.into()\n}"),
+            ),
+        ]);
+
+        let scaffold: Scaffold = serde_json::from_str(
+            r#"{"src": {"config.rs": "Loads the database configuration"}}"#,
+        )
+        .unwrap();
+
+        let ranked = rank_files(
+            &codebase,
+            Some(&scaffold),
+            "config.rs",
+            "Load settings",
+        );
+        assert_eq!(ranked[0].0, "src/main.rs");
+
+        let context = select_context(
+            &codebase,
+            Some(&scaffold),
+            "handlers/cart_handler.rs",
+            "Handles the shopping cart orders",
+            3,
+            1,
+        );
+
+        assert_eq!(context.len(), 3);
+        assert_eq!(context[0].path, "src/handlers/order_handler.rs");
+        assert!(!context[0].summarised);
+
+        // Unrelated files are left out
+        assert!(context.iter().all(|file| file.path != "src/utils.rs"));
+
+        let models = context
+            .iter()
+            .find(|file| file.path == "src/models.rs")
+            .unwrap();
+
+        assert!(models.summarised);
+        assert_eq!(
+            models.content,
+            "pub struct Order { ... }\npub struct Product;"
+        );
+    }
+}
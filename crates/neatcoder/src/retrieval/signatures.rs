@@ -0,0 +1,75 @@
+/// Declarations whose body is elided
+const ITEMS: [&str; 10] = [
+    "fn",
+    "struct",
+    "enum",
+    "type",
+    "const",
+    "def",
+    "function",
+    "func",
+    "union",
+    "macro_rules!",
+];
+/// Declarations whose members are kept
+const CONTAINERS: [&str; 5] = ["impl", "trait", "class", "interface", "mod"];
+/// Modifiers preceding the declaration keyword
+const MODIFIERS: [&str; 11] = [
+    "pub",
+    "pub(crate)",
+    "export",
+    "async",
+    "unsafe",
+    "default",
+    "static",
+    "abstract",
+    "public",
+    "private",
+    "protected",
+];
+
+/// Reduces source code to the signatures of its declarations, eliding the
+/// bodies of functions and types. This is a language-agnostic heuristic
+/// based on keywords and brace depth.
+pub fn signatures(code: &str) -> String {
+    let mut lines = Vec::new();
+    // Depth and indentation of the containers currently open
+    let mut containers: Vec<(i32, &str)> = Vec::new();
+    let mut depth = 0;
+
+    for line in code.lines() {
+        let trimmed = line.trim();
+        let indent = &line[..line.len() - line.trim_start().len()];
+
+        let keyword = trimmed
+            .split_whitespace()
+            .find(|word| !MODIFIERS.contains(word))
+            .unwrap_or("");
+
+        if CONTAINERS.contains(&keyword) && trimmed.ends_with('{') {
+            lines.push(line.to_string());
+            containers.push((depth, indent));
+        } else if ITEMS.contains(&keyword) {
+            match trimmed.strip_suffix('{') {
+                Some(signature) => {
+                    lines.push(format!("{}{}{{ ... }}", indent, signature))
+                }
+                None => lines.push(line.to_string()),
+            }
+        }
+
+        depth += trimmed.matches('{').count() as i32
+            - trimmed.matches('}').count() as i32;
+
+        while let Some((open_depth, indent)) = containers.last() {
+            if depth > *open_depth {
+                break;
+            }
+
+            lines.push(format!("{}}}", indent));
+            containers.pop();
+        }
+    }
+
+    lines.join("\n")
+}
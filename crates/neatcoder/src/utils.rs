@@ -1,6 +1,8 @@
 ///< Contains utility functions and helpers.
+use crate::consts::MAX_JSON_ATTEMPTS;
 use crate::openai::request::chat_raw;
 use crate::openai::{msg::OpenAIMsg, params::OpenAIParams};
+use crate::providers::LlmProvider;
 use crate::JsError;
 use anyhow::{anyhow, Result};
 use js_sys::Function;
@@ -13,17 +15,21 @@ use wasm_bindgen::JsValue;
 use web_sys::console;
 
 pub async fn write_json(
+    provider: &dyn LlmProvider,
     ai_params: &OpenAIParams,
     prompts: &Vec<&OpenAIMsg>,
     request_callback: &Function,
 ) -> Result<(String, Value)> {
-    let mut retries = 3;
+    // Transport errors are retried by `chat_raw`, whereas here we retry
+    // answers that are not valid JSON
+    let mut attempts = MAX_JSON_ATTEMPTS;
 
     loop {
         log("[INFO] Prompting the LLM..."); // TODO: remove this log in the next release
 
         let chat =
-            chat_raw(request_callback, ai_params, prompts, &[], &[]).await?;
+            chat_raw(request_callback, provider, ai_params, prompts, &[])
+                .await?;
 
         let answer = chat
             .choices
@@ -40,9 +46,9 @@ pub async fn write_json(
             }
             Err(e) => {
                 log(&format!("Failed to parse json: \n{}", e));
-                retries -= 1;
+                attempts -= 1;
 
-                if retries <= 0 {
+                if attempts == 0 {
                     return Err(anyhow!("Failed to parse json."));
                 }
 
@@ -0,0 +1,423 @@
+///< Syntax validation of generated code.
+use parser::parser::{
+    graphql::{AsGraphQl, GraphQl, TypeKind},
+    html::AsHtml,
+    json::AsJson,
+    proto::AsProto,
+    python::AsPython,
+    rust::AsRust,
+    sql::AsSql,
+    yaml::AsYaml,
+};
+use std::{collections::BTreeSet, fmt, path::Path};
+
+use crate::models::app_data::{
+    interfaces::sql_schema::SqlSchema,
+    language::{Language, LanguageType},
+};
+
+/// Keywords starting the queries which are checked against the schemas of
+/// the databases.
+const QUERY_KEYWORDS: [&str; 5] =
+    ["SELECT", "INSERT", "UPDATE", "DELETE", "WITH"];
+
+/// Prefixes of the functions resolving GraphQL fields, e.g. `resolve_user`.
+const RESOLVER_PREFIXES: [&str; 2] = ["resolve", "get"];
+
+/// Syntaxes which can be checked with the `parser` crate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Syntax {
+    Rust,
+    Python,
+    Sql,
+    Json,
+    Yaml,
+    GraphQl,
+    ProtoBuf,
+    Html,
+}
+
+impl Syntax {
+    /// Resolves the syntax of `filename`, from its extension or otherwise
+    /// from the language of the project. Returns `None` if the syntax
+    /// cannot be checked.
+    pub fn new(filename: &str, language: &Language) -> Option<Syntax> {
+        let extension = Path::new(filename)
+            .extension()
+            .and_then(|extension| extension.to_str());
+
+        match extension {
+            Some("rs") => Some(Syntax::Rust),
+            Some("py") => Some(Syntax::Python),
+            Some("sql") => Some(Syntax::Sql),
+            Some("json") => Some(Syntax::Json),
+            Some("yaml") | Some("yml") => Some(Syntax::Yaml),
+            Some("graphql") | Some("gql") => Some(Syntax::GraphQl),
+            Some("proto") => Some(Syntax::ProtoBuf),
+            Some("html") | Some("htm") => Some(Syntax::Html),
+            Some(_) => None,
+            None => match language.language {
+                LanguageType::Rust => Some(Syntax::Rust),
+                LanguageType::Python => Some(Syntax::Python),
+                _ => None,
+            },
+        }
+    }
+
+    /// Parses `code`, returning the parser error if the code is invalid.
+    pub fn check(&self, code: &str) -> Result<(), String> {
+        let result = match self {
+            Syntax::Rust => code.as_rust().map(|_| ()),
+            Syntax::Python => code.as_python().map(|_| ()),
+            Syntax::Sql => code.as_sql().map(|_| ()),
+            Syntax::Json => code.as_json().map(|_| ()),
+            Syntax::Yaml => code.as_yaml().map(|_| ()),
+            Syntax::GraphQl => code.as_graphql().map(|_| ()),
+            Syntax::ProtoBuf => code.as_proto().map(|_| ()),
+            // HTML parsers recover from any input, hence the errors they
+            // recovered from are reported instead. Files without a doctype,
+            // such as templates and partials, are parsed as fragments.
+            Syntax::Html => {
+                let is_document =
+                    code.trim_start().get(..9).is_some_and(|start| {
+                        start.eq_ignore_ascii_case("<!doctype")
+                    });
+
+                let dom = if is_document {
+                    code.as_html()
+                } else {
+                    code.as_html_fragment()
+                }
+                .map_err(|e| e.to_string())?;
+
+                return match dom.html.errors.first() {
+                    Some(error) => Err(error.to_string()),
+                    None => Ok(()),
+                };
+            }
+        };
+
+        result.map_err(|e| e.to_string())
+    }
+
+    /// Info string of the markdown code blocks in this syntax.
+    pub fn tag(&self) -> &str {
+        match self {
+            Syntax::Rust => "rust",
+            Syntax::Python => "python",
+            Syntax::Sql => "sql",
+            Syntax::Json => "json",
+            Syntax::Yaml => "yaml",
+            Syntax::GraphQl => "graphql",
+            Syntax::ProtoBuf => "protobuf",
+            Syntax::Html => "html",
+        }
+    }
+}
+
+impl fmt::Display for Syntax {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tag = match self {
+            Syntax::Rust => "Rust",
+            Syntax::Python => "Python",
+            Syntax::Sql => "SQL",
+            Syntax::Json => "JSON",
+            Syntax::Yaml => "YAML",
+            Syntax::GraphQl => "GraphQL",
+            Syntax::ProtoBuf => "Protocol Buffers",
+            Syntax::Html => "HTML",
+        };
+
+        f.write_str(tag)
+    }
+}
+
+/// Extracts the code from an LLM answer. If the answer contains markdown
+/// code blocks, the first one is returned, preferring blocks tagged with
+/// `tag`. Otherwise the whole answer is considered to be code.
+pub fn extract_code(answer: &str, tag: &str) -> String {
+    let mut blocks = Vec::new();
+    let mut lines = answer.lines();
+
+    while let Some(line) = lines.next() {
+        let info = match line.trim_start().strip_prefix("```") {
+            Some(info) => info.trim(),
+            None => continue,
+        };
+
+        let body = lines
+            .by_ref()
+            .take_while(|line| line.trim() != "```")
+            .collect::<Vec<&str>>();
+
+        blocks.push((info, body.join("\n")));
+    }
+
+    if blocks.is_empty() {
+        return String::from(answer);
+    }
+
+    let position = blocks
+        .iter()
+        .position(|(info, _)| info.eq_ignore_ascii_case(tag))
+        .unwrap_or(0);
+
+    let (_, mut code) = blocks.swap_remove(position);
+    code.push('\n');
+
+    code
+}
+
+/// Checks the queries of `code` against the tables and columns of `schema`,
+/// and returns the issues found. SQL files are checked as a whole, along
+/// with the tables they create, whereas the queries of other files are
+/// looked up in their string literals. Queries which cannot be parsed, e.g.
+/// because they are built with string formatting, are not checked.
+pub fn check_queries(
+    schema: &SqlSchema,
+    code: &str,
+    syntax: Option<Syntax>,
+) -> Vec<String> {
+    if schema.is_empty() {
+        return Vec::new();
+    }
+
+    let mut schema = schema.clone();
+
+    let queries = match syntax {
+        Some(Syntax::Sql) => {
+            if let Ok(tables) = SqlSchema::from_ddl_(code) {
+                schema.merge(tables);
+            }
+
+            vec![String::from(code)]
+        }
+        _ => embedded_queries(code),
+    };
+
+    let mut issues = Vec::new();
+
+    for query in queries.iter() {
+        for issue in schema.validate_query_(query).unwrap_or_default() {
+            if !issues.contains(&issue) {
+                issues.push(issue);
+            }
+        }
+    }
+
+    issues
+}
+
+/// Extracts the SQL queries from the string literals of `code`. Placeholders
+/// in the `%s` style of Python drivers are replaced by `?`, such that the
+/// queries can be parsed.
+pub fn embedded_queries(code: &str) -> Vec<String> {
+    let chars = code.chars().collect::<Vec<char>>();
+    let mut queries = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let quote = chars[i];
+
+        if !matches!(quote, '"' | '\'' | '`') {
+            i += 1;
+            continue;
+        }
+
+        let is_delimiter = |at: usize, width: usize| {
+            (at..at + width).all(|j| chars.get(j) == Some(&quote))
+        };
+
+        // Python triple-quoted strings
+        let width = if is_delimiter(i, 3) { 3 } else { 1 };
+        let start = i + width;
+        let mut end = start;
+
+        while end < chars.len() && !is_delimiter(end, width) {
+            // Quotes in Rust lifetimes do not start a literal
+            if quote == '\'' && chars[end] == '\n' {
+                break;
+            }
+
+            end += if chars[end] == '\\' { 2 } else { 1 };
+        }
+
+        if end >= chars.len() || !is_delimiter(end, width) {
+            i = start;
+            continue;
+        }
+
+        let literal = chars[start..end].iter().collect::<String>();
+
+        let is_query = literal.split_whitespace().next().is_some_and(|word| {
+            QUERY_KEYWORDS.contains(&word.to_uppercase().as_str())
+        });
+
+        if is_query {
+            queries.push(literal.trim().replace("%s", "?"));
+        }
+
+        i = end + width;
+    }
+
+    queries
+}
+
+/// Checks that `code` resolves the fields of the GraphQL types `roots`, and
+/// returns the fields it misses. The fields of the other object types
+/// reached from the roots only need a resolver if they take arguments, as
+/// the others are usually read from their parent object. A field counts as
+/// resolved if an identifier of the code is named after it, in any case and
+/// possibly prefixed with `resolve` or `get`.
+pub fn check_resolvers(
+    schema: &GraphQl,
+    roots: &[&str],
+    code: &str,
+) -> Vec<String> {
+    let normalize = |name: &str| name.replace('_', "").to_lowercase();
+
+    let identifiers = code
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|word| !word.is_empty())
+        .map(normalize)
+        .flat_map(|word| {
+            let unprefixed = RESOLVER_PREFIXES
+                .iter()
+                .filter_map(|prefix| word.strip_prefix(prefix))
+                .map(String::from)
+                .collect::<Vec<String>>();
+
+            unprefixed.into_iter().chain(std::iter::once(word))
+        })
+        .collect::<BTreeSet<String>>();
+
+    let mut issues = Vec::new();
+
+    let objects = schema
+        .subset(roots)
+        .types
+        .into_iter()
+        .filter(|ty| ty.kind == TypeKind::Object);
+
+    for ty in objects {
+        let is_root = roots.contains(&ty.name.as_str());
+
+        for field in ty.fields.iter() {
+            let needs_resolver = is_root || !field.args.is_empty();
+
+            if needs_resolver && !identifiers.contains(&normalize(&field.name))
+            {
+                issues.push(format!(
+                    "No resolver for the field `{}.{}`",
+                    ty.name, field.name
+                ));
+            }
+        }
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn extracts_and_checks_code() {
+        let rust = Language::new(LanguageType::Rust);
+
+        let answer = "Here is the file:\n\n```toml\n[package]\n```\n\n```rust\nfn main() {\n    println!(\"Hello\");\n}\n```\nDone.";
+
+        let code = extract_code(answer, "rust");
+        assert_eq!(code, "fn main() {\n    println!(\"Hello\");\n}\n");
+
+        let syntax = Syntax::new("main", &rust).unwrap();
+        assert_eq!(syntax, Syntax::Rust);
+        assert!(syntax.check(&code).is_ok());
+        assert!(syntax.check("fn main() {").is_err());
+
+        // Answers without code blocks are considered to be code
+        assert_eq!(extract_code("x = 1\n", "python"), "x = 1\n");
+
+        assert_eq!(Syntax::new("schema.sql", &rust), Some(Syntax::Sql));
+        assert_eq!(Syntax::new("index.html", &rust), Some(Syntax::Html));
+        assert_eq!(Syntax::new("logo.svg", &rust), None);
+
+        let html = Syntax::Html;
+        assert!(html
+            .check("<!DOCTYPE html>\n<html><body><p>Hi</p></body></html>")
+            .is_ok());
+        assert!(html.check("<!DOCTYPE html>\n<p>Hi</b>").is_err());
+        assert!(html.check("<ul>\n  <li>{{ item }}</li>\n</ul>").is_ok());
+        assert!(html.check("<ul><li>Hi</b></ul>").is_err());
+
+        let sql = Syntax::Sql;
+        assert!(sql.check("SELECT id FROM users;").is_ok());
+        assert!(sql.check("SELEC id FROM users;").is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn checks_embedded_queries() {
+        let schema =
+            SqlSchema::from_ddl_("CREATE TABLE users (id INT, email TEXT);\n")
+                .unwrap();
+
+        let code = r#"
+fn find<'a>(pool: &'a Pool) -> Query<'a> {
+    sqlx::query("SELECT id, mail FROM users WHERE id = $1")
+}
+
+const LABEL: &str = "Select a user";
+"#;
+
+        // Literals starting like a query are checked if they parse as SQL
+        assert_eq!(
+            embedded_queries(code),
+            vec!["SELECT id, mail FROM users WHERE id = $1", "Select a user"]
+        );
+
+        assert_eq!(
+            check_queries(&schema, code, Some(Syntax::Rust)),
+            vec!["Unknown column `mail`"]
+        );
+
+        let code = "cursor.execute(\"\"\"\n    UPDATE users SET email = %s\n\"\"\", (email,))";
+        assert!(check_queries(&schema, code, Some(Syntax::Python)).is_empty());
+
+        // Tables created by SQL files are known to their queries
+        let migration = "CREATE TABLE teams (id INT);\nSELECT id FROM teams;\n";
+        assert!(check_queries(&schema, migration, Some(Syntax::Sql)).is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn checks_resolvers() {
+        let schema = "type Query {\n  user(id: ID!): User\n  users: [User!]!\n}\n\ntype Mutation {\n  deleteUser(id: ID!): Boolean!\n}\n\ntype User {\n  id: ID!\n  posts(first: Int): [String!]!\n}\n"
+            .as_graphql()
+            .unwrap();
+
+        let code = r#"
+const resolvers = {
+  Query: {
+    user: (_, { id }) => db.findUser(id),
+  },
+};
+"#;
+
+        assert_eq!(
+            check_resolvers(&schema, &["Query"], code),
+            vec![
+                "No resolver for the field `Query.users`",
+                "No resolver for the field `User.posts`",
+            ]
+        );
+
+        let code = "def resolve_users(info):\n    ...\n\ndef resolve_user(info, id):\n    ...\n\ndef resolve_posts(user, info, first=None):\n    ...\n";
+        assert!(check_resolvers(&schema, &["Query"], code).is_empty());
+
+        assert_eq!(
+            Syntax::new("schema.graphql", &Language::new(LanguageType::Rust)),
+            Some(Syntax::GraphQl)
+        );
+    }
+}
@@ -6,13 +6,13 @@ pub enum ParseError {
     SerdeJson(#[from] serde_json::Error),
     #[error(transparent)]
     SerdeYaml(#[from] serde_yaml::Error),
-    #[cfg(feature = "full")]
+    #[cfg(feature = "csv")]
     #[error(transparent)]
     Csv(#[from] csv::Error),
-    #[cfg(feature = "full")]
+    #[cfg(feature = "rust")]
     #[error(transparent)]
     RustSyn(#[from] syn::Error),
-    #[cfg(feature = "full")]
+    #[cfg(feature = "python")]
     #[error(transparent)]
     RustPython(#[from] rustpython_parser::error::ParseError),
     #[error(transparent)]
@@ -9,15 +9,25 @@ use crate::err::ParseError;
 
 use super::AsFormat;
 
-/// A structure representing a CSV table, essentially a transparent
-/// newtype wrapper around a Vector of `CsvRow`.
+/// A structure representing a CSV table, essentially a wrapper around a
+/// Vector of `CsvRow` which also keeps the header row.
 #[derive(Debug)]
-pub struct CsvTable(Vec<CsvRow>);
+pub struct CsvTable {
+    headers: CsvRow,
+    rows: Vec<CsvRow>,
+}
+
+impl CsvTable {
+    /// Provides the header row, i.e. the names of the columns.
+    pub fn headers(&self) -> &CsvRow {
+        &self.headers
+    }
+}
 
 impl AsRef<Vec<CsvRow>> for CsvTable {
     /// Provides a reference to the underlying Vector of `CsvRow`.
     fn as_ref(&self) -> &Vec<CsvRow> {
-        &self.0
+        &self.rows
     }
 }
 
@@ -26,14 +36,14 @@ impl Deref for CsvTable {
 
     /// Dereferences to the underlying Vector of `CsvRow`.
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.rows
     }
 }
 
 impl DerefMut for CsvTable {
     /// Mutably dereferences to the underlying Vector of `CsvRow`.
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+        &mut self.rows
     }
 }
 
@@ -78,7 +88,7 @@ pub trait AsCsv: AsFormat {
     fn strip_csvs(&self) -> Result<Vec<CsvTable>, ParseError>;
 }
 
-impl<'a> AsCsv for &'a str {
+impl AsCsv for &str {
     /// Converts string slices to a CSV table.
     /// Assumes that the whole string is the CSV object
     fn as_csv(&self) -> Result<CsvTable, ParseError> {
@@ -109,11 +119,12 @@ impl<'a> AsCsv for &'a str {
 /// A result containing a `CsvTable` if the deserialization is successful, or a `ParseError` otherwise.
 fn deserialize_csv(input: &str) -> Result<CsvTable, ParseError> {
     let mut reader = Reader::from_reader(Cursor::new(input));
-    let mut records = Vec::new();
+    let headers = CsvRow(reader.headers()?.clone());
+    let mut rows = Vec::new();
 
     for record in reader.records() {
-        records.push(CsvRow(record?));
+        rows.push(CsvRow(record?));
     }
 
-    Ok(CsvTable(records))
+    Ok(CsvTable { headers, rows })
 }
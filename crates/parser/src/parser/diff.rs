@@ -0,0 +1,474 @@
+use anyhow::anyhow;
+use std::fmt;
+
+use super::AsFormat;
+use crate::err::ParseError;
+
+/// Trait providing methods for working with unified diffs.
+pub trait AsDiff: AsFormat {
+    /// Converts the object to a unified diff.
+    fn as_diff(&self) -> Result<Diff, ParseError>;
+
+    /// Strips the diff formatting, expecting encapsulation as in OpenAI's format, and returns the unified diff.
+    fn strip_diff(&self) -> Result<Diff, ParseError>;
+}
+
+impl AsDiff for &str {
+    /// Implementation for converting a string slice to a unified diff.
+    fn as_diff(&self) -> Result<Diff, ParseError> {
+        self.as_format(deserialize_diff)
+    }
+
+    /// Implementation for stripping a unified diff from a string slice, assuming encapsulation like OpenAI.
+    fn strip_diff(&self) -> Result<Diff, ParseError> {
+        self.strip_format(deserialize_diff, "diff")
+    }
+}
+
+/// Unified diff of a single file, made of hunks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diff {
+    /// Raw text of the diff
+    pub raw: String,
+    /// Hunks of the diff, in the order of the file
+    pub hunks: Vec<Hunk>,
+}
+
+/// Contiguous block of changes, introduced by a `@@ -l,s +l,s @@` header.
+///
+/// Headers without line numbers, such as `@@ ... @@`, leave the starts and
+/// lengths at 0, in which case the hunk is located by its content only.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hunk {
+    /// Line of the original file where the hunk starts, from 1
+    pub old_start: usize,
+    /// Number of lines of the original file covered by the hunk
+    pub old_len: usize,
+    /// Line of the new file where the hunk starts, from 1
+    pub new_start: usize,
+    /// Number of lines of the new file covered by the hunk
+    pub new_len: usize,
+    pub lines: Vec<HunkLine>,
+}
+
+/// Line of a hunk.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HunkLine {
+    Context(String),
+    Removed(String),
+    Added(String),
+}
+
+/// Hunk which could not be applied because the original file does not
+/// contain its context and removed lines.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    /// Index of the hunk in the diff, from 1
+    pub hunk: usize,
+    /// Line of the original file where the hunk was expected
+    pub old_start: usize,
+    /// Lines the hunk expected to find in the original file
+    pub expected: Vec<String>,
+}
+
+/// Result of applying a diff, with the hunks that could not be applied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Patched {
+    pub content: String,
+    pub conflicts: Vec<Conflict>,
+}
+
+impl Hunk {
+    /// Lines expected in the original file.
+    pub fn old_lines(&self) -> Vec<&str> {
+        self.lines
+            .iter()
+            .filter_map(|line| match line {
+                HunkLine::Context(line) | HunkLine::Removed(line) => {
+                    Some(line.as_str())
+                }
+                HunkLine::Added(_) => None,
+            })
+            .collect()
+    }
+
+    /// Lines replacing the old ones in the new file.
+    pub fn new_lines(&self) -> Vec<&str> {
+        self.lines
+            .iter()
+            .filter_map(|line| match line {
+                HunkLine::Context(line) | HunkLine::Added(line) => {
+                    Some(line.as_str())
+                }
+                HunkLine::Removed(_) => None,
+            })
+            .collect()
+    }
+}
+
+impl Diff {
+    /// Applies the diff to `original`.
+    ///
+    /// Line numbers in hunk headers are only used as a hint, as LLMs often
+    /// get them wrong. Each hunk is applied at the closest position where
+    /// its context and removed lines match the original file, first
+    /// exactly and then ignoring trailing whitespace. Hunks which cannot be
+    /// located are reported as conflicts and left out.
+    pub fn apply(&self, original: &str) -> Patched {
+        let lines = original.lines().collect::<Vec<&str>>();
+
+        let mut content: Vec<&str> = Vec::new();
+        let mut conflicts = Vec::new();
+        // Position in the original file up to which lines were copied
+        let mut cursor = 0;
+        // Difference between new and old line numbers so far
+        let mut offset: isize = 0;
+
+        for (i, hunk) in self.hunks.iter().enumerate() {
+            let old = hunk.old_lines();
+
+            let hint = match old.is_empty() {
+                // Pure additions are inserted after the line `old_start`
+                true => hunk.old_start,
+                false => hunk.old_start.saturating_sub(1),
+            };
+            let hint = match hunk.old_start {
+                // Without line numbers, the hunk is searched from the end
+                // of the previous one
+                0 => cursor,
+                _ => (hint as isize + offset).max(0) as usize,
+            };
+
+            let position = locate(&lines, &old, cursor, hint, |a, b| a == b)
+                .or_else(|| {
+                    locate(&lines, &old, cursor, hint, |a, b| {
+                        a.trim_end() == b.trim_end()
+                    })
+                });
+
+            match position {
+                Some(position) => {
+                    content.extend(&lines[cursor..position]);
+                    content.extend(hunk.new_lines());
+
+                    cursor = position + old.len();
+                    offset +=
+                        hunk.new_lines().len() as isize - old.len() as isize;
+                }
+                None => conflicts.push(Conflict {
+                    hunk: i + 1,
+                    old_start: hunk.old_start,
+                    expected: old.iter().map(|line| line.to_string()).collect(),
+                }),
+            }
+        }
+
+        content.extend(&lines[cursor..]);
+
+        let mut content = content.join("\n");
+
+        if original.ends_with('\n') || original.is_empty() {
+            content.push('\n');
+        }
+
+        Patched { content, conflicts }
+    }
+}
+
+/// Finds the position closest to `hint`, and not before `from`, where
+/// `lines` contain `pattern`.
+fn locate<F>(
+    lines: &[&str],
+    pattern: &[&str],
+    from: usize,
+    hint: usize,
+    eq: F,
+) -> Option<usize>
+where
+    F: Fn(&str, &str) -> bool,
+{
+    if lines.len() < from + pattern.len() {
+        return None;
+    }
+
+    let last = lines.len() - pattern.len();
+    let hint = hint.clamp(from, last);
+
+    let matches = |position: usize| {
+        pattern
+            .iter()
+            .enumerate()
+            .all(|(i, line)| eq(lines[position + i], line))
+    };
+
+    let max_distance = (hint - from).max(last - hint);
+
+    (0..=max_distance).find_map(|distance| {
+        [hint.checked_add(distance), hint.checked_sub(distance)]
+            .into_iter()
+            .flatten()
+            .filter(|position| (from..=last).contains(position))
+            .find(|position| matches(*position))
+    })
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Hunk #{} (line {}) does not match the file, expected:\n{}",
+            self.hunk,
+            self.old_start,
+            self.expected.join("\n")
+        )
+    }
+}
+
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.raw)
+    }
+}
+
+/// Function to deserialize a unified diff string into a `Diff` struct.
+///
+/// File headers (`---`, `+++`, `diff --git`, `index`) and any text outside of
+/// hunks are ignored, such that prose around the diff does not matter.
+/// Within a hunk, `---` and `+++` lines are removed and added lines until
+/// the line counts of its header are used up.
+///
+/// # Arguments
+/// * `diff_str` - The unified diff string to be deserialized.
+///
+/// # Returns
+/// * A `Result` containing a `Diff` struct if successful, or a `ParseError` if an error occurred.
+fn deserialize_diff(diff_str: &str) -> Result<Diff, ParseError> {
+    let mut hunks: Vec<Hunk> = Vec::new();
+    let mut in_hunk = false;
+    // Lines of the original and new file left in the current hunk, unknown
+    // if its header has no line numbers
+    let mut remaining: Option<(usize, usize)> = None;
+    let mut lines = diff_str.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        if line.starts_with("@@") {
+            let hunk = parse_hunk_header(line)?;
+
+            remaining = if hunk.old_start == 0 && hunk.new_start == 0 {
+                None
+            } else {
+                Some((hunk.old_len, hunk.new_len))
+            };
+            hunks.push(hunk);
+            in_hunk = true;
+            continue;
+        }
+
+        // A removed line may start with `---`, so file headers are only
+        // recognised when followed by the `+++` line, and outside of hunks
+        // unless their line counts are used up
+        let is_hunk_done = !in_hunk || remaining.is_none_or(|r| r == (0, 0));
+
+        let is_file_header = is_hunk_done
+            && line.starts_with("--- ")
+            && lines.peek().is_some_and(|next| next.starts_with("+++ "));
+
+        if is_file_header {
+            lines.next();
+            in_hunk = false;
+            continue;
+        }
+
+        if !in_hunk {
+            continue;
+        }
+
+        let hunk = hunks.last_mut().unwrap(); // Safe as `in_hunk`
+
+        let hunk_line = match line.chars().next() {
+            Some(' ') => HunkLine::Context(line[1..].to_string()),
+            Some('-') => HunkLine::Removed(line[1..].to_string()),
+            Some('+') => HunkLine::Added(line[1..].to_string()),
+            // Blank context lines often lose their leading space
+            None => HunkLine::Context(String::new()),
+            // `\ No newline at end of file`
+            Some('\\') => continue,
+            Some(_) => {
+                in_hunk = false;
+                continue;
+            }
+        };
+
+        if let Some((old_len, new_len)) = remaining.as_mut() {
+            match hunk_line {
+                HunkLine::Context(_) => {
+                    *old_len = old_len.saturating_sub(1);
+                    *new_len = new_len.saturating_sub(1);
+                }
+                HunkLine::Removed(_) => *old_len = old_len.saturating_sub(1),
+                HunkLine::Added(_) => *new_len = new_len.saturating_sub(1),
+            }
+        }
+
+        hunk.lines.push(hunk_line);
+    }
+
+    if hunks.is_empty() {
+        return Err(ParseError::from(anyhow!(
+            "Unable to find any hunk in the diff: \n{}",
+            diff_str
+        )));
+    }
+
+    if let Some(i) = hunks.iter().position(|hunk| hunk.lines.is_empty()) {
+        return Err(ParseError::from(anyhow!(
+            "Hunk #{} of the diff is empty",
+            i + 1
+        )));
+    }
+
+    Ok(Diff {
+        raw: diff_str.to_string(),
+        hunks,
+    })
+}
+
+/// Parses a hunk header such as `@@ -12,5 +12,7 @@ fn main() {`. Headers
+/// without line numbers, such as `@@ ... @@` or `@@ @@`, give a hunk
+/// starting at line 0.
+fn parse_hunk_header(line: &str) -> Result<Hunk, ParseError> {
+    let invalid = || {
+        ParseError::from(anyhow!("Invalid hunk header in the diff: {}", line))
+    };
+
+    let ranges = line
+        .trim_start_matches('@')
+        .split("@@")
+        .next()
+        .ok_or_else(invalid)?;
+
+    if !ranges.contains(|c: char| c.is_ascii_digit()) {
+        return Ok(Hunk {
+            old_start: 0,
+            old_len: 0,
+            new_start: 0,
+            new_len: 0,
+            lines: Vec::new(),
+        });
+    }
+
+    let mut ranges = ranges.split_whitespace();
+
+    let mut range = |sign: char| -> Result<(usize, usize), ParseError> {
+        let range = ranges
+            .next()
+            .and_then(|range| range.strip_prefix(sign))
+            .ok_or_else(invalid)?;
+
+        let (start, len) = match range.split_once(',') {
+            Some((start, len)) => (start, len),
+            None => (range, "1"),
+        };
+
+        Ok((
+            start.parse().map_err(|_| invalid())?,
+            len.parse().map_err(|_| invalid())?,
+        ))
+    };
+
+    let (old_start, old_len) = range('-')?;
+    let (new_start, new_len) = range('+')?;
+
+    Ok(Hunk {
+        old_start,
+        old_len,
+        new_start,
+        new_len,
+        lines: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_and_apply() -> Result<()> {
+        let original = "use std::env;\n\nfn main() {\n    let name = \"world\";\n    println!(\"Hello {}\", name);\n}\n";
+
+        let prompt = "Here is the change:\n```diff\n--- a/src/main.rs\n+++ b/src/main.rs\n@@ -3,4 +3,5 @@\n fn main() {\n-    let name = \"world\";\n+    let name = env::args().nth(1).unwrap_or_default();\n+\n     println!(\"Hello {}\", name);\n }\n```";
+
+        let diff = prompt.strip_diff()?;
+
+        assert_eq!(diff.hunks.len(), 1);
+        assert_eq!(diff.hunks[0].old_start, 3);
+        assert_eq!(diff.hunks[0].new_len, 5);
+
+        let patched = diff.apply(original);
+
+        assert!(patched.conflicts.is_empty());
+        assert_eq!(
+            patched.content,
+            "use std::env;\n\nfn main() {\n    let name = env::args().nth(1).unwrap_or_default();\n\n    println!(\"Hello {}\", name);\n}\n"
+        );
+
+        // Wrong line numbers are tolerated, but mismatching lines conflict
+        let diff = "@@ -1 +1,2 @@\n use std::env;\n+use std::io;\n@@ -40,1 +41,1 @@\n-fn run() {}\n+fn run() -> io::Result<()> {}"
+            .as_diff()?;
+
+        let patched = diff.apply(original);
+
+        assert!(patched
+            .content
+            .starts_with("use std::env;\nuse std::io;\n\nfn main"));
+        assert_eq!(patched.conflicts.len(), 1);
+        assert_eq!(patched.conflicts[0].hunk, 2);
+
+        assert!("No changes needed.".as_diff().is_err());
+
+        Ok(())
+    }
+    #[test]
+    fn test_parse_hunks_without_line_numbers() -> Result<()> {
+        let original = "fn main() {\n    run();\n}\n\nfn run() {}\n";
+
+        let diff = "@@ ... @@\n fn main() {\n-    run();\n+    run().unwrap();\n }\n@@ @@\n-fn run() {}\n+fn run() -> Result<()> {\n+    Ok(())\n+}"
+            .as_diff()?;
+
+        assert_eq!(diff.hunks.len(), 2);
+        assert_eq!(diff.hunks[1].old_start, 0);
+
+        let patched = diff.apply(original);
+
+        assert!(patched.conflicts.is_empty());
+        assert_eq!(
+            patched.content,
+            "fn main() {\n    run().unwrap();\n}\n\nfn run() -> Result<()> {\n    Ok(())\n}\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_dashed_lines_within_hunks() -> Result<()> {
+        // Removing a `-- ` SQL comment and adding a `++ ` line look like
+        // file headers, but are part of the hunk until its counts are used up
+        let diff = "--- a/schema.sql\n+++ b/schema.sql\n@@ -1,2 +1,2 @@\n--- users\n+++ accounts\n CREATE TABLE users (id INT);\n--- a/seed.sql\n+++ b/seed.sql\n@@ -1 +1 @@\n-INSERT INTO users VALUES (1);\n+INSERT INTO users VALUES (2);"
+            .as_diff()?;
+
+        assert_eq!(diff.hunks.len(), 2);
+        assert_eq!(
+            diff.hunks[0].lines,
+            vec![
+                HunkLine::Removed(String::from("-- users")),
+                HunkLine::Added(String::from("++ accounts")),
+                HunkLine::Context(String::from("CREATE TABLE users (id INT);")),
+            ]
+        );
+        assert_eq!(diff.hunks[1].lines.len(), 2);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,1158 @@
+use anyhow::anyhow;
+use std::{collections::BTreeSet, fmt};
+
+use super::AsFormat;
+use crate::err::ParseError;
+
+/// Scalars defined by the GraphQL specification.
+const BUILT_IN_SCALARS: [&str; 5] = ["Int", "Float", "String", "Boolean", "ID"];
+
+/// Keywords starting the definitions of a schema.
+const DEFINITION_KEYWORDS: [&str; 9] = [
+    "schema",
+    "scalar",
+    "type",
+    "interface",
+    "union",
+    "enum",
+    "input",
+    "directive",
+    "extend",
+];
+
+/// Trait providing methods for working with GraphQL SDL schemas.
+pub trait AsGraphQl: AsFormat {
+    /// Converts the object to a GraphQL type system.
+    fn as_graphql(&self) -> Result<GraphQl, ParseError>;
+
+    /// Strips the GraphQL formatting and returns the GraphQL type system.
+    fn strip_graphql(&self) -> Result<GraphQl, ParseError>;
+}
+
+impl AsGraphQl for &str {
+    /// Implementation of converting a string slice to a GraphQL type system.
+    fn as_graphql(&self) -> Result<GraphQl, ParseError> {
+        self.as_format(deserialize_graphql)
+    }
+
+    /// Implementation of stripping a GraphQL schema from a string slice.
+    fn strip_graphql(&self) -> Result<GraphQl, ParseError> {
+        self.strip_format(deserialize_graphql, "graphql")
+    }
+}
+
+/// Type system of a GraphQL API, as described by its SDL schema. Extensions
+/// and repeated definitions are merged into the type they define, such that
+/// each type appears once.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GraphQl {
+    /// Root operation types declared by a `schema` definition, if any
+    pub roots: RootTypes,
+    /// Types of the schema, in the order of their first definition
+    pub types: Vec<TypeDef>,
+    /// Custom directives of the schema
+    pub directives: Vec<DirectiveDef>,
+}
+
+/// Root operation types of a `schema` definition. Missing roots default to
+/// the types named `Query`, `Mutation` and `Subscription`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RootTypes {
+    pub query: Option<String>,
+    pub mutation: Option<String>,
+    pub subscription: Option<String>,
+}
+
+/// Kind of the operations of a GraphQL API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationKind {
+    Query,
+    Mutation,
+    Subscription,
+}
+
+impl OperationKind {
+    pub const ALL: [OperationKind; 3] = [
+        OperationKind::Query,
+        OperationKind::Mutation,
+        OperationKind::Subscription,
+    ];
+}
+
+/// Kind of a named type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeKind {
+    Scalar,
+    Object,
+    Interface,
+    Union,
+    Enum,
+    Input,
+}
+
+/// Named type of the schema.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeDef {
+    pub kind: TypeKind,
+    pub name: String,
+    pub description: Option<String>,
+    /// Interfaces implemented by an object or an interface
+    pub interfaces: Vec<String>,
+    /// Directives applied to the type, e.g. `@key(fields: "id")`
+    pub directives: Vec<String>,
+    /// Fields of an object or an interface, or input fields of an input
+    pub fields: Vec<FieldDef>,
+    /// Values of an enum
+    pub values: Vec<EnumValue>,
+    /// Members of a union
+    pub members: Vec<String>,
+}
+
+/// Field of an object or an interface, argument or input field.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldDef {
+    pub name: String,
+    pub description: Option<String>,
+    /// Arguments of the field, always empty for arguments and input fields
+    pub args: Vec<FieldDef>,
+    /// Type reference as written in the schema, e.g. `[User!]!`
+    pub ty: String,
+    /// Default value of an argument or an input field
+    pub default: Option<String>,
+    pub directives: Vec<String>,
+}
+
+/// Value of an enum.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnumValue {
+    pub name: String,
+    pub directives: Vec<String>,
+}
+
+/// Definition of a custom directive.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DirectiveDef {
+    pub name: String,
+    pub description: Option<String>,
+    pub args: Vec<FieldDef>,
+    pub repeatable: bool,
+    /// Locations where the directive applies, e.g. `FIELD_DEFINITION`
+    pub locations: Vec<String>,
+}
+
+impl GraphQl {
+    /// Returns the type `name`, if it is defined.
+    pub fn get(&self, name: &str) -> Option<&TypeDef> {
+        self.types.iter().find(|ty| ty.name == name)
+    }
+
+    /// Returns the root type of the operations of `kind`, if the schema
+    /// supports them.
+    pub fn root(&self, kind: OperationKind) -> Option<&TypeDef> {
+        let declared = match kind {
+            OperationKind::Query => &self.roots.query,
+            OperationKind::Mutation => &self.roots.mutation,
+            OperationKind::Subscription => &self.roots.subscription,
+        };
+
+        let name = declared.clone().unwrap_or_else(|| kind.to_string());
+
+        self.get(&name)
+    }
+
+    /// Returns the operations of `kind`, i.e. the fields of its root type.
+    pub fn operations(&self, kind: OperationKind) -> &[FieldDef] {
+        self.root(kind)
+            .map(|root| root.fields.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Merges the definitions of `other`, e.g. from another file of the
+    /// schema. Definitions of types which already exist extend them.
+    pub fn merge(&mut self, other: GraphQl) {
+        let RootTypes {
+            query,
+            mutation,
+            subscription,
+        } = other.roots;
+
+        self.roots.query = self.roots.query.take().or(query);
+        self.roots.mutation = self.roots.mutation.take().or(mutation);
+        self.roots.subscription =
+            self.roots.subscription.take().or(subscription);
+
+        for ty in other.types {
+            self.add_type(ty);
+        }
+
+        for directive in other.directives {
+            if !self.directives.iter().any(|d| d.name == directive.name) {
+                self.directives.push(directive);
+            }
+        }
+    }
+
+    /// Returns the types reachable from the types `names`, through their
+    /// fields, arguments, interfaces and union members. The implementations
+    /// of the interfaces reached are included as well.
+    pub fn subset(&self, names: &[&str]) -> GraphQl {
+        let mut reached = BTreeSet::new();
+        let mut pending = names
+            .iter()
+            .map(|name| name.to_string())
+            .collect::<Vec<String>>();
+
+        while let Some(name) = pending.pop() {
+            if !reached.insert(name.clone()) {
+                continue;
+            }
+
+            let ty = match self.get(&name) {
+                Some(ty) => ty,
+                None => continue,
+            };
+
+            let fields = ty
+                .fields
+                .iter()
+                .flat_map(|field| std::iter::once(field).chain(&field.args))
+                .map(|field| named_type(&field.ty).to_string());
+
+            pending.extend(fields);
+            pending.extend(ty.interfaces.iter().cloned());
+            pending.extend(ty.members.iter().cloned());
+
+            if ty.kind == TypeKind::Interface {
+                let implementations = self
+                    .types
+                    .iter()
+                    .filter(|other| other.interfaces.contains(&ty.name))
+                    .map(|other| other.name.clone());
+
+                pending.extend(implementations);
+            }
+        }
+
+        let keep = |root: &Option<String>| {
+            root.clone().filter(|root| reached.contains(root))
+        };
+
+        GraphQl {
+            roots: RootTypes {
+                query: keep(&self.roots.query),
+                mutation: keep(&self.roots.mutation),
+                subscription: keep(&self.roots.subscription),
+            },
+            types: self
+                .types
+                .iter()
+                .filter(|ty| reached.contains(&ty.name))
+                .cloned()
+                .collect(),
+            directives: self.directives.clone(),
+        }
+    }
+
+    fn add_type(&mut self, ty: TypeDef) {
+        let existing = match self.types.iter_mut().find(|t| t.name == ty.name) {
+            Some(existing) => existing,
+            None => {
+                self.types.push(ty);
+                return;
+            }
+        };
+
+        existing.description = existing.description.take().or(ty.description);
+
+        extend_unique(&mut existing.interfaces, ty.interfaces);
+        extend_unique(&mut existing.directives, ty.directives);
+        extend_unique(&mut existing.members, ty.members);
+
+        for field in ty.fields {
+            if !existing.fields.iter().any(|f| f.name == field.name) {
+                existing.fields.push(field);
+            }
+        }
+
+        for value in ty.values {
+            if !existing.values.iter().any(|v| v.name == value.name) {
+                existing.values.push(value);
+            }
+        }
+    }
+}
+
+impl TypeKind {
+    /// Keyword defining the types of this kind.
+    pub fn keyword(&self) -> &str {
+        match self {
+            TypeKind::Scalar => "scalar",
+            TypeKind::Object => "type",
+            TypeKind::Interface => "interface",
+            TypeKind::Union => "union",
+            TypeKind::Enum => "enum",
+            TypeKind::Input => "input",
+        }
+    }
+}
+
+/// Returns the named type of a type reference, e.g. `User` for `[User!]!`.
+pub fn named_type(ty: &str) -> &str {
+    ty.trim_matches(|c: char| matches!(c, '[' | ']' | '!') || c.is_whitespace())
+}
+
+/// Whether `name` is one of the scalars defined by the specification.
+pub fn is_built_in_scalar(name: &str) -> bool {
+    BUILT_IN_SCALARS.contains(&name)
+}
+
+fn extend_unique(items: &mut Vec<String>, others: Vec<String>) {
+    for other in others {
+        if !items.contains(&other) {
+            items.push(other);
+        }
+    }
+}
+
+impl fmt::Display for OperationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            OperationKind::Query => "Query",
+            OperationKind::Mutation => "Mutation",
+            OperationKind::Subscription => "Subscription",
+        };
+
+        f.write_str(name)
+    }
+}
+
+/// Renders the schema in SDL, without descriptions and comments, such that
+/// it summarises the type system.
+impl fmt::Display for GraphQl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut definitions = Vec::new();
+
+        let roots = [
+            ("query", &self.roots.query),
+            ("mutation", &self.roots.mutation),
+            ("subscription", &self.roots.subscription),
+        ];
+
+        let roots = roots
+            .iter()
+            .filter_map(|(operation, root)| {
+                root.as_ref()
+                    .map(|root| format!("  {}: {}", operation, root))
+            })
+            .collect::<Vec<String>>();
+
+        if !roots.is_empty() {
+            definitions.push(format!("schema {{\n{}\n}}", roots.join("\n")));
+        }
+
+        definitions.extend(self.directives.iter().map(|d| d.to_string()));
+        definitions.extend(self.types.iter().map(|ty| ty.to_string()));
+
+        write!(f, "{}", definitions.join("\n\n"))
+    }
+}
+
+impl fmt::Display for TypeDef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.kind.keyword(), self.name)?;
+
+        if !self.interfaces.is_empty() {
+            write!(f, " implements {}", self.interfaces.join(" & "))?;
+        }
+
+        for directive in self.directives.iter() {
+            write!(f, " {}", directive)?;
+        }
+
+        let body = match self.kind {
+            TypeKind::Union if !self.members.is_empty() => {
+                return write!(f, " = {}", self.members.join(" | "));
+            }
+            TypeKind::Enum => self
+                .values
+                .iter()
+                .map(|value| with_directives(&value.name, &value.directives))
+                .collect::<Vec<String>>(),
+            _ => self.fields.iter().map(|field| field.to_string()).collect(),
+        };
+
+        if body.is_empty() {
+            return Ok(());
+        }
+
+        write!(f, " {{\n  {}\n}}", body.join("\n  "))
+    }
+}
+
+impl fmt::Display for FieldDef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+
+        if !self.args.is_empty() {
+            let args = self
+                .args
+                .iter()
+                .map(|arg| arg.to_string())
+                .collect::<Vec<String>>();
+
+            write!(f, "({})", args.join(", "))?;
+        }
+
+        write!(f, ": {}", self.ty)?;
+
+        if let Some(default) = &self.default {
+            write!(f, " = {}", default)?;
+        }
+
+        for directive in self.directives.iter() {
+            write!(f, " {}", directive)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for DirectiveDef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "directive @{}", self.name)?;
+
+        if !self.args.is_empty() {
+            let args = self
+                .args
+                .iter()
+                .map(|arg| arg.to_string())
+                .collect::<Vec<String>>();
+
+            write!(f, "({})", args.join(", "))?;
+        }
+
+        if self.repeatable {
+            write!(f, " repeatable")?;
+        }
+
+        write!(f, " on {}", self.locations.join(" | "))
+    }
+}
+
+fn with_directives(name: &str, directives: &[String]) -> String {
+    std::iter::once(name)
+        .chain(directives.iter().map(String::as_str))
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+/// Lexical token of a GraphQL document.
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Name(String),
+    Punctuator(char),
+    Spread,
+    Str(String),
+    Number(String),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Name(name) => write!(f, "`{}`", name),
+            Token::Punctuator(c) => write!(f, "`{}`", c),
+            Token::Spread => write!(f, "`...`"),
+            Token::Str(_) => write!(f, "string"),
+            Token::Number(number) => write!(f, "`{}`", number),
+        }
+    }
+}
+
+/// Splits a GraphQL document into tokens, leaving out whitespace, commas
+/// and comments.
+fn tokenize(document: &str) -> Result<Vec<Token>, ParseError> {
+    let chars = document.chars().collect::<Vec<char>>();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    let starts_with = |at: usize, pattern: &str| {
+        pattern
+            .chars()
+            .enumerate()
+            .all(|(j, c)| chars.get(at + j) == Some(&c))
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c.is_whitespace() || c == ',' || c == '\u{feff}' {
+            i += 1;
+            continue;
+        }
+
+        if c == '#' {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            continue;
+        }
+
+        if starts_with(i, "\"\"\"") {
+            let start = i + 3;
+            let mut end = start;
+
+            while end < chars.len() && !starts_with(end, "\"\"\"") {
+                end += if starts_with(end, "\\\"\"\"") { 4 } else { 1 };
+            }
+
+            if end >= chars.len() {
+                return Err(ParseError::from(anyhow!(
+                    "Unterminated block string in the GraphQL schema"
+                )));
+            }
+
+            let block = chars[start..end]
+                .iter()
+                .collect::<String>()
+                .replace("\\\"\"\"", "\"\"\"");
+
+            let lines = block
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .collect::<Vec<&str>>();
+
+            tokens.push(Token::Str(lines.join("\n")));
+            i = end + 3;
+            continue;
+        }
+
+        if c == '"' {
+            let mut value = String::new();
+            i += 1;
+
+            loop {
+                match chars.get(i) {
+                    Some('"') => break,
+                    Some('\\') => {
+                        let escaped = match chars.get(i + 1) {
+                            Some('n') => '\n',
+                            Some('t') => '\t',
+                            Some('r') => '\r',
+                            Some(other) => *other,
+                            None => break,
+                        };
+                        value.push(escaped);
+                        i += 2;
+                    }
+                    Some('\n') | None => {
+                        return Err(ParseError::from(anyhow!(
+                            "Unterminated string in the GraphQL schema"
+                        )))
+                    }
+                    Some(other) => {
+                        value.push(*other);
+                        i += 1;
+                    }
+                }
+            }
+
+            tokens.push(Token::Str(value));
+            i += 1;
+            continue;
+        }
+
+        if starts_with(i, "...") {
+            tokens.push(Token::Spread);
+            i += 3;
+            continue;
+        }
+
+        if c == '_' || c.is_ascii_alphabetic() {
+            let start = i;
+
+            while i < chars.len()
+                && (chars[i] == '_' || chars[i].is_ascii_alphanumeric())
+            {
+                i += 1;
+            }
+
+            tokens.push(Token::Name(chars[start..i].iter().collect()));
+            continue;
+        }
+
+        if c == '-' || c.is_ascii_digit() {
+            let start = i;
+            i += 1;
+
+            while i < chars.len()
+                && (chars[i].is_ascii_alphanumeric()
+                    || matches!(chars[i], '.' | '+' | '-'))
+            {
+                i += 1;
+            }
+
+            tokens.push(Token::Number(chars[start..i].iter().collect()));
+            continue;
+        }
+
+        if "!$&()/:=@[]{}|".contains(c) {
+            tokens.push(Token::Punctuator(c));
+            i += 1;
+            continue;
+        }
+
+        return Err(ParseError::from(anyhow!(
+            "Unexpected character `{}` in the GraphQL schema",
+            c
+        )));
+    }
+
+    Ok(tokens)
+}
+
+/// Recursive descent parser of the type system definitions.
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Result<Token, ParseError> {
+        let token =
+            self.tokens.get(self.position).cloned().ok_or_else(|| {
+                ParseError::from(anyhow!(
+                    "Unexpected end of the GraphQL schema"
+                ))
+            })?;
+
+        self.position += 1;
+
+        Ok(token)
+    }
+
+    fn unexpected(token: &Token) -> ParseError {
+        ParseError::from(anyhow!("Unexpected {} in the GraphQL schema", token))
+    }
+
+    fn is_punctuator(&self, c: char) -> bool {
+        self.peek() == Some(&Token::Punctuator(c))
+    }
+
+    fn is_name(&self, name: &str) -> bool {
+        matches!(self.peek(), Some(Token::Name(n)) if n == name)
+    }
+
+    /// Consumes the punctuator `c` if it is next.
+    fn eat(&mut self, c: char) -> bool {
+        let found = self.is_punctuator(c);
+
+        if found {
+            self.position += 1;
+        }
+
+        found
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), ParseError> {
+        match self.next()? {
+            Token::Punctuator(p) if p == c => Ok(()),
+            token => Err(Self::unexpected(&token)),
+        }
+    }
+
+    fn name(&mut self) -> Result<String, ParseError> {
+        match self.next()? {
+            Token::Name(name) => Ok(name),
+            token => Err(Self::unexpected(&token)),
+        }
+    }
+
+    fn description(&mut self) -> Option<String> {
+        match self.peek() {
+            Some(Token::Str(description)) => {
+                let description = description.clone();
+                self.position += 1;
+                Some(description)
+            }
+            _ => None,
+        }
+    }
+
+    /// Parses the definitions of the document into `graphql`.
+    fn definitions(&mut self, graphql: &mut GraphQl) -> Result<(), ParseError> {
+        while self.peek().is_some() {
+            let description = self.description();
+
+            // Extensions are parsed as definitions, and merged when added
+            if self.is_name("extend") {
+                self.position += 1;
+            }
+
+            let keyword = match self.next()? {
+                Token::Name(keyword) => keyword,
+                Token::Punctuator('{') => {
+                    return Err(ParseError::from(anyhow!(
+                        "Operations are not part of a GraphQL schema"
+                    )))
+                }
+                token => return Err(Self::unexpected(&token)),
+            };
+
+            let kind = match keyword.as_str() {
+                "schema" => {
+                    self.schema(graphql)?;
+                    continue;
+                }
+                "directive" => {
+                    let directive = self.directive_definition(description)?;
+                    graphql.merge(GraphQl {
+                        directives: vec![directive],
+                        ..Default::default()
+                    });
+                    continue;
+                }
+                "scalar" => TypeKind::Scalar,
+                "type" => TypeKind::Object,
+                "interface" => TypeKind::Interface,
+                "union" => TypeKind::Union,
+                "enum" => TypeKind::Enum,
+                "input" => TypeKind::Input,
+                "query" | "mutation" | "subscription" | "fragment" => {
+                    return Err(ParseError::from(anyhow!(
+                        "Operations are not part of a GraphQL schema"
+                    )))
+                }
+                _ => return Err(Self::unexpected(&Token::Name(keyword))),
+            };
+
+            let ty = self.type_definition(kind, description)?;
+            graphql.add_type(ty);
+        }
+
+        Ok(())
+    }
+
+    fn schema(&mut self, graphql: &mut GraphQl) -> Result<(), ParseError> {
+        self.directives()?;
+
+        if !self.eat('{') {
+            return Ok(());
+        }
+
+        while !self.eat('}') {
+            let operation = self.name()?;
+            self.expect(':')?;
+            let root = Some(self.name()?);
+
+            match operation.as_str() {
+                "query" => graphql.roots.query = root,
+                "mutation" => graphql.roots.mutation = root,
+                "subscription" => graphql.roots.subscription = root,
+                _ => return Err(Self::unexpected(&Token::Name(operation))),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn type_definition(
+        &mut self,
+        kind: TypeKind,
+        description: Option<String>,
+    ) -> Result<TypeDef, ParseError> {
+        let mut ty = TypeDef {
+            kind,
+            name: self.name()?,
+            description,
+            interfaces: Vec::new(),
+            directives: Vec::new(),
+            fields: Vec::new(),
+            values: Vec::new(),
+            members: Vec::new(),
+        };
+
+        if matches!(kind, TypeKind::Object | TypeKind::Interface)
+            && self.is_name("implements")
+        {
+            self.position += 1;
+            ty.interfaces = self.implements()?;
+        }
+
+        ty.directives = self.directives()?;
+
+        match kind {
+            TypeKind::Object | TypeKind::Interface if self.eat('{') => {
+                ty.fields = self.fields()?;
+            }
+            TypeKind::Input if self.eat('{') => {
+                ty.fields = self.input_values('}')?;
+            }
+            TypeKind::Enum if self.eat('{') => {
+                while !self.eat('}') {
+                    self.description();
+
+                    ty.values.push(EnumValue {
+                        name: self.name()?,
+                        directives: self.directives()?,
+                    });
+                }
+            }
+            TypeKind::Union if self.eat('=') => {
+                self.eat('|');
+                ty.members.push(self.name()?);
+
+                while self.eat('|') {
+                    ty.members.push(self.name()?);
+                }
+            }
+            _ => {}
+        }
+
+        Ok(ty)
+    }
+
+    /// Parses the interfaces after `implements`, separated by `&` or, in
+    /// older schemas, by whitespace.
+    fn implements(&mut self) -> Result<Vec<String>, ParseError> {
+        self.eat('&');
+
+        let mut interfaces = vec![self.name()?];
+
+        loop {
+            let separated = self.eat('&');
+
+            match self.peek() {
+                Some(Token::Name(name))
+                    if separated
+                        || !DEFINITION_KEYWORDS.contains(&name.as_str()) =>
+                {
+                    interfaces.push(self.name()?)
+                }
+                _ => break,
+            }
+        }
+
+        Ok(interfaces)
+    }
+
+    fn directive_definition(
+        &mut self,
+        description: Option<String>,
+    ) -> Result<DirectiveDef, ParseError> {
+        self.expect('@')?;
+
+        let name = self.name()?;
+
+        let args = match self.eat('(') {
+            true => self.input_values(')')?,
+            false => Vec::new(),
+        };
+
+        let repeatable = self.is_name("repeatable");
+
+        if repeatable {
+            self.position += 1;
+        }
+
+        match self.name()?.as_str() {
+            "on" => {}
+            other => return Err(Self::unexpected(&Token::Name(other.into()))),
+        }
+
+        self.eat('|');
+
+        let mut locations = vec![self.name()?];
+
+        while self.eat('|') {
+            locations.push(self.name()?);
+        }
+
+        Ok(DirectiveDef {
+            name,
+            description,
+            args,
+            repeatable,
+            locations,
+        })
+    }
+
+    /// Parses the fields of an object or an interface, after the `{`.
+    fn fields(&mut self) -> Result<Vec<FieldDef>, ParseError> {
+        let mut fields = Vec::new();
+
+        while !self.eat('}') {
+            let description = self.description();
+            let name = self.name()?;
+
+            let args = match self.eat('(') {
+                true => self.input_values(')')?,
+                false => Vec::new(),
+            };
+
+            self.expect(':')?;
+
+            fields.push(FieldDef {
+                name,
+                description,
+                args,
+                ty: self.type_reference()?,
+                default: None,
+                directives: self.directives()?,
+            });
+        }
+
+        Ok(fields)
+    }
+
+    /// Parses arguments or input fields until `close`.
+    fn input_values(
+        &mut self,
+        close: char,
+    ) -> Result<Vec<FieldDef>, ParseError> {
+        let mut values = Vec::new();
+
+        while !self.eat(close) {
+            let description = self.description();
+            let name = self.name()?;
+
+            self.expect(':')?;
+
+            let ty = self.type_reference()?;
+
+            let default = match self.eat('=') {
+                true => Some(self.value()?),
+                false => None,
+            };
+
+            values.push(FieldDef {
+                name,
+                description,
+                args: Vec::new(),
+                ty,
+                default,
+                directives: self.directives()?,
+            });
+        }
+
+        Ok(values)
+    }
+
+    fn type_reference(&mut self) -> Result<String, ParseError> {
+        let mut ty = match self.eat('[') {
+            true => {
+                let item = self.type_reference()?;
+                self.expect(']')?;
+                format!("[{}]", item)
+            }
+            false => self.name()?,
+        };
+
+        if self.eat('!') {
+            ty.push('!');
+        }
+
+        Ok(ty)
+    }
+
+    /// Parses the directives applied to a definition, as written in SDL.
+    fn directives(&mut self) -> Result<Vec<String>, ParseError> {
+        let mut directives = Vec::new();
+
+        while self.eat('@') {
+            let mut directive = format!("@{}", self.name()?);
+
+            if self.eat('(') {
+                let mut args = Vec::new();
+
+                while !self.eat(')') {
+                    let name = self.name()?;
+                    self.expect(':')?;
+                    args.push(format!("{}: {}", name, self.value()?));
+                }
+
+                directive.push_str(&format!("({})", args.join(", ")));
+            }
+
+            directives.push(directive);
+        }
+
+        Ok(directives)
+    }
+
+    /// Parses a constant or variable value, as written in SDL.
+    fn value(&mut self) -> Result<String, ParseError> {
+        let value = match self.next()? {
+            Token::Name(name) => name,
+            Token::Number(number) => number,
+            Token::Str(value) => quote(&value),
+            Token::Punctuator('$') => format!("${}", self.name()?),
+            Token::Punctuator('[') => {
+                let mut items = Vec::new();
+
+                while !self.eat(']') {
+                    items.push(self.value()?);
+                }
+
+                format!("[{}]", items.join(", "))
+            }
+            Token::Punctuator('{') => {
+                let mut fields = Vec::new();
+
+                while !self.eat('}') {
+                    let name = self.name()?;
+                    self.expect(':')?;
+                    fields.push(format!("{}: {}", name, self.value()?));
+                }
+
+                format!("{{{}}}", fields.join(", "))
+            }
+            token => return Err(Self::unexpected(&token)),
+        };
+
+        Ok(value)
+    }
+}
+
+fn quote(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+
+    format!("\"{}\"", escaped)
+}
+
+/// Function to deserialize a GraphQL SDL string into a `GraphQl` struct.
+///
+/// # Arguments
+/// * `sdl_str` - The SDL schema to be deserialized.
+///
+/// # Returns
+/// * A `Result` containing a `GraphQl` struct if successful, or a `ParseError` if an error occurred.
+fn deserialize_graphql(sdl_str: &str) -> Result<GraphQl, ParseError> {
+    let mut parser = Parser {
+        tokens: tokenize(sdl_str)?,
+        position: 0,
+    };
+
+    let mut graphql = GraphQl::default();
+
+    parser.definitions(&mut graphql)?;
+
+    let is_empty = graphql.types.is_empty()
+        && graphql.directives.is_empty()
+        && graphql.roots == RootTypes::default();
+
+    if is_empty {
+        return Err(ParseError::from(anyhow!(
+            "Unable to find any definition in the GraphQL schema"
+        )));
+    }
+
+    Ok(graphql)
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+
+    use super::*;
+
+    const SCHEMA: &str = r#"
+"""
+Entry point of the API
+"""
+type Query {
+  "Finds a user"
+  user(id: ID!): User
+  users(first: Int = 10, role: Role): [User!]!
+}
+
+type Mutation {
+  createUser(input: NewUser!): User! @auth(requires: ADMIN)
+}
+
+interface Node {
+  id: ID!
+}
+
+type User implements Node @key(fields: "id") {
+  id: ID!
+  name: String
+  # Paginated posts
+  posts(first: Int, after: String): [Post!]!
+}
+
+type Post implements Node { id: ID! title: String! }
+
+enum Role { ADMIN USER @deprecated(reason: "Use ADMIN") }
+
+input NewUser {
+  name: String!
+  role: Role = USER
+}
+
+union SearchResult = | User | Post
+
+scalar DateTime
+
+directive @auth(requires: Role = ADMIN) on OBJECT | FIELD_DEFINITION
+"#;
+
+    #[test]
+    fn test_parse_schema() -> Result<()> {
+        let mut graphql = SCHEMA.as_graphql()?;
+
+        assert_eq!(graphql.types.len(), 9);
+        assert_eq!(graphql.directives.len(), 1);
+
+        let users = &graphql.operations(OperationKind::Query)[1];
+        assert_eq!(users.ty, "[User!]!");
+        assert_eq!(users.args[0].default.as_deref(), Some("10"));
+        assert_eq!(named_type(&users.ty), "User");
+
+        assert!(graphql.root(OperationKind::Subscription).is_none());
+
+        // Extensions in other files are merged into the types they extend
+        let extension = "extend type Query {\n  user(id: ID!): User\n  search(text: String!): [SearchResult!]!\n}\n\ntype Subscription { userCreated: User! }";
+        graphql.merge(extension.as_graphql()?);
+
+        assert_eq!(graphql.operations(OperationKind::Query).len(), 3);
+        assert_eq!(graphql.types.len(), 10);
+
+        let summary = graphql.to_string();
+
+        assert!(summary.contains(
+            "type User implements Node @key(fields: \"id\") {\n  id: ID!\n  name: String\n  posts(first: Int, after: String): [Post!]!\n}"
+        ));
+        assert!(summary.contains("USER @deprecated(reason: \"Use ADMIN\")"));
+        assert!(summary.contains("union SearchResult = User | Post"));
+        assert!(!summary.contains("Finds a user"));
+
+        // The summary is itself a valid schema
+        assert_eq!(summary.as_str().as_graphql()?.to_string(), summary);
+
+        // Mutations only reach the types of their fields and arguments
+        let mutations = graphql.subset(&["Mutation"]);
+        let names = mutations
+            .types
+            .iter()
+            .map(|ty| ty.name.as_str())
+            .collect::<Vec<&str>>();
+
+        assert_eq!(
+            names,
+            ["Mutation", "Node", "User", "Post", "Role", "NewUser"]
+        );
+
+        assert!("query { users { id } }".as_graphql().is_err());
+        assert!("# Nothing yet".as_graphql().is_err());
+
+        Ok(())
+    }
+}
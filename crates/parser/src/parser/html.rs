@@ -11,6 +11,13 @@ pub trait AsHtml: AsFormat {
     /// - `Result<Dom, ParseError>`: The parsed DOM or a parse error.
     fn as_html(&self) -> Result<Dom, ParseError>;
 
+    /// Parse the complete string as an HTML fragment, i.e. markup without
+    /// the doctype and the `html` element of a document.
+    ///
+    /// # Returns
+    /// - `Result<Dom, ParseError>`: The parsed DOM or a parse error.
+    fn as_html_fragment(&self) -> Result<Dom, ParseError>;
+
     /// Extract and parse an HTML segment encapsulated by a specific format (e.g. "```html") within the string.
     ///
     /// # Returns
@@ -24,11 +31,15 @@ pub trait AsHtml: AsFormat {
     fn strip_htmls(&self) -> Result<Vec<Dom>, ParseError>;
 }
 
-impl<'a> AsHtml for &'a str {
+impl AsHtml for &str {
     fn as_html(&self) -> Result<Dom, ParseError> {
         self.as_format(deserialize_html)
     }
 
+    fn as_html_fragment(&self) -> Result<Dom, ParseError> {
+        self.as_format(deserialize_html_fragment)
+    }
+
     fn strip_html(&self) -> Result<Dom, ParseError> {
         self.strip_format(deserialize_html, "html")
     }
@@ -60,3 +71,17 @@ fn deserialize_html(html_str: &str) -> Result<Dom, ParseError> {
         html: Html::parse_document(html_str),
     })
 }
+
+/// Deserialize an HTML fragment into a `Dom` object.
+///
+/// # Arguments
+/// - `html_str`: A reference to the HTML fragment.
+///
+/// # Returns
+/// - `Result<Dom, ParseError>`: The parsed DOM or a parse error.
+fn deserialize_html_fragment(html_str: &str) -> Result<Dom, ParseError> {
+    Ok(Dom {
+        raw: html_str.to_string(),
+        html: Html::parse_fragment(html_str),
+    })
+}
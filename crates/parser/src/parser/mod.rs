@@ -3,17 +3,20 @@ use std::fmt::Debug;
 
 use crate::err::ParseError;
 
+pub mod diff;
+pub mod graphql;
 pub mod json;
+pub mod proto;
 pub mod yaml;
-#[cfg(feature = "full")]
+#[cfg(feature = "csv")]
 pub mod csv;
-#[cfg(feature = "full")]
+#[cfg(feature = "html")]
 pub mod html;
-#[cfg(feature = "full")]
+#[cfg(feature = "python")]
 pub mod python;
-#[cfg(feature = "full")]
+#[cfg(feature = "rust")]
 pub mod rust;
-#[cfg(feature = "full")]
+#[cfg(feature = "sql")]
 pub mod sql;
 
 /// A supertrait defining methods to convert LLM string outputs into various Rust
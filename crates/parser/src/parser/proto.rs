@@ -0,0 +1,1133 @@
+use anyhow::anyhow;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+use super::AsFormat;
+use crate::err::ParseError;
+
+/// Trait providing methods for working with Protocol Buffers schemas.
+pub trait AsProto: AsFormat {
+    /// Converts the object to a `.proto` file definition.
+    fn as_proto(&self) -> Result<Proto, ParseError>;
+
+    /// Strips the protobuf formatting and returns the `.proto` file definition.
+    fn strip_proto(&self) -> Result<Proto, ParseError>;
+}
+
+impl AsProto for &str {
+    /// Implementation of converting a string slice to a `.proto` file.
+    fn as_proto(&self) -> Result<Proto, ParseError> {
+        self.as_format(deserialize_proto)
+    }
+
+    /// Implementation of stripping a `.proto` file from a string slice.
+    fn strip_proto(&self) -> Result<Proto, ParseError> {
+        self.strip_format(deserialize_proto, "protobuf")
+    }
+}
+
+/// Definitions of a `.proto` file. Options, reserved ranges and extensions
+/// are left out, as they do not change the shape of the messages.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Proto {
+    /// Syntax of the file, e.g. `proto3`
+    pub syntax: Option<String>,
+    pub package: Option<String>,
+    /// Paths of the files imported, e.g. `google/protobuf/empty.proto`
+    pub imports: Vec<String>,
+    pub messages: Vec<Message>,
+    pub enums: Vec<Enum>,
+    pub services: Vec<Service>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    pub name: String,
+    pub fields: Vec<Field>,
+    pub oneofs: Vec<Oneof>,
+    /// Messages nested in the message
+    pub messages: Vec<Message>,
+    /// Enums nested in the message
+    pub enums: Vec<Enum>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    pub name: String,
+    /// Type as written in the schema, e.g. `string`, `.foo.Bar` or
+    /// `map<string, Bar>`
+    pub ty: String,
+    pub number: String,
+    /// Label of the field, i.e. `repeated`, `optional` or `required`
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Oneof {
+    pub name: String,
+    pub fields: Vec<Field>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Enum {
+    pub name: String,
+    /// Values of the enum, along with their number
+    pub values: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Service {
+    pub name: String,
+    pub rpcs: Vec<Rpc>,
+}
+
+/// Method of a service.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rpc {
+    pub name: String,
+    pub input: String,
+    pub output: String,
+    /// Whether the client sends a stream of `input` messages
+    pub client_streaming: bool,
+    /// Whether the server answers with a stream of `output` messages
+    pub server_streaming: bool,
+}
+
+/// Message or enum of a `ProtoSet`, along with the file defining it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Definition<'a> {
+    Message(&'a str, &'a Message),
+    Enum(&'a str, &'a Enum),
+}
+
+/// Schema split into several `.proto` files, whose definitions refer to
+/// each other through imports.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProtoSet {
+    /// Files of the schema, keyed by path
+    pub files: BTreeMap<String, Proto>,
+}
+
+impl Message {
+    /// Fields of the message, including the fields of its oneofs.
+    pub fn all_fields(&self) -> impl Iterator<Item = &Field> {
+        self.fields
+            .iter()
+            .chain(self.oneofs.iter().flat_map(|oneof| oneof.fields.iter()))
+    }
+}
+
+impl Field {
+    /// Types referenced by the field, i.e. the key and value of maps.
+    pub fn types(&self) -> Vec<&str> {
+        match self
+            .ty
+            .strip_prefix("map<")
+            .and_then(|ty| ty.strip_suffix('>'))
+        {
+            Some(entry) => entry.split(',').map(str::trim).collect(),
+            None => vec![self.ty.as_str()],
+        }
+    }
+}
+
+impl ProtoSet {
+    pub fn new(files: BTreeMap<String, Proto>) -> Self {
+        ProtoSet { files }
+    }
+
+    /// Services of all the files, along with the path of their file.
+    pub fn services(&self) -> Vec<(&str, &Service)> {
+        self.files
+            .iter()
+            .flat_map(|(path, proto)| {
+                proto
+                    .services
+                    .iter()
+                    .map(move |service| (path.as_str(), service))
+            })
+            .collect()
+    }
+
+    /// Imports which are neither part of the set nor well-known types of
+    /// the `google/protobuf` folder. Files are matched by the end of their
+    /// path, as the schemas are often named after their file only.
+    pub fn missing_imports(&self) -> Vec<String> {
+        let mut missing = Vec::new();
+
+        for import in self.files.values().flat_map(|proto| &proto.imports) {
+            let is_known = import.starts_with("google/protobuf/")
+                || self.files.keys().any(|path| {
+                    path.ends_with(import.as_str())
+                        || import.ends_with(path.as_str())
+                });
+
+            if !is_known && !missing.contains(import) {
+                missing.push(import.clone());
+            }
+        }
+
+        missing
+    }
+
+    /// Definitions of all the files, keyed by their fully qualified name.
+    pub fn definitions(&self) -> BTreeMap<String, Definition<'_>> {
+        fn add<'a>(
+            path: &'a str,
+            prefix: &str,
+            message: &'a Message,
+            definitions: &mut BTreeMap<String, Definition<'a>>,
+        ) {
+            let name = join(prefix, &message.name);
+
+            for nested in message.messages.iter() {
+                add(path, &name, nested, definitions);
+            }
+
+            for nested in message.enums.iter() {
+                definitions.insert(
+                    join(&name, &nested.name),
+                    Definition::Enum(path, nested),
+                );
+            }
+
+            definitions.insert(name, Definition::Message(path, message));
+        }
+
+        let mut definitions = BTreeMap::new();
+
+        for (path, proto) in self.files.iter() {
+            let package = proto.package.clone().unwrap_or_default();
+
+            for message in proto.messages.iter() {
+                add(path, &package, message, &mut definitions);
+            }
+
+            for en in proto.enums.iter() {
+                definitions.insert(
+                    join(&package, &en.name),
+                    Definition::Enum(path, en),
+                );
+            }
+        }
+
+        definitions
+    }
+
+    /// Resolves the type `name` referenced from the scope `scope`, e.g.
+    /// `foo.Outer` for the fields of the message `Outer` of the package
+    /// `foo`, following the scoping rules of protobuf. Returns the fully
+    /// qualified name of the type if it is defined in the set.
+    pub fn resolve(
+        &self,
+        definitions: &BTreeMap<String, Definition<'_>>,
+        scope: &str,
+        name: &str,
+    ) -> Option<String> {
+        if let Some(name) = name.strip_prefix('.') {
+            return definitions.contains_key(name).then(|| name.to_string());
+        }
+
+        let mut scope = scope.to_string();
+
+        loop {
+            let candidate = join(&scope, name);
+
+            if definitions.contains_key(&candidate) {
+                return Some(candidate);
+            }
+
+            match scope.rfind('.') {
+                Some(position) => scope.truncate(position),
+                None if !scope.is_empty() => scope.clear(),
+                None => return None,
+            }
+        }
+    }
+
+    /// Summary of the service `name`, along with the messages and enums
+    /// reachable from its methods, across files. Returns `None` if the set
+    /// has no such service.
+    pub fn service_summary(&self, name: &str) -> Option<String> {
+        let (service_path, service) = self
+            .services()
+            .into_iter()
+            .find(|(_, service)| service.name == name)?;
+
+        let proto = &self.files[service_path];
+        let definitions = self.definitions();
+        let scope = proto.package.clone().unwrap_or_default();
+
+        let mut reached = BTreeSet::new();
+        let mut pending = service
+            .rpcs
+            .iter()
+            .flat_map(|rpc| [rpc.input.as_str(), rpc.output.as_str()])
+            .filter_map(|ty| self.resolve(&definitions, &scope, ty))
+            .collect::<Vec<String>>();
+
+        while let Some(name) = pending.pop() {
+            if !reached.insert(name.clone()) {
+                continue;
+            }
+
+            if let Some(Definition::Message(_, message)) =
+                definitions.get(&name)
+            {
+                let types = message.all_fields().flat_map(Field::types);
+
+                pending
+                    .extend(types.filter_map(|ty| {
+                        self.resolve(&definitions, &name, ty)
+                    }));
+            }
+        }
+
+        let mut blocks = vec![package_header(proto), service.to_string()];
+
+        // Nested definitions are rendered within their top-level message
+        for (path, other) in self.files.iter() {
+            let package = other.package.clone().unwrap_or_default();
+
+            let is_reached = |top: &str| {
+                let top = join(&package, top);
+                reached.iter().any(|name| {
+                    *name == top || name.starts_with(&format!("{}.", top))
+                })
+            };
+
+            let mut definitions = other
+                .enums
+                .iter()
+                .filter(|en| is_reached(&en.name))
+                .map(|en| en.to_string())
+                .chain(
+                    other
+                        .messages
+                        .iter()
+                        .filter(|message| is_reached(&message.name))
+                        .map(|message| message.to_string()),
+                )
+                .collect::<Vec<String>>();
+
+            if definitions.is_empty() {
+                continue;
+            }
+
+            if path != service_path {
+                blocks.push(package_header(other));
+            }
+
+            blocks.append(&mut definitions);
+        }
+
+        Some(blocks.join("\n\n"))
+    }
+}
+
+fn join(prefix: &str, name: &str) -> String {
+    match prefix.is_empty() {
+        true => String::from(name),
+        false => format!("{}.{}", prefix, name),
+    }
+}
+
+fn package_header(proto: &Proto) -> String {
+    match &proto.package {
+        Some(package) => format!("package {};", package),
+        None => String::from("// No package"),
+    }
+}
+
+fn indent(block: &str) -> String {
+    block
+        .lines()
+        .map(|line| format!("  {}", line))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Renders the definitions of the file, without comments and options, such
+/// that it summarises the schema.
+impl fmt::Display for Proto {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut blocks = Vec::new();
+
+        let mut header = Vec::new();
+
+        if let Some(syntax) = &self.syntax {
+            header.push(format!("syntax = \"{}\";", syntax));
+        }
+
+        if self.package.is_some() {
+            header.push(package_header(self));
+        }
+
+        for import in self.imports.iter() {
+            header.push(format!("import \"{}\";", import));
+        }
+
+        if !header.is_empty() {
+            blocks.push(header.join("\n"));
+        }
+
+        blocks.extend(self.enums.iter().map(|en| en.to_string()));
+        blocks.extend(self.messages.iter().map(|message| message.to_string()));
+        blocks.extend(self.services.iter().map(|service| service.to_string()));
+
+        write!(f, "{}", blocks.join("\n\n"))
+    }
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut body = Vec::new();
+
+        body.extend(self.enums.iter().map(|en| en.to_string()));
+        body.extend(self.messages.iter().map(|message| message.to_string()));
+        body.extend(self.fields.iter().map(|field| field.to_string()));
+
+        for oneof in self.oneofs.iter() {
+            let fields = oneof
+                .fields
+                .iter()
+                .map(|field| field.to_string())
+                .collect::<Vec<String>>();
+
+            body.push(format!(
+                "oneof {} {{\n{}\n}}",
+                oneof.name,
+                indent(&fields.join("\n"))
+            ));
+        }
+
+        match body.is_empty() {
+            true => write!(f, "message {} {{}}", self.name),
+            false => write!(
+                f,
+                "message {} {{\n{}\n}}",
+                self.name,
+                indent(&body.join("\n"))
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(label) = &self.label {
+            write!(f, "{} ", label)?;
+        }
+
+        write!(f, "{} {} = {};", self.ty, self.name, self.number)
+    }
+}
+
+impl fmt::Display for Enum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let values = self
+            .values
+            .iter()
+            .map(|(name, number)| format!("{} = {};", name, number))
+            .collect::<Vec<String>>();
+
+        write!(
+            f,
+            "enum {} {{\n{}\n}}",
+            self.name,
+            indent(&values.join("\n"))
+        )
+    }
+}
+
+impl fmt::Display for Service {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rpcs = self
+            .rpcs
+            .iter()
+            .map(|rpc| rpc.to_string())
+            .collect::<Vec<String>>();
+
+        write!(
+            f,
+            "service {} {{\n{}\n}}",
+            self.name,
+            indent(&rpcs.join("\n"))
+        )
+    }
+}
+
+impl fmt::Display for Rpc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stream = |streaming: bool| match streaming {
+            true => "stream ",
+            false => "",
+        };
+
+        write!(
+            f,
+            "rpc {}({}{}) returns ({}{});",
+            self.name,
+            stream(self.client_streaming),
+            self.input,
+            stream(self.server_streaming),
+            self.output
+        )
+    }
+}
+
+/// Lexical token of a `.proto` file.
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// Identifier, possibly dotted, e.g. `foo.Bar` or `.foo.Bar`
+    Name(String),
+    Punctuator(char),
+    Str(String),
+    Number(String),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Name(name) => write!(f, "`{}`", name),
+            Token::Punctuator(c) => write!(f, "`{}`", c),
+            Token::Str(_) => write!(f, "string"),
+            Token::Number(number) => write!(f, "`{}`", number),
+        }
+    }
+}
+
+/// Splits a `.proto` file into tokens, leaving out whitespace and comments.
+fn tokenize(proto: &str) -> Result<Vec<Token>, ParseError> {
+    let chars = proto.chars().collect::<Vec<char>>();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    let is_name_char = |c: char| c == '_' || c == '.' || c.is_alphanumeric();
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        if c.is_whitespace() || c == '\u{feff}' {
+            i += 1;
+            continue;
+        }
+
+        if c == '/' && next == Some('/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            continue;
+        }
+
+        if c == '/' && next == Some('*') {
+            i += 2;
+
+            while i < chars.len()
+                && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/'))
+            {
+                i += 1;
+            }
+
+            i += 2;
+            continue;
+        }
+
+        if c == '"' || c == '\'' {
+            let mut value = String::new();
+            i += 1;
+
+            loop {
+                match chars.get(i) {
+                    Some(quote) if *quote == c => break,
+                    Some('\\') => {
+                        if let Some(escaped) = chars.get(i + 1) {
+                            value.push(*escaped);
+                        }
+                        i += 2;
+                    }
+                    Some('\n') | None => {
+                        return Err(ParseError::from(anyhow!(
+                            "Unterminated string in the protobuf schema"
+                        )))
+                    }
+                    Some(other) => {
+                        value.push(*other);
+                        i += 1;
+                    }
+                }
+            }
+
+            tokens.push(Token::Str(value));
+            i += 1;
+            continue;
+        }
+
+        let starts_number = c.is_ascii_digit()
+            || (matches!(c, '-' | '+')
+                && next.is_some_and(|n| n.is_ascii_digit()));
+
+        if starts_number {
+            let start = i;
+            i += 1;
+
+            while i < chars.len()
+                && (chars[i].is_ascii_alphanumeric() || chars[i] == '.')
+            {
+                i += 1;
+            }
+
+            tokens.push(Token::Number(chars[start..i].iter().collect()));
+            continue;
+        }
+
+        if c == '_'
+            || c.is_alphabetic()
+            || (c == '.' && next.is_some_and(char::is_alphabetic))
+        {
+            let start = i;
+
+            while i < chars.len() && is_name_char(chars[i]) {
+                i += 1;
+            }
+
+            tokens.push(Token::Name(chars[start..i].iter().collect()));
+            continue;
+        }
+
+        if "{}[]()<>=;,:-".contains(c) {
+            tokens.push(Token::Punctuator(c));
+            i += 1;
+            continue;
+        }
+
+        return Err(ParseError::from(anyhow!(
+            "Unexpected character `{}` in the protobuf schema",
+            c
+        )));
+    }
+
+    Ok(tokens)
+}
+
+/// Recursive descent parser of `.proto` files.
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Result<Token, ParseError> {
+        let token =
+            self.tokens.get(self.position).cloned().ok_or_else(|| {
+                ParseError::from(anyhow!(
+                    "Unexpected end of the protobuf schema"
+                ))
+            })?;
+
+        self.position += 1;
+
+        Ok(token)
+    }
+
+    fn unexpected(token: &Token) -> ParseError {
+        ParseError::from(anyhow!("Unexpected {} in the protobuf schema", token))
+    }
+
+    fn is_name(&self, name: &str) -> bool {
+        matches!(self.peek(), Some(Token::Name(n)) if n == name)
+    }
+
+    /// Consumes the punctuator `c` if it is next.
+    fn eat(&mut self, c: char) -> bool {
+        let found = self.peek() == Some(&Token::Punctuator(c));
+
+        if found {
+            self.position += 1;
+        }
+
+        found
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), ParseError> {
+        match self.next()? {
+            Token::Punctuator(p) if p == c => Ok(()),
+            token => Err(Self::unexpected(&token)),
+        }
+    }
+
+    fn name(&mut self) -> Result<String, ParseError> {
+        match self.next()? {
+            Token::Name(name) => Ok(name),
+            token => Err(Self::unexpected(&token)),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, ParseError> {
+        match self.next()? {
+            Token::Str(value) => Ok(value),
+            token => Err(Self::unexpected(&token)),
+        }
+    }
+
+    fn number(&mut self) -> Result<String, ParseError> {
+        match self.next()? {
+            Token::Number(number) => Ok(number),
+            token => Err(Self::unexpected(&token)),
+        }
+    }
+
+    /// Skips the tokens up to the end of the statement, e.g. of an option
+    /// or a reserved range, including its nested blocks.
+    fn skip_statement(&mut self) -> Result<(), ParseError> {
+        let mut depth = 0;
+
+        loop {
+            match self.next()? {
+                Token::Punctuator('{' | '[' | '(') => depth += 1,
+                Token::Punctuator('}' | ']' | ')') => depth -= 1,
+                Token::Punctuator(';') if depth == 0 => return Ok(()),
+                _ => {}
+            }
+        }
+    }
+
+    /// Skips a block whose `{` is next, e.g. an `extend` block.
+    fn skip_block(&mut self) -> Result<(), ParseError> {
+        let mut depth = 0;
+
+        loop {
+            match self.next()? {
+                Token::Punctuator('{') => depth += 1,
+                Token::Punctuator('}') => {
+                    depth -= 1;
+
+                    if depth == 0 {
+                        return Ok(());
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Skips the options of a field or an enum value, e.g. `[packed = true]`.
+    fn skip_options(&mut self) -> Result<(), ParseError> {
+        if !self.eat('[') {
+            return Ok(());
+        }
+
+        let mut depth = 1;
+
+        while depth > 0 {
+            match self.next()? {
+                Token::Punctuator('[') => depth += 1,
+                Token::Punctuator(']') => depth -= 1,
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn file(&mut self) -> Result<Proto, ParseError> {
+        let mut proto = Proto::default();
+
+        while self.peek().is_some() {
+            if self.eat(';') {
+                continue;
+            }
+
+            let keyword = self.name()?;
+
+            match keyword.as_str() {
+                "edition" => self.skip_statement()?,
+                "syntax" => {
+                    self.expect('=')?;
+                    proto.syntax = Some(self.string()?);
+                    self.expect(';')?;
+                }
+                "package" => {
+                    proto.package = Some(self.name()?);
+                    self.expect(';')?;
+                }
+                "import" => {
+                    if self.is_name("public") || self.is_name("weak") {
+                        self.position += 1;
+                    }
+
+                    proto.imports.push(self.string()?);
+                    self.expect(';')?;
+                }
+                "option" => self.skip_statement()?,
+                "extend" => {
+                    self.name()?;
+                    self.skip_block()?;
+                }
+                "message" => proto.messages.push(self.message()?),
+                "enum" => proto.enums.push(self.enumeration()?),
+                "service" => proto.services.push(self.service()?),
+                _ => return Err(Self::unexpected(&Token::Name(keyword))),
+            }
+        }
+
+        Ok(proto)
+    }
+
+    fn message(&mut self) -> Result<Message, ParseError> {
+        let mut message = Message {
+            name: self.name()?,
+            fields: Vec::new(),
+            oneofs: Vec::new(),
+            messages: Vec::new(),
+            enums: Vec::new(),
+        };
+
+        self.expect('{')?;
+
+        while !self.eat('}') {
+            if self.eat(';') {
+                continue;
+            }
+
+            let keyword = self.name()?;
+
+            match keyword.as_str() {
+                "message" => message.messages.push(self.message()?),
+                "enum" => message.enums.push(self.enumeration()?),
+                "option" | "reserved" | "extensions" => {
+                    self.skip_statement()?
+                }
+                "extend" => {
+                    self.name()?;
+                    self.skip_block()?;
+                }
+                "oneof" => {
+                    let mut oneof = Oneof {
+                        name: self.name()?,
+                        fields: Vec::new(),
+                    };
+
+                    self.expect('{')?;
+
+                    while !self.eat('}') {
+                        if self.is_name("option") {
+                            self.skip_statement()?;
+                            continue;
+                        }
+
+                        let ty = self.name()?;
+                        oneof.fields.push(self.field(ty, None)?);
+                    }
+
+                    message.oneofs.push(oneof);
+                }
+                "repeated" | "optional" | "required" => {
+                    let ty = self.field_type()?;
+                    let field = self.field(ty, Some(keyword))?;
+                    message.fields.push(field);
+                }
+                _ => {
+                    // The keyword is the type of the field
+                    self.position -= 1;
+                    let ty = self.field_type()?;
+                    message.fields.push(self.field(ty, None)?);
+                }
+            }
+        }
+
+        Ok(message)
+    }
+
+    fn field_type(&mut self) -> Result<String, ParseError> {
+        let ty = self.name()?;
+
+        if ty != "map" || !self.eat('<') {
+            return Ok(ty);
+        }
+
+        let key = self.name()?;
+        self.expect(',')?;
+        let value = self.name()?;
+        self.expect('>')?;
+
+        Ok(format!("map<{}, {}>", key, value))
+    }
+
+    /// Parses the rest of a field, after its label and type.
+    fn field(
+        &mut self,
+        ty: String,
+        label: Option<String>,
+    ) -> Result<Field, ParseError> {
+        let name = self.name()?;
+        self.expect('=')?;
+        let number = self.number()?;
+
+        // Proto2 groups define their message inline
+        if ty == "group" {
+            return Err(ParseError::from(anyhow!(
+                "Groups are not supported in protobuf schemas, found `{}`",
+                name
+            )));
+        }
+
+        self.skip_options()?;
+        self.expect(';')?;
+
+        Ok(Field {
+            name,
+            ty,
+            number,
+            label,
+        })
+    }
+
+    fn enumeration(&mut self) -> Result<Enum, ParseError> {
+        let mut en = Enum {
+            name: self.name()?,
+            values: Vec::new(),
+        };
+
+        self.expect('{')?;
+
+        while !self.eat('}') {
+            if self.eat(';') {
+                continue;
+            }
+
+            if self.is_name("option") || self.is_name("reserved") {
+                self.skip_statement()?;
+                continue;
+            }
+
+            let name = self.name()?;
+            self.expect('=')?;
+            let number = self.number()?;
+
+            self.skip_options()?;
+            self.expect(';')?;
+
+            en.values.push((name, number));
+        }
+
+        Ok(en)
+    }
+
+    fn service(&mut self) -> Result<Service, ParseError> {
+        let mut service = Service {
+            name: self.name()?,
+            rpcs: Vec::new(),
+        };
+
+        self.expect('{')?;
+
+        while !self.eat('}') {
+            if self.eat(';') {
+                continue;
+            }
+
+            match self.name()?.as_str() {
+                "option" => self.skip_statement()?,
+                "rpc" => service.rpcs.push(self.rpc()?),
+                other => {
+                    return Err(Self::unexpected(&Token::Name(other.into())))
+                }
+            }
+        }
+
+        Ok(service)
+    }
+
+    fn rpc(&mut self) -> Result<Rpc, ParseError> {
+        let name = self.name()?;
+
+        let message = |parser: &mut Parser| -> Result<_, ParseError> {
+            parser.expect('(')?;
+
+            let streaming = parser.is_name("stream")
+                && matches!(
+                    parser.tokens.get(parser.position + 1),
+                    Some(Token::Name(_))
+                );
+
+            if streaming {
+                parser.position += 1;
+            }
+
+            let ty = parser.name()?;
+            parser.expect(')')?;
+
+            Ok((ty, streaming))
+        };
+
+        let (input, client_streaming) = message(self)?;
+
+        match self.name()?.as_str() {
+            "returns" => {}
+            other => return Err(Self::unexpected(&Token::Name(other.into()))),
+        }
+
+        let (output, server_streaming) = message(self)?;
+
+        // Methods either end with `;` or with a block of options
+        if self.peek() == Some(&Token::Punctuator('{')) {
+            self.skip_block()?;
+        } else {
+            self.expect(';')?;
+        }
+
+        Ok(Rpc {
+            name,
+            input,
+            output,
+            client_streaming,
+            server_streaming,
+        })
+    }
+}
+
+/// Function to deserialize a `.proto` file into a `Proto` struct.
+///
+/// # Arguments
+/// * `proto_str` - The content of the `.proto` file to be deserialized.
+///
+/// # Returns
+/// * A `Result` containing a `Proto` struct if successful, or a `ParseError` if an error occurred.
+fn deserialize_proto(proto_str: &str) -> Result<Proto, ParseError> {
+    let mut parser = Parser {
+        tokens: tokenize(proto_str)?,
+        position: 0,
+    };
+
+    let proto = parser.file()?;
+
+    let is_empty = proto.messages.is_empty()
+        && proto.enums.is_empty()
+        && proto.services.is_empty();
+
+    if is_empty {
+        return Err(ParseError::from(anyhow!(
+            "Unable to find any message, enum or service in the protobuf schema"
+        )));
+    }
+
+    Ok(proto)
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+
+    use super::*;
+
+    const GREETER: &str = r#"
+syntax = "proto3";
+
+package helloworld.v1;
+
+import "google/protobuf/timestamp.proto";
+import "common/types.proto";
+
+option go_package = "example.com/helloworld";
+
+// The greeting service definition.
+service Greeter {
+  rpc SayHello (HelloRequest) returns (HelloReply) {}
+  rpc Chat (stream HelloRequest) returns (stream HelloReply) {
+    option (google.api.http) = { post: "/v1/chat" body: "*" };
+  }
+}
+
+message HelloRequest {
+  string name = 1;
+  common.Locale locale = 2 [deprecated = true];
+  map<string, Tag> tags = 3;
+
+  message Tag { string value = 1; }
+}
+
+message HelloReply {
+  reserved 2, 15 to 20;
+  repeated string messages = 1;
+  oneof status {
+    Status code = 3;
+    string error = 4;
+  }
+
+  enum Status {
+    STATUS_UNSPECIFIED = 0;
+    STATUS_OK = 1 [(custom) = "ok"];
+  }
+}
+
+message Unused { int32 id = 1; }
+"#;
+
+    const TYPES: &str = r#"
+syntax = "proto3";
+package common;
+
+message Locale { string language = 1; Region region = 2; }
+enum Region { REGION_UNSPECIFIED = 0; EU = 1; }
+message Other {}
+"#;
+
+    #[test]
+    fn test_parse_proto() -> Result<()> {
+        let greeter = GREETER.as_proto()?;
+
+        assert_eq!(greeter.package.as_deref(), Some("helloworld.v1"));
+        assert_eq!(greeter.imports.len(), 2);
+        assert_eq!(greeter.messages.len(), 3);
+
+        let chat = &greeter.services[0].rpcs[1];
+        assert!(chat.client_streaming && chat.server_streaming);
+        assert!(!greeter.services[0].rpcs[0].client_streaming);
+
+        let request = &greeter.messages[0];
+        assert_eq!(request.fields[2].types(), ["string", "Tag"]);
+        assert_eq!(greeter.messages[1].all_fields().count(), 3);
+
+        let set = ProtoSet::new(BTreeMap::from([
+            (String::from("greeter.proto"), greeter),
+            (String::from("common/types.proto"), TYPES.as_proto()?),
+        ]));
+
+        assert!(set.missing_imports().is_empty());
+
+        let definitions = set.definitions();
+        assert_eq!(
+            set.resolve(&definitions, "helloworld.v1.HelloRequest", "Tag"),
+            Some(String::from("helloworld.v1.HelloRequest.Tag"))
+        );
+        assert_eq!(
+            set.resolve(&definitions, "helloworld.v1", "common.Locale"),
+            Some(String::from("common.Locale"))
+        );
+
+        let summary = set.service_summary("Greeter").unwrap();
+
+        assert!(summary.starts_with("package helloworld.v1;\n\nservice Greeter {\n  rpc SayHello(HelloRequest) returns (HelloReply);\n  rpc Chat(stream HelloRequest) returns (stream HelloReply);\n}"));
+        assert!(summary.contains("package common;"));
+        assert!(summary.contains("message Locale"));
+        assert!(summary.contains("enum Region"));
+        assert!(summary.contains("  map<string, Tag> tags = 3;"));
+        assert!(!summary.contains("Unused"));
+        assert!(!summary.contains("Other"));
+
+        assert!(set.service_summary("Missing").is_none());
+
+        assert!("syntax = \"proto3\";".as_proto().is_err());
+        assert!("type Query { id: ID }".as_proto().is_err());
+
+        Ok(())
+    }
+}
@@ -15,7 +15,7 @@ pub trait AsPython: AsFormat {
     fn strip_pythons(&self) -> Result<Vec<Python>, ParseError>;
 }
 
-impl<'a> AsPython for &'a str {
+impl AsPython for &str {
     /// Implementation for converting a string slice to a Python AST.
     fn as_python(&self) -> Result<Python, ParseError> {
         self.as_format(deserialize_python)
@@ -15,7 +15,7 @@ pub trait AsRust: AsFormat {
     fn strip_rusts(&self) -> Result<Vec<Rust>, ParseError>;
 }
 
-impl<'a> AsRust for &'a str {
+impl AsRust for &str {
     /// Implementation for converting a string slice to a Rust syntax tree.
     fn as_rust(&self) -> Result<Rust, ParseError> {
         self.as_format(deserialize_rust)
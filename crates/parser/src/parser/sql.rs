@@ -11,6 +11,10 @@ use std::{
 use super::AsFormat;
 use crate::err::ParseError;
 
+/// Syntax tree of the `sqlparser` crate, re-exported such that dependents can
+/// inspect the statements, for instance with its visitors.
+pub use sqlparser::ast;
+
 /// Trait providing methods for working with SQL code.
 pub trait AsSql: AsFormat {
     /// Converts the object to an SQL syntax tree.
@@ -23,7 +27,7 @@ pub trait AsSql: AsFormat {
     fn strip_sqls(&self) -> Result<Vec<Sql>, ParseError>;
 }
 
-impl<'a> AsSql for &'a str {
+impl AsSql for &str {
     /// Implementation of converting a string slice to an SQL syntax tree.
     fn as_sql(&self) -> Result<Sql, ParseError> {
         self.as_format(deserialize_sql)
@@ -130,7 +134,8 @@ fn deserialize_sql(sql_str: &str) -> Result<Sql, ParseError> {
     let mut sql_vec = Vec::new();
 
     for raw_stmt in statements.iter() {
-        let mut syntax_tree = Parser::parse_sql(&dialect, raw_stmt).unwrap();
+        let mut syntax_tree = Parser::parse_sql(&dialect, raw_stmt)
+            .map_err(|e| ParseError::from(anyhow!(e)))?;
 
         if syntax_tree.len() > 1 {
             return Err(ParseError::from(anyhow!(
@@ -138,7 +143,11 @@ fn deserialize_sql(sql_str: &str) -> Result<Sql, ParseError> {
             )));
         }
 
-        let stmt = syntax_tree.pop().unwrap();
+        // Chunks made of comments only have no statement
+        let stmt = match syntax_tree.pop() {
+            Some(stmt) => stmt,
+            None => continue,
+        };
 
         let sql_stmt = SqlStatement {
             raw: String::from(raw_stmt),
@@ -9,6 +9,7 @@ import {
   getOrSetApiKey,
   getOrSetModelVersion,
   getRoot,
+  readPromptTemplates,
   storeChat,
 } from "../utils/utils";
 import { setWebviewContent } from "./webview";
@@ -136,9 +137,10 @@ const setupWebviewSockets = async (
 
       await getOrSetApiKey();
 
-      promptLLM(panel, message);
+      promptLLM(panel, message, chat);
 
       if (isFirst) {
+        chat.setPromptTemplates(readPromptTemplates());
         await chat.setTitle(makeRequest);
         chatOperationQueue.add(() => storeChat(chat));
 
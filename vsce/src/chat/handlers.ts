@@ -1,29 +1,30 @@
 import * as vscode from "vscode";
 import { window } from "vscode";
-import { getOrSetApiKey } from "../utils";
 import * as wasm from "../../pkg/neatcoder";
-import * as https from "https";
 import * as http from "http";
-import * as url from "url";
-import { MessageBuffer } from "../utils/httpClient";
+import {
+  MessageBuffer,
+  streamedToken,
+  streamingRequestOptions,
+} from "../utils/httpClient";
 import { getLLMParams } from "../utils/utils";
 
 export async function buildRequest(
-  msgs: Array<wasm.Message>,
+  chat: wasm.Chat,
   stream: boolean
 ): Promise<[any, any]> {
-  const apiKey = await getOrSetApiKey();
-
   try {
-    console.log("Messages: " + JSON.stringify(msgs.map((msg) => msg.payload)));
-    let llmParams = await getLLMParams();
+    const [options, client] = await streamingRequestOptions(
+      chat.provider.requestTarget()
+    );
 
-    const body = wasm.requestBody(
-      msgs.map((msg) => msg.payload),
-      llmParams,
-      stream
+    console.log(
+      "Messages: " + JSON.stringify(chat.messages.map((msg) => msg.payload))
     );
-    return [apiKey, body];
+    let llmParams = await getLLMParams();
+
+    const body = chat.requestBody(llmParams, stream);
+    return [[options, client], body];
   } catch (err) {
     console.error(`Failed to build request: ${err}`);
     throw new Error((err as Error).message);
@@ -32,30 +33,18 @@ export async function buildRequest(
 
 export async function promptLLM(
   webviewPanel: vscode.WebviewPanel,
-  message: any
+  message: any,
+  chat: wasm.Chat
 ): Promise<void> {
-  const msgs: Array<wasm.Message> = message.msgs;
   const stream = message.stream;
 
-  const [apiKey, body] = await buildRequest(msgs, stream);
+  const [[options, client], body] = await buildRequest(chat, stream);
 
   return new Promise((resolve, reject) => {
     let messageBuffer = new MessageBuffer();
 
     try {
-      const urlString = "https://api.openai.com/v1/chat/completions";
-      const parsedUrl = url.parse(urlString);
-
-      const options = {
-        ...parsedUrl,
-        method: "POST",
-        headers: {
-          "Content-Type": "application/json",
-          Authorization: `Bearer ${apiKey}`,
-        },
-      };
-
-      const req = https.request(options, async (res) => {
+      const req = client.request(options, async (res: http.IncomingMessage) => {
         console.log(`STATUS: ${res.statusCode}`);
         if (!(res.statusCode === 202 || res.statusCode === 200)) {
           const statusMessage =
@@ -95,8 +84,7 @@ export async function promptLLM(
               const json = JSON.parse(message); // JSONify response
 
               // Here `json` will be an individual message object
-              // You can then access json.choices[0].delta.content and proceed with your existing logic
-              const token = json.choices[0].delta.content;
+              const token = streamedToken(json);
 
               if (token === null || token === undefined) {
                 // TODO: If finish_reason === "stop" then return, else continue...
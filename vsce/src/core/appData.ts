@@ -1,13 +1,34 @@
 import * as wasm from "../../pkg/neatcoder";
-import { window, workspace } from "vscode";
+import {
+  Diagnostic,
+  DiagnosticCollection,
+  DiagnosticSeverity,
+  languages,
+  Range,
+  TextDocument,
+  Uri,
+  window,
+  workspace,
+  WorkspaceEdit,
+} from "vscode";
+import { execFileSync } from "child_process";
 import * as fs from "fs";
 import * as path from "path";
-import { getRoot, readappData, saveappDataToFile } from "../utils/utils";
+import {
+  getRoot,
+  readappData,
+  readPromptTemplates,
+  saveappDataToFile,
+} from "../utils/utils";
 import { buildTreeFromTasks } from "../taskPool/models";
-import { makeRequest, makeStreamingRequest } from "../utils/httpClient";
+import {
+  makeRequest,
+  makeStreamingRequest,
+  UsageMeter,
+} from "../utils/httpClient";
 import { scanSourceFolder, streamCode } from "./workflows/streamCode";
 import { logger } from "../utils/logger";
-import { startLoading } from "../utils/statusBar";
+import { startLoading, stopLoading } from "../utils/statusBar";
 import {
   TaskPoolProvider,
   TasksCompletedProvider,
@@ -23,6 +44,7 @@ export class appDataManager {
   private appData: wasm.AppData;
   private taskPoolProvider: TaskPoolProvider;
   private tasksCompletedProvider: TasksCompletedProvider;
+  private reviewDiagnostics: DiagnosticCollection;
 
   /**
    * Constructor to initialize the appDataManager with given providers and a state read from a file.
@@ -35,11 +57,21 @@ export class appDataManager {
     tasksCompletedProvider: TasksCompletedProvider
   ) {
     this.appData = readappData();
+    this.appData.setPromptTemplates(readPromptTemplates());
     this.taskPoolProvider = taskPoolProvider;
     this.tasksCompletedProvider = tasksCompletedProvider;
+    this.reviewDiagnostics = languages.createDiagnosticCollection("neatcoder");
 
     // Update providers
     this.refresh();
+
+    // Findings of past reviews
+    this.appData
+      .getDoneTasks()
+      .filter((task) => task.taskType() === wasm.TaskType.Review)
+      .forEach((task) =>
+        this.showFindings(task.taskParams.review!.filename, task.findings)
+      );
   }
 
   /**
@@ -129,7 +161,9 @@ export class appDataManager {
   }
 
   /**
-   * Runs all tasks in the task pool in the application state sequentially.
+   * Runs all tasks in the task pool in the application state. Independent
+   * tasks run concurrently, within the concurrency limit and the token
+   * budget of the settings, and failed tasks are retried.
    *
    * @param {wasm.OpenAIParams} llmParams - The parameters for the OpenAI client.
    * @returns {Promise<void>} - A promise indicating the completion of all tasks.
@@ -144,10 +178,55 @@ export class appDataManager {
         return;
       }
 
-      // Run each task sequentially.
-      for (const task of tasks) {
-        const taskId = task.id; // Assuming task object has an id property
-        await this.runTask(taskId, llmParams);
+      const config = workspace.getConfiguration("extension");
+      const scheduler = new wasm.Scheduler(
+        config.get<number>("maxConcurrentTasks", 4),
+        config.get<number>("tokensPerMinute", 90000)
+      );
+
+      // Tasks in flight, resolving to their ID once finished
+      const running = new Map<number, Promise<number>>();
+
+      while (true) {
+        const batch: wasm.Task[] = this.appData.scheduleTasks(
+          scheduler,
+          llmParams
+        );
+
+        if (batch.length > 0) {
+          saveappDataToFile(this.appData);
+          this.refresh();
+        }
+
+        for (const task of batch) {
+          running.set(
+            task.id,
+            this.runScheduledTask(scheduler, task, llmParams)
+          );
+        }
+
+        if (running.size === 0) {
+          if (this.appData.getReadyTasks().length === 0) {
+            break;
+          }
+
+          // The ready tasks wait for the token budget
+          const waitMs = Math.max(scheduler.waitMs(Date.now()), 1000);
+          await new Promise((resolve) => setTimeout(resolve, waitMs));
+          continue;
+        }
+
+        const taskId = await Promise.race(running.values());
+        running.delete(taskId);
+      }
+
+      const remaining = this.appData.getTodoTasks().length;
+
+      if (remaining > 0) {
+        window.showWarningMessage(
+          `${remaining} task(s) failed or depend on failed tasks.`
+        );
+        return;
       }
 
       window.showInformationMessage("All tasks completed.");
@@ -158,6 +237,53 @@ export class appDataManager {
     }
   }
 
+  /**
+   * Runs a task started by the scheduler, and reports its outcome to the
+   * scheduler. Failed tasks are queued again if they have attempts left.
+   *
+   * @param {wasm.Scheduler} scheduler - The scheduler which started the task.
+   * @param {wasm.Task} task - The task to run.
+   * @param {wasm.OpenAIParams} llmParams - The parameters for the OpenAI client.
+   * @returns {Promise<number>} - A promise resolving to the ID of the task.
+   */
+  private async runScheduledTask(
+    scheduler: wasm.Scheduler,
+    task: wasm.Task,
+    llmParams: wasm.OpenAIParams
+  ): Promise<number> {
+    // Requests run on a copy of the state, as it is updated by the other
+    // tasks in the meantime. Scaffolding however updates the state itself.
+    const appData =
+      task.taskType() === wasm.TaskType.ScaffoldProject
+        ? this.appData
+        : this.appData.snapshot();
+
+    const meter = new UsageMeter();
+
+    try {
+      await this.executeTask(task, llmParams, appData, meter);
+      this.appData.completeScheduledTask(scheduler, task.id, meter.usage());
+    } catch (err) {
+      const requeued = this.appData.failScheduledTask(
+        scheduler,
+        task.id,
+        `${err}`,
+        meter.usage()
+      );
+
+      console.error(`Error while performing task ${task.name}. ERROR: ${err}`);
+
+      if (!requeued) {
+        window.showErrorMessage(`Error while performing task: ${err}`);
+      }
+    }
+
+    saveappDataToFile(this.appData);
+    this.refresh();
+
+    return task.id;
+  }
+
   /**
    * Initiates a task based on the task ID and the associated task parameters.
    *
@@ -169,71 +295,621 @@ export class appDataManager {
     taskId: number,
     llmParams: wasm.OpenAIParams
   ): Promise<void> {
-    const task = this.appData.popTodo(taskId);
+    const task = this.appData.getTodoTasks().find((t) => t.id === taskId);
+
+    if (task === undefined) {
+      throw new Error(`[ERROR] Could not find to-do task ${taskId}.`);
+    }
+
+    this.appData.startTask(taskId, llmParams);
+    saveappDataToFile(this.appData);
+
+    this.refresh();
+    const meter = new UsageMeter();
+    try {
+      await this.executeTask(task, llmParams, this.appData, meter);
+
+      // Update Task Pool
+      this.appData.completeTask(taskId, meter.usage());
+      // Persist updated state
+      saveappDataToFile(this.appData);
+    } catch (err) {
+      this.appData.failTask(taskId, `${err}`, meter.usage());
+      saveappDataToFile(this.appData);
+      this.refresh();
+      console.error(`Error while performing task ${task.name}. ERROR: ${err}`);
+      window.showErrorMessage(`Error while performing task: ${err}`);
+      throw new Error((err as Error).message);
+    }
+
+    this.refresh();
+  }
+
+  /**
+   * Performs the work of a started task.
+   *
+   * @param {wasm.Task} task - The task to perform.
+   * @param {wasm.OpenAIParams} llmParams - The parameters for the OpenAI client.
+   * @param {wasm.AppData} appData - The application state the requests are made with.
+   * @param {UsageMeter} meter - Records the usage of the requests.
+   * @returns {Promise<void>} - A promise indicating the completion of the task.
+   */
+  private async executeTask(
+    task: wasm.Task,
+    llmParams: wasm.OpenAIParams,
+    appData: wasm.AppData,
+    meter: UsageMeter
+  ): Promise<void> {
     const taskType = task.taskType();
     const taskParams = task.taskParams;
 
+    if (taskType === undefined) {
+      window.showErrorMessage(`[ERROR] Task Type is undefined.`);
+      throw new Error(`[ERROR] Task Type is undefined.`);
+    }
+
+    // The pattern matching should be offloaded to Rust
+    if (taskType === wasm.TaskType.ScaffoldProject) {
+      await appData.scaffoldProject(llmParams, taskParams, meter.request);
+    }
+
+    if (taskType === wasm.TaskType.CodeGen) {
+      // If a new file should be created (or overwritten)
+      const relPath: string = taskParams.streamCode!.filename;
+
+      const filePath = path.join(getRoot(), "src", relPath);
+
+      const directoryPath = path.dirname(filePath);
+
+      if (!fs.existsSync(directoryPath)) {
+        fs.mkdirSync(directoryPath, { recursive: true }); // recursive ensures that nested directories are created
+      }
+
+      const tokenWriter = fs.createWriteStream(filePath, { flags: "w" });
+
+      // Open the file in the editor
+      logger.appendLine(`[INFO] Opening code editor`);
+      const activeTextDocument = await workspace.openTextDocument(filePath);
+
+      await window.showTextDocument(activeTextDocument);
+      const codebase = await scanSourceFolder();
+
+      logger.appendLine(`[INFO] Making StreamCode call to WASM Module.`);
+      startLoading("Awaiting Code Stream");
+
+      let requestBody = appData.streamCode(llmParams, taskParams, codebase);
+
+      await makeStreamingRequest(
+        requestBody,
+        activeTextDocument,
+        appData.provider.requestTarget(),
+        meter
+      );
+
+      await this.validateCode(
+        llmParams,
+        relPath,
+        activeTextDocument,
+        appData,
+        meter
+      );
+    }
+
+    if (taskType === wasm.TaskType.CodeEdit) {
+      await this.editCode(llmParams, taskParams, appData, meter);
+    }
+
+    if (taskType === wasm.TaskType.TestGen) {
+      await this.genTests(llmParams, taskParams, appData, meter);
+    }
+
+    if (taskType === wasm.TaskType.Review) {
+      await this.reviewCode(task, llmParams, appData, meter);
+    }
+
+    if (taskType === wasm.TaskType.DocGen) {
+      await this.genDocs(llmParams, taskParams, appData, meter);
+    }
+
+    if (taskType === wasm.TaskType.ConfigGen) {
+      await this.genConfig(llmParams, taskParams, appData, meter);
+    }
+
+    if (taskType === wasm.TaskType.ApiStubGen) {
+      await this.genApiStubs(llmParams, taskParams, appData, meter);
+    }
+  }
+
+  /**
+   * Adds a task modifying an existing file according to an instruction.
+   *
+   * @param {string} relPath - The path of the file, relative to `src`.
+   * @param {string} instruction - The change requested to the file.
+   */
+  public addEditTask(relPath: string, instruction: string) {
+    this.appData.addEditTask(relPath, instruction);
+    saveappDataToFile(this.appData);
     this.refresh();
+  }
+
+  /**
+   * Adds a task writing the unit tests of an existing file.
+   *
+   * @param {string} relPath - The path of the file, relative to `src`.
+   */
+  public addTestTask(relPath: string) {
+    this.appData.addTestTask(relPath);
+    saveappDataToFile(this.appData);
+    this.refresh();
+  }
+
+  /**
+   * Adds a task reviewing an existing file.
+   *
+   * @param {string} relPath - The path of the file, relative to `src`.
+   * @param {boolean} changesOnly - Whether only the uncommitted changes are reviewed.
+   */
+  public addReviewTask(relPath: string, changesOnly: boolean) {
+    this.appData.addReviewTask(relPath, changesOnly);
+    saveappDataToFile(this.appData);
+    this.refresh();
+  }
+
+  /**
+   * Adds the tasks writing the README of the project, the docs of each
+   * module of the scaffold and the reference of each interface. Running the
+   * tasks again updates the docs when they are stale.
+   *
+   * @returns {number} - The number of tasks added.
+   */
+  public addDocTasks(): number {
+    const taskIds = this.appData.addDocTasks();
+    saveappDataToFile(this.appData);
+    this.refresh();
+
+    return taskIds.length;
+  }
+
+  /**
+   * Adds the tasks writing the configuration files of the scaffold and the
+   * manifests of the language. Running the tasks again updates the files,
+   * e.g. with the dependencies imported by new code.
+   *
+   * @returns {number} - The number of tasks added.
+   */
+  public addConfigTasks(): number {
+    const taskIds = this.appData.addConfigTasks();
+    saveappDataToFile(this.appData);
+    this.refresh();
+
+    return taskIds.length;
+  }
+
+  /**
+   * Adds the tasks writing the client or the handlers of an API interface,
+   * one per group of operations of its spec.
+   *
+   * @param {string} interfaceName - The name of the API interface.
+   * @param {wasm.StubKind} kind - Whether the client, the handlers, the producers or the consumers are written.
+   * @returns {number} - The number of tasks added.
+   */
+  public addApiStubTasks(interfaceName: string, kind: wasm.StubKind): number {
+    const taskIds = this.appData.addApiStubTasks(interfaceName, kind);
+    saveappDataToFile(this.appData);
+    this.refresh();
+
+    return taskIds.length;
+  }
+
+  /**
+   * Writes the stubs of an `ApiStubGen` task, updating the existing stubs
+   * if any, and opens them in the editor.
+   *
+   * @param {wasm.OpenAIParams} llmParams - The parameters for the OpenAI client.
+   * @param {wasm.TaskParams} taskParams - The parameters of the `ApiStubGen` task.
+   * @param {wasm.AppData} appData - The application state the request is made with.
+   * @param {UsageMeter} meter - Records the usage of the requests.
+   * @returns {Promise<void>} - A promise indicating the completion of the stubs.
+   */
+  private async genApiStubs(
+    llmParams: wasm.OpenAIParams,
+    taskParams: wasm.TaskParams,
+    appData: wasm.AppData,
+    meter: UsageMeter
+  ): Promise<void> {
+    const apiStubGen = taskParams.apiStubGen!;
+    const filePath = path.join(getRoot(), "src", apiStubGen.filename);
+
+    const existing = fs.existsSync(filePath)
+      ? fs.readFileSync(filePath, "utf8")
+      : undefined;
+
+    logger.appendLine(`[INFO] Making GenApiStubs call to WASM Module.`);
+    startLoading("Awaiting API Stubs");
+
+    let stubs: wasm.CodeValidation;
     try {
-      if (taskType === undefined) {
-        window.showErrorMessage(`[ERROR] Task Type is undefined.`);
-        throw new Error(`[ERROR] Task Type is undefined.`);
-      }
+      stubs = await appData.genApiStubs(
+        llmParams,
+        taskParams,
+        existing,
+        meter.request
+      );
+    } finally {
+      stopLoading();
+    }
 
-      // The pattern matching should be offloaded to Rust
-      if (taskType === wasm.TaskType.ScaffoldProject) {
-        await this.appData.scaffoldProject(llmParams, taskParams, makeRequest);
-      }
+    fs.mkdirSync(path.dirname(filePath), { recursive: true });
+    fs.writeFileSync(filePath, stubs.code);
 
-      if (taskType === wasm.TaskType.CodeGen) {
-        // If a new file should be created (or overwritten)
-        const relPath: string = taskParams.streamCode!.filename;
+    if (!stubs.isValid()) {
+      window.showWarningMessage(
+        `${apiStubGen.filename} still has errors: ${stubs.diagnostic}`
+      );
+    }
 
-        const filePath = path.join(getRoot(), "src", relPath);
+    const document = await workspace.openTextDocument(filePath);
+    await window.showTextDocument(document);
+  }
 
-        const directoryPath = path.dirname(filePath);
+  /**
+   * Writes the configuration file of a `ConfigGen` task, updating the
+   * existing file if any, and opens it in the editor.
+   *
+   * @param {wasm.OpenAIParams} llmParams - The parameters for the OpenAI client.
+   * @param {wasm.TaskParams} taskParams - The parameters of the `ConfigGen` task.
+   * @param {wasm.AppData} appData - The application state the request is made with.
+   * @param {UsageMeter} meter - Records the usage of the requests.
+   * @returns {Promise<void>} - A promise indicating the completion of the file.
+   */
+  private async genConfig(
+    llmParams: wasm.OpenAIParams,
+    taskParams: wasm.TaskParams,
+    appData: wasm.AppData,
+    meter: UsageMeter
+  ): Promise<void> {
+    const configGen = taskParams.configGen!;
+    const filePath = path.join(getRoot(), configGen.filename);
 
-        if (!fs.existsSync(directoryPath)) {
-          fs.mkdirSync(directoryPath, { recursive: true }); // recursive ensures that nested directories are created
-        }
+    const existing = fs.existsSync(filePath)
+      ? fs.readFileSync(filePath, "utf8")
+      : undefined;
 
-        const tokenWriter = fs.createWriteStream(filePath, { flags: "w" });
+    const codebase = await scanSourceFolder();
 
-        // Open the file in the editor
-        logger.appendLine(`[INFO] Opening code editor`);
-        const activeTextDocument = await workspace.openTextDocument(filePath);
+    logger.appendLine(`[INFO] Making GenConfig call to WASM Module.`);
+    startLoading("Awaiting Configuration");
 
-        await window.showTextDocument(activeTextDocument);
-        const codebase = await scanSourceFolder();
+    let config: string;
+    try {
+      config = await appData.genConfig(
+        llmParams,
+        taskParams,
+        codebase,
+        existing,
+        meter.request
+      );
+    } finally {
+      stopLoading();
+    }
 
-        logger.appendLine(`[INFO] Making StreamCode call to WASM Module.`);
-        startLoading("Awaiting Code Stream");
+    fs.mkdirSync(path.dirname(filePath), { recursive: true });
+    fs.writeFileSync(filePath, config);
 
-        let requestBody = this.appData.streamCode(
-          llmParams,
-          taskParams,
-          codebase
-        );
+    const document = await workspace.openTextDocument(filePath);
+    await window.showTextDocument(document);
+  }
 
-        await makeStreamingRequest(requestBody, activeTextDocument);
+  /**
+   * Writes the Markdown docs of a `DocGen` task, updating the existing docs
+   * if any, and opens them in the editor.
+   *
+   * @param {wasm.OpenAIParams} llmParams - The parameters for the OpenAI client.
+   * @param {wasm.TaskParams} taskParams - The parameters of the `DocGen` task.
+   * @param {wasm.AppData} appData - The application state the request is made with.
+   * @param {UsageMeter} meter - Records the usage of the requests.
+   * @returns {Promise<void>} - A promise indicating the completion of the docs.
+   */
+  private async genDocs(
+    llmParams: wasm.OpenAIParams,
+    taskParams: wasm.TaskParams,
+    appData: wasm.AppData,
+    meter: UsageMeter
+  ): Promise<void> {
+    const docGen = taskParams.docGen!;
+    const filePath = path.join(getRoot(), docGen.filename);
+
+    const existing = fs.existsSync(filePath)
+      ? fs.readFileSync(filePath, "utf8")
+      : undefined;
+
+    const codebase = await scanSourceFolder();
+
+    logger.appendLine(`[INFO] Making GenDocs call to WASM Module.`);
+    startLoading("Awaiting Documentation");
+
+    let docs: string;
+    try {
+      docs = await appData.genDocs(
+        llmParams,
+        taskParams,
+        codebase,
+        existing,
+        meter.request
+      );
+    } finally {
+      stopLoading();
+    }
+
+    fs.mkdirSync(path.dirname(filePath), { recursive: true });
+    fs.writeFileSync(filePath, docs);
+
+    const document = await workspace.openTextDocument(filePath);
+    await window.showTextDocument(document);
+  }
+
+  /**
+   * Reviews the file of a `Review` task, or its uncommitted changes, and
+   * shows the findings as diagnostics of the file. The findings are stored
+   * on the task.
+   *
+   * @param {wasm.Task} task - The `Review` task.
+   * @param {wasm.OpenAIParams} llmParams - The parameters for the OpenAI client.
+   * @param {wasm.AppData} appData - The application state the request is made with.
+   * @param {UsageMeter} meter - Records the usage of the requests.
+   * @returns {Promise<void>} - A promise indicating the completion of the review.
+   */
+  private async reviewCode(
+    task: wasm.Task,
+    llmParams: wasm.OpenAIParams,
+    appData: wasm.AppData,
+    meter: UsageMeter
+  ): Promise<void> {
+    const review = task.taskParams.review!;
+    const filePath = path.join(getRoot(), "src", review.filename);
+
+    const content = fs.readFileSync(filePath, "utf8");
+
+    let diff: string | undefined;
+    if (review.changesOnly) {
+      diff = execFileSync("git", ["diff", "HEAD", "--", filePath], {
+        cwd: getRoot(),
+        encoding: "utf8",
+      });
+
+      if (diff.trim() === "") {
+        window.showInformationMessage(
+          `${review.filename} has no uncommitted changes to review.`
+        );
+        return;
       }
+    }
 
-      // Update Task Pool
-      this.appData.addDone(task);
-      // Persist updated state
-      saveappDataToFile(this.appData);
-    } catch (err) {
-      this.appData.addBackTodo(task);
-      console.error(`Error while performing task ${task.name}. ERROR: ${err}`);
-      window.showErrorMessage(`Error while performing task: ${err}`);
-      throw new Error((err as Error).message);
+    logger.appendLine(`[INFO] Making ReviewCode call to WASM Module.`);
+    startLoading("Awaiting Code Review");
+
+    let findings: wasm.ReviewFinding[];
+    try {
+      findings = await appData.reviewCode(
+        llmParams,
+        task.taskParams,
+        content,
+        diff,
+        meter.request
+      );
+    } finally {
+      stopLoading();
+    }
+
+    this.appData.setFindings(task.id, findings);
+    this.showFindings(review.filename, findings);
+
+    window.showInformationMessage(
+      `Review of ${review.filename}: ${findings.length} finding(s).`
+    );
+  }
+
+  /**
+   * Shows the findings of a review as diagnostics of the reviewed file,
+   * replacing the findings of previous reviews.
+   *
+   * @param {string} relPath - The path of the file, relative to `src`.
+   * @param {wasm.ReviewFinding[]} findings - The findings of the review.
+   */
+  private showFindings(relPath: string, findings: wasm.ReviewFinding[]) {
+    const uri = Uri.file(path.join(getRoot(), "src", relPath));
+
+    const diagnostics = findings.map((finding) => {
+      const range = new Range(
+        finding.startLine - 1,
+        0,
+        finding.endLine - 1,
+        Number.MAX_SAFE_INTEGER
+      );
+
+      const message = finding.suggestedFix
+        ? `${finding.message}\nSuggested fix: ${finding.suggestedFix}`
+        : finding.message;
+
+      const diagnostic = new Diagnostic(
+        range,
+        message,
+        toDiagnosticSeverity(finding.severity)
+      );
+      diagnostic.source = "Neatcoder";
+      diagnostic.code = wasm.FindingCategory[finding.category];
+
+      return diagnostic;
+    });
+
+    this.reviewDiagnostics.set(uri, diagnostics);
+  }
+
+  /**
+   * Applies the unified diff generated by the LLM to the file of a
+   * `CodeEdit` task. Hunks which do not match the file are shown to the user.
+   *
+   * @param {wasm.OpenAIParams} llmParams - The parameters for the OpenAI client.
+   * @param {wasm.TaskParams} taskParams - The parameters of the `CodeEdit` task.
+   * @param {wasm.AppData} appData - The application state the request is made with.
+   * @param {UsageMeter} meter - Records the usage of the requests.
+   * @returns {Promise<void>} - A promise indicating the completion of the edit.
+   */
+  private async editCode(
+    llmParams: wasm.OpenAIParams,
+    taskParams: wasm.TaskParams,
+    appData: wasm.AppData,
+    meter: UsageMeter
+  ): Promise<void> {
+    const relPath: string = taskParams.codeEdit!.filename;
+    const filePath = path.join(getRoot(), "src", relPath);
+
+    const document = await workspace.openTextDocument(filePath);
+    await window.showTextDocument(document);
+
+    const content = document.getText();
+
+    logger.appendLine(`[INFO] Making EditCode call to WASM Module.`);
+    startLoading("Awaiting Code Edit");
+
+    let edit: wasm.CodeEdit;
+    try {
+      edit = await appData.editCode(
+        llmParams,
+        taskParams,
+        content,
+        meter.request
+      );
+    } finally {
+      stopLoading();
     }
 
-    this.refresh(); // need to refresh to reflect the state rollback
+    const workspaceEdit = new WorkspaceEdit();
+    workspaceEdit.replace(
+      document.uri,
+      new Range(document.positionAt(0), document.positionAt(content.length)),
+      edit.content
+    );
+
+    await workspace.applyEdit(workspaceEdit);
+    await document.save();
+
+    if (edit.hasConflicts()) {
+      const conflicts = edit.conflicts;
+      conflicts.forEach((conflict) =>
+        logger.appendLine(`[WARN] ${relPath}: ${conflict}`)
+      );
+
+      window.showWarningMessage(
+        `${conflicts.length} change(s) to ${relPath} could not be applied. See the logs for details.`
+      );
+    }
+  }
+
+  /**
+   * Writes the unit tests generated by the LLM for the file of a `TestGen`
+   * task. Inline tests, as in Rust, are appended to the file under test.
+   *
+   * @param {wasm.OpenAIParams} llmParams - The parameters for the OpenAI client.
+   * @param {wasm.TaskParams} taskParams - The parameters of the `TestGen` task.
+   * @param {wasm.AppData} appData - The application state the request is made with.
+   * @param {UsageMeter} meter - Records the usage of the requests.
+   * @returns {Promise<void>} - A promise indicating the completion of the tests.
+   */
+  private async genTests(
+    llmParams: wasm.OpenAIParams,
+    taskParams: wasm.TaskParams,
+    appData: wasm.AppData,
+    meter: UsageMeter
+  ): Promise<void> {
+    const testGen = taskParams.testGen!;
+    const filePath = path.join(getRoot(), "src", testGen.filename);
+    const testPath = path.join(getRoot(), testGen.testFilename);
+
+    const content = fs.readFileSync(filePath, "utf8");
+
+    logger.appendLine(`[INFO] Making GenTests call to WASM Module.`);
+    startLoading("Awaiting Unit Tests");
+
+    let tests: string;
+    try {
+      tests = await appData.genTests(
+        llmParams,
+        taskParams,
+        content,
+        meter.request
+      );
+    } finally {
+      stopLoading();
+    }
+
+    if (testGen.isInline()) {
+      fs.writeFileSync(testPath, `${content.trimEnd()}\n\n${tests}\n`);
+    } else {
+      fs.mkdirSync(path.dirname(testPath), { recursive: true });
+      fs.writeFileSync(testPath, `${tests}\n`);
+    }
+
+    const document = await workspace.openTextDocument(testPath);
+    await window.showTextDocument(document);
+  }
+
+  /**
+   * Checks the syntax of the generated code and lets the LLM repair it if
+   * needed. The remaining parser error, if any, is shown to the user.
+   *
+   * @param {wasm.OpenAIParams} llmParams - The parameters for the OpenAI client.
+   * @param {string} relPath - The path of the generated file, relative to `src`.
+   * @param {TextDocument} document - The document holding the generated code.
+   * @param {wasm.AppData} appData - The application state the request is made with.
+   * @param {UsageMeter} meter - Records the usage of the requests.
+   * @returns {Promise<void>} - A promise indicating the completion of the validation.
+   */
+  private async validateCode(
+    llmParams: wasm.OpenAIParams,
+    relPath: string,
+    document: TextDocument,
+    appData: wasm.AppData,
+    meter: UsageMeter
+  ): Promise<void> {
+    const code = document.getText();
+
+    startLoading("Validating Code");
+    const validation = await appData.validateCode(
+      llmParams,
+      relPath,
+      code,
+      meter.request
+    );
+    stopLoading();
+
+    if (validation.code !== code) {
+      const edit = new WorkspaceEdit();
+      const fullRange = new Range(
+        document.positionAt(0),
+        document.positionAt(code.length)
+      );
+      edit.replace(document.uri, fullRange, validation.code);
+
+      await workspace.applyEdit(edit);
+      await document.save();
+
+      logger.appendLine(
+        `[INFO] Repaired ${relPath} in ${validation.rounds} round(s)`
+      );
+    }
+
+    if (!validation.isValid()) {
+      window.showWarningMessage(
+        `${relPath} still has syntax errors: ${validation.diagnostic}`
+      );
+    }
   }
 
   /**
    * Retries a task based on the task ID and the associated task parameters.
+   * Done tasks are moved back to the to-do tasks, whereas failed tasks are
+   * run again directly.
    *
    * @param {number} taskId - The ID of the task to retry.
    * @param {wasm.OpenAIParams} llmParams - The parameters for the OpenAI client.
@@ -243,8 +919,14 @@ export class appDataManager {
     taskId: number,
     llmParams: wasm.OpenAIParams
   ): Promise<void> {
-    const task = this.appData.popDone(taskId);
-    this.appData.addBackTodo(task);
+    const isDone = this.appData
+      .getDoneTasks()
+      .some((task) => task.id === taskId);
+
+    if (isDone) {
+      this.appData.reopenTask(taskId);
+    }
+
     return this.runTask(taskId, llmParams);
   }
 
@@ -268,6 +950,61 @@ export class appDataManager {
     }
   }
 
+  /**
+   * Extends the existing project with a change request. The scaffold diff
+   * is turned into tasks for the added and edited files, whereas the
+   * removed and renamed files are updated on disk once confirmed.
+   *
+   * @param {wasm.OpenAIParams} llmParams - The parameters for the OpenAI client.
+   * @param {string} changeRequest - The change requested to the project.
+   * @returns {Promise<wasm.ScaffoldDiff>} - A promise resolving to the scaffold diff.
+   */
+  public async extendProject(
+    llmParams: wasm.OpenAIParams,
+    changeRequest: string
+  ): Promise<wasm.ScaffoldDiff> {
+    const codebase = await scanSourceFolder();
+
+    const diff = await this.appData.rescaffoldProject(
+      llmParams,
+      new wasm.RescaffoldParams(changeRequest),
+      codebase,
+      makeRequest
+    );
+
+    saveappDataToFile(this.appData);
+    this.refresh();
+
+    const srcPath = path.join(getRoot(), "src");
+    const moves = diff.renamed.length + diff.removed.length;
+
+    if (moves > 0) {
+      const answer = await window.showWarningMessage(
+        `Apply ${diff.renamed.length} rename(s) and ${diff.removed.length} removal(s) to the files of the project?`,
+        { modal: true },
+        "Apply"
+      );
+
+      if (answer === "Apply") {
+        for (const rename of diff.renamed) {
+          const from = path.join(srcPath, rename.from);
+          const to = path.join(srcPath, rename.to);
+
+          if (fs.existsSync(from)) {
+            fs.mkdirSync(path.dirname(to), { recursive: true });
+            fs.renameSync(from, to);
+          }
+        }
+
+        for (const removed of diff.removed) {
+          fs.rmSync(path.join(srcPath, removed), { force: true });
+        }
+      }
+    }
+
+    return diff;
+  }
+
   /**
    * Initiates a scaffold project operation using specified OpenAI client, parameters, and user input.
    * This method creates necessary task parameters and invokes the scaffold project method from the appData object.
@@ -276,12 +1013,13 @@ export class appDataManager {
    * @param {string} userInput - The user input string.
    */
   async scaffoldProject(llmParams: wasm.OpenAIParams, userInput: string) {
-    const taskType = wasm.TaskType.ScaffoldProject;
-
-    const taskPayload = new wasm.TaskParamsInner(
+    const taskParams = wasm.TaskParams.newScaffoldProject(
       new wasm.ScaffoldParams(userInput)
     );
-    const taskParams = new wasm.TaskParams(taskType, taskPayload);
+
+    this.appData.setAutoTests(
+      workspace.getConfiguration("extension").get<boolean>("autoTests", false)
+    );
 
     try {
       await this.appData.scaffoldProject(llmParams, taskParams, makeRequest);
@@ -337,3 +1075,22 @@ export class appDataManager {
     this.handleUpdateTasksCompleted();
   }
 }
+
+/**
+ * Converts the severity of a review finding to the severity of a diagnostic.
+ *
+ * @param {wasm.Severity} severity - The severity of the finding.
+ * @returns {DiagnosticSeverity} - The severity of the diagnostic.
+ */
+function toDiagnosticSeverity(severity: wasm.Severity): DiagnosticSeverity {
+  switch (severity) {
+    case wasm.Severity.Error:
+      return DiagnosticSeverity.Error;
+    case wasm.Severity.Warning:
+      return DiagnosticSeverity.Warning;
+    case wasm.Severity.Info:
+      return DiagnosticSeverity.Information;
+    default:
+      return DiagnosticSeverity.Hint;
+  }
+}
@@ -0,0 +1,36 @@
+import * as vscode from "vscode";
+import * as path from "path";
+import { appDataManager } from "../appData";
+import { getRoot } from "../../utils/utils";
+
+/**
+ * Adds a task which writes the unit tests of the file open in the editor,
+ * placing them where the conventions of the language expect them.
+ *
+ * @param appManager - The application state manager instance where the task will be added.
+ * @return Promise<void> - A promise that resolves once the task is added.
+ */
+export async function addTests(appManager: appDataManager): Promise<void> {
+  const editor = vscode.window.activeTextEditor;
+
+  if (!editor) {
+    vscode.window.showErrorMessage("Open the file to test first.");
+    return;
+  }
+
+  const srcPath = path.join(getRoot(), "src");
+  const relPath = path.relative(srcPath, editor.document.uri.fsPath);
+
+  if (relPath.startsWith("..") || path.isAbsolute(relPath)) {
+    vscode.window.showErrorMessage(
+      "Only the files of the `src` folder can be tested."
+    );
+    return;
+  }
+
+  try {
+    appManager.addTestTask(relPath);
+  } catch (err) {
+    vscode.window.showErrorMessage((err as Error).message);
+  }
+}
@@ -0,0 +1,41 @@
+import * as vscode from "vscode";
+import * as path from "path";
+import { appDataManager } from "../appData";
+import { getRoot } from "../../utils/utils";
+
+/**
+ * Prompts the user for a change to the file open in the editor and adds a
+ * task which modifies the file with a unified diff, preserving the rest of it.
+ *
+ * @param appManager - The application state manager instance where the task will be added.
+ * @return Promise<void> - A promise that resolves once the task is added, or the user cancels.
+ */
+export async function editFile(appManager: appDataManager): Promise<void> {
+  const editor = vscode.window.activeTextEditor;
+
+  if (!editor) {
+    vscode.window.showErrorMessage("Open the file to edit first.");
+    return;
+  }
+
+  const srcPath = path.join(getRoot(), "src");
+  const relPath = path.relative(srcPath, editor.document.uri.fsPath);
+
+  if (relPath.startsWith("..") || path.isAbsolute(relPath)) {
+    vscode.window.showErrorMessage(
+      "Only the files of the `src` folder can be edited."
+    );
+    return;
+  }
+
+  const instruction = await vscode.window.showInputBox({
+    prompt: `Describe the change to ${relPath}`,
+    placeHolder: "e.g. Read the port from the PORT environment variable",
+  });
+
+  if (!instruction) {
+    return;
+  }
+
+  appManager.addEditTask(relPath, instruction);
+}
@@ -0,0 +1,64 @@
+import * as vscode from "vscode";
+import * as wasm from "../../../pkg/neatcoder";
+import { appDataManager } from "../appData";
+
+/**
+ * Adds the tasks which write the client or the handlers of an API interface,
+ * one per group of operations of its OpenAPI, Swagger, GraphQL or protobuf
+ * schema, e.g. one per gRPC service. Event-driven APIs described by AsyncAPI
+ * specs get producers or consumers instead, one per channel.
+ *
+ * @param appManager - The application state manager instance where the tasks will be added.
+ * @return Promise<void> - A promise that resolves once the tasks are added, or the user cancels.
+ */
+export async function genApiStubs(appManager: appDataManager): Promise<void> {
+  const interfaces = appManager.getReadOnlyState().interfaces;
+
+  const apis = Object.keys(interfaces).filter(
+    (name) => interfaces[name].inner.api
+  );
+
+  if (apis.length === 0) {
+    vscode.window.showErrorMessage("Add an API interface first.");
+    return;
+  }
+
+  const api = await vscode.window.showQuickPick(apis, {
+    placeHolder: "Which API should the stubs be written for?",
+  });
+
+  if (!api) {
+    return;
+  }
+
+  const schemas: string[] = Object.values(interfaces[api].inner.api.schemas);
+  const isAsyncApi = schemas.some((schema) =>
+    /^\s*"?asyncapi"?\s*:/m.test(schema)
+  );
+
+  const sides: { [side: string]: wasm.StubKind } = isAsyncApi
+    ? { Producer: wasm.StubKind.Producer, Consumer: wasm.StubKind.Consumer }
+    : { Client: wasm.StubKind.Client, Server: wasm.StubKind.Server };
+
+  const side = await vscode.window.showQuickPick(Object.keys(sides), {
+    placeHolder: `Which side of ${api} should be written?`,
+  });
+
+  if (!side) {
+    return;
+  }
+
+  const kind = sides[side];
+
+  try {
+    const count = appManager.addApiStubTasks(api, kind);
+
+    if (count === 0) {
+      vscode.window.showInformationMessage(
+        `The stubs of ${api} are already queued in the task pool.`
+      );
+    }
+  } catch (err) {
+    vscode.window.showErrorMessage((err as Error).message);
+  }
+}
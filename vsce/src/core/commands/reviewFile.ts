@@ -0,0 +1,45 @@
+import * as vscode from "vscode";
+import * as path from "path";
+import { appDataManager } from "../appData";
+import { getRoot } from "../../utils/utils";
+
+/**
+ * Adds a task which reviews the file open in the editor, or only its
+ * uncommitted changes. The findings are shown as diagnostics of the file.
+ *
+ * @param appManager - The application state manager instance where the task will be added.
+ * @return Promise<void> - A promise that resolves once the task is added, or the user cancels.
+ */
+export async function reviewFile(appManager: appDataManager): Promise<void> {
+  const editor = vscode.window.activeTextEditor;
+
+  if (!editor) {
+    vscode.window.showErrorMessage("Open the file to review first.");
+    return;
+  }
+
+  const srcPath = path.join(getRoot(), "src");
+  const relPath = path.relative(srcPath, editor.document.uri.fsPath);
+
+  if (relPath.startsWith("..") || path.isAbsolute(relPath)) {
+    vscode.window.showErrorMessage(
+      "Only the files of the `src` folder can be reviewed."
+    );
+    return;
+  }
+
+  const scope = await vscode.window.showQuickPick(
+    ["Whole file", "Uncommitted changes"],
+    { placeHolder: `What should be reviewed in ${relPath}?` }
+  );
+
+  if (!scope) {
+    return;
+  }
+
+  try {
+    appManager.addReviewTask(relPath, scope === "Uncommitted changes");
+  } catch (err) {
+    vscode.window.showErrorMessage((err as Error).message);
+  }
+}
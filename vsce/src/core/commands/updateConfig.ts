@@ -0,0 +1,23 @@
+import * as vscode from "vscode";
+import { appDataManager } from "../appData";
+
+/**
+ * Adds the tasks which write or update the configuration files of the
+ * project, such as its manifest and the dependencies it declares.
+ *
+ * @param appManager - The application state manager instance where the tasks will be added.
+ * @return Promise<void> - A promise that resolves once the tasks are added.
+ */
+export async function updateConfig(appManager: appDataManager): Promise<void> {
+  try {
+    const count = appManager.addConfigTasks();
+
+    if (count === 0) {
+      vscode.window.showInformationMessage(
+        "The configuration files are already queued in the task pool."
+      );
+    }
+  } catch (err) {
+    vscode.window.showErrorMessage((err as Error).message);
+  }
+}
@@ -0,0 +1,23 @@
+import * as vscode from "vscode";
+import { appDataManager } from "../appData";
+
+/**
+ * Adds the tasks which write or update the docs of the project: its README,
+ * the docs of its modules and the reference of its interfaces.
+ *
+ * @param appManager - The application state manager instance where the tasks will be added.
+ * @return Promise<void> - A promise that resolves once the tasks are added.
+ */
+export async function updateDocs(appManager: appDataManager): Promise<void> {
+  try {
+    const count = appManager.addDocTasks();
+
+    if (count === 0) {
+      vscode.window.showInformationMessage(
+        "The docs of the project are already queued in the task pool."
+      );
+    }
+  } catch (err) {
+    vscode.window.showErrorMessage((err as Error).message);
+  }
+}
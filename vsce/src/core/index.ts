@@ -1,3 +1,4 @@
 export { initCodeBase } from "./workflows/initCodeBase";
+export { extendProject } from "./workflows/extendProject";
 export { appDataManager } from "./appData";
 export { setupDotNeatWatcher } from "./watchers";
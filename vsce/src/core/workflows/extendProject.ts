@@ -0,0 +1,52 @@
+import * as vscode from "vscode";
+import * as wasm from "../../../pkg/neatcoder";
+import { appDataManager } from "../appData";
+import { startLoading, stopLoading } from "../../utils/statusBar";
+import MixpanelHelper from "../../utils/mixpanelHelper";
+
+/**
+ * Prompts the user for a change to the existing project, and adds the tasks
+ * implementing it to the task pool. Only the files added or edited by the
+ * change are turned into tasks.
+ *
+ * @param llmParams - Parameters configuring the OpenAI client for task processing.
+ * @param appManager - The appDataManager instance responsible for managing the application state.
+ * @returns Promise<void> - A promise that resolves once the tasks are added, or the user cancels.
+ */
+export async function extendProject(
+  llmParams: wasm.OpenAIParams,
+  appManager: appDataManager
+): Promise<void> {
+  const changeRequest = await vscode.window.showInputBox({
+    prompt: "Describe the change to the project",
+    placeHolder: "Add an endpoint listing the orders of a customer...",
+  });
+
+  if (!changeRequest) {
+    return;
+  }
+
+  let mixpanel = MixpanelHelper.getInstance();
+  mixpanel.trackEvent("extendProject", { changeRequest: changeRequest });
+
+  startLoading("Prompting the LLM..");
+  try {
+    const diff = await appManager.extendProject(llmParams, changeRequest);
+    stopLoading();
+
+    if (diff.isEmpty()) {
+      vscode.window.showInformationMessage(
+        "The change does not require any change to the files of the project."
+      );
+      return;
+    }
+
+    vscode.window.showInformationMessage(
+      `${diff.added.length} file(s) to add and ${diff.edited.length} file(s) to edit are now available as tasks.`
+    );
+  } catch (err) {
+    stopLoading();
+    console.error(err);
+    vscode.window.showErrorMessage(`Error while extending the project: ${err}`);
+  }
+}
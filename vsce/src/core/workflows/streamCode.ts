@@ -65,7 +65,8 @@ export async function scanSourceFolder(): Promise<Record<string, string>> {
       } else {
         try {
           const fileContent = fs.readFileSync(filePath, "utf-8");
-          record[file] = fileContent;
+          // Files are keyed by their path relative to `src`
+          record[path.relative(srcFolderPath, filePath)] = fileContent;
         } catch (err) {
           logger.appendLine(`[ERROR] Could not read file: ${err}`);
           console.error(err);
@@ -21,7 +21,18 @@ import {
   TaskPoolProvider,
   TasksCompletedProvider,
 } from "./taskPool";
-import { initCodeBase, appDataManager, setupDotNeatWatcher } from "./core";
+import {
+  initCodeBase,
+  extendProject,
+  appDataManager,
+  setupDotNeatWatcher,
+} from "./core";
+import { editFile } from "./core/commands/editFile";
+import { addTests } from "./core/commands/addTests";
+import { reviewFile } from "./core/commands/reviewFile";
+import { updateDocs } from "./core/commands/updateDocs";
+import { updateConfig } from "./core/commands/updateConfig";
+import { genApiStubs } from "./core/commands/genApiStubs";
 import { initStatusBar, initLogger, logger } from "./utils";
 import { ChatProvider, initChat, setupChatWatcher } from "./chat";
 import {
@@ -131,6 +142,55 @@ export async function activate(context: vscode.ExtensionContext) {
     })
   );
 
+  context.subscriptions.push(
+    vscode.commands.registerCommand("extension.extendProject", async () => {
+      let llmParams = await getLLMParams();
+      extendProject(llmParams, appManager);
+    })
+  );
+
+  context.subscriptions.push(
+    vscode.commands.registerCommand("extension.editFile", async () => {
+      mixpanel.trackEvent("editFile");
+      await editFile(appManager);
+    })
+  );
+
+  context.subscriptions.push(
+    vscode.commands.registerCommand("extension.addTests", async () => {
+      mixpanel.trackEvent("addTests");
+      await addTests(appManager);
+    })
+  );
+
+  context.subscriptions.push(
+    vscode.commands.registerCommand("extension.reviewFile", async () => {
+      mixpanel.trackEvent("reviewFile");
+      await reviewFile(appManager);
+    })
+  );
+
+  context.subscriptions.push(
+    vscode.commands.registerCommand("extension.updateDocs", async () => {
+      mixpanel.trackEvent("updateDocs");
+      await updateDocs(appManager);
+    })
+  );
+
+  context.subscriptions.push(
+    vscode.commands.registerCommand("extension.updateConfig", async () => {
+      mixpanel.trackEvent("updateConfig");
+      await updateConfig(appManager);
+    })
+  );
+
+  context.subscriptions.push(
+    vscode.commands.registerCommand("extension.genApiStubs", async () => {
+      mixpanel.trackEvent("genApiStubs");
+      await genApiStubs(appManager);
+    })
+  );
+
   context.subscriptions.push(
     vscode.commands.registerCommand("extension.addDatastore", async () => {
       mixpanel.trackEvent("addDatastore");
@@ -16,8 +16,17 @@ export class TaskView extends vscode.TreeItem {
 
     this.task = task;
     if (task) {
-      this.iconPath = new vscode.ThemeIcon("zap");
+      this.iconPath = new vscode.ThemeIcon(statusIcon(task.status));
       this.contextValue = "taskItem";
+
+      if (task.retries > 0) {
+        this.description = `retry ${task.retries}`;
+      }
+
+      const error = task.lastError;
+      if (task.status === wasm.TaskStatus.Failed && error) {
+        this.tooltip = error;
+      }
     } else {
       this.iconPath = new vscode.ThemeIcon("folder"); // You can choose an appropriate icon for folders
       this.contextValue = "groupItem";
@@ -25,6 +34,19 @@ export class TaskView extends vscode.TreeItem {
   }
 }
 
+function statusIcon(status: wasm.TaskStatus): string {
+  switch (status) {
+    case wasm.TaskStatus.InProgress:
+      return "sync";
+    case wasm.TaskStatus.Failed:
+      return "error";
+    case wasm.TaskStatus.Skipped:
+      return "debug-step-over";
+    default:
+      return "zap";
+  }
+}
+
 export function buildTreeFromTasks(tasks: wasm.Task[]): TaskView {
   const root = new TaskView("root");
 
@@ -4,64 +4,237 @@ import { getOrSetApiKey } from "./utils";
 import { TextDocument } from "vscode";
 import { streamCode } from "../core/workflows/streamCode";
 import * as https from "https";
+import * as http from "http";
 import * as url from "url";
 import { logger } from "./logger";
 import { startLoading, stopLoading } from "./statusBar";
 import * as wasm from "../../pkg/neatcoder";
 
-let isProcessing = false;
-let isCodeBlock = false;
-let isCodeBlockMaybeEnding = false;
-let waitingForNewline = false;
-let isCodeBlockEnded = false;
+/**
+ * State of a streaming request, tracking whether the streamed tokens are
+ * inside the code block. Each request has its own state such that several
+ * files can be streamed concurrently.
+ */
+interface StreamState {
+  isProcessing: boolean;
+  isCodeBlock: boolean;
+  isCodeBlockMaybeEnding: boolean;
+  waitingForNewline: boolean;
+  isCodeBlockEnded: boolean;
+}
+
+/**
+ * Endpoint and headers of the LLM backend, as serialized by
+ * `Provider.requestTarget` and passed to the request callbacks.
+ */
+interface RequestTarget {
+  uri: string;
+  headers: Record<string, string>;
+  authHeader?: string;
+  authPrefix: string;
+}
+
+const DEFAULT_TARGET: RequestTarget = {
+  uri: "https://api.openai.com/v1/chat/completions",
+  headers: { "Content-Type": "application/json" },
+  authHeader: "Authorization",
+  authPrefix: "Bearer ",
+};
+
+/**
+ * Parses the request target, falling back to OpenAI when none is given.
+ *
+ * @param {string} target - The serialized request target, if any.
+ * @return {RequestTarget} - The request target.
+ */
+function parseTarget(target?: string): RequestTarget {
+  return target ? (JSON.parse(target) as RequestTarget) : DEFAULT_TARGET;
+}
 
 /**
- * This function makes an HTTP POST request to the OpenAI API
+ * Builds the headers of a request to the target, authenticated with the
+ * API key of the user.
+ *
+ * @param {RequestTarget} target - The request target.
+ * @param {string} apiKey - The API key of the user.
+ * @return {Record<string, string>} - The headers of the request.
+ */
+function targetHeaders(
+  target: RequestTarget,
+  apiKey: string
+): Record<string, string> {
+  const headers = { ...target.headers };
+
+  if (target.authHeader) {
+    headers[target.authHeader] = `${target.authPrefix}${apiKey}`;
+  }
+
+  return headers;
+}
+
+/**
+ * Builds the options of a streaming request to the target.
+ *
+ * @param {string} target - The serialized request target, if any.
+ * @return {Promise<[any, typeof https]>} - The request options and the module
+ * sending the request, depending on the protocol of the target.
+ */
+export async function streamingRequestOptions(
+  target?: string
+): Promise<[any, typeof https]> {
+  const apiKey = await getOrSetApiKey();
+  const requestTarget = parseTarget(target);
+  const parsedUrl = url.parse(requestTarget.uri);
+
+  const options = {
+    ...parsedUrl,
+    method: "POST",
+    headers: targetHeaders(requestTarget, apiKey),
+  };
+
+  const client: any = parsedUrl.protocol === "http:" ? http : https;
+
+  return [options, client];
+}
+
+/**
+ * Extracts the streamed token from a message of the stream, either an OpenAI
+ * chunk or an Anthropic `content_block_delta` event.
+ *
+ * @param {any} json - The parsed message of the stream.
+ * @return {string | null | undefined} - The token, if the message has any.
+ */
+export function streamedToken(json: any): string | null | undefined {
+  if (json.choices) {
+    return json.choices[0]?.delta?.content;
+  }
+
+  if (json.type === "content_block_delta") {
+    return json.delta?.text;
+  }
+
+  return undefined;
+}
+
+/**
+ * Sums the tokens reported by the responses of the requests of a task, such
+ * that the scheduler accounts for its actual usage. OpenAI reports them as
+ * `prompt_tokens` and `completion_tokens`, Anthropic as `input_tokens` and
+ * `output_tokens`, also in the `message_start` event of a stream.
+ */
+export class UsageMeter {
+  private promptTokens = 0;
+  private completionTokens = 0;
+  private reported = false;
+
+  /**
+   * Makes a request with `makeRequest`, recording the usage of the response.
+   * It is passed as the request callback of the WASM module.
+   */
+  request = async (body: string, target?: string): Promise<object> => {
+    const response = await makeRequest(body, target);
+    this.record(response);
+    return response;
+  };
+
+  /**
+   * Records the usage of a response or of a message of a stream, if any.
+   *
+   * @param {any} json - The parsed response or message.
+   */
+  record(json: any) {
+    const usage = json?.usage ?? json?.message?.usage;
+
+    if (!usage) {
+      return;
+    }
+
+    this.promptTokens += usage.prompt_tokens ?? usage.input_tokens ?? 0;
+    this.completionTokens +=
+      usage.completion_tokens ?? usage.output_tokens ?? 0;
+    this.reported = true;
+  }
+
+  /**
+   * Returns the tokens recorded, or `undefined` if no response reported any.
+   *
+   * @return {wasm.TokenUsage | undefined} - The tokens recorded.
+   */
+  usage(): wasm.TokenUsage | undefined {
+    return this.reported
+      ? new wasm.TokenUsage(this.promptTokens, this.completionTokens)
+      : undefined;
+  }
+}
+
+function newStreamState(): StreamState {
+  return {
+    isProcessing: false,
+    isCodeBlock: false,
+    isCodeBlockMaybeEnding: false,
+    waitingForNewline: false,
+    isCodeBlockEnded: false,
+  };
+}
+
+/**
+ * This function makes an HTTP POST request to the LLM API
  * to get the completion suggestions based on the input body.
  * Note that this function will return the response in full.
  *
  * @param {string} body - The request body which possibly contains the text to be completed.
- * @return {Promise<object>} - A promise that resolves to the response object from the OpenAI API.
+ * @param {string} target - The serialized endpoint and headers of the LLM backend, defaults to OpenAI.
+ * @return {Promise<object>} - A promise that resolves to the response object from the LLM API.
  */
-export async function makeRequest(body: string): Promise<object> {
+export async function makeRequest(
+  body: string,
+  target?: string
+): Promise<object> {
   const apiKey = await getOrSetApiKey();
+  const requestTarget = parseTarget(target);
 
   try {
-    const response = await fetch("https://api.openai.com/v1/chat/completions", {
+    const response = await fetch(requestTarget.uri, {
       method: "POST",
-      headers: {
-        "Content-Type": "application/json",
-        Authorization: `Bearer ${apiKey}`,
-      },
+      headers: targetHeaders(requestTarget, apiKey),
       body,
     });
 
     if (!response.ok) {
-      throw new Error(`HTTP error! status: ${response.status}`);
+      // Status and headers allow the retry policy to classify the error
+      // and to wait for the rate limits to reset
+      const error: any = new Error(`HTTP error! status: ${response.status}`);
+      error.status = response.status;
+      error.headers = Object.fromEntries(response.headers.entries());
+      throw error;
     }
 
     return (await response.json()) as object;
   } catch (err) {
     console.error("Error making request:", err);
-    throw new Error((err as Error).message);
+    throw err;
   }
 }
 
 /**
- * This function initiates a streaming request to the OpenAI API.
+ * This function initiates a streaming request to the LLM API.
  * It handles the streaming response, processes the received chunks of data,
  * manages the state variables related to code block streaming, and writes logs to files.
  *
  * @param {string} body - The request body which contains the text to be completed.
  * @param {TextDocument} activeTextDocument - The active text document in VS Code where the streamed data might be used.
+ * @param {string} target - The serialized endpoint and headers of the LLM backend, defaults to OpenAI.
+ * @param {UsageMeter} meter - Records the usage reported by the stream, if any.
  * @return {Promise<void>} - A promise that resolves when the streaming process completes successfully.
  */
 export async function makeStreamingRequest(
   body: string,
-  activeTextDocument: TextDocument
+  activeTextDocument: TextDocument,
+  target?: string,
+  meter?: UsageMeter
 ): Promise<void> {
-  cleanup();
-  const apiKey = await getOrSetApiKey();
+  const state = newStreamState();
+  const [options, client] = await streamingRequestOptions(target);
 
   return new Promise((resolve, reject) => {
     // let responseLog: string[] = []; // TODO: Only debug
@@ -70,29 +243,17 @@ export async function makeStreamingRequest(
     let messageBuffer = new MessageBuffer();
 
     try {
-      const urlString = "https://api.openai.com/v1/chat/completions";
-      const parsedUrl = url.parse(urlString);
-
-      const options = {
-        ...parsedUrl,
-        method: "POST",
-        headers: {
-          "Content-Type": "application/json",
-          Authorization: `Bearer ${apiKey}`,
-        },
-      };
-
-      const req = https.request(options, async (res) => {
+      const req = client.request(options, async (res) => {
         console.log(`STATUS: ${res.statusCode}`);
         res.setEncoding("utf8");
         res.pause();
 
         res.on("readable", async () => {
-          if (isProcessing) {
+          if (state.isProcessing) {
             return;
           }
 
-          isProcessing = true;
+          state.isProcessing = true;
           let chunk;
           while (null !== (chunk = res.read())) {
             // Assuming 'chunk' is a Buffer, convert it to a string
@@ -104,9 +265,9 @@ export async function makeStreamingRequest(
             for (const message of messages) {
               if (message === "[DONE]") {
                 stopLoading();
-                cleanup();
-                isProcessing = false; // Reset state
-                isCodeBlockEnded = false; // Reset state
+                cleanup(state);
+                state.isProcessing = false; // Reset state
+                state.isCodeBlockEnded = false; // Reset state
 
                 if (streamedTokens === 0) {
                   vscode.window.showErrorMessage(
@@ -126,10 +287,10 @@ export async function makeStreamingRequest(
               }
 
               const json = JSON.parse(message); // JSONify response
+              meter?.record(json);
 
               // Here `json` will be an individual message object
-              // You can then access json.choices[0].delta.content and proceed with your existing logic
-              const token = json.choices[0].delta.content;
+              const token = streamedToken(json);
 
               if (token === null || token === undefined) {
                 // TODO: If finish_reason === "stop" then return, else continue...
@@ -140,52 +301,52 @@ export async function makeStreamingRequest(
               // responseLog.push(token); // TODO: only in debug
               // writeLogs(responseLog); // TODO: only in debug
 
-              if (isCodeBlock) {
-                if (checkIfCodeBlockMaybeEnding(token)) {
+              if (state.isCodeBlock) {
+                if (checkIfCodeBlockMaybeEnding(state, token)) {
                   // If we get `` then it means the end of the code block may
                   // be near so we signal that
-                  isCodeBlockMaybeEnding = true;
+                  state.isCodeBlockMaybeEnding = true;
                   continue;
                 }
 
-                if (checkIfCodeBlockEnds(token)) {
+                if (checkIfCodeBlockEnds(state, token)) {
                   // Here we have gotten the confirmation that the code block
                   // is completed
-                  cleanup();
-                  isCodeBlockEnded = true;
+                  cleanup(state);
+                  state.isCodeBlockEnded = true;
                   continue;
                 }
 
-                isCodeBlockMaybeEnding = false;
+                state.isCodeBlockMaybeEnding = false;
 
-                if (checkForStreamStartSignal(token)) {
+                if (checkForStreamStartSignal(state, token)) {
                   console.log(`[INFO] Stream is about to start.`);
-                  waitingForNewline = false;
+                  state.waitingForNewline = false;
                   startLoading("Streaming");
                   continue;
                 }
 
-                if (checkIfCanStream()) {
+                if (checkIfCanStream(state)) {
                   await streamCode(token, activeTextDocument);
                   streamedTokens += 1;
                 }
               } else {
-                if (checkIfCodeBlockIsStarting(token)) {
+                if (checkIfCodeBlockIsStarting(state, token)) {
                   logger.appendLine(`[INFO] Starting Code Stream: ${token}`);
-                  prepareStartStreaming();
+                  prepareStartStreaming(state);
                 } else {
-                  isCodeBlockMaybeEnding = false;
+                  state.isCodeBlockMaybeEnding = false;
                 }
               }
             }
           }
-          isProcessing = false;
+          state.isProcessing = false;
         });
 
         res.on("end", () => {
-          cleanup();
-          isProcessing = false; // Reset state
-          isCodeBlockEnded = false; // Reset state
+          cleanup(state);
+          state.isProcessing = false; // Reset state
+          state.isCodeBlockEnded = false; // Reset state
           stopLoading();
           console.log("No more data in response.");
           resolve();
@@ -193,8 +354,8 @@ export async function makeStreamingRequest(
       });
 
       req.on("error", (e) => {
-        cleanup();
-        isProcessing = false;
+        cleanup(state);
+        state.isProcessing = false;
         console.error(`Problem with request: ${e.message}`);
         reject(e);
       });
@@ -203,8 +364,8 @@ export async function makeStreamingRequest(
       req.end();
     } catch (err) {
       console.error(err);
-      cleanup();
-      isProcessing = false;
+      cleanup(state);
+      state.isProcessing = false;
       reject(err);
     }
   });
@@ -214,32 +375,33 @@ export async function makeStreamingRequest(
  * This function prepares the environment to start streaming data by
  * setting the appropriate state variables.
  */
-function prepareStartStreaming() {
-  isCodeBlockMaybeEnding = false;
-  isCodeBlock = true;
-  waitingForNewline = true;
+function prepareStartStreaming(state: StreamState) {
+  state.isCodeBlockMaybeEnding = false;
+  state.isCodeBlock = true;
+  state.waitingForNewline = true;
 }
 
 /**
  * This function resets the state variables used in the streaming process
  * to their initial values.
  */
-function cleanup() {
-  isCodeBlock = false;
-  isCodeBlockMaybeEnding = false;
-  waitingForNewline = false;
+function cleanup(state: StreamState) {
+  state.isCodeBlock = false;
+  state.isCodeBlockMaybeEnding = false;
+  state.waitingForNewline = false;
 }
 
 /**
  * This function checks whether a code block is ending based on the current token
  * and the state of the `isCodeBlockMaybeEnding` and `isCodeBlock` variables.
  *
+ * @param {StreamState} state - The state of the streaming request.
  * @param {any} token - The current token or string being processed.
  * @return {boolean} - True if a code block is ending, false otherwise.
  */
-function checkIfCodeBlockEnds(token: any): boolean {
-  const check1 = isCodeBlockMaybeEnding === true && token[0] === "`";
-  const check2 = token === "```" && isCodeBlock;
+function checkIfCodeBlockEnds(state: StreamState, token: any): boolean {
+  const check1 = state.isCodeBlockMaybeEnding === true && token[0] === "`";
+  const check2 = token === "```" && state.isCodeBlock;
 
   return check1 || check2;
 }
@@ -248,43 +410,47 @@ function checkIfCodeBlockEnds(token: any): boolean {
  * This function checks if a code block might be ending soon, based on the
  * current token and the state of the `isCodeBlock` variable.
  *
+ * @param {StreamState} state - The state of the streaming request.
  * @param {any} token - The current token or string being processed.
  * @return {boolean} - True if a code block might be ending soon, false otherwise.
  */
-function checkIfCodeBlockMaybeEnding(token: any): boolean {
-  return token === "``" && isCodeBlock;
+function checkIfCodeBlockMaybeEnding(state: StreamState, token: any): boolean {
+  return token === "``" && state.isCodeBlock;
 }
 
 /**
  * This function checks for a signal in the response data that indicates the
  * start of a stream, based on the current token and the state variables.
  *
+ * @param {StreamState} state - The state of the streaming request.
  * @param {any} token - The current token or string being processed.
  * @return {boolean} - True if a stream start signal is detected, false otherwise.
  */
-function checkForStreamStartSignal(token: any): boolean {
-  return isCodeBlock && waitingForNewline && token === "\n";
+function checkForStreamStartSignal(state: StreamState, token: any): boolean {
+  return state.isCodeBlock && state.waitingForNewline && token === "\n";
 }
 
 /**
  * This function checks whether the script can continue streaming data
  * based on the current state variables.
  *
+ * @param {StreamState} state - The state of the streaming request.
  * @return {boolean} - True if the script can continue streaming data, false otherwise.
  */
-function checkIfCanStream(): boolean {
-  return isCodeBlock && !waitingForNewline;
+function checkIfCanStream(state: StreamState): boolean {
+  return state.isCodeBlock && !state.waitingForNewline;
 }
 
 /**
  * This function checks if a code block is starting based on the current token
  * and the state of the `isCodeBlock` variable.
  *
+ * @param {StreamState} state - The state of the streaming request.
  * @param {any} token - The current token or string being processed.
  * @return {boolean} - True if a code block is starting, false otherwise.
  */
-function checkIfCodeBlockIsStarting(token: any): boolean {
-  return !isCodeBlock && token === "```" && !isCodeBlockEnded;
+function checkIfCodeBlockIsStarting(state: StreamState, token: any): boolean {
+  return !state.isCodeBlock && token === "```" && !state.isCodeBlockEnded;
 }
 
 // TODO: Produce this only in debug mode
@@ -346,7 +512,11 @@ export class MessageBuffer {
     let flushedMessages: string[] = [];
 
     while ((index = this.buffer.indexOf("\n\n")) >= 0) {
-      const message = this.flush(index);
+      // Anthropic names its events on a line preceding the data
+      const message = this.flush(index)
+        .split("\n")
+        .filter((line) => !line.startsWith("event: "))
+        .join("\n");
 
       if (message.startsWith("data: ")) {
         try {
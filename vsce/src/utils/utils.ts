@@ -225,6 +225,48 @@ export function getRoot(): string {
   return root;
 }
 
+/**
+ * Reads the prompt template overrides from the `prompts` folder of the
+ * workspace, e.g. `prompts/code_gen.txt` overrides the `code_gen` template.
+ * Files which do not match a known template are ignored.
+ *
+ * @returns {wasm.PromptTemplates} - The prompt templates.
+ */
+export function readPromptTemplates(): wasm.PromptTemplates {
+  const templates = new wasm.PromptTemplates();
+
+  if (!vscode.workspace.workspaceFolders) {
+    return templates;
+  }
+
+  const promptsPath = path.join(getRoot(), "prompts");
+
+  if (!fs.existsSync(promptsPath)) {
+    return templates;
+  }
+
+  const names = wasm.PromptTemplates.templateNames();
+
+  for (const file of fs.readdirSync(promptsPath)) {
+    const name = path.basename(file, ".txt");
+
+    if (path.extname(file) !== ".txt" || !names.includes(name)) {
+      continue;
+    }
+
+    try {
+      const template = fs.readFileSync(path.join(promptsPath, file), "utf-8");
+      templates.setTemplate(name, template);
+    } catch (err) {
+      vscode.window.showErrorMessage(
+        `Failed to load the prompt template ${file}: ${err}`
+      );
+    }
+  }
+
+  return templates;
+}
+
 export function getFilename(filepath: string): string {
   const parts = filepath.split(/[/\\]/);
   return parts[parts.length - 1];
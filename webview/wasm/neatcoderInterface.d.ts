@@ -6,6 +6,13 @@ export interface Api {
     schemas: Record<string, string>;
 }
 
+export interface ApiStubParams {
+    filename: string;
+    group?: string;
+    interface: string;
+    kind: number;
+}
+
 export interface AppData {
     interfaces: Record<string, Interface>;
     scaffold: string | undefined;
@@ -20,7 +27,18 @@ export interface Chat {
     title: string;
 }
 
+export interface CodeEditParams {
+    filename: string;
+    instruction: string;
+}
+
 export interface CodeGenParams {
+    description: string;
+    filename: string;
+}
+
+export interface ConfigGenParams {
+    description: string;
     filename: string;
 }
 
@@ -32,6 +50,12 @@ export interface Database {
     schemas: Record<string, string>;
 }
 
+export interface DocGenParams {
+    filename: string;
+    kind: number;
+    subject?: string;
+}
+
 export interface Interface {
     interface: any;
     interfaceType: number;
@@ -80,6 +104,11 @@ export interface Pipeline {
     tasks: Record<number, Task>;
 }
 
+export interface ReviewParams {
+    changesOnly: boolean;
+    filename: string;
+}
+
 export interface ScaffoldParams {
     specs: string;
 }
@@ -101,18 +130,33 @@ export interface Task {
 }
 
 export interface TaskParams {
+    apiStubGen: ApiStubParams | undefined;
+    codeEdit: CodeEditParams | undefined;
+    configGen: ConfigGenParams | undefined;
+    docGen: DocGenParams | undefined;
     inner: TaskParamsInner;
+    review: ReviewParams | undefined;
     scaffoldProject: ScaffoldParams | undefined;
     streamCode: CodeGenParams | undefined;
     taskType: number;
+    testGen: TestGenParams | undefined;
 }
 
-export interface TaskParamsInner {
-    scaffoldProject: ScaffoldParams | undefined;
-    streamCode: CodeGenParams | undefined;
-}
+export type TaskParamsInner =
+    | { scaffoldProject: ScaffoldParams }
+    | { streamCode: CodeGenParams }
+    | { codeEdit: CodeEditParams }
+    | { testGen: TestGenParams }
+    | { review: ReviewParams }
+    | { docGen: DocGenParams }
+    | { configGen: ConfigGenParams }
+    | { apiStubGen: ApiStubParams };
 
 export interface TaskPool {
     counter: number;
 }
 
+export interface TestGenParams {
+    filename: string;
+    testFilename: string;
+}